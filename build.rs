@@ -1,4 +1,33 @@
-fn main() {
-    // embed an icon into the executable file
-    embed_resource::compile("embed_icon.rc", embed_resource::NONE);
-}
\ No newline at end of file
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use image::RgbaImage;
+
+fn main() {
+    // embed an icon into the executable file
+    embed_resource::compile("embed_icon.rc", embed_resource::NONE);
+
+    // decode the tray and window icon here, so the executable holds its raw pixels and starting up
+    // has nothing to decode
+    let icon: RgbaImage = image::open("resources/tray_and_window_icon.png")
+        .expect("Failed to decode the tray and window icon")
+        .into_rgba8();
+    let out_dir: PathBuf = PathBuf::from(std::env::var_os("OUT_DIR").expect("Cargo sets OUT_DIR"));
+    std::fs::write(out_dir.join("icon.rgba"), icon.as_raw()).expect("Failed to write the icon's pixels");
+    std::fs::write(out_dir.join("icon.rs"), format!(
+        "const ICON_WIDTH: u32 = {};\nconst ICON_HEIGHT: u32 = {};\n\
+        const ICON_RGBA: &[u8] = include_bytes!(concat!(env!(\"OUT_DIR\"), \"/icon.rgba\"));\n",
+        icon.width(), icon.height()
+    )).expect("Failed to write the icon's dimensions");
+
+    // record when this executable was built so the About panel can show it.
+    // Rerun whenever the sources change so the timestamp doesn't go stale.
+    let build_timestamp: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or(0);
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={build_timestamp}");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=embed_icon.rc");
+    println!("cargo:rerun-if-changed=resources/tray_and_window_icon.png");
+}
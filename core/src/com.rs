@@ -0,0 +1,29 @@
+use anyhow::Result;
+use windows::Win32::Foundation::RPC_E_CHANGED_MODE;
+use windows::Win32::System::Com::{COINIT_APARTMENTTHREADED, CoInitializeEx, CoUninitialize};
+
+use crate::err;
+
+// Keeps COM initialized on the current thread for as long as this lives.
+// iced's thread already has COM set up for drag and drop, in which case this does nothing.
+pub struct ComApartment {
+    initialized_here: bool,
+}
+
+impl ComApartment {
+    pub fn enter() -> Result<ComApartment> {
+        match unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) } {
+            Ok(()) => Ok(ComApartment { initialized_here: true }),
+            Err(error) if error.code() == RPC_E_CHANGED_MODE => Ok(ComApartment { initialized_here: false }),
+            Err(error) => Err(err::ComInitializationError(error).into()),
+        }
+    }
+}
+
+impl Drop for ComApartment {
+    fn drop(&mut self) {
+        if self.initialized_here {
+            unsafe { CoUninitialize() };
+        }
+    }
+}
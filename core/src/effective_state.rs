@@ -0,0 +1,172 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::Result;
+use tracing::{instrument, trace};
+use winreg::HKEY;
+use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+use crate::i18n::tr;
+use crate::registry::RegistryProvider;
+use crate::windows_ops;
+use crate::windows_ops::{HIDE_FILE_EXT_VALUE_NAME, WINDOWS_EXPLORER_REGKEY_SUBPATH};
+
+// Path to the Group Policy key for Windows Explorer, under both HKEY_LOCAL_MACHINE and HKEY_CURRENT_USER.
+const EXPLORER_POLICY_REGKEY_SUBPATH: &str = "Software\\Policies\\Microsoft\\Windows\\Explorer";
+
+// Where the HideFileExt setting Windows Explorer actually follows comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingSource {
+    // Group Policy applied to the whole machine
+    MachinePolicy,
+    // Group Policy applied to the current user
+    UserPolicy,
+    // The current user's own Folder Options, which is what this app changes
+    UserPreference,
+    // A machine-wide value for users who haven't chosen for themselves
+    MachineDefault,
+    // Nothing sets the value, so Windows hides file extensions
+    WindowsDefault,
+}
+
+impl SettingSource {
+    // Group Policy overrides the user's choice and reverts changes to it, so fixing the setting
+    // from this app would not stick
+    pub fn is_group_policy(&self) -> bool {
+        matches!(self, SettingSource::MachinePolicy | SettingSource::UserPolicy)
+    }
+
+    // Every place the setting can come from, from the one which wins to the one which loses
+    const PRECEDENCE: [(SettingSource, HKEY, &'static str); 4] = [
+        (SettingSource::MachinePolicy, HKEY_LOCAL_MACHINE, EXPLORER_POLICY_REGKEY_SUBPATH),
+        (SettingSource::UserPolicy, HKEY_CURRENT_USER, EXPLORER_POLICY_REGKEY_SUBPATH),
+        (SettingSource::UserPreference, HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH),
+        (SettingSource::MachineDefault, HKEY_LOCAL_MACHINE, WINDOWS_EXPLORER_REGKEY_SUBPATH),
+    ];
+}
+
+impl Display for SettingSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name: String = match self {
+            SettingSource::MachinePolicy => tr("source-machine-policy"),
+            SettingSource::UserPolicy => tr("source-user-policy"),
+            SettingSource::UserPreference => tr("source-user-preference"),
+            SettingSource::MachineDefault => tr("source-machine-default"),
+            SettingSource::WindowsDefault => tr("source-windows-default"),
+        };
+        write!(f, "{name}")
+    }
+}
+
+// Whether Windows Explorer hides file extensions once every source of the setting is taken into account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EffectiveState {
+    pub file_extensions_hidden: bool,
+    pub source: SettingSource,
+}
+
+// Looking at HKEY_CURRENT_USER alone can mislead users on managed machines, where a policy
+// overrides whatever the user picks
+#[instrument]
+pub fn file_extensions_hidden(registry: &dyn RegistryProvider) -> Result<EffectiveState> {
+    for (source, predefined_key, subkey_path) in SettingSource::PRECEDENCE {
+        let value_data: Option<u32> = windows_ops::get_dword_value(
+            registry, predefined_key, subkey_path, HIDE_FILE_EXT_VALUE_NAME
+        )?;
+        if let Some(value_data) = value_data {
+            trace!("{:?} sets {} to {}", source, HIDE_FILE_EXT_VALUE_NAME, value_data);
+            return Ok(EffectiveState { file_extensions_hidden: value_data != 0, source });
+        }
+    }
+
+    trace!("Nothing sets {}, so Windows' default applies", HIDE_FILE_EXT_VALUE_NAME);
+    Ok(EffectiveState { file_extensions_hidden: true, source: SettingSource::WindowsDefault })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    use crate::effective_state::{EffectiveState, EXPLORER_POLICY_REGKEY_SUBPATH, file_extensions_hidden, SettingSource};
+    use crate::registry::InMemoryRegistry;
+    use crate::windows_ops;
+    use crate::windows_ops::{HIDE_FILE_EXT_VALUE_NAME, SettingScope, WINDOWS_EXPLORER_REGKEY_SUBPATH};
+
+    #[test]
+    fn a_missing_value_means_hidden() -> Result<()> {
+        // the key exists, as it does on most profiles, but nothing in it sets HideFileExt
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, "Hidden", 2u32);
+
+        assert_eq!(windows_ops::get_hide_file_ext_value(&registry, SettingScope::CurrentUser)?, None);
+        assert_eq!(
+            file_extensions_hidden(&registry)?,
+            EffectiveState { file_extensions_hidden: true, source: SettingSource::WindowsDefault }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn the_users_own_value_applies_without_a_policy() -> Result<()> {
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 0u32);
+
+        assert_eq!(
+            file_extensions_hidden(&registry)?,
+            EffectiveState { file_extensions_hidden: false, source: SettingSource::UserPreference }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn the_machine_default_applies_to_users_who_havent_chosen() -> Result<()> {
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_LOCAL_MACHINE, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 0u32);
+
+        assert_eq!(
+            file_extensions_hidden(&registry)?,
+            EffectiveState { file_extensions_hidden: false, source: SettingSource::MachineDefault }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn the_users_own_value_beats_the_machine_default() -> Result<()> {
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_LOCAL_MACHINE, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 0u32)
+            .with_value(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 1u32);
+
+        assert_eq!(
+            file_extensions_hidden(&registry)?,
+            EffectiveState { file_extensions_hidden: true, source: SettingSource::UserPreference }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn policies_beat_both_hives() -> Result<()> {
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_LOCAL_MACHINE, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 0u32)
+            .with_value(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 0u32)
+            .with_value(HKEY_CURRENT_USER, EXPLORER_POLICY_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 1u32);
+
+        assert_eq!(
+            file_extensions_hidden(&registry)?,
+            EffectiveState { file_extensions_hidden: true, source: SettingSource::UserPolicy }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn the_machine_policy_beats_the_user_policy() -> Result<()> {
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_CURRENT_USER, EXPLORER_POLICY_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 1u32)
+            .with_value(HKEY_LOCAL_MACHINE, EXPLORER_POLICY_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 0u32);
+
+        assert_eq!(
+            file_extensions_hidden(&registry)?,
+            EffectiveState { file_extensions_hidden: false, source: SettingSource::MachinePolicy }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("The current executable path could not be converted into a UTF-8 string")]
+pub struct NonUtf8ExecutablePathError;
+
+#[derive(Error, Debug)]
+pub enum RegistryOpsError {
+    #[error("Failed to look up registry data for key {key} and value {value} with error")]
+    FailedToGetValueData {
+        key: String,
+        value: String,
+        source: std::io::Error,
+    },
+
+    #[error("Registry value {value} under key {key} doesn't hold the type of data expected")]
+    UnexpectedValueType {
+        key: String,
+        value: String,
+    },
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to find a running process with name {0}")]
+pub struct ProcessNotFoundError(pub String);
+
+#[derive(Error, Debug)]
+#[error("Failed to restart Windows Explorer in order for it to pick up registry changes")]
+pub struct UnableToRestartWindowsExplorer;
+
+#[derive(Error, Debug)]
+#[error("Failed to check whether this program runs as administrator, error code {0}")]
+pub struct ElevationCheckError(pub u32);
+
+#[derive(Error, Debug)]
+#[error("Failed to tell Windows Explorer about the changed settings, error code {0}")]
+pub struct ExplorerRefreshError(pub u32);
+
+#[derive(Error, Debug)]
+#[error("Windows didn't start Windows Explorer back up, and starting it failed too")]
+pub struct ExplorerRelaunchError(#[source] pub std::io::Error);
+
+#[derive(Error, Debug)]
+#[error("Failed to set up COM on this thread")]
+pub struct ComInitializationError(#[source] pub windows::core::Error);
+
+#[derive(Error, Debug)]
+#[error("Failed to take a snapshot of running processes, error code {0}")]
+pub struct ProcessSnapshotError(pub u32);
+
+#[derive(Error, Debug)]
+#[error("Failed to watch a registry key for changes, error code {0}")]
+pub struct RegistryWatchError(pub u32);
+
+#[derive(Error, Debug)]
+#[error("Failed to find out which session this program runs in, error code {0}")]
+pub struct SessionLookupError(pub u32);
+
+#[derive(Error, Debug)]
+#[error("{operation} kept failing after {attempts} attempts")]
+pub struct RetriesExhaustedError {
+    pub operation: String,
+    pub attempts: u32,
+    pub source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+#[derive(Error, Debug)]
+pub enum NotificationStateError {
+    #[error("Failed to query whether the user accepts notifications, HRESULT {0:#x}")]
+    FailedToQueryNotificationState(i32),
+
+    #[error("Failed to query the Focus Assist state, NTSTATUS {0:#x}")]
+    FailedToQueryFocusAssist(i32),
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to look up the user's Windows display languages")]
+pub struct UiLanguageLookupError;
+
+#[derive(Error, Debug)]
+pub enum LocalizationError {
+    #[error("{0} is not a valid language identifier")]
+    InvalidLanguage(String),
+
+    #[error("The translations for {0} could not be loaded")]
+    InvalidTranslations(String),
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to enable a privilege this program needs, error code {0}")]
+pub struct PrivilegeError(pub u32);
+
+#[derive(Error, Debug)]
+pub enum HiveLoadingError {
+    #[error("Failed to expand the profile path {0}")]
+    FailedToExpandPath(String),
+
+    #[error("Failed to load the registry hive at {path}, error code {error_code}")]
+    FailedToLoad {
+        path: std::path::PathBuf,
+        error_code: u32,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum RuleError {
+    #[error("The rules built into this program could not be parsed")]
+    InvalidBuiltInRules(#[source] toml::de::Error),
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to open {target}, error code {error_code}")]
+pub struct ShellOpenError {
+    pub target: String,
+    pub error_code: u32,
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to open Folder Options")]
+pub struct FolderOptionsError(#[source] pub std::io::Error);
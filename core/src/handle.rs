@@ -0,0 +1,48 @@
+use tracing::warn;
+use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, HANDLE, INVALID_HANDLE_VALUE};
+use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+// A Win32 handle which is closed when this is dropped, including on early returns.
+// The checked constructors hand back GetLastError's code on failure, for callers to wrap in their own error.
+#[derive(Debug)]
+pub struct Win32Handle(HANDLE);
+
+impl Win32Handle {
+    // For APIs such as CreateToolhelp32Snapshot, which return INVALID_HANDLE_VALUE on failure
+    pub fn from_invalid_on_failure(handle: HANDLE) -> Result<Win32Handle, u32> {
+        match handle {
+            INVALID_HANDLE_VALUE => Err(unsafe { GetLastError() }),
+            _ => Ok(Win32Handle(handle)),
+        }
+    }
+
+    // For APIs such as OpenProcess, which return a null handle on failure
+    pub fn from_null_on_failure(handle: HANDLE) -> Result<Win32Handle, u32> {
+        match handle {
+            0 => Err(unsafe { GetLastError() }),
+            _ => Ok(Win32Handle(handle)),
+        }
+    }
+
+    // The access token of this program's own process
+    pub fn current_process_token(desired_access: u32) -> Result<Win32Handle, u32> {
+        let mut token: HANDLE = 0;
+        match unsafe { OpenProcessToken(GetCurrentProcess(), desired_access, &mut token) } {
+            0 => Err(unsafe { GetLastError() }),
+            _ => Ok(Win32Handle(token)),
+        }
+    }
+
+    // Only valid for as long as this is alive
+    pub fn raw(&self) -> HANDLE {
+        self.0
+    }
+}
+
+impl Drop for Win32Handle {
+    fn drop(&mut self) {
+        if unsafe { CloseHandle(self.0) } == 0 {
+            warn!("Failed to close handle {}, error code {}", self.0, unsafe { GetLastError() });
+        }
+    }
+}
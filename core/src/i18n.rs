@@ -0,0 +1,154 @@
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use fluent_bundle::bundle::FluentBundle;
+use fluent_langneg::{negotiate_languages, NegotiationStrategy};
+use intl_memoizer::concurrent::IntlLangMemoizer;
+use tracing::{instrument, trace, warn};
+use unic_langid::{CharacterDirection, LanguageIdentifier};
+
+use crate::err;
+use crate::windows_ops;
+
+// The language every message is guaranteed to exist in
+pub const FALLBACK_LANGUAGE: &str = "en-US";
+
+// Every shipped translation, embedded into the executable at compile-time
+const LOCALES: [(&str, &str); 2] = [
+    ("en-US", include_str!("..\\..\\resources\\locales\\en-US.ftl")),
+    ("de-DE", include_str!("..\\..\\resources\\locales\\de-DE.ftl")),
+];
+
+// A thread-safe bundle, so messages can be looked up from any thread
+type Bundle = FluentBundle<FluentResource, IntlLangMemoizer>;
+
+// Which way text in the active language reads, which the UI mirrors its layout to match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+struct Localizer {
+    active: Bundle,
+    fallback: Bundle,
+    layout_direction: LayoutDirection,
+}
+
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+// Picks the language to show messages in: the override if one is given, otherwise
+// the best match for the Windows display languages. Must be called before any message is looked up.
+#[instrument]
+pub fn init(language_override: Option<&str>) -> Result<()> {
+    let requested: Vec<LanguageIdentifier> = match language_override {
+        Some(language) => vec![language.parse()
+            .map_err(|_| err::LocalizationError::InvalidLanguage(String::from(language)))?],
+        None => windows_ops::get_user_preferred_ui_languages()?
+            .iter()
+            .filter_map(|language| language.parse().ok())
+            .collect(),
+    };
+    let available: Vec<LanguageIdentifier> = LOCALES.iter()
+        .map(|(language, _)| language.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| err::LocalizationError::InvalidLanguage(String::from("shipped locale")))?;
+    let fallback_language: LanguageIdentifier = FALLBACK_LANGUAGE.parse()
+        .map_err(|_| err::LocalizationError::InvalidLanguage(String::from(FALLBACK_LANGUAGE)))?;
+
+    let negotiated: Vec<&LanguageIdentifier> = negotiate_languages(
+        &requested, &available, Some(&fallback_language), NegotiationStrategy::Filtering
+    );
+    let active_language: LanguageIdentifier = negotiated.first()
+        .map(|language| (*language).clone())
+        .unwrap_or_else(|| fallback_language.clone());
+    trace!("Requested languages {:?}, using {}", requested, active_language);
+
+    let layout_direction: LayoutDirection = match active_language.character_direction() {
+        CharacterDirection::RTL => LayoutDirection::RightToLeft,
+        _ => LayoutDirection::LeftToRight,
+    };
+
+    let localizer = Localizer {
+        active: create_bundle(&active_language)?,
+        fallback: create_bundle(&fallback_language)?,
+        layout_direction,
+    };
+    if LOCALIZER.set(localizer).is_err() {
+        warn!("The language was already chosen, ignoring {}", active_language);
+    }
+    Ok(())
+}
+
+// Shows every message in the language they are guaranteed to exist in, for when `init()` fails
+#[instrument]
+pub fn init_fallback() -> Result<()> {
+    let fallback_language: LanguageIdentifier = FALLBACK_LANGUAGE.parse()
+        .map_err(|_| err::LocalizationError::InvalidLanguage(String::from(FALLBACK_LANGUAGE)))?;
+    let localizer = Localizer {
+        active: create_bundle(&fallback_language)?,
+        fallback: create_bundle(&fallback_language)?,
+        layout_direction: LayoutDirection::LeftToRight,
+    };
+    if LOCALIZER.set(localizer).is_err() {
+        warn!("The language was already chosen, ignoring {}", fallback_language);
+    }
+    Ok(())
+}
+
+fn create_bundle(language: &LanguageIdentifier) -> Result<Bundle> {
+    let language_name: String = language.to_string();
+    let (_, source) = LOCALES.iter()
+        .find(|(name, _)| *name == language_name)
+        .ok_or_else(|| err::LocalizationError::InvalidLanguage(language_name.clone()))?;
+
+    let resource: FluentResource = FluentResource::try_new(String::from(*source))
+        .map_err(|_| err::LocalizationError::InvalidTranslations(language_name.clone()))?;
+
+    let mut bundle: Bundle = FluentBundle::new_concurrent(vec![language.clone()]);
+    // iced draws the Unicode isolation marks fluent inserts around arguments as boxes
+    bundle.set_use_isolating(false);
+    bundle.add_resource(resource)
+        .map_err(|_| err::LocalizationError::InvalidTranslations(language_name))?;
+    Ok(bundle)
+}
+
+pub fn layout_direction() -> LayoutDirection {
+    LOCALIZER.get()
+        .map(|localizer| localizer.layout_direction)
+        .unwrap_or(LayoutDirection::LeftToRight)
+}
+
+// Looks up a message in the active language
+pub fn tr(id: &str) -> String {
+    tr_args(id, &[])
+}
+
+// Looks up a message in the active language, filling in its `{ $name }` placeholders
+pub fn tr_args(id: &str, args: &[(&str, FluentValue)]) -> String {
+    let Some(localizer) = LOCALIZER.get() else {
+        warn!("Message {} was looked up before a language was chosen", id);
+        return String::from(id);
+    };
+    let fluent_args: FluentArgs = args.iter()
+        .map(|(name, value)| (*name, value.clone()))
+        .collect();
+
+    format_message(&localizer.active, id, &fluent_args)
+        .or_else(|| format_message(&localizer.fallback, id, &fluent_args))
+        .unwrap_or_else(|| {
+            warn!("No translation exists for message {}", id);
+            String::from(id)
+        })
+}
+
+fn format_message(bundle: &Bundle, id: &str, args: &FluentArgs) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let message: String = bundle.format_pattern(pattern, Some(args), &mut errors).into_owned();
+    if !errors.is_empty() {
+        warn!("Errors while formatting message {}: {:?}", id, errors);
+    }
+    Some(message)
+}
@@ -0,0 +1,16 @@
+// Everything which reads, watches and fixes the registry and Explorer, without any UI.
+// The no-hidden-extensions binary puts its window, tray icon and command line on top of this.
+
+pub mod com;
+pub mod effective_state;
+pub mod err;
+pub mod handle;
+pub mod i18n;
+pub mod process;
+pub mod registry;
+pub mod registry_watcher;
+pub mod retry;
+pub mod rules;
+pub mod shell_windows;
+pub mod user_hives;
+pub mod windows_ops;
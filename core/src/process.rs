@@ -0,0 +1,208 @@
+use std::fmt::Debug;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use tracing::{trace, warn};
+use windows_sys::Win32::Foundation::{BOOL, GetLastError};
+use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+};
+use windows_sys::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+use windows_sys::Win32::System::Threading::{GetCurrentProcessId, OpenProcess, PROCESS_TERMINATE, TerminateProcess};
+
+use crate::err;
+use crate::handle::Win32Handle;
+
+// One running process, as a snapshot of every process lists it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub process_id: u32,
+    // the executable's file name, e.g. "explorer.exe"
+    pub name: String,
+    // None when the session can't be looked up, such as for processes which have already exited
+    pub session_id: Option<u32>,
+}
+
+// Everything windows_ops needs to find, terminate and start processes. Restarting Windows Explorer only goes
+// through this trait, so it can run against `MockProcessManager` or another way of restarting it.
+pub trait ProcessManager: Debug + Send + Sync {
+    fn list_processes(&self) -> Result<Vec<ProcessInfo>>;
+
+    // The session this program runs in
+    fn current_session_id(&self) -> Result<u32>;
+
+    // This method returns whether the process was terminated. It may already have exited,
+    // or be protected from this program.
+    fn terminate(&self, process_id: u32) -> bool;
+
+    // Starts a program without waiting for it to exit
+    fn launch(&self, program: &str) -> std::io::Result<()>;
+}
+
+// The processes actually running on this machine
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsProcessManager;
+
+impl ProcessManager for WindowsProcessManager {
+    fn list_processes(&self) -> Result<Vec<ProcessInfo>> {
+        let all_processes_snapshot: Win32Handle = Win32Handle::from_invalid_on_failure(
+            unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }
+        ).map_err(err::ProcessSnapshotError)?;
+        // use during iteration
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            cntUsage: 0,
+            th32ProcessID: 0,
+            th32DefaultHeapID: 0,
+            th32ModuleID: 0,
+            cntThreads: 0,
+            th32ParentProcessID: 0,
+            pcPriClassBase: 0,
+            dwFlags: 0,
+            szExeFile: [0; 260],
+        };
+
+        let mut processes: Vec<ProcessInfo> = Vec::new();
+        let mut was_data_copied_to_entry: BOOL = unsafe { Process32FirstW(all_processes_snapshot.raw(), &mut entry) };
+        while was_data_copied_to_entry != 0 {
+            let name_length: usize = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+            processes.push(ProcessInfo {
+                process_id: entry.th32ProcessID,
+                name: String::from_utf16_lossy(&entry.szExeFile[..name_length]),
+                session_id: session_id_of(entry.th32ProcessID),
+            });
+            was_data_copied_to_entry = unsafe { Process32NextW(all_processes_snapshot.raw(), &mut entry) };
+        }
+        Ok(processes)
+    }
+
+    fn current_session_id(&self) -> Result<u32> {
+        session_id_of(unsafe { GetCurrentProcessId() })
+            .ok_or_else(|| err::SessionLookupError(unsafe { GetLastError() }).into())
+    }
+
+    fn terminate(&self, process_id: u32) -> bool {
+        let process_handle: Win32Handle = match Win32Handle::from_null_on_failure(unsafe {
+            OpenProcess(PROCESS_TERMINATE, BOOL::from(false), process_id)
+        }) {
+            Ok(handle) => handle,
+            // the process may have exited between finding it and opening it
+            Err(error_code) => {
+                trace!("Couldn't open process {}, error code {}", process_id, error_code);
+                return false;
+            }
+        };
+        match unsafe { TerminateProcess(process_handle.raw(), 0) } {
+            0i32 => {
+                warn!("Failed to terminate process {}", process_id);
+                false
+            },
+            _ => true,
+        }
+    }
+
+    fn launch(&self, program: &str) -> std::io::Result<()> {
+        std::process::Command::new(program).spawn()?;
+        Ok(())
+    }
+}
+
+fn session_id_of(process_id: u32) -> Option<u32> {
+    let mut session_id: u32 = 0;
+    match unsafe { ProcessIdToSessionId(process_id, &mut session_id) } {
+        0 => None,
+        _ => Some(session_id),
+    }
+}
+
+// Processes which only exist in memory, for running the logic on top of `ProcessManager`
+// without terminating anything. Terminating a process named by `respawning()` starts a new one in its
+// place, the way Windows brings Windows Explorer back.
+#[derive(Debug, Default)]
+pub struct MockProcessManager {
+    current_session_id: u32,
+    processes: Mutex<Vec<ProcessInfo>>,
+    respawned_names: Vec<String>,
+    protected_process_ids: Vec<u32>,
+    terminated_process_ids: Mutex<Vec<u32>>,
+    launched_programs: Mutex<Vec<String>>,
+}
+
+impl MockProcessManager {
+    pub fn new(current_session_id: u32) -> MockProcessManager {
+        MockProcessManager { current_session_id, ..MockProcessManager::default() }
+    }
+
+    // Starts out with the given process running, in any session
+    pub fn with_process(self, process_id: u32, name: &str, session_id: u32) -> MockProcessManager {
+        lock(&self.processes).push(ProcessInfo {
+            process_id,
+            name: String::from(name),
+            session_id: Some(session_id),
+        });
+        self
+    }
+
+    pub fn respawning(mut self, name: &str) -> MockProcessManager {
+        self.respawned_names.push(name.to_lowercase());
+        self
+    }
+
+    // Terminating the process fails, like for processes which run with higher rights than this program
+    pub fn protecting(mut self, process_id: u32) -> MockProcessManager {
+        self.protected_process_ids.push(process_id);
+        self
+    }
+
+    pub fn terminated_process_ids(&self) -> Vec<u32> {
+        lock(&self.terminated_process_ids).clone()
+    }
+
+    pub fn launched_programs(&self) -> Vec<String> {
+        lock(&self.launched_programs).clone()
+    }
+
+    fn start(&self, name: &str, session_id: u32) {
+        let mut processes = lock(&self.processes);
+        let process_id: u32 = processes.iter().map(|process| process.process_id).max().unwrap_or(0) + 1;
+        processes.push(ProcessInfo { process_id, name: String::from(name), session_id: Some(session_id) });
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl ProcessManager for MockProcessManager {
+    fn list_processes(&self) -> Result<Vec<ProcessInfo>> {
+        Ok(lock(&self.processes).clone())
+    }
+
+    fn current_session_id(&self) -> Result<u32> {
+        Ok(self.current_session_id)
+    }
+
+    fn terminate(&self, process_id: u32) -> bool {
+        if self.protected_process_ids.contains(&process_id) {
+            return false;
+        }
+        let mut processes = lock(&self.processes);
+        let Some(index) = processes.iter().position(|process| process.process_id == process_id) else {
+            return false;
+        };
+        let terminated: ProcessInfo = processes.remove(index);
+        drop(processes);
+        lock(&self.terminated_process_ids).push(process_id);
+
+        if self.respawned_names.contains(&terminated.name.to_lowercase()) {
+            self.start(&terminated.name, terminated.session_id.unwrap_or(self.current_session_id));
+        }
+        true
+    }
+
+    fn launch(&self, program: &str) -> std::io::Result<()> {
+        lock(&self.launched_programs).push(String::from(program));
+        self.start(program, self.current_session_id);
+        Ok(())
+    }
+}
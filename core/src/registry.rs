@@ -0,0 +1,461 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::io::ErrorKind;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+use winreg::{HKEY, RegKey, RegValue};
+use winreg::enums::{
+    KEY_QUERY_VALUE, KEY_READ, KEY_SET_VALUE, REG_DWORD, REG_EXPAND_SZ, REG_MULTI_SZ, REG_SZ, RegDisposition,
+};
+use winreg::transaction::Transaction;
+use winreg::types::FromRegValue;
+
+use crate::err;
+use crate::registry_watcher::{ChangeNotifier, RegistryWatcher};
+
+// The kinds of registry data this program reads and writes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegistryValue {
+    Dword(u32),
+    String(String),
+}
+
+impl From<u32> for RegistryValue {
+    fn from(data: u32) -> RegistryValue {
+        RegistryValue::Dword(data)
+    }
+}
+
+impl From<String> for RegistryValue {
+    fn from(data: String) -> RegistryValue {
+        RegistryValue::String(data)
+    }
+}
+
+impl From<&str> for RegistryValue {
+    fn from(data: &str) -> RegistryValue {
+        RegistryValue::String(String::from(data))
+    }
+}
+
+// Everything windows_ops needs from the registry. The logic on top only goes through this trait,
+// so it can run against `InMemoryRegistry` without touching the real registry.
+pub trait RegistryProvider: Debug + Send + Sync {
+    // Reads a value, or None if the key or the value doesn't exist. An empty value name reads the key's default value.
+    fn get_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str) -> Result<Option<RegistryValue>>;
+
+    // Writes a value, creating the key first if it's missing
+    fn set_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str, value: &RegistryValue) -> Result<()>;
+
+    // This method returns whether the value existed
+    fn delete_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str) -> Result<bool>;
+
+    // Deletes a key along with everything under it. This method returns whether the key existed.
+    fn delete_key(&self, predefined_key: HKEY, subkey_path: &str) -> Result<bool>;
+
+    // Runs the operations so that either all of their writes happen or none do, and returns what they return.
+    // The operations get a provider which takes part in the transaction.
+    fn transacted(&self, operations: &mut dyn FnMut(&dyn RegistryProvider) -> Result<bool>) -> Result<bool>;
+
+    // Watches for changes to any value under a key, creating the key first if it's missing
+    fn watch_key(&self, predefined_key: HKEY, subkey_path: &str) -> Result<RegistryWatcher>;
+}
+
+// The Windows registry itself
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WinregRegistry;
+
+impl RegistryProvider for WinregRegistry {
+    fn get_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str) -> Result<Option<RegistryValue>> {
+        let hive: RegKey = RegKey::predef(predefined_key);
+        return match hive.open_subkey_with_flags(subkey_path, KEY_QUERY_VALUE) {
+            Ok(subkey) => read_value(&subkey, subkey_path, value_name),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        };
+    }
+
+    fn set_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str, value: &RegistryValue) -> Result<()> {
+        let hive: RegKey = RegKey::predef(predefined_key);
+        let (subkey, disposition): (RegKey, RegDisposition) = hive.create_subkey_with_flags(
+            subkey_path, KEY_QUERY_VALUE | KEY_SET_VALUE
+        )?;
+        log_if_created(disposition, subkey_path);
+        write_value(&subkey, value_name, value)
+    }
+
+    fn delete_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str) -> Result<bool> {
+        let hive: RegKey = RegKey::predef(predefined_key);
+        return match hive.open_subkey_with_flags(subkey_path, KEY_SET_VALUE) {
+            Ok(subkey) => delete_value_from(&subkey, value_name),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(error.into()),
+        };
+    }
+
+    fn delete_key(&self, predefined_key: HKEY, subkey_path: &str) -> Result<bool> {
+        let hive: RegKey = RegKey::predef(predefined_key);
+        return match hive.delete_subkey_all(subkey_path) {
+            Ok(()) => Ok(true),
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                trace!("No existing key found, so there was nothing to delete.");
+                Ok(false)
+            },
+            Err(error) => Err(error.into()),
+        };
+    }
+
+    fn transacted(&self, operations: &mut dyn FnMut(&dyn RegistryProvider) -> Result<bool>) -> Result<bool> {
+        // an uncommitted transaction is rolled back when it's dropped, including on early returns
+        let registry = TransactedRegistry { transaction: Transaction::new()? };
+        let result: bool = operations(&registry)?;
+        registry.transaction.commit()?;
+        Ok(result)
+    }
+
+    fn watch_key(&self, predefined_key: HKEY, subkey_path: &str) -> Result<RegistryWatcher> {
+        // Fresh profiles and stripped-down images may not have keys such as Windows Explorer's Advanced key yet.
+        // Creating one empty changes nothing for Windows, but lets it be read and watched like on any other profile.
+        let hive: RegKey = RegKey::predef(predefined_key);
+        let (subkey, disposition): (RegKey, RegDisposition) = hive.create_subkey_with_flags(subkey_path, KEY_READ)?;
+        log_if_created(disposition, subkey_path);
+        RegistryWatcher::new(subkey)
+    }
+}
+
+// The Windows registry within a kernel transaction, which `WinregRegistry::transacted()` commits
+struct TransactedRegistry {
+    transaction: Transaction,
+}
+
+impl Debug for TransactedRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TransactedRegistry")
+    }
+}
+
+impl RegistryProvider for TransactedRegistry {
+    fn get_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str) -> Result<Option<RegistryValue>> {
+        let hive: RegKey = RegKey::predef(predefined_key);
+        return match hive.open_subkey_transacted_with_flags(subkey_path, &self.transaction, KEY_QUERY_VALUE) {
+            Ok(subkey) => read_value(&subkey, subkey_path, value_name),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        };
+    }
+
+    fn set_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str, value: &RegistryValue) -> Result<()> {
+        let hive: RegKey = RegKey::predef(predefined_key);
+        let (subkey, disposition): (RegKey, RegDisposition) = hive.create_subkey_transacted_with_flags(
+            subkey_path, &self.transaction, KEY_QUERY_VALUE | KEY_SET_VALUE
+        )?;
+        log_if_created(disposition, subkey_path);
+        write_value(&subkey, value_name, value)
+    }
+
+    fn delete_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str) -> Result<bool> {
+        let hive: RegKey = RegKey::predef(predefined_key);
+        return match hive.open_subkey_transacted_with_flags(subkey_path, &self.transaction, KEY_SET_VALUE) {
+            Ok(subkey) => delete_value_from(&subkey, value_name),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(error.into()),
+        };
+    }
+
+    // Windows can only delete a key without subkeys within a transaction
+    fn delete_key(&self, predefined_key: HKEY, subkey_path: &str) -> Result<bool> {
+        let hive: RegKey = RegKey::predef(predefined_key);
+        return match hive.delete_subkey_transacted(subkey_path, &self.transaction) {
+            Ok(()) => Ok(true),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(error.into()),
+        };
+    }
+
+    // already within a transaction, which the outermost call commits
+    fn transacted(&self, operations: &mut dyn FnMut(&dyn RegistryProvider) -> Result<bool>) -> Result<bool> {
+        operations(self)
+    }
+
+    // notifications aren't part of the transaction, so the key is watched outside of it
+    fn watch_key(&self, predefined_key: HKEY, subkey_path: &str) -> Result<RegistryWatcher> {
+        WinregRegistry.watch_key(predefined_key, subkey_path)
+    }
+}
+
+fn read_value(subkey: &RegKey, subkey_path: &str, value_name: &str) -> Result<Option<RegistryValue>> {
+    let failed_to_get_value_data = |error: std::io::Error| err::RegistryOpsError::FailedToGetValueData {
+        key: String::from(subkey_path),
+        value: String::from(value_name),
+        source: error,
+    };
+    let raw_value: RegValue = match subkey.get_raw_value(value_name) {
+        Ok(raw_value) => raw_value,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(failed_to_get_value_data(error).into()),
+    };
+
+    let value: RegistryValue = match raw_value.vtype {
+        REG_DWORD => RegistryValue::Dword(u32::from_reg_value(&raw_value).map_err(failed_to_get_value_data)?),
+        // environment variables are left unexpanded
+        REG_SZ | REG_EXPAND_SZ | REG_MULTI_SZ => {
+            RegistryValue::String(String::from_reg_value(&raw_value).map_err(failed_to_get_value_data)?)
+        },
+        _ => return Err(err::RegistryOpsError::UnexpectedValueType {
+            key: String::from(subkey_path),
+            value: String::from(value_name),
+        }.into()),
+    };
+    Ok(Some(value))
+}
+
+fn write_value(subkey: &RegKey, value_name: &str, value: &RegistryValue) -> Result<()> {
+    match value {
+        RegistryValue::Dword(data) => subkey.set_value(value_name, data)?,
+        RegistryValue::String(data) => subkey.set_value(value_name, data)?,
+    }
+    Ok(())
+}
+
+fn delete_value_from(subkey: &RegKey, value_name: &str) -> Result<bool> {
+    return match subkey.delete_value(value_name) {
+        Ok(()) => Ok(true),
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            trace!("No existing value found, so there was nothing to delete.");
+            Ok(false)
+        },
+        Err(error) => Err(error.into()),
+    };
+}
+
+fn log_if_created(disposition: RegDisposition, subkey_path: &str) {
+    if matches!(disposition, RegDisposition::REG_CREATED_NEW_KEY) {
+        trace!("Created the missing registry key {}", subkey_path);
+    }
+}
+
+// Keys by hive and path, where the path is lowercase since the registry ignores case
+type InMemoryKeys = BTreeMap<(HKEY, String), BTreeMap<String, RegistryValue>>;
+
+// A registry which only lives in memory, for running the logic on top of `RegistryProvider`
+// without touching the real one. Like the real registry, key paths and value names ignore case.
+#[derive(Debug, Default)]
+pub struct InMemoryRegistry {
+    keys: Mutex<InMemoryKeys>,
+    watchers: Mutex<Vec<(HKEY, String, ChangeNotifier)>>,
+}
+
+impl InMemoryRegistry {
+    pub fn new() -> InMemoryRegistry {
+        InMemoryRegistry::default()
+    }
+
+    // Starts out with the given value already set
+    pub fn with_value(
+        self, predefined_key: HKEY, subkey_path: &str, value_name: &str, value: impl Into<RegistryValue>
+    ) -> InMemoryRegistry {
+        self.lock_keys()
+            .entry((predefined_key, subkey_path.to_lowercase()))
+            .or_default()
+            .insert(value_name.to_lowercase(), value.into());
+        self
+    }
+
+    // Whether the key exists, e.g. to see that a watch created it
+    pub fn has_key(&self, predefined_key: HKEY, subkey_path: &str) -> bool {
+        self.lock_keys().contains_key(&(predefined_key, subkey_path.to_lowercase()))
+    }
+
+    fn lock_keys(&self) -> std::sync::MutexGuard<'_, InMemoryKeys> {
+        self.keys.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Like RegNotifyChangeKeyValue as `RegistryWatcher` calls it, a watch doesn't cover the key's subkeys
+    fn notify_watchers(&self, predefined_key: HKEY, subkey_path: &str) {
+        let subkey_path: String = subkey_path.to_lowercase();
+        self.watchers.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .retain(|(watched_predefined_key, watched_path, notifier)| {
+                let is_watched: bool = *watched_predefined_key == predefined_key && subkey_path == *watched_path;
+                // a watcher which was dropped won't be notified again
+                !is_watched || notifier.notify()
+            });
+    }
+}
+
+impl RegistryProvider for InMemoryRegistry {
+    fn get_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str) -> Result<Option<RegistryValue>> {
+        Ok(self.lock_keys()
+            .get(&(predefined_key, subkey_path.to_lowercase()))
+            .and_then(|values| values.get(&value_name.to_lowercase()))
+            .cloned())
+    }
+
+    fn set_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str, value: &RegistryValue) -> Result<()> {
+        self.lock_keys()
+            .entry((predefined_key, subkey_path.to_lowercase()))
+            .or_default()
+            .insert(value_name.to_lowercase(), value.clone());
+        self.notify_watchers(predefined_key, subkey_path);
+        Ok(())
+    }
+
+    fn delete_value(&self, predefined_key: HKEY, subkey_path: &str, value_name: &str) -> Result<bool> {
+        let was_deleted: bool = self.lock_keys()
+            .get_mut(&(predefined_key, subkey_path.to_lowercase()))
+            .and_then(|values| values.remove(&value_name.to_lowercase()))
+            .is_some();
+        if was_deleted {
+            self.notify_watchers(predefined_key, subkey_path);
+        }
+        Ok(was_deleted)
+    }
+
+    fn delete_key(&self, predefined_key: HKEY, subkey_path: &str) -> Result<bool> {
+        let subkey_path_lowercase: String = subkey_path.to_lowercase();
+        let subkey_prefix: String = format!("{subkey_path_lowercase}\\");
+        let mut keys = self.lock_keys();
+        let key_count: usize = keys.len();
+        keys.retain(|(key_predefined_key, key_path), _| {
+            *key_predefined_key != predefined_key
+                || (*key_path != subkey_path_lowercase && !key_path.starts_with(&subkey_prefix))
+        });
+        let was_deleted: bool = keys.len() != key_count;
+        drop(keys);
+
+        if was_deleted {
+            self.notify_watchers(predefined_key, subkey_path);
+        }
+        Ok(was_deleted)
+    }
+
+    // Watchers hear about writes which are then rolled back, which only costs them a needless check
+    fn transacted(&self, operations: &mut dyn FnMut(&dyn RegistryProvider) -> Result<bool>) -> Result<bool> {
+        let snapshot: InMemoryKeys = self.lock_keys().clone();
+        let result: Result<bool> = operations(self);
+        if result.is_err() {
+            *self.lock_keys() = snapshot;
+        }
+        result
+    }
+
+    fn watch_key(&self, predefined_key: HKEY, subkey_path: &str) -> Result<RegistryWatcher> {
+        self.lock_keys().entry((predefined_key, subkey_path.to_lowercase())).or_default();
+        let watcher: RegistryWatcher = RegistryWatcher::detached()?;
+        self.watchers.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push((predefined_key, subkey_path.to_lowercase(), watcher.notifier()));
+        Ok(watcher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::{anyhow, Result};
+    use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    use crate::registry::{InMemoryRegistry, RegistryProvider, RegistryValue};
+    use crate::registry_watcher::{RegistryWatcher, WatchEvent};
+    use crate::windows_ops;
+    use crate::windows_ops::{HIDE_FILE_EXT_VALUE_NAME, SettingScope, WINDOWS_EXPLORER_REGKEY_SUBPATH};
+
+    const OTHER_REGKEY_SUBPATH: &str = "Software\\NoHiddenExtensions\\Test";
+
+    #[test]
+    fn a_set_value_reads_back() -> Result<()> {
+        let registry = InMemoryRegistry::new();
+        let was_changed: bool = windows_ops::set_or_update_registry_value(
+            &registry, HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 0u32
+        )?;
+        assert!(was_changed);
+        // the registry ignores case, so the fake does too
+        let value_data: Option<u32> = windows_ops::get_dword_value(
+            &registry, HKEY_CURRENT_USER, &WINDOWS_EXPLORER_REGKEY_SUBPATH.to_uppercase(), "hidefileext"
+        )?;
+        assert_eq!(value_data, Some(0));
+
+        let was_changed_again: bool = windows_ops::set_or_update_registry_value(
+            &registry, HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 0u32
+        )?;
+        assert!(!was_changed_again);
+        Ok(())
+    }
+
+    #[test]
+    fn a_missing_key_and_a_missing_value_both_read_as_none() -> Result<()> {
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, "Hidden", 1u32);
+
+        assert!(registry.has_key(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH));
+        let missing_value: Option<u32> = windows_ops::get_hide_file_ext_value(
+            &registry, SettingScope::CurrentUser
+        )?;
+        assert_eq!(missing_value, None);
+
+        assert!(!registry.has_key(HKEY_LOCAL_MACHINE, WINDOWS_EXPLORER_REGKEY_SUBPATH));
+        let missing_key: Option<u32> = windows_ops::get_hide_file_ext_value(
+            &registry, SettingScope::AllUsers
+        )?;
+        assert_eq!(missing_key, None);
+
+        // neither is there to delete
+        assert!(!windows_ops::delete_registry_value(
+            &registry, HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME
+        )?);
+        assert!(!windows_ops::delete_regkey(&registry, HKEY_LOCAL_MACHINE, WINDOWS_EXPLORER_REGKEY_SUBPATH)?);
+        Ok(())
+    }
+
+    #[test]
+    fn a_value_of_the_wrong_type_is_an_error() {
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, "0");
+        assert!(windows_ops::get_hide_file_ext_value(&registry, SettingScope::CurrentUser).is_err());
+    }
+
+    #[test]
+    fn a_failed_transaction_is_rolled_back() -> Result<()> {
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 1u32);
+
+        let result: Result<bool> = registry.transacted(&mut |registry: &dyn RegistryProvider| -> Result<bool> {
+            registry.set_value(
+                HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, &RegistryValue::Dword(0)
+            )?;
+            registry.set_value(HKEY_CURRENT_USER, OTHER_REGKEY_SUBPATH, "Written", &RegistryValue::from("yes"))?;
+            Err(anyhow!("Failing after both writes"))
+        });
+
+        assert!(result.is_err());
+        let value_data: Option<u32> = windows_ops::get_hide_file_ext_value(
+            &registry, SettingScope::CurrentUser
+        )?;
+        assert_eq!(value_data, Some(1));
+        assert!(!registry.has_key(HKEY_CURRENT_USER, OTHER_REGKEY_SUBPATH));
+        Ok(())
+    }
+
+    #[test]
+    fn a_watcher_hears_about_changes_to_its_own_key_only() -> Result<()> {
+        let registry = InMemoryRegistry::new();
+        let watcher: RegistryWatcher = windows_ops::watch_regkey(
+            &registry, HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH
+        )?;
+        // watching creates the key, as it does in the real registry
+        assert!(registry.has_key(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH));
+
+        windows_ops::set_or_update_registry_value(&registry, HKEY_CURRENT_USER, OTHER_REGKEY_SUBPATH, "Other", 1u32)?;
+        assert_eq!(watcher.wait_for_change_within(Duration::ZERO)?, None);
+
+        windows_ops::set_or_update_registry_value(
+            &registry, HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 0u32
+        )?;
+        assert_eq!(watcher.wait_for_change_within(Duration::ZERO)?, Some(WatchEvent::Changed));
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use std::sync::{Arc, Mutex, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::{instrument, trace};
+use windows_sys::Win32::Foundation::{ERROR_SUCCESS, GetLastError, WAIT_OBJECT_0, WAIT_TIMEOUT};
+use windows_sys::Win32::System::Registry::{REG_NOTIFY_CHANGE_LAST_SET, RegNotifyChangeKeyValue};
+use windows_sys::Win32::System::Threading::{CreateEventW, SetEvent, WaitForMultipleObjects};
+use winreg::RegKey;
+
+use crate::err;
+use crate::handle::Win32Handle;
+
+// Every watcher still running, so they can all be stopped when the program exits
+static RUNNING_WATCHERS: Mutex<Vec<WatcherSignals>> = Mutex::new(Vec::new());
+
+// What ended a wait for changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchEvent {
+    Changed,
+    // the watcher may have missed changes, so the caller should check the key and watch it anew
+    RearmRequested,
+    Stopped,
+}
+
+// Watches a registry key for changes to its values. Unlike a plain blocking wait, the wait can be
+// cut short from another thread through `stop()`.
+#[derive(Debug)]
+pub struct RegistryWatcher {
+    // None when changes are reported through `notifier()` rather than by Windows
+    key: Option<RegKey>,
+    change_event: Arc<Win32Handle>,
+    // whether Windows already has a notification set up from a wait which timed out
+    armed: AtomicBool,
+    signals: WatcherSignals,
+}
+
+// Reports a change to a watcher which has no registry key of its own, such as one from `InMemoryRegistry`
+#[derive(Debug, Clone)]
+pub struct ChangeNotifier(Weak<Win32Handle>);
+
+// Interrupts a `RegistryWatcher`'s wait from any thread
+#[derive(Debug, Clone)]
+struct WatcherSignals {
+    shutdown_event: Arc<Win32Handle>,
+    rearm_event: Arc<Win32Handle>,
+}
+
+impl RegistryWatcher {
+    #[instrument]
+    pub fn new(key: RegKey) -> Result<RegistryWatcher> {
+        RegistryWatcher::create(Some(key))
+    }
+
+    // A watcher which only wakes for changes reported through `notifier()`
+    pub fn detached() -> Result<RegistryWatcher> {
+        RegistryWatcher::create(None)
+    }
+
+    fn create(key: Option<RegKey>) -> Result<RegistryWatcher> {
+        // auto-reset, so each change wakes exactly one wait
+        let change_event: Win32Handle = create_event(false)?;
+        // manual-reset, so a stopped watcher stays stopped
+        let shutdown_event: Win32Handle = create_event(true)?;
+        let rearm_event: Win32Handle = create_event(false)?;
+
+        let signals = WatcherSignals {
+            shutdown_event: Arc::new(shutdown_event),
+            rearm_event: Arc::new(rearm_event),
+        };
+        RUNNING_WATCHERS.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(signals.clone());
+        Ok(RegistryWatcher { key, change_event: Arc::new(change_event), armed: AtomicBool::new(false), signals })
+    }
+
+    pub fn notifier(&self) -> ChangeNotifier {
+        ChangeNotifier(Arc::downgrade(&self.change_event))
+    }
+
+    // Blocks until any value under the key changes or the watcher is interrupted
+    pub fn wait_for_change(&self) -> Result<WatchEvent> {
+        // a wait without a timeout only ends with an event
+        Ok(self.wait(u32::MAX)?.unwrap_or(WatchEvent::Stopped))
+    }
+
+    // Like `wait_for_change()`, but returns None once the timeout passes without an event. Nothing is missed
+    // between a wait which timed out and the next one.
+    pub fn wait_for_change_within(&self, timeout: Duration) -> Result<Option<WatchEvent>> {
+        // u32::MAX would mean waiting forever
+        self.wait(u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX - 1))
+    }
+
+    fn wait(&self, timeout_millis: u32) -> Result<Option<WatchEvent>> {
+        // the notification only fires once, so it is set up again before every wait which follows a change
+        if let Some(key) = &self.key {
+            if !self.armed.swap(true, Ordering::SeqCst) {
+                let result: u32 = unsafe {
+                    RegNotifyChangeKeyValue(key.raw_handle(), 0, REG_NOTIFY_CHANGE_LAST_SET, self.change_event.raw(), 1)
+                };
+                if result != ERROR_SUCCESS {
+                    self.armed.store(false, Ordering::SeqCst);
+                    return Err(err::RegistryWatchError(result).into());
+                }
+            }
+        }
+
+        // when several are signaled, the first in this order wins, so stopping beats everything else
+        let events = [self.signals.shutdown_event.raw(), self.signals.rearm_event.raw(), self.change_event.raw()];
+        let wait_result: u32 = unsafe {
+            WaitForMultipleObjects(events.len() as u32, events.as_ptr(), 0, timeout_millis)
+        };
+        if wait_result == WAIT_TIMEOUT {
+            return Ok(None);
+        }
+        self.armed.store(false, Ordering::SeqCst);
+        match wait_result.wrapping_sub(WAIT_OBJECT_0) {
+            0 => {
+                trace!("Registry watcher stopped");
+                Ok(Some(WatchEvent::Stopped))
+            },
+            1 => {
+                trace!("Registry watcher asked to rearm");
+                Ok(Some(WatchEvent::RearmRequested))
+            },
+            2 => Ok(Some(WatchEvent::Changed)),
+            _ => Err(err::RegistryWatchError(unsafe { GetLastError() }).into()),
+        }
+    }
+
+    pub fn stop(&self) {
+        self.signals.stop();
+    }
+}
+
+impl Drop for RegistryWatcher {
+    fn drop(&mut self) {
+        RUNNING_WATCHERS.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .retain(|signals| !Arc::ptr_eq(&signals.shutdown_event, &self.signals.shutdown_event));
+    }
+}
+
+impl ChangeNotifier {
+    // Wakes the watcher's next or current wait. This method returns false once the watcher is gone.
+    pub fn notify(&self) -> bool {
+        return match self.0.upgrade() {
+            Some(change_event) => {
+                unsafe { SetEvent(change_event.raw()) };
+                true
+            },
+            None => false,
+        };
+    }
+}
+
+impl WatcherSignals {
+    fn stop(&self) {
+        unsafe { SetEvent(self.shutdown_event.raw()) };
+    }
+
+    fn rearm(&self) {
+        unsafe { SetEvent(self.rearm_event.raw()) };
+    }
+}
+
+// Stops every watcher, waking any thread blocked waiting on one so it can finish
+#[instrument]
+pub fn stop_all() {
+    let running_watchers: Vec<WatcherSignals> = std::mem::take(
+        &mut *RUNNING_WATCHERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    );
+    for signals in running_watchers {
+        signals.stop();
+    }
+}
+
+// Asks every watcher to start over, for when change notifications may have been lost,
+// such as while the computer was asleep
+#[instrument]
+pub fn rearm_all() {
+    for signals in RUNNING_WATCHERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).iter() {
+        signals.rearm();
+    }
+}
+
+fn create_event(manual_reset: bool) -> Result<Win32Handle> {
+    let event: Win32Handle = Win32Handle::from_null_on_failure(unsafe {
+        CreateEventW(std::ptr::null(), i32::from(manual_reset), 0, std::ptr::null())
+    }).map_err(err::RegistryWatchError)?;
+    Ok(event)
+}
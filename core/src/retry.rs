@@ -0,0 +1,110 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::{trace, warn};
+use windows_sys::Win32::Foundation::{
+    ERROR_BUSY, ERROR_LOCK_VIOLATION, ERROR_NO_SYSTEM_RESOURCES, ERROR_NOT_ENOUGH_MEMORY, ERROR_SHARING_VIOLATION,
+};
+
+use crate::err;
+
+// How hard to try operations which can fail transiently, such as while Windows is still logging the
+// user on or while Windows Explorer is restarting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    // Total number of tries, including the first
+    pub attempts: u32,
+    pub initial_backoff_millis: u64,
+    pub max_backoff_millis: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy { attempts: 4, initial_backoff_millis: 250, max_backoff_millis: 2000 }
+    }
+}
+
+impl RetryPolicy {
+    // Doubles after every failed attempt, up to the maximum
+    fn backoff_after(&self, failed_attempts: u32) -> Duration {
+        let backoff_millis: u64 = self.initial_backoff_millis
+            .saturating_mul(2u64.saturating_pow(failed_attempts.saturating_sub(1)))
+            .min(self.max_backoff_millis);
+        Duration::from_millis(backoff_millis)
+    }
+}
+
+static POLICY: OnceLock<RetryPolicy> = OnceLock::new();
+
+// Sets the policy every retried operation follows. Operations before this use the default policy.
+pub fn init(policy: RetryPolicy) {
+    if POLICY.set(policy).is_err() {
+        warn!("The retry policy was already set, ignoring {:?}", policy);
+    }
+}
+
+// The policy set through `init()`, or the default one before that
+pub fn policy() -> RetryPolicy {
+    POLICY.get().copied().unwrap_or_default()
+}
+
+// Runs the operation until it succeeds, fails in a way retrying won't fix, or runs out of attempts
+pub fn with_retries<T>(operation: &str, attempt: impl FnMut() -> Result<T>) -> Result<T> {
+    with_retries_following(policy(), operation, attempt)
+}
+
+// Like `with_retries()`, but with a policy of the caller's own, e.g. one which doesn't wait between attempts
+pub fn with_retries_following<T>(
+    policy: RetryPolicy, operation: &str, mut attempt: impl FnMut() -> Result<T>
+) -> Result<T> {
+    let mut failed_attempts: u32 = 0;
+
+    loop {
+        let error: anyhow::Error = match attempt() {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        failed_attempts += 1;
+
+        // permanent failures are passed through untouched so callers can still tell what went wrong
+        if !is_transient(&error) {
+            return Err(error);
+        }
+        if failed_attempts >= policy.attempts {
+            return Err(err::RetriesExhaustedError {
+                operation: String::from(operation),
+                attempts: failed_attempts,
+                source: error.into(),
+            }.into());
+        }
+
+        let backoff: Duration = policy.backoff_after(failed_attempts);
+        trace!("{} failed with {:#}, retrying in {:?}", operation, error, backoff);
+        std::thread::sleep(backoff);
+    }
+}
+
+// Whether an error is likely to go away if the operation is tried again shortly
+fn is_transient(error: &anyhow::Error) -> bool {
+    if error.downcast_ref::<err::ProcessNotFoundError>().is_some()
+        || error.downcast_ref::<err::UnableToRestartWindowsExplorer>().is_some()
+        || error.downcast_ref::<err::ProcessSnapshotError>().is_some() {
+        return true;
+    }
+
+    let io_error: Option<&std::io::Error> = error.downcast_ref::<std::io::Error>()
+        .or_else(|| match error.downcast_ref::<err::RegistryOpsError>() {
+            Some(err::RegistryOpsError::FailedToGetValueData { source, .. }) => Some(source),
+            // the value will hold the same wrong type on the next try
+            Some(err::RegistryOpsError::UnexpectedValueType { .. }) => None,
+            None => None,
+        });
+    match io_error.and_then(|io_error| io_error.raw_os_error()) {
+        Some(code) => [ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION, ERROR_BUSY, ERROR_NO_SYSTEM_RESOURCES, ERROR_NOT_ENOUGH_MEMORY]
+            .contains(&(code as u32)),
+        None => false,
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace, warn};
+use winreg::HKEY;
+use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+use crate::err;
+use crate::i18n::tr;
+use crate::registry::{RegistryProvider, RegistryValue};
+use crate::registry_watcher::RegistryWatcher;
+use crate::windows_ops;
+use crate::windows_ops::{ExplorerRestartPolicy, FixOutcome};
+
+// How much it matters when a check fails. Info is only recorded, while the others also notify the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    // How much a check counts toward the overall score
+    pub fn weight(&self) -> u32 {
+        match self {
+            Severity::Info => 1,
+            Severity::Warning => 2,
+            Severity::Critical => 4,
+        }
+    }
+}
+
+// The rules which ship with this program, embedded into the executable at compile-time
+const BUILT_IN_RULES: &str = include_str!("..\\..\\resources\\rules.toml");
+
+// The rule for HideFileExt itself. Group Policy and other users' hives are handled on top of it elsewhere,
+// so the UI shows it apart from the rest.
+pub const FILE_EXTENSIONS_RULE_ID: &str = "file-extensions";
+
+static PARSED_BUILT_IN_RULES: OnceLock<Vec<RegistryRule>> = OnceLock::new();
+
+// Which registry hive a rule's key lives in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RuleHive {
+    CurrentUser,
+    // needs administrator rights to fix
+    LocalMachine,
+}
+
+impl RuleHive {
+    pub fn predefined_key(&self) -> HKEY {
+        match self {
+            RuleHive::CurrentUser => HKEY_CURRENT_USER,
+            RuleHive::LocalMachine => HKEY_LOCAL_MACHINE,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            RuleHive::CurrentUser => "HKEY_CURRENT_USER",
+            RuleHive::LocalMachine => "HKEY_LOCAL_MACHINE",
+        }
+    }
+}
+
+// What has to happen after a rule is fixed for Windows to act on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RefreshAction {
+    #[default]
+    None,
+    WindowsExplorer,
+}
+
+// A registry value which should hold specific DWORD data. Anything else, including no value at all, breaks the rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryRule {
+    pub id: String,
+    // shown to the user. Built-in rules leave this out and are named in the locale files instead.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub hive: RuleHive,
+    pub key: String,
+    pub value: String,
+    pub desired_data: u32,
+    pub severity: Severity,
+    #[serde(default)]
+    pub refresh: RefreshAction,
+    // only checked once the user turns it on
+    #[serde(default)]
+    pub opt_in: bool,
+    // changes what Windows lets the user do rather than only what it shows, so the user has to confirm the fix
+    #[serde(default)]
+    pub advanced: bool,
+    // the fix can be undone by deleting the value, which gives Windows its default back
+    #[serde(default)]
+    pub undoable: bool,
+    // part of the security baseline which the `harden` command applies
+    #[serde(default)]
+    pub baseline: bool,
+}
+
+// A registry key which at least one check reads values from, along with the names of those values.
+// Windows only says that something under the key changed, so the values are compared against a snapshot
+// to find out which.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WatchedKey {
+    hive: RuleHive,
+    key: String,
+    // lowercase, since value names aren't case-sensitive
+    values: Vec<String>,
+}
+
+// The data of every value a `WatchedKey` names, by lowercase value name. None where the value doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValueSnapshot(BTreeMap<String, Option<RegistryValue>>);
+
+#[derive(Debug, Deserialize)]
+struct RuleSet {
+    rule: Vec<RegistryRule>,
+}
+
+impl RegistryRule {
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => tr(&format!("rule-{}", self.id)),
+        }
+    }
+
+    // The full path of the value, e.g. for showing the user what this program touches
+    pub fn location(&self) -> String {
+        format!("{}\\{}\\{}", self.hive.name(), self.key, self.value)
+    }
+
+    pub fn watched_key(&self) -> WatchedKey {
+        WatchedKey::new(self.hive, &self.key, &[&self.value])
+    }
+
+    #[instrument]
+    pub fn is_broken(&self, registry: &dyn RegistryProvider) -> Result<bool> {
+        Ok(self.current_data(registry)? != Some(self.desired_data))
+    }
+
+    // The data the value holds now, or None when there is no value
+    pub fn current_data(&self, registry: &dyn RegistryProvider) -> Result<Option<u32>> {
+        windows_ops::get_dword_value(registry, self.hive.predefined_key(), &self.key, &self.value)
+    }
+
+    // Writes the desired data without getting Windows to act on it. This method returns whether a change was made.
+    #[instrument]
+    pub fn apply(&self, registry: &dyn RegistryProvider) -> Result<bool> {
+        windows_ops::set_or_update_registry_value(
+            registry, self.hive.predefined_key(), &self.key, &self.value, self.desired_data
+        )
+    }
+
+    // Writes the desired data, then gets Windows to act on it
+    #[instrument]
+    pub fn fix(&self, registry: &dyn RegistryProvider, restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome> {
+        if !self.apply(registry)? {
+            return Ok(FixOutcome::AlreadyVisible);
+        }
+
+        self.refresh(restart_policy)
+    }
+
+    // Deletes the value again, then gets Windows to act on it
+    #[instrument]
+    pub fn undo(&self, registry: &dyn RegistryProvider, restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome> {
+        if !windows_ops::delete_registry_value(registry, self.hive.predefined_key(), &self.key, &self.value)? {
+            return Ok(FixOutcome::AlreadyVisible);
+        }
+        self.refresh(restart_policy)
+    }
+
+    fn refresh(&self, restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome> {
+        return match self.refresh {
+            RefreshAction::WindowsExplorer => windows_ops::make_windows_explorer_pick_up_changes(restart_policy),
+            RefreshAction::None => Ok(FixOutcome::Applied),
+        };
+    }
+}
+
+impl WatchedKey {
+    pub fn new(hive: RuleHive, key: &str, values: &[&str]) -> WatchedKey {
+        let mut watched_key = WatchedKey { hive, key: String::from(key), values: Vec::new() };
+        watched_key.add_values(values.iter().map(|value| value.to_lowercase()));
+        watched_key
+    }
+
+    // Whether both watch the same key, whichever values they read from it
+    pub fn is_same_key(&self, other: &WatchedKey) -> bool {
+        self.hive == other.hive && self.key.eq_ignore_ascii_case(&other.key)
+    }
+
+    // Watches the other's values too. Both have to be for the same key.
+    pub fn merge(&mut self, other: WatchedKey) {
+        self.add_values(other.values.into_iter());
+    }
+
+    // Whether any of the given lowercase value names are among the ones this key is watched for
+    pub fn reads_any(&self, value_names: &[String]) -> bool {
+        value_names.iter().any(|value_name| self.values.contains(value_name))
+    }
+
+    // Reads every value this key is watched for
+    pub fn snapshot(&self, registry: &dyn RegistryProvider) -> Result<ValueSnapshot> {
+        let mut snapshot: BTreeMap<String, Option<RegistryValue>> = BTreeMap::new();
+        for value_name in &self.values {
+            let value: Option<RegistryValue> = registry.get_value(self.hive.predefined_key(), &self.key, value_name)?;
+            snapshot.insert(value_name.clone(), value);
+        }
+        Ok(ValueSnapshot(snapshot))
+    }
+
+    fn add_values(&mut self, values: impl Iterator<Item = String>) {
+        for value in values {
+            if !self.values.contains(&value) {
+                self.values.push(value);
+            }
+        }
+        // sorted, so the same values in another order still make an equal key
+        self.values.sort();
+    }
+
+    pub fn watch(&self, registry: &dyn RegistryProvider) -> Result<RegistryWatcher> {
+        windows_ops::watch_regkey(registry, self.hive.predefined_key(), &self.key)
+    }
+}
+
+// Parses the built-in rules. Must be called before any rule is looked up.
+#[instrument]
+pub fn init() -> Result<()> {
+    let rule_set: RuleSet = toml::from_str(BUILT_IN_RULES).map_err(err::RuleError::InvalidBuiltInRules)?;
+    trace!("Loaded {} built-in rules", rule_set.rule.len());
+    if PARSED_BUILT_IN_RULES.set(rule_set.rule).is_err() {
+        warn!("The built-in rules were already loaded");
+    }
+    Ok(())
+}
+
+impl ValueSnapshot {
+    // The lowercase names of the values whose data differs from an earlier snapshot of the same key
+    pub fn changed_since(&self, earlier: &ValueSnapshot) -> Vec<String> {
+        self.0.iter()
+            .filter(|(value_name, value)| earlier.0.get(*value_name) != Some(*value))
+            .map(|(value_name, _)| value_name.clone())
+            .collect()
+    }
+}
+
+pub fn built_in_rules() -> &'static [RegistryRule] {
+    PARSED_BUILT_IN_RULES.get().map(Vec::as_slice).unwrap_or(&[])
+}
@@ -0,0 +1,86 @@
+use std::mem::ManuallyDrop;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+use windows::core::{ComInterface, PCWSTR, PWSTR};
+use windows::Win32::System::Com::{
+    CLSCTX_LOCAL_SERVER, CoCreateInstance, IDispatch, VARIANT, VARIANT_0, VARIANT_0_0, VARIANT_0_0_0, VT_I4,
+};
+use windows::Win32::UI::Shell::{IShellWindows, IWebBrowser2, PathCreateFromUrlW, ShellWindows};
+
+use crate::com::ComApartment;
+use crate::windows_ops;
+
+// Longest path PathCreateFromUrlW can hand back
+const MAX_FOLDER_PATH_LENGTH: usize = 32768;
+
+// Lists the folders open in Windows Explorer windows, so they can be reopened after Explorer restarts.
+// Windows showing something other than a folder on disk, such as This PC, are skipped.
+#[instrument]
+pub fn open_folder_paths() -> Result<Vec<PathBuf>> {
+    let _com: ComApartment = ComApartment::enter()?;
+
+    let shell_windows: IShellWindows = unsafe { CoCreateInstance(&ShellWindows, None, CLSCTX_LOCAL_SERVER)? };
+    let window_count: i32 = unsafe { shell_windows.Count()? };
+
+    let mut folder_paths: Vec<PathBuf> = Vec::new();
+    for index in 0..window_count {
+        let window: IDispatch = match unsafe { shell_windows.Item(&variant_from_index(index)) } {
+            Ok(window) => window,
+            // windows can close while we're looking at them
+            Err(error) => {
+                trace!("Skipping shell window {}: {:?}", index, error);
+                continue;
+            }
+        };
+        let Ok(browser) = window.cast::<IWebBrowser2>() else {
+            continue;
+        };
+        let location_url: String = unsafe { browser.LocationURL()? }.to_string();
+        match folder_path_from_url(&location_url) {
+            Some(folder_path) => folder_paths.push(folder_path),
+            None => trace!("Not reopening shell window showing {:?}", location_url),
+        }
+    }
+    trace!("Open folders: {:?}", folder_paths);
+    Ok(folder_paths)
+}
+
+// Opens each folder in its own Explorer window. Windows Explorer has to be running again first.
+#[instrument]
+pub fn reopen_folders(folder_paths: &[PathBuf]) {
+    for folder_path in folder_paths {
+        if let Err(error) = std::process::Command::new("explorer.exe").arg(folder_path).spawn() {
+            warn!("Failed to reopen {:?}: {:?}", folder_path, error);
+        }
+    }
+}
+
+// Turns a file:/// URL into a path on disk. Anything else, such as a shell namespace location, yields None.
+fn folder_path_from_url(url: &str) -> Option<PathBuf> {
+    if !url.starts_with("file:") {
+        return None;
+    }
+    let wide_url: Vec<u16> = windows_ops::to_wide_string(url);
+    let mut buffer: Vec<u16> = vec![0; MAX_FOLDER_PATH_LENGTH];
+    let mut length: u32 = buffer.len() as u32;
+    unsafe {
+        PathCreateFromUrlW(PCWSTR(wide_url.as_ptr()), PWSTR(buffer.as_mut_ptr()), &mut length, 0).ok()?;
+    }
+    Some(PathBuf::from(String::from_utf16_lossy(&buffer[..length as usize])))
+}
+
+fn variant_from_index(index: i32) -> VARIANT {
+    VARIANT {
+        Anonymous: VARIANT_0 {
+            Anonymous: ManuallyDrop::new(VARIANT_0_0 {
+                vt: VT_I4,
+                wReserved1: 0,
+                wReserved2: 0,
+                wReserved3: 0,
+                Anonymous: VARIANT_0_0_0 { lVal: index },
+            }),
+        },
+    }
+}
@@ -0,0 +1,193 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+use windows_sys::Win32::Foundation::{ERROR_SUCCESS, PSID};
+use windows_sys::Win32::Security::{LookupAccountSidW, SE_BACKUP_NAME, SE_RESTORE_NAME, SID_NAME_USE};
+use windows_sys::Win32::Security::Authorization::ConvertStringSidToSidW;
+use windows_sys::Win32::System::Environment::ExpandEnvironmentStringsW;
+use windows_sys::Win32::System::Memory::LocalFree;
+use windows_sys::Win32::System::Registry::{RegLoadKeyW, RegUnLoadKeyW};
+use winreg::RegKey;
+use winreg::enums::{HKEY_LOCAL_MACHINE, HKEY_USERS};
+
+use crate::err;
+use crate::registry::WinregRegistry;
+use crate::windows_ops;
+
+// Every real account's SID starts with one of these. Service accounts such as LocalSystem
+// (S-1-5-18) have hives too, but nobody browses files as them.
+const USER_ACCOUNT_SID_PREFIXES: [&str; 2] = [
+    // local and domain accounts
+    "S-1-5-21-",
+    // Azure AD accounts
+    "S-1-12-1-",
+];
+
+// Each hive's classes are loaded as a separate hive named after it
+const CLASSES_HIVE_SUFFIX: &str = "_Classes";
+
+// Where Windows records the profile new accounts are copied from
+const PROFILE_LIST_REGKEY_SUBPATH: &str = "Software\\Microsoft\\Windows NT\\CurrentVersion\\ProfileList";
+const DEFAULT_PROFILE_VALUE_NAME: &str = "Default";
+
+// The registry hive file inside every profile directory
+const USER_HIVE_FILE_NAME: &str = "NTUSER.DAT";
+
+// Where the Default User hive is temporarily mounted under HKEY_USERS
+const DEFAULT_USER_MOUNT_NAME: &str = "NoHiddenExtensionsDefaultUser";
+
+// How applying the setting went for one user
+#[derive(Debug, Clone)]
+pub struct UserHiveResult {
+    pub sid: String,
+    // DOMAIN\name, when the SID can be resolved
+    pub account_name: Option<String>,
+    // whether a change was made, or why it failed
+    pub outcome: Result<bool, String>,
+}
+
+impl UserHiveResult {
+    pub fn display_name(&self) -> &str {
+        self.account_name.as_deref().unwrap_or(self.sid.as_str())
+    }
+}
+
+// Stops hiding file extensions for every user whose hive is loaded under HKEY_USERS, i.e. everyone
+// signed in on this machine. Needs administrator rights. One user failing doesn't stop the others.
+// Explorer only picks up the change for other users the next time they sign in.
+#[instrument]
+pub fn turn_off_file_extension_hiding_for_loaded_users() -> Result<Vec<UserHiveResult>> {
+    let users: RegKey = RegKey::predef(HKEY_USERS);
+
+    let mut results: Vec<UserHiveResult> = Vec::new();
+    for sid in users.enum_keys() {
+        let sid: String = sid?;
+        if !is_user_account_hive(&sid) {
+            trace!("Skipping hive {}", sid);
+            continue;
+        }
+
+        let outcome: Result<bool, String> = windows_ops::turn_off_file_extension_hiding_under(
+            &WinregRegistry, HKEY_USERS, &sid
+        ).map_err(|error| format!("{:#}", error));
+        if let Err(error) = &outcome {
+            warn!("Failed to stop hiding file extensions for {}: {}", sid, error);
+        }
+        results.push(UserHiveResult { account_name: lookup_account_name(&sid), sid, outcome });
+    }
+    Ok(results)
+}
+
+fn is_user_account_hive(sid: &str) -> bool {
+    !sid.ends_with(CLASSES_HIVE_SUFFIX)
+        && USER_ACCOUNT_SID_PREFIXES.iter().any(|prefix| sid.starts_with(prefix))
+}
+
+// Resolves a SID such as S-1-5-21-...-1001 to DOMAIN\name
+fn lookup_account_name(sid: &str) -> Option<String> {
+    let wide_sid: Vec<u16> = windows_ops::to_wide_string(sid);
+    let mut psid: PSID = std::ptr::null_mut();
+    if unsafe { ConvertStringSidToSidW(wide_sid.as_ptr(), &mut psid) } == 0 {
+        return None;
+    }
+
+    let mut name: [u16; 256] = [0; 256];
+    let mut name_length: u32 = name.len() as u32;
+    let mut domain: [u16; 256] = [0; 256];
+    let mut domain_length: u32 = domain.len() as u32;
+    let mut sid_name_use: SID_NAME_USE = 0;
+    let succeeded = unsafe {
+        LookupAccountSidW(
+            std::ptr::null(),
+            psid,
+            name.as_mut_ptr(),
+            &mut name_length,
+            domain.as_mut_ptr(),
+            &mut domain_length,
+            &mut sid_name_use,
+        )
+    };
+    unsafe { LocalFree(psid as isize) };
+
+    match succeeded {
+        0 => None,
+        _ => Some(format!(
+            "{}\\{}",
+            String::from_utf16_lossy(&domain[..domain_length as usize]),
+            String::from_utf16_lossy(&name[..name_length as usize]),
+        ))
+    }
+}
+
+// Stops hiding file extensions in the Default User profile, which every new account on this machine
+// is copied from. Needs administrator rights.
+// This method returns whether a change was made.
+#[instrument]
+pub fn turn_off_file_extension_hiding_for_new_accounts() -> Result<bool> {
+    let hive_file_path: PathBuf = default_user_hive_file_path()?;
+    trace!("Default User hive is at {:?}", hive_file_path);
+
+    // administrators have these privileges, but they are disabled until asked for
+    windows_ops::enable_privilege(SE_BACKUP_NAME)?;
+    windows_ops::enable_privilege(SE_RESTORE_NAME)?;
+
+    let mounted_hive: MountedHive = MountedHive::load(DEFAULT_USER_MOUNT_NAME, &hive_file_path)?;
+    let was_change_made: bool = windows_ops::turn_off_file_extension_hiding_under(
+        &WinregRegistry, HKEY_USERS, mounted_hive.name
+    )?;
+    drop(mounted_hive);
+    Ok(was_change_made)
+}
+
+fn default_user_hive_file_path() -> Result<PathBuf> {
+    let profile_list: RegKey = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(PROFILE_LIST_REGKEY_SUBPATH)?;
+    // usually %SystemDrive%\Users\Default
+    let default_profile_dir: String = profile_list.get_value(DEFAULT_PROFILE_VALUE_NAME)?;
+    Ok(PathBuf::from(expand_environment_strings(&default_profile_dir)?).join(USER_HIVE_FILE_NAME))
+}
+
+fn expand_environment_strings(unexpanded: &str) -> Result<String> {
+    let wide_unexpanded: Vec<u16> = windows_ops::to_wide_string(unexpanded);
+    let mut buffer: Vec<u16> = vec![0; 1024];
+    let length: u32 = unsafe {
+        ExpandEnvironmentStringsW(wide_unexpanded.as_ptr(), buffer.as_mut_ptr(), buffer.len() as u32)
+    };
+    if length == 0 || length as usize > buffer.len() {
+        return Err(err::HiveLoadingError::FailedToExpandPath(String::from(unexpanded)).into());
+    }
+    // the length includes the terminating null
+    Ok(String::from_utf16_lossy(&buffer[..length as usize - 1]))
+}
+
+// A hive file mounted under HKEY_USERS, which is unmounted again when this is dropped.
+// Every handle to keys inside it has to be closed first, or unmounting fails.
+struct MountedHive {
+    name: &'static str,
+}
+
+impl MountedHive {
+    fn load(name: &'static str, hive_file_path: &std::path::Path) -> Result<MountedHive> {
+        let wide_name: Vec<u16> = windows_ops::to_wide_string(name);
+        let wide_path: Vec<u16> = windows_ops::to_wide_string(&hive_file_path.to_string_lossy());
+        let result: u32 = unsafe {
+            RegLoadKeyW(windows_sys::Win32::System::Registry::HKEY_USERS, wide_name.as_ptr(), wide_path.as_ptr())
+        };
+        match result {
+            ERROR_SUCCESS => Ok(MountedHive { name }),
+            error_code => Err(err::HiveLoadingError::FailedToLoad { path: hive_file_path.to_path_buf(), error_code }.into())
+        }
+    }
+}
+
+impl Drop for MountedHive {
+    fn drop(&mut self) {
+        let wide_name: Vec<u16> = windows_ops::to_wide_string(self.name);
+        let result: u32 = unsafe {
+            RegUnLoadKeyW(windows_sys::Win32::System::Registry::HKEY_USERS, wide_name.as_ptr())
+        };
+        if result != ERROR_SUCCESS {
+            warn!("Failed to unload the hive mounted at HKEY_USERS\\{}, error code {}", self.name, result);
+        }
+    }
+}
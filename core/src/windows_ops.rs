@@ -0,0 +1,999 @@
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace, warn};
+use windows_sys::Win32::Foundation::{BOOL, ERROR_NOT_ALL_ASSIGNED, GetLastError, HWND, LPARAM, LUID};
+use windows_sys::Win32::Globalization::{GetUserPreferredUILanguages, MUI_LANGUAGE_NAME};
+use windows_sys::Win32::Security::{
+    AdjustTokenPrivileges, GetTokenInformation, LookupPrivilegeValueW, LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED,
+    TOKEN_ADJUST_PRIVILEGES, TOKEN_ELEVATION, TOKEN_PRIVILEGES, TOKEN_QUERY, TokenElevation,
+};
+use windows_sys::Win32::System::Threading::{
+    GetCurrentThreadId, OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION, QueryFullProcessImageNameW,
+};
+use windows_sys::Win32::UI::Shell::{
+    QUERY_USER_NOTIFICATION_STATE, QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_RUNNING_D3D_FULL_SCREEN, SHCNE_ASSOCCHANGED,
+    SHCNF_FLUSH, SHCNF_IDLIST, SHChangeNotify, SHQueryUserNotificationState, ShellExecuteW,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    EnumThreadWindows, GetClassNameW, HWND_BROADCAST, SendMessageTimeoutW, SMTO_ABORTIFHUNG, SW_SHOWNORMAL,
+    WM_SETTINGCHANGE,
+};
+use winreg::HKEY;
+use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+use crate::err;
+use crate::handle::Win32Handle;
+use crate::process::{ProcessManager, WindowsProcessManager};
+use crate::registry::{RegistryProvider, RegistryValue};
+use crate::registry_watcher::RegistryWatcher;
+use crate::retry;
+use crate::retry::RetryPolicy;
+use crate::shell_windows;
+
+// Path to the registry key containing the value for hiding file extensions.
+pub const WINDOWS_EXPLORER_REGKEY_SUBPATH: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\Advanced";
+
+// The registry value under `WINDOWS_EXPLORER_REGKEY_SUBPATH` responsible for hiding file extensions.
+pub const HIDE_FILE_EXT_VALUE_NAME: &str = "HideFileExt";
+
+// Path to the registry key containing the user's light/dark mode preferences, e.g. `AppsUseLightTheme`.
+const PERSONALIZE_REGKEY_SUBPATH: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize";
+
+// Path to the registry key containing the "Make text bigger" accessibility setting.
+const ACCESSIBILITY_REGKEY_SUBPATH: &str = "Software\\Microsoft\\Accessibility";
+
+// The registry value under `ACCESSIBILITY_REGKEY_SUBPATH` holding the text scale as a percentage.
+const TEXT_SCALE_FACTOR_VALUE_NAME: &str = "TextScaleFactor";
+
+// Path to the registry key for registering applications which should run on Windows startup.
+const WINDOWS_STARTUP_REGKEY_SUBPATH: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+
+// The name of this application as it should be known by the Windows Registry.
+// Let's just use a hardcoded string to avoid multiple of this program from running at once.
+const WINDOWS_STARTUP_VALUE_NAME: &str = "NoHiddenExtensions";
+
+// Passed by every way of starting this program at sign-in, so it knows to wait out the startup delay
+pub const AT_LOGON_FLAG: &str = "--at-logon";
+
+// The registry value this program writes besides those its rules check, as (key path under HKEY_CURRENT_USER, value name)
+pub const STARTUP_REGISTRY_VALUE: (&str, &str) = (WINDOWS_STARTUP_REGKEY_SUBPATH, WINDOWS_STARTUP_VALUE_NAME);
+
+// The window class winit registers for every window it creates, including iced's.
+const WINIT_WINDOW_CLASS_NAME: &str = "Window Class";
+
+// Windows Notification Facility state which holds the active Focus Assist profile.
+// This is undocumented, but it is the only way to read Focus Assist without scraping binary blobs
+// out of the CloudStore registry keys.
+const WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED: u64 = 0x0D83063EA3BF1C75;
+
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtQueryWnfStateData(
+        state_name: *const u64,
+        type_id: *const std::ffi::c_void,
+        explicit_scope: *const std::ffi::c_void,
+        change_stamp: *mut u32,
+        buffer: *mut std::ffi::c_void,
+        buffer_size: *mut u32,
+    ) -> i32;
+}
+
+// Reasons the user shouldn't be interrupted right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoNotDisturbReason {
+    FocusAssist,
+    FullscreenApp,
+    PresentationMode,
+}
+
+// Checks the current session's notification state for fullscreen apps, presentations, and Focus Assist.
+#[instrument]
+pub fn get_do_not_disturb_reason() -> Result<Option<DoNotDisturbReason>> {
+    let mut notification_state: QUERY_USER_NOTIFICATION_STATE = 0;
+    let hresult: i32 = unsafe { SHQueryUserNotificationState(&mut notification_state) };
+    if hresult < 0 {
+        return Err(err::NotificationStateError::FailedToQueryNotificationState(hresult).into());
+    }
+    trace!("User notification state: {}", notification_state);
+
+    match notification_state {
+        QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN => return Ok(Some(DoNotDisturbReason::FullscreenApp)),
+        QUNS_PRESENTATION_MODE => return Ok(Some(DoNotDisturbReason::PresentationMode)),
+        _ => {}
+    }
+
+    return match is_focus_assist_on()? {
+        true => Ok(Some(DoNotDisturbReason::FocusAssist)),
+        false => Ok(None)
+    };
+}
+
+// Reads the active Focus Assist profile: 0 is off, 1 is priority only, and 2 is alarms only.
+fn is_focus_assist_on() -> Result<bool> {
+    let mut profile: u32 = 0;
+    let mut change_stamp: u32 = 0;
+    let mut buffer_size: u32 = std::mem::size_of::<u32>() as u32;
+
+    let status: i32 = unsafe {
+        NtQueryWnfStateData(
+            &WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED,
+            std::ptr::null(),
+            std::ptr::null(),
+            &mut change_stamp,
+            &mut profile as *mut u32 as *mut std::ffi::c_void,
+            &mut buffer_size,
+        )
+    };
+    if status < 0 {
+        return Err(err::NotificationStateError::FailedToQueryFocusAssist(status).into());
+    }
+    trace!("Focus Assist profile: {}", profile);
+    Ok(profile != 0)
+}
+
+// Converts a string into the null-terminated UTF-16 form the wide Win32 APIs expect
+pub fn to_wide_string(string: &str) -> Vec<u16> {
+    OsStr::new(string).encode_wide().chain(std::iter::once(0)).collect()
+}
+
+// Finds the iced window, which iced doesn't expose a handle to. It is the only winit window
+// created by the calling thread, so this must be called from the thread iced runs on.
+#[instrument]
+pub fn find_own_window() -> Option<HWND> {
+    unsafe extern "system" fn check_window(hwnd: HWND, found_hwnd: LPARAM) -> BOOL {
+        let mut class_name: [u16; 256] = [0; 256];
+        let length: i32 = GetClassNameW(hwnd, class_name.as_mut_ptr(), class_name.len() as i32);
+        if String::from_utf16_lossy(&class_name[..length.max(0) as usize]) == WINIT_WINDOW_CLASS_NAME {
+            *(found_hwnd as *mut HWND) = hwnd;
+            // stop enumerating
+            return 0;
+        }
+        1
+    }
+
+    let mut found_hwnd: HWND = 0;
+    unsafe {
+        EnumThreadWindows(GetCurrentThreadId(), Some(check_window), &mut found_hwnd as *mut HWND as LPARAM);
+    }
+    match found_hwnd {
+        0 => None,
+        hwnd => Some(hwnd)
+    }
+}
+
+// Checks whether the currently running program will run on Windows startup.
+// This is sensitive to the executable file being moved.
+#[instrument]
+pub fn will_app_run_at_startup(registry: &dyn RegistryProvider) -> Result<bool> {
+    let Some(reg_value) = get_string_value(
+        registry, HKEY_CURRENT_USER, WINDOWS_STARTUP_REGKEY_SUBPATH, WINDOWS_STARTUP_VALUE_NAME
+    )? else {
+        trace!("Found no windows startup registry value for {WINDOWS_STARTUP_VALUE_NAME}");
+        return Ok(false);
+    };
+    let current_exe_path: PathBuf = std::env::current_exe()?;
+
+    let current_exe_path_str: &str = current_exe_path.to_str()
+        .ok_or_else(|| err::NonUtf8ExecutablePathError)?;
+
+    // make sure the path of the app which runs at startup is actually the path for this app.
+    // Earlier versions stored the bare path.
+    Ok(reg_value == startup_command(current_exe_path_str) || reg_value == current_exe_path_str)
+}
+
+// Reads a DWORD registry value, or None if the key or the value doesn't exist
+#[instrument]
+pub fn get_dword_value(
+    registry: &dyn RegistryProvider, predefined_key: HKEY, subkey_path: &str, value_name: &str
+) -> Result<Option<u32>> {
+    return match registry.get_value(predefined_key, subkey_path, value_name)? {
+        Some(RegistryValue::Dword(value_data)) => Ok(Some(value_data)),
+        Some(_) => Err(unexpected_value_type(subkey_path, value_name)),
+        None => Ok(None),
+    };
+}
+
+// Reads a string registry value, or None if the key or the value doesn't exist.
+// An empty value name reads the key's default value. Environment variables are left unexpanded.
+#[instrument]
+pub fn get_string_value(
+    registry: &dyn RegistryProvider, predefined_key: HKEY, subkey_path: &str, value_name: &str
+) -> Result<Option<String>> {
+    return match registry.get_value(predefined_key, subkey_path, value_name)? {
+        Some(RegistryValue::String(value_data)) => Ok(Some(value_data)),
+        Some(_) => Err(unexpected_value_type(subkey_path, value_name)),
+        None => Ok(None),
+    };
+}
+
+fn unexpected_value_type(subkey_path: &str, value_name: &str) -> Error {
+    err::RegistryOpsError::UnexpectedValueType {
+        key: String::from(subkey_path),
+        value: String::from(value_name),
+    }.into()
+}
+
+// Reads Windows' "Make text bigger" setting as a multiplier, where 1.0 means no extra scaling.
+// The value only exists once the user has changed the setting.
+#[instrument]
+pub fn get_windows_text_scale_factor(registry: &dyn RegistryProvider) -> Result<f64> {
+    return match get_dword_value(
+        registry, HKEY_CURRENT_USER, ACCESSIBILITY_REGKEY_SUBPATH, TEXT_SCALE_FACTOR_VALUE_NAME
+    )? {
+        Some(percent) => Ok(f64::from(percent) / 100.0),
+        None => {
+            trace!("Found no {TEXT_SCALE_FACTOR_VALUE_NAME} value, so text is not scaled");
+            Ok(1.0)
+        },
+    };
+}
+
+// Lists the user's Windows display languages as BCP 47 tags, e.g. "de-DE", most preferred first.
+#[instrument]
+pub fn get_user_preferred_ui_languages() -> Result<Vec<String>> {
+    let mut language_count: u32 = 0;
+    let mut buffer_length: u32 = 0;
+
+    // the first call only asks how big the buffer needs to be
+    let succeeded: BOOL = unsafe {
+        GetUserPreferredUILanguages(MUI_LANGUAGE_NAME, &mut language_count, std::ptr::null_mut(), &mut buffer_length)
+    };
+    if succeeded == 0 {
+        return Err(err::UiLanguageLookupError.into());
+    }
+
+    let mut buffer: Vec<u16> = vec![0; buffer_length as usize];
+    let succeeded: BOOL = unsafe {
+        GetUserPreferredUILanguages(MUI_LANGUAGE_NAME, &mut language_count, buffer.as_mut_ptr(), &mut buffer_length)
+    };
+    if succeeded == 0 {
+        return Err(err::UiLanguageLookupError.into());
+    }
+
+    // the buffer holds null-separated names followed by an extra null
+    let languages: Vec<String> = buffer.split(|character| *character == 0)
+        .filter(|language| !language.is_empty())
+        .map(String::from_utf16_lossy)
+        .collect();
+    trace!("Preferred UI languages: {:?}", languages);
+    Ok(languages)
+}
+
+// Looks up every process with the given name in this program's own session, retrying in case the
+// process is still starting up
+#[instrument]
+pub fn find_process_ids_by_name(processes: &dyn ProcessManager, target_process_name: &str) -> Result<Vec<u32>> {
+    retry::with_retries(&format!("Finding process {target_process_name}"), || {
+        find_process_ids_by_name_once(processes, target_process_name)
+    })
+}
+
+// Other users signed in on the same machine, e.g. over Remote Desktop, run their own copies of
+// processes such as explorer.exe, which must be left alone.
+// Names are compared case-insensitively, since e.g. Explorer shows up as "Explorer.EXE" on some systems.
+// This method fails with `ProcessNotFoundError` rather than returning no process ids.
+fn find_process_ids_by_name_once(processes: &dyn ProcessManager, target_process_name: &str) -> Result<Vec<u32>> {
+    let current_session_id: u32 = processes.current_session_id()?;
+
+    let target_process_name_lowercase: String = target_process_name.to_lowercase();
+    let mut process_ids: Vec<u32> = Vec::new();
+    for process in processes.list_processes()? {
+        trace!("Evaluating process with name: {}", process.name);
+
+        if process.name.to_lowercase() == target_process_name_lowercase {
+            match process.session_id {
+                Some(session_id) if session_id == current_session_id => process_ids.push(process.process_id),
+                session_id => trace!("Skipping process {} in session {:?}", process.process_id, session_id),
+            }
+        }
+    }
+
+    if process_ids.is_empty() {
+        let target_process_name: String = String::from(target_process_name);
+        return Err(err::ProcessNotFoundError(target_process_name).into());
+    }
+    Ok(process_ids)
+}
+
+// The full path of a process's executable. None once the process has exited, or for protected processes.
+pub fn get_process_image_path(process_id: u32) -> Option<String> {
+    let process: Win32Handle = Win32Handle::from_null_on_failure(unsafe {
+        OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id)
+    }).ok()?;
+    let mut buffer: Vec<u16> = vec![0; 1024];
+    let mut length: u32 = buffer.len() as u32;
+    match unsafe { QueryFullProcessImageNameW(process.raw(), PROCESS_NAME_WIN32, buffer.as_mut_ptr(), &mut length) } {
+        0 => None,
+        _ => Some(String::from_utf16_lossy(&buffer[..length as usize])),
+    }
+}
+
+// How long to wait for each top-level window to handle the settings change broadcast
+const SETTING_CHANGE_BROADCAST_TIMEOUT_MILLIS: u32 = 5000;
+
+// How long Windows gets to start Windows Explorer back up before this program starts it itself
+const EXPLORER_RESTART_TIMEOUT: Duration = Duration::from_secs(5);
+
+// How often to check whether Windows Explorer is back while waiting for it
+const EXPLORER_RESTART_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// How long restarting Windows Explorer waits on it, which tests shorten to nothing
+#[derive(Debug, Clone, Copy)]
+struct ExplorerRestartTiming {
+    timeout: Duration,
+    poll_interval: Duration,
+    // for terminating it, which can fail while it's still starting up
+    retry_policy: RetryPolicy,
+}
+
+impl ExplorerRestartTiming {
+    fn standard() -> ExplorerRestartTiming {
+        ExplorerRestartTiming {
+            timeout: EXPLORER_RESTART_TIMEOUT,
+            poll_interval: EXPLORER_RESTART_POLL_INTERVAL,
+            retry_policy: retry::policy(),
+        }
+    }
+}
+
+// Something which keeps turning the setting back on would otherwise have Windows Explorer restarting
+// over and over, so automatic restarts are spaced at least this far apart
+const MIN_TIME_BETWEEN_EXPLORER_RESTARTS: Duration = Duration::from_secs(5 * 60);
+
+static LAST_EXPLORER_RESTART: Mutex<Option<Instant>> = Mutex::new(None);
+
+// How to get Windows Explorer to show the changed setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplorerRestartPolicy {
+    // ask it to refresh, which keeps its windows open, and only restart it if that fails. Whether a refresh
+    // made it re-read the setting can't be told, so the user is offered a restart in case it didn't.
+    RestartIfNeeded,
+    // restart it right away, which is the only way to be sure it re-reads the setting
+    AlwaysRestart,
+    // leave the restart to the user, or to their next sign-in
+    Defer,
+}
+
+// What fixing the setting amounted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixOutcome {
+    // nothing needed changing
+    AlreadyVisible,
+    // Windows Explorer was restarted, so it picked up the change
+    Applied,
+    // Windows Explorer was asked to refresh, which may or may not have been enough
+    Refreshed,
+    // the registry is fixed, but Windows Explorer shows the old setting until it restarts
+    RestartPending,
+    // like `RestartPending`, but because Windows Explorer was restarted only recently, which suggests
+    // something keeps changing the setting back
+    RestartRateLimited,
+}
+
+// Windows Explorer won't pick up registry changes unless it is refreshed or restarted.
+// Refreshing keeps the user's open windows, so restarting is only the fallback unless the policy says otherwise.
+#[instrument]
+pub fn make_windows_explorer_pick_up_changes(restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome> {
+    if restart_policy == ExplorerRestartPolicy::AlwaysRestart {
+        return restart_windows_explorer_unless_too_soon();
+    }
+
+    let error: anyhow::Error = match refresh_windows_explorer() {
+        Ok(()) => return Ok(FixOutcome::Refreshed),
+        Err(error) => error,
+    };
+    match restart_policy {
+        ExplorerRestartPolicy::Defer => {
+            warn!("Failed to refresh Windows Explorer, leaving the restart for later: {:?}", error);
+            Ok(FixOutcome::RestartPending)
+        },
+        _ => {
+            warn!("Failed to refresh Windows Explorer, restarting it instead: {:?}", error);
+            restart_windows_explorer_unless_too_soon()
+        },
+    }
+}
+
+// Something which keeps turning the setting back on would otherwise have Windows Explorer restarting over and over
+fn restart_windows_explorer_unless_too_soon() -> Result<FixOutcome> {
+    if was_explorer_restarted_recently() {
+        warn!("Windows Explorer was restarted too recently to restart it again, refreshing it instead");
+        refresh_windows_explorer_if_possible();
+        return Ok(FixOutcome::RestartRateLimited);
+    }
+    restart_windows_explorer(&WindowsProcessManager)?;
+    Ok(FixOutcome::Applied)
+}
+
+fn refresh_windows_explorer_if_possible() {
+    if let Err(error) = refresh_windows_explorer() {
+        warn!("Failed to refresh Windows Explorer: {:?}", error);
+    }
+}
+
+// Tells every window, including Explorer's, that shell settings changed, then has the shell
+// flush and redraw its views
+fn refresh_windows_explorer() -> Result<()> {
+    let changed_area: Vec<u16> = to_wide_string("ShellState");
+    let mut broadcast_result: usize = 0;
+    let succeeded: isize = unsafe {
+        SendMessageTimeoutW(
+            HWND_BROADCAST,
+            WM_SETTINGCHANGE,
+            0,
+            changed_area.as_ptr() as LPARAM,
+            SMTO_ABORTIFHUNG,
+            SETTING_CHANGE_BROADCAST_TIMEOUT_MILLIS,
+            &mut broadcast_result,
+        )
+    };
+    if succeeded == 0 {
+        return Err(err::ExplorerRefreshError(unsafe { GetLastError() }).into());
+    }
+
+    unsafe {
+        SHChangeNotify(SHCNE_ASSOCCHANGED, SHCNF_IDLIST | SHCNF_FLUSH, std::ptr::null(), std::ptr::null());
+    }
+    trace!("Asked Windows Explorer to refresh");
+    Ok(())
+}
+
+fn was_explorer_restarted_recently() -> bool {
+    let last_explorer_restart: Option<Instant> = *LAST_EXPLORER_RESTART.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    last_explorer_restart.is_some_and(|last_restart| last_restart.elapsed() < MIN_TIME_BETWEEN_EXPLORER_RESTARTS)
+}
+
+// Restart the Windows Explorer process. Open folders are reopened afterward.
+// This always restarts when asked, since the user may be the one asking; only automatic restarts are rate limited.
+pub fn restart_windows_explorer(processes: &dyn ProcessManager) -> Result<()> {
+    // the user's open folders would otherwise disappear along with the process
+    let open_folder_paths: Vec<PathBuf> = shell_windows::open_folder_paths().unwrap_or_else(|error| {
+        warn!("Failed to list open Windows Explorer windows, they won't be reopened: {:?}", error);
+        Vec::new()
+    });
+
+    restart_windows_explorer_processes(processes, ExplorerRestartTiming::standard())?;
+    shell_windows::reopen_folders(&open_folder_paths);
+    Ok(())
+}
+
+// Terminates Windows Explorer and makes sure it comes back, without touching the user's open folders
+fn restart_windows_explorer_processes(processes: &dyn ProcessManager, timing: ExplorerRestartTiming) -> Result<()> {
+    let terminated_process_ids: Vec<u32> = retry::with_retries_following(
+        timing.retry_policy, "Restarting Windows Explorer", || restart_windows_explorer_once(processes)
+    )?;
+    *LAST_EXPLORER_RESTART.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Instant::now());
+    ensure_windows_explorer_is_back(processes, &terminated_process_ids, timing)
+}
+
+// Windows normally starts a new Windows Explorer right after the old one dies, but on some
+// configurations it doesn't, which would leave the user without a taskbar or desktop
+#[instrument]
+fn ensure_windows_explorer_is_back(
+    processes: &dyn ProcessManager, terminated_process_ids: &[u32], timing: ExplorerRestartTiming
+) -> Result<()> {
+    let deadline: Instant = Instant::now() + timing.timeout;
+    // checked at least once, however short the timeout
+    loop {
+        // terminated processes can linger for a moment before they disappear from the snapshot
+        let new_process_id: Option<u32> = find_process_ids_by_name_once(processes, "explorer.exe")
+            .unwrap_or_default()
+            .into_iter()
+            .find(|process_id| !terminated_process_ids.contains(process_id));
+        if let Some(process_id) = new_process_id {
+            trace!("Windows Explorer is back with process id {}", process_id);
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            break;
+        }
+        std::thread::sleep(timing.poll_interval);
+    }
+
+    warn!("Windows Explorer didn't come back within {:?}, starting it", timing.timeout);
+    processes.launch("explorer.exe").map_err(err::ExplorerRelaunchError)?;
+    Ok(())
+}
+
+// Every Windows Explorer process holds on to the old setting, including the separate ones folder
+// windows run in when "Launch folder windows in a separate process" is on, so all of them are terminated.
+// This method returns the ids of the processes which were terminated. It only fails when none were,
+// since retrying after some were would risk terminating the replacement Windows already started.
+fn restart_windows_explorer_once(processes: &dyn ProcessManager) -> Result<Vec<u32>> {
+    let win_explorer_process_ids: Vec<u32> = find_process_ids_by_name_once(processes, "explorer.exe")?;
+    trace!("Windows Explorer process ids: {:?}", win_explorer_process_ids);
+
+    // The most simple and reliable way of restarting Windows Explorer is terminating its process
+    // and letting Windows start another explorer process back up.
+    // Alternatively, we can post a message to the Shell_TrayWnd window, as described here:
+    // https://stackoverflow.com/questions/5689904/gracefully-exit-explorer-programmatically
+    // but then we would be responsible for reliably waiting until explorer.exe is really dead
+    // before starting it back up.
+    let terminated_process_ids: Vec<u32> = win_explorer_process_ids.into_iter()
+        .filter(|win_explorer_process_id| processes.terminate(*win_explorer_process_id))
+        .collect();
+
+    if terminated_process_ids.is_empty() {
+        return Err(err::UnableToRestartWindowsExplorer.into());
+    }
+    Ok(terminated_process_ids)
+}
+
+// Opens Windows Explorer's Folder Options on its View tab, where file extensions can be shown by hand. Control
+// Panel's entry for it is tried when rundll32 can't be started.
+#[instrument]
+pub fn open_folder_options() -> Result<()> {
+    // rundll32 reads its own command line, and wouldn't understand the entry point and tab number once quoted
+    let rundll32_error: std::io::Error = match std::process::Command::new("rundll32.exe")
+        .raw_arg("shell32.dll,Options_RunDLL 7")
+        .spawn()
+    {
+        Ok(_) => return Ok(()),
+        Err(error) => error,
+    };
+    warn!("Failed to start rundll32 for Folder Options, trying Control Panel instead: {:?}", rundll32_error);
+    std::process::Command::new("control.exe")
+        .arg("folders")
+        .spawn()
+        .map_err(err::FolderOptionsError)?;
+    Ok(())
+}
+
+// Registry Editor opens at whichever key it showed last, which it remembers here
+const REGEDIT_LAST_KEY_SUBPATH: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Applets\\Regedit";
+
+// Opens Registry Editor at the key under HKEY_CURRENT_USER which holds HideFileExt. An already running Registry
+// Editor only comes to the front, and stays at whatever key it shows.
+#[instrument]
+pub fn open_in_registry_editor(registry: &dyn RegistryProvider) -> Result<()> {
+    let last_key: String = format!("Computer\\HKEY_CURRENT_USER\\{}", WINDOWS_EXPLORER_REGKEY_SUBPATH);
+    registry.set_value(HKEY_CURRENT_USER, REGEDIT_LAST_KEY_SUBPATH, "LastKey", &RegistryValue::String(last_key))?;
+    // Registry Editor asks for administrator rights, which starting it directly can't do
+    open_with_shell("regedit.exe")
+}
+
+// Opens a program, document or URL the way double-clicking it would.
+// Unlike starting a process directly, this lets Windows ask for administrator rights when the target needs them.
+#[instrument]
+pub fn open_with_shell(target: &str) -> Result<()> {
+    let verb: Vec<u16> = to_wide_string("open");
+    let file: Vec<u16> = to_wide_string(target);
+    let result: isize = unsafe {
+        ShellExecuteW(0, verb.as_ptr(), file.as_ptr(), std::ptr::null(), std::ptr::null(), SW_SHOWNORMAL as i32)
+    };
+
+    // anything at or below 32 is an error code
+    match result {
+        0..=32 => Err(err::ShellOpenError { target: String::from(target), error_code: unsafe { GetLastError() } }.into()),
+        _ => Ok(())
+    }
+}
+
+// Checks whether this process runs with administrator rights, which writing to HKEY_LOCAL_MACHINE needs
+#[instrument]
+pub fn is_running_elevated() -> Result<bool> {
+    let token: Win32Handle = Win32Handle::current_process_token(TOKEN_QUERY).map_err(err::ElevationCheckError)?;
+
+    let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+    let mut returned_length: u32 = 0;
+    let succeeded: BOOL = unsafe {
+        GetTokenInformation(
+            token.raw(),
+            TokenElevation,
+            &mut elevation as *mut TOKEN_ELEVATION as *mut std::ffi::c_void,
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned_length,
+        )
+    };
+
+    match succeeded {
+        0 => Err(err::ElevationCheckError(unsafe { GetLastError() }).into()),
+        _ => Ok(elevation.TokenIsElevated != 0)
+    }
+}
+
+// Turns on a privilege this process holds, such as SE_SECURITY_NAME. Administrators have most privileges,
+// but they are disabled until asked for.
+pub fn enable_privilege(privilege_name: *const u16) -> Result<()> {
+    let token: Win32Handle = Win32Handle::current_process_token(TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY)
+        .map_err(err::PrivilegeError)?;
+
+    let mut luid = LUID { LowPart: 0, HighPart: 0 };
+    if unsafe { LookupPrivilegeValueW(std::ptr::null(), privilege_name, &mut luid) } == 0 {
+        return Err(err::PrivilegeError(unsafe { GetLastError() }).into());
+    }
+
+    let privileges = TOKEN_PRIVILEGES {
+        PrivilegeCount: 1,
+        Privileges: [LUID_AND_ATTRIBUTES { Luid: luid, Attributes: SE_PRIVILEGE_ENABLED }],
+    };
+    let succeeded = unsafe {
+        AdjustTokenPrivileges(token.raw(), 0, &privileges, 0, std::ptr::null_mut(), std::ptr::null_mut())
+    };
+    // AdjustTokenPrivileges succeeds even when the token doesn't hold the privilege at all
+    let last_error: u32 = unsafe { GetLastError() };
+
+    match (succeeded, last_error) {
+        (0, _) | (_, ERROR_NOT_ALL_ASSIGNED) => Err(err::PrivilegeError(last_error).into()),
+        _ => Ok(())
+    }
+}
+
+// Which registry hive a change to the file extension setting is written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettingScope {
+    // HKEY_CURRENT_USER, which only affects the user running this program
+    CurrentUser,
+    // HKEY_LOCAL_MACHINE, the default for every user on the machine. Needs administrator rights.
+    AllUsers,
+}
+
+impl SettingScope {
+    pub fn predefined_key(&self) -> HKEY {
+        match self {
+            SettingScope::CurrentUser => HKEY_CURRENT_USER,
+            SettingScope::AllUsers => HKEY_LOCAL_MACHINE,
+        }
+    }
+}
+
+// Reads the raw HideFileExt value in one hive, or None if that hive doesn't set it
+pub fn get_hide_file_ext_value(registry: &dyn RegistryProvider, scope: SettingScope) -> Result<Option<u32>> {
+    get_dword_value(registry, scope.predefined_key(), WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME)
+}
+
+// Sets HideFileExt in a user hive mounted somewhere other than HKEY_CURRENT_USER, e.g. under HKEY_USERS.
+// Windows Explorer isn't restarted, since it belongs to another user or to nobody yet.
+// This method returns whether a change was made.
+#[instrument]
+pub fn turn_off_file_extension_hiding_under(
+    registry: &dyn RegistryProvider, predefined_key: HKEY, hive_path: &str
+) -> Result<bool> {
+    set_or_update_registry_value(
+        registry,
+        predefined_key,
+        &format!("{hive_path}\\{WINDOWS_EXPLORER_REGKEY_SUBPATH}"),
+        HIDE_FILE_EXT_VALUE_NAME,
+        0u32
+    )
+}
+
+// Updates the current user's registry so that Windows Explorer will not hide file extensions.
+// Note that it is possible for Windows Explorer to be out of sync with the registry.
+#[instrument]
+pub fn turn_off_file_extension_hiding(
+    registry: &dyn RegistryProvider, restart_policy: ExplorerRestartPolicy
+) -> Result<FixOutcome> {
+    turn_off_file_extension_hiding_in(registry, &[SettingScope::CurrentUser], restart_policy)
+}
+
+// Updates the registry in every given hive so that Windows Explorer will not hide file extensions.
+#[instrument]
+pub fn turn_off_file_extension_hiding_in(
+    registry: &dyn RegistryProvider, scopes: &[SettingScope], restart_policy: ExplorerRestartPolicy
+) -> Result<FixOutcome> {
+    let mut was_change_was_made: bool = false;
+    for scope in scopes {
+        was_change_was_made |= set_or_update_registry_value(
+            registry,
+            scope.predefined_key(),
+            WINDOWS_EXPLORER_REGKEY_SUBPATH,
+            HIDE_FILE_EXT_VALUE_NAME,
+            0u32
+        )?;
+    }
+
+    return match was_change_was_made {
+        true => make_windows_explorer_pick_up_changes(restart_policy),
+        false => Ok(FixOutcome::AlreadyVisible),
+    };
+}
+
+// Updates the registry so that the currently running program will run on Windows startup.
+// This method returns whether a change was made.
+// If the executable was moved, the registry value will be updated to reflect
+// the executable's new location.
+#[instrument]
+pub fn run_this_program_at_startup(registry: &dyn RegistryProvider) -> Result<bool> {
+    let current_executable_path: PathBuf = std::env::current_exe()?;
+    run_program_at_startup(registry, &current_executable_path)
+}
+
+// Like `run_this_program_at_startup()`, but for a copy of this program somewhere else
+#[instrument]
+pub fn run_program_at_startup(registry: &dyn RegistryProvider, executable_path: &Path) -> Result<bool> {
+    let executable_path_str: &str = executable_path.to_str()
+        .ok_or(err::NonUtf8ExecutablePathError)?;
+
+    set_or_update_registry_value(
+        registry,
+        HKEY_CURRENT_USER,
+        WINDOWS_STARTUP_REGKEY_SUBPATH,
+        WINDOWS_STARTUP_VALUE_NAME,
+        startup_command(executable_path_str)
+    )
+}
+
+// Deletes the registry value whichever copy of this program it names.
+// This method returns whether a change was made.
+#[instrument]
+pub fn remove_startup_registration(registry: &dyn RegistryProvider) -> Result<bool> {
+    delete_registry_value(registry, HKEY_CURRENT_USER, WINDOWS_STARTUP_REGKEY_SUBPATH, WINDOWS_STARTUP_VALUE_NAME)
+}
+
+// The command line the Run key holds, quoted in case the path has spaces
+fn startup_command(executable_path: &str) -> String {
+    format!("\"{executable_path}\" {AT_LOGON_FLAG}")
+}
+
+// Points the Run key back at this executable when it names one which no longer exists, e.g. because the user
+// moved this program out of Downloads or OneDrive moved their folders. A Run key naming another copy which still
+// exists is left alone. This method returns the path the Run key named before, if it was changed.
+#[instrument]
+pub fn repair_startup_registration(registry: &dyn RegistryProvider) -> Result<Option<String>> {
+    let Some(reg_value) = get_string_value(
+        registry, HKEY_CURRENT_USER, WINDOWS_STARTUP_REGKEY_SUBPATH, WINDOWS_STARTUP_VALUE_NAME
+    )? else {
+        return Ok(None);
+    };
+
+    // either a quoted path followed by arguments, or a bare path from an earlier version
+    let registered_path: &str = match reg_value.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().unwrap_or_default(),
+        None => reg_value.as_str(),
+    };
+    if Path::new(registered_path).exists() {
+        return Ok(None);
+    }
+    trace!("The Run key names {}, which no longer exists", registered_path);
+    run_this_program_at_startup(registry)?;
+    Ok(Some(String::from(registered_path)))
+}
+
+// Deletes the registry value for this program so that it will not run on Windows startup.
+// This method returns whether a change was made.
+#[instrument]
+pub fn dont_run_this_program_at_startup(registry: &dyn RegistryProvider) -> Result<bool> {
+    if !will_app_run_at_startup(registry)? {
+        trace!("Executable already will not run at startup anyway");
+        return Ok(false);
+    }
+
+    delete_registry_value(registry, HKEY_CURRENT_USER, WINDOWS_STARTUP_REGKEY_SUBPATH, WINDOWS_STARTUP_VALUE_NAME)
+}
+
+// If a value with the given name already exists, update the value. Otherwise, create a new one.
+// This method returns whether a change was made. Transient failures are retried.
+pub fn set_or_update_registry_value<V>(
+    registry: &dyn RegistryProvider, predefined_key: HKEY, subkey_path: &str, value_name: &str, desired_value: V
+) -> Result<bool>
+where
+    V: Into<RegistryValue>
+{
+    let desired_value: RegistryValue = desired_value.into();
+    retry::with_retries(&format!("Setting registry value {subkey_path}\\{value_name}"), || {
+        set_or_update_registry_value_once(registry, predefined_key, subkey_path, value_name, &desired_value)
+    })
+}
+
+fn set_or_update_registry_value_once(
+    registry: &dyn RegistryProvider,
+    predefined_key: HKEY,
+    subkey_path: &str,
+    value_name: &str,
+    desired_value: &RegistryValue,
+) -> Result<bool> {
+    // reading and writing in one transaction, so nothing can change the value in between.
+    // The key may not exist yet, e.g. on a freshly created profile, in which case writing creates it.
+    registry.transacted(&mut |registry: &dyn RegistryProvider| {
+        return match registry.get_value(predefined_key, subkey_path, value_name)? {
+            // only change the value if it needs changing
+            Some(current_value) if current_value == *desired_value => {
+                trace!("Existing value found which matched the desired value.");
+                Ok(false)
+            },
+            Some(_) => {
+                trace!("Existing value found which did not match the desired value.");
+                registry.set_value(predefined_key, subkey_path, value_name, desired_value)?;
+                Ok(true)
+            },
+            None => {
+                trace!("No existing value found. Create the new value.");
+                registry.set_value(predefined_key, subkey_path, value_name, desired_value)?;
+                Ok(true)
+            },
+        };
+    })
+}
+
+// Deletes a registry value, which gives whatever reads it its default back.
+// This method returns whether a change was made.
+#[instrument]
+pub fn delete_registry_value(
+    registry: &dyn RegistryProvider, predefined_key: HKEY, subkey_path: &str, value_name: &str
+) -> Result<bool> {
+    registry.delete_value(predefined_key, subkey_path, value_name)
+}
+
+// Deletes a registry key along with everything under it. This method returns whether a change was made.
+#[instrument]
+pub fn delete_regkey(registry: &dyn RegistryProvider, predefined_key: HKEY, subkey_path: &str) -> Result<bool> {
+    registry.delete_key(predefined_key, subkey_path)
+}
+
+// Watches for changes to any value under a registry key, creating the key first if it's missing
+pub fn watch_regkey(
+    registry: &dyn RegistryProvider, predefined_key: HKEY, subkey_path: &str
+) -> Result<RegistryWatcher> {
+    registry.watch_key(predefined_key, subkey_path)
+}
+
+// Watches for changes to any value under the theme personalization registry key,
+// which is where Windows records switches between light and dark mode
+pub fn watch_personalize_regkey(registry: &dyn RegistryProvider) -> Result<RegistryWatcher> {
+    watch_regkey(registry, HKEY_CURRENT_USER, PERSONALIZE_REGKEY_SUBPATH)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    use crate::err;
+    use crate::process::MockProcessManager;
+    use crate::registry::{InMemoryRegistry, RegistryProvider};
+    use crate::retry::RetryPolicy;
+    use crate::windows_ops::{
+        ACCESSIBILITY_REGKEY_SUBPATH, dont_run_this_program_at_startup, ExplorerRestartPolicy, ExplorerRestartTiming,
+        FixOutcome, get_hide_file_ext_value, get_windows_text_scale_factor, HIDE_FILE_EXT_VALUE_NAME,
+        restart_windows_explorer_processes, run_this_program_at_startup, SettingScope, TEXT_SCALE_FACTOR_VALUE_NAME,
+        turn_off_file_extension_hiding_in, will_app_run_at_startup, WINDOWS_EXPLORER_REGKEY_SUBPATH,
+        WINDOWS_STARTUP_REGKEY_SUBPATH, WINDOWS_STARTUP_VALUE_NAME,
+    };
+
+    const CURRENT_SESSION_ID: u32 = 1;
+    const OTHER_SESSION_ID: u32 = 2;
+
+    // Windows Explorer is checked on once, and retries follow each other right away
+    const NO_WAITING: ExplorerRestartTiming = ExplorerRestartTiming {
+        timeout: Duration::ZERO,
+        poll_interval: Duration::ZERO,
+        retry_policy: RetryPolicy { attempts: 4, initial_backoff_millis: 0, max_backoff_millis: 0 },
+    };
+
+    #[test]
+    fn only_explorer_in_this_session_is_restarted() -> Result<()> {
+        let processes = MockProcessManager::new(CURRENT_SESSION_ID)
+            .with_process(10, "Explorer.EXE", CURRENT_SESSION_ID)
+            .with_process(11, "explorer.exe", CURRENT_SESSION_ID)
+            .with_process(20, "explorer.exe", OTHER_SESSION_ID)
+            .with_process(30, "notepad.exe", CURRENT_SESSION_ID)
+            .respawning("explorer.exe");
+
+        restart_windows_explorer_processes(&processes, NO_WAITING)?;
+
+        assert_eq!(processes.terminated_process_ids(), vec![10, 11]);
+        // Windows brought Windows Explorer back by itself
+        assert!(processes.launched_programs().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn restarting_fails_when_explorer_isnt_running() {
+        let processes = MockProcessManager::new(CURRENT_SESSION_ID)
+            .with_process(20, "explorer.exe", OTHER_SESSION_ID);
+
+        let error: anyhow::Error = restart_windows_explorer_processes(&processes, NO_WAITING).unwrap_err();
+
+        assert!(error.chain().any(|cause| cause.is::<err::ProcessNotFoundError>()));
+        assert!(processes.terminated_process_ids().is_empty());
+        assert!(processes.launched_programs().is_empty());
+    }
+
+    #[test]
+    fn restarting_fails_when_explorer_cant_be_terminated() {
+        let processes = MockProcessManager::new(CURRENT_SESSION_ID)
+            .with_process(10, "explorer.exe", CURRENT_SESSION_ID)
+            .protecting(10);
+
+        let error: anyhow::Error = restart_windows_explorer_processes(&processes, NO_WAITING).unwrap_err();
+
+        assert!(error.chain().any(|cause| cause.is::<err::UnableToRestartWindowsExplorer>()));
+        assert!(processes.launched_programs().is_empty());
+    }
+
+    #[test]
+    fn explorer_is_started_when_windows_doesnt_bring_it_back() -> Result<()> {
+        let processes = MockProcessManager::new(CURRENT_SESSION_ID)
+            .with_process(10, "explorer.exe", CURRENT_SESSION_ID);
+
+        restart_windows_explorer_processes(&processes, NO_WAITING)?;
+
+        assert_eq!(processes.terminated_process_ids(), vec![10]);
+        assert_eq!(processes.launched_programs(), vec![String::from("explorer.exe")]);
+        Ok(())
+    }
+
+    #[test]
+    fn fixing_sets_hide_file_ext_to_zero_in_every_scope() -> Result<()> {
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_LOCAL_MACHINE, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 1u32)
+            .with_value(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 1u32);
+
+        // deferring the restart means the real Windows Explorer is at most asked to refresh, never restarted
+        let fix_outcome: FixOutcome = turn_off_file_extension_hiding_in(
+            &registry, &[SettingScope::AllUsers, SettingScope::CurrentUser], ExplorerRestartPolicy::Defer
+        )?;
+
+        assert!(matches!(fix_outcome, FixOutcome::Refreshed | FixOutcome::RestartPending));
+        assert_eq!(get_hide_file_ext_value(&registry, SettingScope::AllUsers)?, Some(0));
+        assert_eq!(get_hide_file_ext_value(&registry, SettingScope::CurrentUser)?, Some(0));
+        Ok(())
+    }
+
+    #[test]
+    fn fixing_creates_the_value_when_its_missing() -> Result<()> {
+        let registry = InMemoryRegistry::new();
+
+        let fix_outcome: FixOutcome = turn_off_file_extension_hiding_in(
+            &registry, &[SettingScope::CurrentUser], ExplorerRestartPolicy::Defer
+        )?;
+
+        assert!(matches!(fix_outcome, FixOutcome::Refreshed | FixOutcome::RestartPending));
+        assert_eq!(get_hide_file_ext_value(&registry, SettingScope::CurrentUser)?, Some(0));
+        assert!(registry.has_key(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH));
+        Ok(())
+    }
+
+    #[test]
+    fn fixing_an_already_visible_setting_changes_nothing() -> Result<()> {
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_CURRENT_USER, WINDOWS_EXPLORER_REGKEY_SUBPATH, HIDE_FILE_EXT_VALUE_NAME, 0u32);
+
+        // Windows Explorer is only restarted after a change, so this policy is never acted on
+        let fix_outcome: FixOutcome = turn_off_file_extension_hiding_in(
+            &registry, &[SettingScope::CurrentUser], ExplorerRestartPolicy::RestartIfNeeded
+        )?;
+
+        assert_eq!(fix_outcome, FixOutcome::AlreadyVisible);
+        assert_eq!(get_hide_file_ext_value(&registry, SettingScope::CurrentUser)?, Some(0));
+        Ok(())
+    }
+
+    #[test]
+    fn text_is_unscaled_until_the_user_changes_the_setting() -> Result<()> {
+        assert_eq!(get_windows_text_scale_factor(&InMemoryRegistry::new())?, 1.0);
+
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_CURRENT_USER, ACCESSIBILITY_REGKEY_SUBPATH, TEXT_SCALE_FACTOR_VALUE_NAME, 150u32);
+        assert_eq!(get_windows_text_scale_factor(&registry)?, 1.5);
+        Ok(())
+    }
+
+    #[test]
+    fn startup_registration_round_trips() -> Result<()> {
+        let registry = InMemoryRegistry::new();
+        assert!(!will_app_run_at_startup(&registry)?);
+
+        assert!(run_this_program_at_startup(&registry)?);
+        assert!(will_app_run_at_startup(&registry)?);
+        // already registered
+        assert!(!run_this_program_at_startup(&registry)?);
+
+        assert!(dont_run_this_program_at_startup(&registry)?);
+        assert!(!will_app_run_at_startup(&registry)?);
+        assert_eq!(
+            registry.get_value(HKEY_CURRENT_USER, WINDOWS_STARTUP_REGKEY_SUBPATH, WINDOWS_STARTUP_VALUE_NAME)?, None
+        );
+        Ok(())
+    }
+}
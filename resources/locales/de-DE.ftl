@@ -0,0 +1,312 @@
+extensions-hidden-warning = Warnung - Dateierweiterungen sind im Windows Explorer ausgeblendet. Dadurch steigt das Risiko, auf einen Phishing-Angriff hereinzufallen.
+extensions-visible-status = Dateierweiterungen sind im Windows Explorer sichtbar, sehr gut! So fallen Sie schwerer auf einen Phishing-Angriff herein.
+stop-hiding-button = Dateierweiterungen einblenden
+stop-hiding-tooltip = Setzt HideFileExt unter HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Explorer\Advanced auf 0 und fordert den Windows-Explorer dann zum Aktualisieren auf, wobei seine Fenster geöffnet bleiben. Lässt er sich nicht aktualisieren, wird er neu gestartet: Die Taskleiste verschwindet kurz, und geöffnete Explorer-Fenster werden geschlossen und wieder geöffnet. Tastenkürzel: Strg+F
+stop-hiding-deferred-restart-tooltip = Setzt HideFileExt unter HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Explorer\Advanced auf 0 und fordert den Windows-Explorer dann zum Aktualisieren auf. Werden Dateierweiterungen weiterhin nicht angezeigt, können Sie ihn neu starten, wann es Ihnen passt. Dabei werden geöffnete Explorer-Fenster kurz geschlossen. Tastenkürzel: Strg+F
+stop-hiding-restart-tooltip = Setzt HideFileExt unter HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Explorer\Advanced auf 0 und startet dann den Windows-Explorer neu, damit er die Änderung anzeigt: Die Taskleiste verschwindet kurz, und geöffnete Explorer-Fenster werden geschlossen und wieder geöffnet. Tastenkürzel: Strg+F
+run-at-startup-checkbox = Beim Windows-Start ausführen
+run-at-startup-run-key-tooltip = Fügt den Wert NoHiddenExtensions unter HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Run hinzu, damit Windows dieses Programm bei Ihrer Anmeldung startet.
+run-at-startup-scheduled-task-tooltip = Legt eine Aufgabe in der Aufgabenplanung an, deren Name mit NoHiddenExtensions beginnt und die dieses Programm bei Ihrer Anmeldung startet.
+startup-method-label = Starten über:
+start-minimized-checkbox = Minimiert im Infobereich starten
+tray-only-checkbox = Nur mit dem Symbol im Infobereich starten und das Fenster erst bei Bedarf öffnen
+startup-method-run-key = Run-Schlüssel in der Registrierung
+startup-method-scheduled-task = Geplante Aufgabe
+startup-task-elevated-checkbox = Als Administrator starten, ohne jedes Mal nachzufragen
+startup-entry-repaired = Diese App wurde von { $path } verschoben. Windows startet sie daher jetzt von ihrem neuen Speicherort.
+show-history-button = Verlauf anzeigen
+hide-history-button = Verlauf ausblenden
+dismiss-button = Schließen
+theme-label = Design
+theme-system = System
+theme-light = Hell
+theme-dark = Dunkel
+text-size-label = Textgröße
+
+history-empty = Bisher ist nichts passiert.
+export-events-button = Ereignisse exportieren …
+export-dialog-title = Änderungen an der Einstellung exportieren
+export-summary = { $count ->
+    [one] 1 Änderung nach { $path } exportiert
+   *[other] { $count } Änderungen nach { $path } exportiert
+}
+history-extensions-hidden = Warnung: Dateierweiterungen wurden ausgeblendet
+history-extensions-hidden-by = Warnung: { $process } hat Dateierweiterungen ausgeblendet
+history-extensions-visible = Dateierweiterungen wurden eingeblendet
+history-remediated-by-user = Das Ausblenden von Dateierweiterungen wurde in dieser App abgeschaltet
+
+quiet-hours-summary = { $count ->
+    [one] Während der Ruhezeit wurden Dateierweiterungen um { $first } ausgeblendet.
+   *[other] Während der Ruhezeit wurden Dateierweiterungen { $count }-mal zwischen { $first } und { $last } ausgeblendet.
+}
+
+tray-warning-tooltip = no-hidden-extensions - Dateierweiterungen sind ausgeblendet!
+
+demo-show-button = Warum ist das wichtig?
+demo-hide-button = Beispiel ausblenden
+demo-explanation = Angreifer geben Programmen Namen, die wie Dokumente aussehen. Welche dieser Dateien würden Sie öffnen?
+demo-hidden-heading = Erweiterungen ausgeblendet
+demo-visible-heading = Erweiterungen sichtbar
+
+about-show-button = Info
+about-hide-button = Info ausblenden
+about-built = Erstellt am { $timestamp }
+about-registry-heading = Diese App liest und schreibt nur diese Registrierungswerte:
+about-license = Lizenziert unter der { $license }-Lizenz
+
+tray-menu-open = Öffnen
+tray-menu-about = Info
+
+settings-button = Einstellungen
+copy-status-button = Status kopieren
+folder-options-button = Ordneroptionen öffnen
+registry-editor-button = Im Registrierungs-Editor öffnen
+status-copied = Kopiert
+settings-back-button = Zurück
+settings-tab-notifications = Benachrichtigungen
+settings-tab-startup = Autostart
+settings-tab-enforcement = Durchsetzung
+settings-tab-advanced = Erweitert
+settings-quiet-hours = Die Ruhezeit dauert von { $start } bis { $end }. Sie lässt sich in config.toml ändern.
+settings-quiet-hours-off = Die Ruhezeit ist aus. Sie lässt sich in config.toml festlegen.
+settings-enforcement-description = Die Windows-Explorer-Einstellung, die Dateinamenerweiterungen ausblendet, wird überwacht. Sobald etwas sie einschaltet, werden Sie gewarnt.
+
+sound-label = Ton
+keep-warning-on-top-checkbox = Die Warnung vor anderen Fenstern halten, solange Dateierweiterungen ausgeblendet sind
+webhook-url-label = Webhook
+webhook-url-placeholder = https://… erhält jede Änderung an der Einstellung
+webhook-url-save-button = Speichern
+webhook-url-invalid = Geben Sie eine Webadresse ein, die mit http:// oder https:// beginnt, oder lassen Sie das Feld leer, um den Webhook auszuschalten.
+sound-silent = Keiner
+sound-default-beep = Standardsignal
+sound-exclamation = Hinweis
+sound-asterisk = Sternchen
+sound-critical-stop = Kritischer Fehler
+sound-notification = Benachrichtigung
+
+retry-button = Wiederholen
+error-check-state = Es konnte nicht geprüft werden, ob Dateinamenerweiterungen ausgeblendet sind.
+error-run-at-startup = Die App konnte nicht zum Windows-Start hinzugefügt werden.
+error-dont-run-at-startup = Die App konnte nicht aus dem Windows-Start entfernt werden.
+error-turn-off-hiding = Dateinamenerweiterungen konnten nicht eingeblendet werden.
+error-watch-for-changes = Änderungen an der Einstellung für Dateinamenerweiterungen können nicht überwacht werden. Neuer Versuch läuft.
+error-register-hotkey = Die Tastenkombination { $hotkey } konnte nicht verwendet werden. Möglicherweise verwendet ein anderes Programm sie bereits. Wählen Sie in der Konfigurationsdatei eine andere.
+error-check-for-updates = Die Suche nach Updates ist fehlgeschlagen. Überprüfen Sie Ihre Internetverbindung.
+error-install-update = Das Update konnte nicht installiert werden. Sie können es stattdessen von der Release-Seite herunterladen.
+error-restore-backup = Die vorherigen Einstellungen konnten nicht wiederhergestellt werden. Prüfen Sie, ob Sie Einstellungen für alle Benutzer dieses Computers ändern dürfen.
+error-undo-last-change = Die letzte Änderung konnte nicht rückgängig gemacht werden.
+error-open-folder-options = Die Ordneroptionen von Windows konnten nicht geöffnet werden.
+error-open-registry-editor = Der Registrierungs-Editor konnte nicht geöffnet werden.
+
+crash-dialog-message = Ein Fehler ist aufgetreten und no-hidden-extensions muss beendet werden. Ein Absturzbericht wurde unter { $path } gespeichert.
+crash-dialog-message-unsaved = Ein Fehler ist aufgetreten und no-hidden-extensions muss beendet werden. Der Absturzbericht konnte nicht gespeichert werden: { $error }
+
+setting-source = Diese Einstellung stammt aus { $source }.
+source-machine-policy = einer Gruppenrichtlinie für diesen Computer
+source-user-policy = einer Gruppenrichtlinie für Ihr Konto
+source-user-preference = Ihren Ordneroptionen
+source-machine-default = der Vorgabe dieses Computers für alle Benutzer
+source-windows-default = der Windows-Vorgabe, da nichts anderes sie festlegt
+managed-by-organization = Diese Einstellung wird von Ihrer Organisation verwaltet und kann hier nicht geändert werden. Bitten Sie Ihre IT-Abteilung, Dateinamenerweiterungen einzublenden.
+
+apply-for-all-users-button = Für alle Benutzer anwenden
+requires-administrator = Erfordert Administratorrechte
+hive-status-current-user = Ihr Konto: { $value }
+hive-status-all-users = Alle Benutzer dieses Computers: { $value }
+hive-value-visible = Erweiterungen sichtbar
+hive-value-hidden = Erweiterungen ausgeblendet
+hive-value-not-set = nicht festgelegt
+error-turn-off-hiding-for-all-users = Dateinamenerweiterungen konnten nicht für alle Benutzer eingeblendet werden.
+retry-as-administrator-button = Als Administrator wiederholen
+
+apply-for-signed-in-users-button = Für alle angemeldeten Benutzer anwenden
+loaded-users-none = Niemand sonst ist angemeldet.
+loaded-user-fixed = behoben
+loaded-user-already-visible = bereits sichtbar
+loaded-user-failed = fehlgeschlagen: { $error }
+error-turn-off-hiding-for-loaded-users = Dateinamenerweiterungen konnten nicht für alle angemeldeten Benutzer eingeblendet werden.
+
+apply-for-new-accounts-button = Für künftig erstellte Konten anwenden
+new-accounts-fixed = Neue Konten zeigen Dateinamenerweiterungen von Anfang an.
+new-accounts-already-visible = Neue Konten zeigen Dateinamenerweiterungen bereits von Anfang an.
+error-turn-off-hiding-for-new-accounts = Die Vorgabe für neue Konten konnte nicht geändert werden.
+error-export-events = Die Änderungen konnten nicht nach { $path } exportiert werden.
+error-turn-on-change-attribution = Die Überwachung von Änderungen an der Einstellung konnte nicht eingeschaltet werden.
+error-turn-off-change-attribution = Die Überwachung von Änderungen an der Einstellung konnte nicht ausgeschaltet werden.
+
+fix-confirmation-message = Windows Explorer übernimmt die Änderung meist sofort, muss aber eventuell neu gestartet werden. Ein Neustart unterbricht laufende Kopiervorgänge, und geöffnete Ordner werden geschlossen und wieder geöffnet.
+fix-confirmation-restart-message = Windows Explorer muss neu gestartet werden, um die Änderung anzuzeigen. Ein Neustart unterbricht laufende Kopiervorgänge, und geöffnete Ordner werden geschlossen und wieder geöffnet.
+dont-ask-again-checkbox = Nicht mehr fragen
+continue-button = Fortfahren
+cancel-button = Abbrechen
+
+defer-explorer-restart-checkbox = Windows Explorer nie automatisch neu starten
+always-restart-explorer-checkbox = Windows Explorer immer neu starten, statt ihn zuerst zum Aktualisieren aufzufordern, für den Fall, dass das Aktualisieren nicht genügt
+attribute-changes-checkbox = Mithilfe der Registrierungsüberwachung von Windows herausfinden, welches Programm Dateierweiterungen ausblendet
+trace-changes-checkbox = Mithilfe der Ereignisablaufverfolgung herausfinden, welches Programm Dateierweiterungen ausblendet, ohne Berechtigungen zu ändern
+explorer-restart-pending = Dateierweiterungen werden sichtbar, sobald Windows Explorer neu gestartet wird.
+explorer-only-refreshed = Windows Explorer wurde zum Aktualisieren aufgefordert. Werden Dateierweiterungen weiterhin nicht angezeigt, starten Sie ihn neu.
+restart-explorer-button = Explorer jetzt neu starten
+tray-menu-restart-explorer = Windows Explorer neu starten
+tray-menu-verbose-logging = Ausführliche Protokollierung
+tray-restart-pending-tooltip = no-hidden-extensions - Windows Explorer muss neu gestartet werden
+tray-paused-tooltip = no-hidden-extensions - pausiert, wird in { $minutes ->
+    [one] 1 Minute
+   *[other] { $minutes } Minuten
+} fortgesetzt
+error-restart-explorer = Windows Explorer konnte nicht neu gestartet werden.
+extensions-hidden-by = { $process } (Prozess { $process_id }) hat sie ausgeblendet.
+setting-keeps-changing-warning = Irgendetwas blendet Dateierweiterungen immer wieder aus. Windows Explorer wurde erst vor wenigen Minuten neu gestartet und wird daher nicht erneut automatisch neu gestartet.
+tray-unavailable-warning = Das Symbol im Infobereich konnte noch nicht hinzugefügt werden, daher bleibt dieses Fenster beim Minimieren in der Taskleiste. Diese App versucht es weiter.
+monitoring-degraded-warning = Die Überwachung auf Änderungen hat kurz nicht funktioniert und musste neu gestartet werden. Zwischenzeitliche Änderungen wurden erneut geprüft, die Überwachung ist aber möglicherweise unzuverlässig.
+
+poll-interval-label = Einstellung zusätzlich prüfen:
+scan-interval-label = Alle Prüfungen von Grund auf durchführen:
+check-for-updates-checkbox = Einmal täglich nach Updates suchen
+check-for-updates-button = Jetzt suchen
+checking-for-updates-button = Wird gesucht…
+verbose-logging-checkbox = Alles protokollieren, zur Fehlersuche
+advanced-mode-checkbox = Erweiterte Aktionen anzeigen, etwa das Öffnen des Registrierungs-Editors
+up-to-date = Sie verwenden die neueste Version.
+latest-backup = Die Einstellungen des Windows Explorers wurden zuletzt am { $time } gesichert.
+no-backup = Es gibt noch keine Sicherung der Einstellungen des Windows Explorers. Sie wird angelegt, bevor diese App sie zum ersten Mal ändert.
+restore-backup-button = Vorherige Werte wiederherstellen
+undo-last-change-button = Letzte Änderung rückgängig machen
+update-available = Version { $version } ist verfügbar.
+update-install-button = Jetzt aktualisieren
+update-installing-button = Wird aktualisiert…
+update-download-button = Release-Seite
+poll-interval-off = Nie
+poll-interval-minutes = Alle { $minutes } Minuten
+scan-interval-off = Nie
+scan-interval-hours = { $hours ->
+    [one] Jede Stunde
+   *[other] Alle { $hours } Stunden
+}
+
+rule-file-extensions = Dateierweiterungen
+rule-hidden-files = Versteckte Dateien
+rule-protected-system-files = Geschützte Systemdateien
+dashboard-score = Sicherheitswert: { $score } %
+status-headline-protected = Sie sind geschützt
+status-detail-protected = Dateierweiterungen werden angezeigt, sodass sich ein Programm nicht als Dokument ausgeben kann.
+status-headline-extensions-hidden = Dateierweiterungen sind ausgeblendet
+status-detail-extensions-hidden = Ein Programm namens rechnung.pdf.exe erscheint als rechnung.pdf. Schalten Sie Dateierweiterungen wieder ein, um zu sehen, was Dateien wirklich sind.
+status-headline-checks-failing = { $count ->
+    [one] 1 Prüfung erfordert Ihre Aufmerksamkeit
+   *[other] { $count } Prüfungen erfordern Ihre Aufmerksamkeit
+}
+status-detail-checks-failing = Dateierweiterungen werden angezeigt, aber andere Einstellungen lassen noch eine Lücke.
+warning-kept-on-top = Dieses Fenster bleibt vor allen anderen, bis Dateierweiterungen wieder angezeigt werden oder Sie die Warnung bestätigen.
+acknowledge-button = Bestätigen
+protection-paused = Der Schutz ist pausiert und wird in { $minutes ->
+    [one] 1 Minute
+   *[other] { $minutes } Minuten
+} fortgesetzt.
+resume-protection-button = Jetzt fortsetzen
+pause-protection-label = Schutz pausieren:
+pause-protection-placeholder = Dauer wählen
+pause-duration-minutes = { $minutes } Minuten
+pause-duration-hours = { $hours ->
+    [one] 1 Stunde
+   *[other] { $hours } Stunden
+}
+stats-no-incidents = Dateierweiterungen wurden nicht wieder ausgeblendet. Geschützt seit { $days ->
+    [one] 1 Tag
+   *[other] { $days } Tagen
+}.
+stats-incidents = { $count ->
+    [one] Einmal wieder ausgeblendet
+   *[other] { $count }-mal wieder ausgeblendet
+}, zuletzt am { $last }. Geschützt seit { $days ->
+    [one] 1 Tag
+   *[other] { $days } Tagen
+}.
+stats-all-users-incidents = { $count ->
+    [one] Einmal davon betraf es die Voreinstellung für alle Benutzer.
+   *[other] { $count }-mal davon betraf es die Voreinstellung für alle Benutzer.
+}
+check-status-pass = OK
+check-status-warn = Handlungsbedarf
+fix-check-button = Beheben
+checks-heading = Durchzuführende Prüfungen:
+history-check-failed = Warnung - Prüfung fehlgeschlagen: { $check }
+history-check-passed = Prüfung bestanden: { $check }
+history-check-remediated-by-user = Von dieser App behoben: { $check }
+error-remediate-check = Konnte nicht behoben werden: { $check }
+rule-windows-script-host = Windows Script Host deaktiviert
+check-confirmation-message = „{ $check }“ zu beheben ändert, was Windows ausführen darf, und manche Installationsprogramme und Anmeldeskripte funktionieren danach eventuell nicht mehr. Sie können es hier rückgängig machen.
+undo-check-button = Rückgängig
+history-check-undone-by-user = Von dieser App rückgängig gemacht: { $check }
+error-undo-check = Konnte nicht rückgängig gemacht werden: { $check }
+
+check-uac = Benutzerkontensteuerung
+check-real-time-protection = Echtzeitschutz des Virenschutzes
+open-settings-button = Einstellungen öffnen
+error-open-check-settings = Die Einstellungen konnten nicht geöffnet werden: { $check }
+
+check-script-associations = Skriptdateien werden sicher geöffnet
+script-associations-show-button = Skriptdateien
+script-associations-hide-button = Skriptdateien ausblenden
+script-associations-explanation = Was beim Doppelklick auf die einzelnen Arten von Skriptdateien passiert. Dateien, die als Skript ausgeführt werden, können alles, was ein Programm kann.
+association-none = Nichts
+association-runs-script = Wird als Skript ausgeführt
+association-safe = Wird nicht ausgeführt
+open-scripts-in-notepad-button = In Editor öffnen
+error-audit-script-associations = Es konnte nicht geprüft werden, womit Skriptdateien geöffnet werden.
+
+disguised-file-warning = Gefahr: „{ $file }“ in Ihrem Downloads-Ordner ist ein Programm, dessen Name etwas anderes vortäuscht. Öffnen Sie es nicht.
+history-disguised-file-found = Gefahr - getarntes Programm heruntergeladen: { $file }
+defuse-button = Entschärfen
+defused-file-status = In „{ $file }“ umbenannt, damit sie als Text geöffnet statt ausgeführt wird.
+history-disguised-file-defused = Umbenannt, damit sie nicht ausgeführt werden kann: { $file }
+history-defused-file-restored = Zurückbenannt: { $file }
+history-startup-entry-repaired = Windows sollte diese App von { $path } starten, wo sie nicht mehr liegt. Windows startet sie jetzt von ihrem neuen Speicherort.
+history-backup-restored = Die Einstellungen des Windows Explorers wurden so wiederhergestellt, wie sie vor der letzten Änderung durch diese App waren.
+history-change-undone = Die letzte Änderung durch diese App wurde rückgängig gemacht.
+history-scan-passed = Laut einer geplanten Überprüfung sind alle Prüfungen bestanden.
+history-scan-failed = { $count ->
+    [one] Eine geplante Überprüfung hat 1 fehlgeschlagene Prüfung gefunden.
+   *[other] Eine geplante Überprüfung hat { $count } fehlgeschlagene Prüfungen gefunden.
+}
+error-defuse-file = Konnte nicht umbenannt werden: { $file }
+error-restore-defused-file = Konnte nicht zurückbenannt werden: { $file }
+
+motw-show-button = Dateiherkunft
+motw-hide-button = Dateiherkunft ausblenden
+motw-explanation = Ziehen Sie eine Datei auf dieses Fenster, um zu sehen, woher sie stammt. Browser und E-Mail-Programme vermerken an gespeicherten Dateien, woher sie sie haben.
+motw-none = Keine Herkunftsmarkierung. Windows behandelt diese Datei, als wäre sie auf diesem Computer erstellt worden.
+motw-zone = Herkunft: { $zone }
+motw-host-url = Heruntergeladen von: { $url }
+motw-referrer-url = Verlinkt von: { $url }
+motw-executable-warning = Dies ist ein Programm aus dem Internet. Öffnen Sie es nur, wenn Sie dem Absender vertrauen.
+zone-local-machine = Dieser Computer
+zone-local-intranet = Lokales Netzwerk
+zone-trusted-sites = Vertrauenswürdige Sites
+zone-internet = Internet
+zone-restricted-sites = Eingeschränkte Sites
+zone-other = Zone { $zone }
+error-inspect-file = Die Herkunft dieser Datei konnte nicht gelesen werden: { $file }
+install-summary = Nach { $path } installiert und von dort gestartet
+uninstall-summary = Deinstalliert. Ihre Einstellungen und der Verlauf wurden beibehalten.
+cleanup-run-key = Autostart-Eintrag
+cleanup-scheduled-task = Geplante Aufgabe
+cleanup-installed-files = Installierte Kopie und Startmenü-Verknüpfung
+cleanup-uri-scheme = Handler für nhe://-Links
+cleanup-event-source = Ereignisprotokoll-Quelle
+cleanup-registry-auditing = Überwachung des Explorer-Einstellungsschlüssels
+cleanup-app-data = Einstellungen, Verlauf und Protokolle
+cleanup-summary = Von diesem Programm ist nichts mehr auf diesem Computer.
+restore-summary = Die Einstellungen des Windows Explorers sind wieder so wie am { $time }.
+undo-summary = Die Änderung nach { $time } wurde rückgängig gemacht.
+undo-nothing = Es gibt keine Änderung, die rückgängig gemacht werden kann.
+harden-column-rule = Regel
+harden-column-before = Vorher
+harden-column-desired = Soll
+harden-column-result = Ergebnis
+harden-no-value = (keiner)
+harden-changed = geändert
+harden-would-change = würde geändert
+harden-already-set = bereits gesetzt
+harden-column-restored = Wiederhergestellt auf
+harden-manifest-written = Um diese Änderungen rückgängig zu machen, führen Sie aus: harden --rollback "{ $path }"
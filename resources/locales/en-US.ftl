@@ -0,0 +1,312 @@
+extensions-hidden-warning = Warning - file extensions are hidden in Windows Explorer. This means a higher risk of falling for a phishing attack.
+extensions-visible-status = File extensions are visible in Windows Explorer, which is great! It is harder for you to fall for a phishing attack.
+stop-hiding-button = Stop hiding file extensions
+stop-hiding-tooltip = Sets HideFileExt to 0 under HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Explorer\Advanced, then asks Windows Explorer to refresh, which keeps its windows open. If it can't be refreshed, it's restarted: the taskbar disappears for a moment, and open File Explorer windows close and open again. Shortcut: Ctrl+F
+stop-hiding-deferred-restart-tooltip = Sets HideFileExt to 0 under HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Explorer\Advanced, then asks Windows Explorer to refresh. If file extensions still don't show, you can restart it when it suits you, which closes open File Explorer windows for a moment. Shortcut: Ctrl+F
+stop-hiding-restart-tooltip = Sets HideFileExt to 0 under HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Explorer\Advanced, then restarts Windows Explorer so it shows the change: the taskbar disappears for a moment, and open File Explorer windows close and open again. Shortcut: Ctrl+F
+run-at-startup-checkbox = Run at Windows startup
+run-at-startup-run-key-tooltip = Adds the value NoHiddenExtensions under HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Run, so Windows starts this program when you sign in.
+run-at-startup-scheduled-task-tooltip = Registers a Task Scheduler task whose name starts with NoHiddenExtensions, and which starts this program when you sign in.
+startup-method-label = Start through:
+start-minimized-checkbox = Start minimized to the tray
+tray-only-checkbox = Start with only the tray icon, and open the window when it's needed
+startup-method-run-key = Run key in the registry
+startup-method-scheduled-task = Scheduled task
+startup-task-elevated-checkbox = Start as administrator, without asking each time
+startup-entry-repaired = This app moved from { $path }, so Windows will now start it from where it is now.
+show-history-button = Show history
+hide-history-button = Hide history
+dismiss-button = Dismiss
+theme-label = Theme
+theme-system = System
+theme-light = Light
+theme-dark = Dark
+text-size-label = Text size
+
+history-empty = Nothing has happened yet.
+export-events-button = Export events…
+export-dialog-title = Export changes to the setting
+export-summary = { $count ->
+    [one] Exported 1 change to { $path }
+   *[other] Exported { $count } changes to { $path }
+}
+history-extensions-hidden = Warning: file extensions became hidden
+history-extensions-hidden-by = Warning: { $process } hid file extensions
+history-extensions-visible = File extensions became visible
+history-remediated-by-user = File extension hiding was turned off from this app
+
+quiet-hours-summary = { $count ->
+    [one] During quiet hours, file extensions became hidden at { $first }.
+   *[other] During quiet hours, file extensions became hidden { $count } times between { $first } and { $last }.
+}
+
+tray-warning-tooltip = no-hidden-extensions - file extensions are hidden!
+
+demo-show-button = Why does this matter?
+demo-hide-button = Hide example
+demo-explanation = Attackers give programs names that look like documents. Which of these files would you open?
+demo-hidden-heading = Extensions hidden
+demo-visible-heading = Extensions visible
+
+about-show-button = About
+about-hide-button = Hide about
+about-built = Built { $timestamp }
+about-registry-heading = This app reads and writes only these registry values:
+about-license = Licensed under the { $license } license
+
+tray-menu-open = Open
+tray-menu-about = About
+
+settings-button = Settings
+copy-status-button = Copy status
+folder-options-button = Open Folder Options
+registry-editor-button = Open in Registry Editor
+status-copied = Copied
+settings-back-button = Back
+settings-tab-notifications = Notifications
+settings-tab-startup = Startup
+settings-tab-enforcement = Enforcement
+settings-tab-advanced = Advanced
+settings-quiet-hours = Quiet hours run from { $start } to { $end }. Change them in config.toml.
+settings-quiet-hours-off = Quiet hours are off. Set them in config.toml.
+settings-enforcement-description = The Windows Explorer setting which hides file extensions is watched for changes. Whenever something turns it on, you will be warned.
+
+sound-label = Sound
+keep-warning-on-top-checkbox = Keep the warning in front of other windows while file extensions are hidden
+webhook-url-label = Webhook
+webhook-url-placeholder = https://… receives every change to the setting
+webhook-url-save-button = Save
+webhook-url-invalid = Enter a web address starting with http:// or https://, or leave the field empty to turn the webhook off.
+sound-silent = None
+sound-default-beep = Default beep
+sound-exclamation = Exclamation
+sound-asterisk = Asterisk
+sound-critical-stop = Critical stop
+sound-notification = Notification
+
+retry-button = Retry
+error-check-state = Could not check whether file extensions are hidden.
+error-run-at-startup = Could not make this app run at Windows startup.
+error-dont-run-at-startup = Could not stop this app from running at Windows startup.
+error-turn-off-hiding = Could not stop hiding file extensions.
+error-watch-for-changes = Could not watch for changes to the file extension setting. Trying again.
+error-register-hotkey = Could not use the hotkey { $hotkey }. Another program may already use it, so choose a different one in the config file.
+error-check-for-updates = Could not check for updates. Check your internet connection.
+error-install-update = Could not install the update. You can download it from the release page instead.
+error-restore-backup = Could not put back the previous settings. Check that you can change settings for everyone on this computer.
+error-undo-last-change = Could not undo the last change.
+error-open-folder-options = Could not open Windows' Folder Options.
+error-open-registry-editor = Could not open Registry Editor.
+
+crash-dialog-message = Something went wrong and no-hidden-extensions has to close. A crash report was saved to { $path }
+crash-dialog-message-unsaved = Something went wrong and no-hidden-extensions has to close. The crash report could not be saved: { $error }
+
+setting-source = This comes from { $source }.
+source-machine-policy = a Group Policy for this computer
+source-user-policy = a Group Policy for your account
+source-user-preference = your Folder Options
+source-machine-default = this computer's default for all users
+source-windows-default = Windows' default, since nothing else sets it
+managed-by-organization = This setting is managed by your organization, so it can't be changed here. Ask your IT department to show file extensions.
+
+apply-for-all-users-button = Apply for all users
+requires-administrator = Requires running as administrator
+hive-status-current-user = Your account: { $value }
+hive-status-all-users = All users on this computer: { $value }
+hive-value-visible = extensions visible
+hive-value-hidden = extensions hidden
+hive-value-not-set = not set
+error-turn-off-hiding-for-all-users = Could not stop hiding file extensions for all users.
+retry-as-administrator-button = Retry as administrator
+
+apply-for-signed-in-users-button = Apply for everyone signed in
+loaded-users-none = Nobody else is signed in.
+loaded-user-fixed = fixed
+loaded-user-already-visible = already visible
+loaded-user-failed = failed: { $error }
+error-turn-off-hiding-for-loaded-users = Could not stop hiding file extensions for everyone signed in.
+
+apply-for-new-accounts-button = Apply for accounts created later
+new-accounts-fixed = New accounts will start with file extensions visible.
+new-accounts-already-visible = New accounts already start with file extensions visible.
+error-turn-off-hiding-for-new-accounts = Could not change the default for new accounts.
+error-export-events = Could not export the changes to { $path }.
+error-turn-on-change-attribution = Could not turn on auditing of changes to the setting.
+error-turn-off-change-attribution = Could not turn off auditing of changes to the setting.
+
+fix-confirmation-message = Windows Explorer usually picks up the change right away, but it may have to restart. A restart interrupts file copies in progress, and open folders are closed and reopened.
+fix-confirmation-restart-message = Windows Explorer has to restart to show the change. A restart interrupts file copies in progress, and open folders are closed and reopened.
+dont-ask-again-checkbox = Don't ask again
+continue-button = Continue
+cancel-button = Cancel
+
+defer-explorer-restart-checkbox = Never restart Windows Explorer automatically
+always-restart-explorer-checkbox = Always restart Windows Explorer instead of first asking it to refresh, for when refreshing doesn't show the change
+attribute-changes-checkbox = Find out which program hides file extensions, using Windows' registry auditing
+trace-changes-checkbox = Find out which program hides file extensions, using event tracing without changing any permissions
+explorer-restart-pending = File extensions will be visible once Windows Explorer restarts.
+explorer-only-refreshed = Windows Explorer was asked to refresh. If file extensions still don't show, restart it.
+restart-explorer-button = Restart Explorer now
+tray-menu-restart-explorer = Restart Windows Explorer
+tray-menu-verbose-logging = Verbose logging
+tray-restart-pending-tooltip = no-hidden-extensions - Windows Explorer needs to restart
+tray-paused-tooltip = no-hidden-extensions - paused, resumes in { $minutes ->
+    [one] 1 minute
+   *[other] { $minutes } minutes
+}
+error-restart-explorer = Could not restart Windows Explorer.
+extensions-hidden-by = { $process } (process { $process_id }) hid them.
+setting-keeps-changing-warning = Something keeps hiding file extensions again. Windows Explorer was restarted only minutes ago, so it won't be restarted again automatically.
+tray-unavailable-warning = The tray icon couldn't be added yet, so minimizing keeps this window on the taskbar. This app keeps trying.
+monitoring-degraded-warning = Watching for changes stopped working for a moment and had to be restarted. Changes made in the meantime were checked again, but monitoring may be unreliable.
+
+poll-interval-label = Double-check the setting:
+scan-interval-label = Scan every check from scratch:
+check-for-updates-checkbox = Check for updates once a day
+check-for-updates-button = Check now
+checking-for-updates-button = Checking…
+verbose-logging-checkbox = Log everything, for troubleshooting
+advanced-mode-checkbox = Show advanced actions, such as opening Registry Editor
+up-to-date = You have the latest version.
+latest-backup = Windows Explorer's settings were last backed up on { $time }.
+no-backup = There is no backup of Windows Explorer's settings yet. One is made before this app first changes them.
+restore-backup-button = Restore previous values
+undo-last-change-button = Undo last change
+update-available = Version { $version } is available.
+update-install-button = Update now
+update-installing-button = Updating…
+update-download-button = Release page
+poll-interval-off = Never
+poll-interval-minutes = Every { $minutes } minutes
+scan-interval-off = Never
+scan-interval-hours = { $hours ->
+    [one] Every hour
+   *[other] Every { $hours } hours
+}
+
+rule-file-extensions = File extensions
+rule-hidden-files = Hidden files
+rule-protected-system-files = Protected operating system files
+dashboard-score = Security score: { $score }%
+status-headline-protected = You're protected
+status-detail-protected = File extensions are shown, so a program can't pass itself off as a document.
+status-headline-extensions-hidden = File extensions are hidden
+status-detail-extensions-hidden = A program named invoice.pdf.exe shows up as invoice.pdf. Turn file extensions back on to see what files really are.
+status-headline-checks-failing = { $count ->
+    [one] 1 check needs attention
+   *[other] { $count } checks need attention
+}
+status-detail-checks-failing = File extensions are shown, but other settings still leave an opening.
+warning-kept-on-top = This window stays in front of the others until file extensions are visible again, or you acknowledge the warning.
+acknowledge-button = Acknowledge
+protection-paused = Protection is paused, and resumes in { $minutes ->
+    [one] 1 minute.
+   *[other] { $minutes } minutes.
+}
+resume-protection-button = Resume now
+pause-protection-label = Pause protection:
+pause-protection-placeholder = Choose how long
+pause-duration-minutes = { $minutes } minutes
+pause-duration-hours = { $hours ->
+    [one] 1 hour
+   *[other] { $hours } hours
+}
+stats-no-incidents = File extensions haven't been hidden again. Protected for { $days ->
+    [one] 1 day
+   *[other] { $days } days
+}.
+stats-incidents = { $count ->
+    [one] Hidden again once
+   *[other] Hidden again { $count } times
+}, last at { $last }. Protected for { $days ->
+    [one] 1 day
+   *[other] { $days } days
+}.
+stats-all-users-incidents = { $count ->
+    [one] One of those times, it was the default for all users.
+   *[other] { $count } of those times, it was the default for all users.
+}
+check-status-pass = OK
+check-status-warn = Needs attention
+fix-check-button = Fix
+checks-heading = Checks to run:
+history-check-failed = Warning - check failed: { $check }
+history-check-passed = Check passed: { $check }
+history-check-remediated-by-user = Fixed from this app: { $check }
+error-remediate-check = Could not fix: { $check }
+rule-windows-script-host = Windows Script Host turned off
+check-confirmation-message = Fixing "{ $check }" changes what Windows lets you run, and some installers and login scripts may stop working. You can undo it from here.
+undo-check-button = Undo
+history-check-undone-by-user = Undone from this app: { $check }
+error-undo-check = Could not undo: { $check }
+
+check-uac = User Account Control
+check-real-time-protection = Antivirus real-time protection
+open-settings-button = Open settings
+error-open-check-settings = Could not open the settings for: { $check }
+
+check-script-associations = Script files open safely
+script-associations-show-button = Script files
+script-associations-hide-button = Hide script files
+script-associations-explanation = What happens when you double-click each kind of script file. Files which run as a script can do anything a program can.
+association-none = Nothing
+association-runs-script = Runs as a script
+association-safe = Does not run
+open-scripts-in-notepad-button = Open them in Notepad
+error-audit-script-associations = Could not check what script files open with.
+
+disguised-file-warning = Danger: "{ $file }" in your Downloads folder is a program whose name is made to look like something else. Don't open it.
+history-disguised-file-found = Danger - disguised program downloaded: { $file }
+defuse-button = Defuse
+defused-file-status = Renamed to "{ $file }", so it opens as text instead of running.
+history-disguised-file-defused = Renamed so it can't run: { $file }
+history-defused-file-restored = Renamed back: { $file }
+history-startup-entry-repaired = Windows was set to start this app from { $path }, where it no longer is. It now starts this app from its new location.
+history-backup-restored = Windows Explorer's settings were put back the way they were before this app last changed them.
+history-change-undone = The last change this app made was undone.
+history-scan-passed = A scheduled scan found every check passing.
+history-scan-failed = { $count ->
+    [one] A scheduled scan found 1 check failing.
+   *[other] A scheduled scan found { $count } checks failing.
+}
+error-defuse-file = Could not rename: { $file }
+error-restore-defused-file = Could not rename back: { $file }
+
+motw-show-button = File origin
+motw-hide-button = Hide file origin
+motw-explanation = Drop a file onto this window to see where it came from. Browsers and mail programs mark the files they save with where they got them.
+motw-none = No mark of the web. Windows treats this file as if it was made on this computer.
+motw-zone = Came from: { $zone }
+motw-host-url = Downloaded from: { $url }
+motw-referrer-url = Linked from: { $url }
+motw-executable-warning = This is a program from the internet. Only open it if you trust whoever sent it.
+zone-local-machine = This computer
+zone-local-intranet = Local network
+zone-trusted-sites = Trusted sites
+zone-internet = The internet
+zone-restricted-sites = Restricted sites
+zone-other = Zone { $zone }
+error-inspect-file = Could not read where this file came from: { $file }
+install-summary = Installed to { $path } and started from there
+uninstall-summary = Uninstalled. Your settings and history were kept.
+cleanup-run-key = Run at startup entry
+cleanup-scheduled-task = Scheduled task
+cleanup-installed-files = Installed copy and Start menu shortcut
+cleanup-uri-scheme = nhe:// link handler
+cleanup-event-source = Event log source
+cleanup-registry-auditing = Auditing of the Explorer settings key
+cleanup-app-data = Settings, history and logs
+cleanup-summary = Nothing from this program is left on this computer.
+restore-summary = Windows Explorer's settings are back the way they were on { $time }.
+undo-summary = Undid the change made after { $time }.
+undo-nothing = There is no change to undo.
+harden-column-rule = Rule
+harden-column-before = Before
+harden-column-desired = Desired
+harden-column-result = Result
+harden-no-value = (none)
+harden-changed = changed
+harden-would-change = would change
+harden-already-set = already set
+harden-column-restored = Restored to
+harden-manifest-written = To undo these changes, run: harden --rollback "{ $path }"
@@ -0,0 +1,219 @@
+use std::cell::RefCell;
+use std::num::NonZeroU128;
+use std::sync::{Mutex, OnceLock};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use accesskit::{
+    Action, ActionHandler, ActionRequest, CheckedState, DefaultActionVerb, Live, Node, NodeBuilder, NodeClassSet,
+    NodeId, Role, Tree, TreeUpdate,
+};
+use accesskit_windows::{HWND, SubclassingAdapter};
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+
+use crate::build_info::APPLICATION_DISPLAY_NAME;
+use crate::err;
+use crate::windows_ops;
+
+// Stable ids for every node in the accessibility tree
+const WINDOW_NODE_ID: NodeId = node_id(1);
+const STATUS_TEXT_NODE_ID: NodeId = node_id(2);
+const FIX_BUTTON_NODE_ID: NodeId = node_id(3);
+const RUN_AT_STARTUP_CHECKBOX_NODE_ID: NodeId = node_id(4);
+const CONFIRMATION_TEXT_NODE_ID: NodeId = node_id(5);
+const CONFIRM_BUTTON_NODE_ID: NodeId = node_id(6);
+const CANCEL_BUTTON_NODE_ID: NodeId = node_id(7);
+
+const fn node_id(id: u128) -> NodeId {
+    match NonZeroU128::new(id) {
+        Some(id) => NodeId(id),
+        None => panic!("Node ids must not be zero"),
+    }
+}
+
+// Controls a screen reader can operate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AccessibleControl {
+    FixButton,
+    RunAtStartupCheckbox,
+    ConfirmButton,
+    CancelButton,
+}
+
+// What the screen reader should be told about the window's current contents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AccessibleView {
+    pub(crate) status_text: String,
+    pub(crate) fix_button_label: String,
+    pub(crate) fix_button_enabled: bool,
+    pub(crate) run_at_startup_label: String,
+    pub(crate) run_at_startup: bool,
+    // shown in place of everything else while the user has to confirm the fix
+    pub(crate) fix_confirmation: Option<AccessibleConfirmation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AccessibleConfirmation {
+    pub(crate) message: String,
+    pub(crate) confirm_label: String,
+    pub(crate) cancel_label: String,
+}
+
+thread_local! {
+    // iced draws everything itself, so Narrator and NVDA see a blank canvas unless we describe
+    // the window through UI Automation. The adapter subclasses iced's window, so it has to live
+    // on the thread which owns that window.
+    static ADAPTER: RefCell<Option<SubclassingAdapter>> = RefCell::new(None);
+}
+
+// Screen readers invoke actions from their own threads, so they are forwarded to the UI over a channel
+static ACTION_CHANNEL: OnceLock<(Sender<AccessibleControl>, Mutex<Receiver<AccessibleControl>>)> = OnceLock::new();
+
+fn action_channel() -> &'static (Sender<AccessibleControl>, Mutex<Receiver<AccessibleControl>>) {
+    ACTION_CHANNEL.get_or_init(|| {
+        let (sender, receiver) = channel();
+        (sender, Mutex::new(receiver))
+    })
+}
+
+struct ActionForwarder;
+
+impl ActionHandler for ActionForwarder {
+    fn do_action(&self, request: ActionRequest) {
+        if request.action != Action::Default {
+            return;
+        }
+        let control: AccessibleControl = match request.target {
+            FIX_BUTTON_NODE_ID => AccessibleControl::FixButton,
+            RUN_AT_STARTUP_CHECKBOX_NODE_ID => AccessibleControl::RunAtStartupCheckbox,
+            CONFIRM_BUTTON_NODE_ID => AccessibleControl::ConfirmButton,
+            CANCEL_BUTTON_NODE_ID => AccessibleControl::CancelButton,
+            _ => return
+        };
+        if let Err(error) = action_channel().0.send(control) {
+            warn!("Failed to forward an accessibility action: {:?}", error);
+        }
+    }
+}
+
+// Publishes the window's contents to assistive technologies, attaching to the window the first time.
+// Must be called from the thread iced runs on.
+#[instrument]
+pub(crate) fn sync(view: &AccessibleView) {
+    ADAPTER.with(|adapter| {
+        let mut adapter = adapter.borrow_mut();
+        let queued_events = match adapter.as_ref() {
+            Some(adapter) => adapter.update_if_active(|| build_tree(view)),
+            None => {
+                let Some(hwnd) = windows_ops::find_own_window() else {
+                    trace!("The window doesn't exist yet");
+                    return;
+                };
+                let initial_view: AccessibleView = view.clone();
+                *adapter = Some(SubclassingAdapter::new(
+                    HWND(hwnd),
+                    move || build_tree(&initial_view),
+                    Box::new(ActionForwarder),
+                ));
+                None
+            }
+        };
+        // events have to be raised without holding any locks the adapter might need
+        drop(adapter);
+        if let Some(queued_events) = queued_events {
+            queued_events.raise();
+        }
+    });
+}
+
+// Blocks until a screen reader activates one of the controls
+pub(crate) fn wait_for_action() -> Result<AccessibleControl> {
+    let receiver = action_channel().1.lock()
+        .map_err(|_| err::AccessibilityError::ActionChannelPoisoned)?;
+    Ok(receiver.recv()?)
+}
+
+fn build_tree(view: &AccessibleView) -> TreeUpdate {
+    let mut classes = NodeClassSet::new();
+    if let Some(fix_confirmation) = &view.fix_confirmation {
+        return build_confirmation_tree(fix_confirmation, &mut classes);
+    }
+
+    let mut window: NodeBuilder = NodeBuilder::new(Role::Window);
+    window.set_name(APPLICATION_DISPLAY_NAME);
+    window.set_children(vec![STATUS_TEXT_NODE_ID, FIX_BUTTON_NODE_ID, RUN_AT_STARTUP_CHECKBOX_NODE_ID]);
+
+    let mut status_text: NodeBuilder = NodeBuilder::new(Role::StaticText);
+    status_text.set_name(view.status_text.as_str());
+    // announce changes in status without the user having to go looking for them
+    status_text.set_live(Live::Polite);
+
+    let mut fix_button: NodeBuilder = NodeBuilder::new(Role::Button);
+    fix_button.set_name(view.fix_button_label.as_str());
+    fix_button.set_default_action_verb(DefaultActionVerb::Click);
+    if view.fix_button_enabled {
+        fix_button.add_action(Action::Default);
+    } else {
+        fix_button.set_disabled();
+    }
+
+    let mut run_at_startup_checkbox: NodeBuilder = NodeBuilder::new(Role::CheckBox);
+    run_at_startup_checkbox.set_name(view.run_at_startup_label.as_str());
+    run_at_startup_checkbox.add_action(Action::Default);
+    match view.run_at_startup {
+        true => {
+            run_at_startup_checkbox.set_checked_state(CheckedState::True);
+            run_at_startup_checkbox.set_default_action_verb(DefaultActionVerb::Uncheck);
+        },
+        false => {
+            run_at_startup_checkbox.set_checked_state(CheckedState::False);
+            run_at_startup_checkbox.set_default_action_verb(DefaultActionVerb::Check);
+        }
+    }
+
+    let nodes: Vec<(NodeId, Node)> = vec![
+        (WINDOW_NODE_ID, window.build(&mut classes)),
+        (STATUS_TEXT_NODE_ID, status_text.build(&mut classes)),
+        (FIX_BUTTON_NODE_ID, fix_button.build(&mut classes)),
+        (RUN_AT_STARTUP_CHECKBOX_NODE_ID, run_at_startup_checkbox.build(&mut classes)),
+    ];
+
+    TreeUpdate {
+        nodes,
+        tree: Some(Tree::new(WINDOW_NODE_ID)),
+        focus: None,
+    }
+}
+
+// The window only shows the confirmation, so that's all the screen reader is told about, with the focus on
+// the button which goes ahead
+fn build_confirmation_tree(fix_confirmation: &AccessibleConfirmation, classes: &mut NodeClassSet) -> TreeUpdate {
+    let mut window: NodeBuilder = NodeBuilder::new(Role::Window);
+    window.set_name(APPLICATION_DISPLAY_NAME);
+    window.set_children(vec![CONFIRMATION_TEXT_NODE_ID, CONFIRM_BUTTON_NODE_ID, CANCEL_BUTTON_NODE_ID]);
+
+    let mut message: NodeBuilder = NodeBuilder::new(Role::StaticText);
+    message.set_name(fix_confirmation.message.as_str());
+    message.set_live(Live::Polite);
+
+    let button = |label: &str| -> NodeBuilder {
+        let mut button: NodeBuilder = NodeBuilder::new(Role::Button);
+        button.set_name(label);
+        button.set_default_action_verb(DefaultActionVerb::Click);
+        button.add_action(Action::Default);
+        button
+    };
+
+    let nodes: Vec<(NodeId, Node)> = vec![
+        (WINDOW_NODE_ID, window.build(classes)),
+        (CONFIRMATION_TEXT_NODE_ID, message.build(classes)),
+        (CONFIRM_BUTTON_NODE_ID, button(&fix_confirmation.confirm_label).build(classes)),
+        (CANCEL_BUTTON_NODE_ID, button(&fix_confirmation.cancel_label).build(classes)),
+    ];
+
+    TreeUpdate {
+        nodes,
+        tree: Some(Tree::new(WINDOW_NODE_ID)),
+        focus: Some(CONFIRM_BUTTON_NODE_ID),
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace, warn};
+use winreg::HKEY;
+use winreg::enums::HKEY_CURRENT_USER;
+
+use crate::err;
+use crate::paths;
+use crate::registry::{RegistryProvider, RegistryValue, WinregRegistry};
+use crate::rules;
+use crate::windows_ops;
+use crate::windows_ops::{
+    HIDE_FILE_EXT_VALUE_NAME, SettingScope, STARTUP_REGISTRY_VALUE, WINDOWS_EXPLORER_REGKEY_SUBPATH,
+};
+
+// Name of the directory under the app data directory which holds one file per backup
+const BACKUP_DIR_NAME: &str = "backups";
+const BACKUP_FILE_PREFIX: &str = "advanced-";
+const BACKUP_FILE_EXTENSION: &str = "json";
+
+// The oldest backups are deleted once there are more than this many
+const MAX_BACKUPS: usize = 20;
+
+// The values under Windows Explorer's Advanced key which this program may write, along with the Run key's entry
+// which starts this program, as they were at one moment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct AdvancedKeyBackup {
+    pub(crate) timestamp: DateTime<Local>,
+    pub(crate) hives: Vec<HiveBackup>,
+    // None in backups written before the Run key was backed up too
+    #[serde(default)]
+    pub(crate) startup_entry: Option<StartupEntryBackup>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct HiveBackup {
+    pub(crate) scope: SettingScope,
+    // data by value name, or None where the value didn't exist
+    pub(crate) values: BTreeMap<String, Option<RegistryValue>>,
+}
+
+// Starting this program through Task Scheduler instead isn't covered
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct StartupEntryBackup {
+    // None where there was no entry
+    pub(crate) command: Option<RegistryValue>,
+}
+
+// Saves what the Advanced key holds in each hive to a new timestamped file, unless the newest backup
+// already holds the same. This method returns the new file's path, if one was written.
+#[instrument]
+pub(crate) fn back_up(scopes: &[SettingScope]) -> Result<Option<PathBuf>> {
+    let hives: Vec<HiveBackup> = scopes.iter()
+        .map(|scope| read_hive(&WinregRegistry, *scope))
+        .collect::<Result<_>>()?;
+    let startup_entry: Option<StartupEntryBackup> = Some(read_startup_entry(&WinregRegistry)?);
+    if latest()?.is_some_and(|latest| latest.hives == hives && latest.startup_entry == startup_entry) {
+        trace!("The newest backup already holds the current values");
+        return Ok(None);
+    }
+
+    let timestamp: DateTime<Local> = Local::now();
+    let path: PathBuf = backup_dir()?.join(format!(
+        "{BACKUP_FILE_PREFIX}{}.{BACKUP_FILE_EXTENSION}", timestamp.format("%Y%m%d-%H%M%S-%3f")
+    ));
+    let contents: String = serde_json::to_string_pretty(&AdvancedKeyBackup { timestamp, hives, startup_entry })?;
+    std::fs::write(&path, contents)
+        .map_err(|error| err::AdvancedKeyBackupError::FailedToWrite { path: path.clone(), source: error })?;
+    trace!("Backed up the Advanced key to {:?}", path);
+
+    delete_old_backups()?;
+    Ok(Some(path))
+}
+
+// Every change goes ahead even when it can't be backed up, since protecting the user comes first
+pub(crate) fn back_up_before_change(scopes: &[SettingScope]) {
+    if let Err(error) = back_up(scopes) {
+        warn!("Failed to back up the Advanced key before changing it: {:?}", error);
+    }
+}
+
+// The newest backup, if there is one
+pub(crate) fn latest() -> Result<Option<AdvancedKeyBackup>> {
+    return match backup_paths()?.pop() {
+        Some(path) => Ok(Some(load(&path)?)),
+        None => Ok(None),
+    };
+}
+
+// Puts every value back the way the newest backup has it, deleting those which didn't exist back then.
+// The caller gets Windows Explorer to pick up the change. This method returns the backup which was restored.
+#[instrument]
+pub(crate) fn restore_latest() -> Result<Option<AdvancedKeyBackup>> {
+    let Some(backup) = latest()? else {
+        trace!("No backup of the Advanced key found, so there is nothing to restore");
+        return Ok(None);
+    };
+
+    restore_hives(&backup)?;
+    Ok(Some(backup))
+}
+
+// Like `restore_latest()`, but also puts back the Run key's entry, and deletes the backup afterwards so that
+// undoing again goes back one more change
+#[instrument]
+pub(crate) fn undo_last_change() -> Result<Option<AdvancedKeyBackup>> {
+    let Some(path) = backup_paths()?.pop() else {
+        trace!("No backup found, so there is no change to undo");
+        return Ok(None);
+    };
+    let backup: AdvancedKeyBackup = load(&path)?;
+
+    restore_hives(&backup)?;
+    if let Some(startup_entry) = &backup.startup_entry {
+        let (subkey_path, value_name): (&str, &str) = STARTUP_REGISTRY_VALUE;
+        restore_value(HKEY_CURRENT_USER, subkey_path, value_name, startup_entry.command.as_ref())?;
+    }
+    std::fs::remove_file(&path)
+        .map_err(|error| err::AdvancedKeyBackupError::FailedToWrite { path: path.clone(), source: error })?;
+    trace!("Undid the change backed up in {:?}", path);
+    Ok(Some(backup))
+}
+
+fn restore_hives(backup: &AdvancedKeyBackup) -> Result<()> {
+    for hive in &backup.hives {
+        for (value_name, value) in &hive.values {
+            restore_value(hive.scope.predefined_key(), WINDOWS_EXPLORER_REGKEY_SUBPATH, value_name, value.as_ref())?;
+        }
+    }
+    Ok(())
+}
+
+// Deletes the value where it didn't exist back then
+fn restore_value(
+    predefined_key: HKEY, subkey_path: &str, value_name: &str, value: Option<&RegistryValue>
+) -> Result<()> {
+    match value {
+        Some(value) => windows_ops::set_or_update_registry_value(
+            &WinregRegistry, predefined_key, subkey_path, value_name, value.clone()
+        )?,
+        None => windows_ops::delete_registry_value(&WinregRegistry, predefined_key, subkey_path, value_name)?,
+    };
+    Ok(())
+}
+
+// HideFileExt, along with whatever the rules check under the same key
+fn managed_value_names() -> Vec<String> {
+    let mut value_names: Vec<String> = vec![String::from(HIDE_FILE_EXT_VALUE_NAME)];
+    for rule in rules::built_in_rules() {
+        if rule.key.eq_ignore_ascii_case(WINDOWS_EXPLORER_REGKEY_SUBPATH)
+            && !value_names.iter().any(|value_name| value_name.eq_ignore_ascii_case(&rule.value)) {
+            value_names.push(rule.value.clone());
+        }
+    }
+    value_names
+}
+
+fn read_hive(registry: &dyn RegistryProvider, scope: SettingScope) -> Result<HiveBackup> {
+    let values: BTreeMap<String, Option<RegistryValue>> = managed_value_names().into_iter()
+        .map(|value_name| {
+            let value: Option<RegistryValue> = registry.get_value(
+                scope.predefined_key(), WINDOWS_EXPLORER_REGKEY_SUBPATH, &value_name
+            )?;
+            Ok((value_name, value))
+        })
+        .collect::<Result<_>>()?;
+    Ok(HiveBackup { scope, values })
+}
+
+fn read_startup_entry(registry: &dyn RegistryProvider) -> Result<StartupEntryBackup> {
+    let (subkey_path, value_name): (&str, &str) = STARTUP_REGISTRY_VALUE;
+    Ok(StartupEntryBackup { command: registry.get_value(HKEY_CURRENT_USER, subkey_path, value_name)? })
+}
+
+fn backup_dir() -> Result<PathBuf> {
+    let backup_dir: PathBuf = paths::app_data_dir()?.join(BACKUP_DIR_NAME);
+    std::fs::create_dir_all(&backup_dir)
+        .map_err(|error| err::AdvancedKeyBackupError::FailedToWrite { path: backup_dir.clone(), source: error })?;
+    Ok(backup_dir)
+}
+
+// Oldest first. The timestamps in the file names sort the same way as the times they stand for.
+fn backup_paths() -> Result<Vec<PathBuf>> {
+    let backup_dir: PathBuf = backup_dir()?;
+    let entries: std::fs::ReadDir = std::fs::read_dir(&backup_dir)
+        .map_err(|error| err::AdvancedKeyBackupError::FailedToRead { path: backup_dir.clone(), source: error })?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_backup_file(path))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+fn is_backup_file(path: &Path) -> bool {
+    let file_name: &str = path.file_name().and_then(|file_name| file_name.to_str()).unwrap_or_default();
+    file_name.starts_with(BACKUP_FILE_PREFIX) && file_name.ends_with(&format!(".{BACKUP_FILE_EXTENSION}"))
+}
+
+fn load(path: &Path) -> Result<AdvancedKeyBackup> {
+    let contents: String = std::fs::read_to_string(path)
+        .map_err(|error| err::AdvancedKeyBackupError::FailedToRead { path: path.to_path_buf(), source: error })?;
+    serde_json::from_str(&contents)
+        .map_err(|error| err::AdvancedKeyBackupError::FailedToParse { path: path.to_path_buf(), source: error }.into())
+}
+
+fn delete_old_backups() -> Result<()> {
+    let paths: Vec<PathBuf> = backup_paths()?;
+    let excess: usize = paths.len().saturating_sub(MAX_BACKUPS);
+    for path in &paths[..excess] {
+        if let Err(error) = std::fs::remove_file(path) {
+            warn!("Failed to delete the old backup {:?}: {:?}", path, error);
+        }
+    }
+    Ok(())
+}
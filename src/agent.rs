@@ -0,0 +1,245 @@
+// with a window, only the tray-only mode uses this module, and only to watch and to answer requests
+#![cfg_attr(feature = "gui", allow(dead_code))]
+
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Local;
+use tracing::{instrument, trace, warn};
+
+use crate::advanced_backup;
+use crate::audit_log;
+use crate::audit_log::AuditEntry;
+use crate::checks;
+use crate::checks::{CheckStatus, SecurityCheck};
+use crate::config::Config;
+use crate::effective_state;
+use crate::effective_state::EffectiveState;
+use crate::ipc;
+use crate::ipc::{IpcCommand, IpcRequest, StatusReport};
+use crate::power;
+use crate::registry::WinregRegistry;
+use crate::registry_watcher;
+use crate::registry_watcher::{RegistryWatcher, WatchEvent};
+use crate::rules::{FILE_EXTENSIONS_RULE_ID, WatchedKey};
+use crate::windows_ops;
+use crate::windows_ops::{ExplorerRestartPolicy, SettingScope};
+
+// How long to wait before watching a key again after watching it failed
+const WATCH_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+// What wakes the agent up to look at the checks again
+#[derive(Debug)]
+pub(crate) enum AgentEvent {
+    ValuesChanged(WatchedKey),
+    // registry notifications may have been lost while the computer slept
+    Resumed,
+    Request(IpcRequest),
+}
+
+// Keeps every active check passing without a window, for builds without the gui feature. Checks which need the user
+// to confirm their fix are only reported in the log, as is everything while protection is paused. This method returns
+// once another process asks it to exit.
+#[instrument(skip(config))]
+pub(crate) fn run(config: &Config) -> Result<()> {
+    let checks: Vec<Arc<dyn SecurityCheck>> = checks::active_checks(config);
+    let (event_sender, event_receiver): (Sender<AgentEvent>, Receiver<AgentEvent>) = channel();
+    for watched_key in checks::watched_keys(&checks) {
+        let event_sender: Sender<AgentEvent> = event_sender.clone();
+        std::thread::Builder::new()
+            .name(String::from("registry watcher"))
+            .spawn(move || watch(watched_key, event_sender))?;
+    }
+    let resume_sender: Sender<AgentEvent> = event_sender.clone();
+    std::thread::Builder::new()
+        .name(String::from("resume listener"))
+        .spawn(move || forward_resumes(resume_sender))?;
+    std::thread::Builder::new()
+        .name(String::from("ipc listener"))
+        .spawn(move || forward_requests(event_sender))?;
+
+    let mut failing_checks: Vec<String> = enforce_unless_paused(config, &checks);
+    loop {
+        let received: Result<AgentEvent, RecvTimeoutError> = match next_wake_up(config) {
+            Some(wait) => event_receiver.recv_timeout(wait),
+            None => event_receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(AgentEvent::ValuesChanged(watched_key)) => trace!("Received a change in {:?}", watched_key),
+            Ok(AgentEvent::Resumed) => registry_watcher::rearm_all(),
+            Ok(AgentEvent::Request(request)) if request.command == IpcCommand::Exit => {
+                request.respond(&status_report(&failing_checks));
+                return Ok(());
+            },
+            // asking for a fix is explicit enough to fix even while protection is paused
+            Ok(AgentEvent::Request(request)) if request.command == IpcCommand::Fix => {
+                failing_checks = enforce(&checks);
+                request.respond(&status_report(&failing_checks));
+                continue;
+            },
+            // there's no window to show, so every other command is answered with where things stand after a fix
+            Ok(AgentEvent::Request(request)) => {
+                failing_checks = enforce_unless_paused(config, &checks);
+                request.respond(&status_report(&failing_checks));
+                continue;
+            },
+            Err(RecvTimeoutError::Timeout) => trace!("Re-reading the settings, since a poll or the pause's end is due"),
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+        failing_checks = enforce_unless_paused(config, &checks);
+    }
+}
+
+// Sends the key whenever something under it changes, until the watcher is stopped
+pub(crate) fn watch(watched_key: WatchedKey, event_sender: Sender<AgentEvent>) {
+    let mut watcher: Option<RegistryWatcher> = None;
+    loop {
+        let watch_result: Result<(RegistryWatcher, WatchEvent)> = watcher.take()
+            .map_or_else(|| watched_key.watch(&WinregRegistry), Ok)
+            .and_then(|watcher| watcher.wait_for_change().map(|watch_event| (watcher, watch_event)));
+        match watch_result {
+            Ok((_, WatchEvent::Stopped)) => return,
+            // a fresh watcher is opened on the next pass
+            Ok((_, WatchEvent::RearmRequested)) => {},
+            Ok((kept_watcher, WatchEvent::Changed)) => watcher = Some(kept_watcher),
+            Err(error) => {
+                warn!("Failed to wait for a change in {:?}: {:?}", watched_key, error);
+                std::thread::sleep(WATCH_RETRY_DELAY);
+                continue;
+            },
+        }
+        if event_sender.send(AgentEvent::ValuesChanged(watched_key.clone())).is_err() {
+            return;
+        }
+    }
+}
+
+fn forward_resumes(event_sender: Sender<AgentEvent>) {
+    while power::wait_for_resume().is_ok() {
+        if event_sender.send(AgentEvent::Resumed).is_err() {
+            return;
+        }
+    }
+}
+
+fn forward_requests(event_sender: Sender<AgentEvent>) {
+    loop {
+        match ipc::wait_for_request() {
+            Ok(request) => if event_sender.send(AgentEvent::Request(request)).is_err() {
+                return;
+            },
+            Err(error) => {
+                warn!("Stopped answering requests from other programs: {:?}", error);
+                return;
+            },
+        }
+    }
+}
+
+// How long to wait for an event before looking at the checks anyway: until the next poll, or until the pause is over
+// so protection resumes right away, whichever comes first
+fn next_wake_up(config: &Config) -> Option<Duration> {
+    let pause_left: Option<Duration> = config.paused_until
+        .and_then(|paused_until| (paused_until - Local::now()).to_std().ok());
+    match (config.poll_interval.duration(), pause_left) {
+        (Some(poll_interval), Some(pause_left)) => Some(poll_interval.min(pause_left)),
+        (poll_interval, pause_left) => poll_interval.or(pause_left),
+    }
+}
+
+fn enforce_unless_paused(config: &Config, checks: &[Arc<dyn SecurityCheck>]) -> Vec<String> {
+    match config.protection_paused(Local::now()) {
+        true => {
+            trace!("Not fixing anything while protection is paused");
+            failing_checks_of(checks)
+        },
+        false => enforce(checks),
+    }
+}
+
+// The ids of the checks which currently fail, or can't be evaluated
+pub(crate) fn failing_checks_of(checks: &[Arc<dyn SecurityCheck>]) -> Vec<String> {
+    checks.iter()
+        .filter(|check| match check.evaluate() {
+            Ok(status) => status == CheckStatus::Warn,
+            Err(error) => {
+                warn!("Failed to evaluate {}: {:?}", check.id(), error);
+                true
+            },
+        })
+        .map(|check| String::from(check.id()))
+        .collect()
+}
+
+// Fixes every failing check which can be fixed without asking. This method returns the ids of those still failing.
+fn enforce(checks: &[Arc<dyn SecurityCheck>]) -> Vec<String> {
+    let mut failing_checks: Vec<String> = Vec::new();
+    for check in checks {
+        let fixed: bool = match check.evaluate() {
+            Ok(CheckStatus::Pass) => true,
+            Ok(CheckStatus::Warn) if !check.can_remediate() || check.needs_confirmation() => {
+                warn!("{} fails, and needs the user to fix it", check.id());
+                false
+            },
+            Ok(CheckStatus::Warn) => remediate(check.as_ref()),
+            Err(error) => {
+                warn!("Failed to evaluate {}: {:?}", check.id(), error);
+                false
+            },
+        };
+        if !fixed {
+            failing_checks.push(String::from(check.id()));
+        }
+    }
+    failing_checks
+}
+
+fn remediate(check: &dyn SecurityCheck) -> bool {
+    let old_value: Option<u32> = hide_file_ext_value();
+    advanced_backup::back_up_before_change(&[SettingScope::CurrentUser]);
+    if let Err(error) = check.remediate(ExplorerRestartPolicy::RestartIfNeeded) {
+        warn!("Failed to fix {}: {:?}", check.id(), error);
+        return false;
+    }
+    trace!("Fixed {}", check.id());
+
+    // no window is watching to record the change in the audit log
+    let new_value: Option<u32> = hide_file_ext_value();
+    if check.id() == FILE_EXTENSIONS_RULE_ID && old_value != new_value {
+        let entry: AuditEntry = AuditEntry::new(SettingScope::CurrentUser, old_value, new_value, true);
+        if let Err(error) = audit_log::record(&entry) {
+            warn!("Failed to add the change to HideFileExt to the audit log: {:?}", error);
+        }
+    }
+    true
+}
+
+fn hide_file_ext_value() -> Option<u32> {
+    windows_ops::get_hide_file_ext_value(&WinregRegistry, SettingScope::CurrentUser).unwrap_or_else(|error| {
+        warn!("Failed to read HideFileExt: {:?}", error);
+        None
+    })
+}
+
+// Windows Explorer is restarted as part of every fix, so no restart is ever left pending
+pub(crate) fn status_report(failing_checks: &[String]) -> StatusReport {
+    let (effective_state, error): (Option<EffectiveState>, Option<String>) =
+        match effective_state::file_extensions_hidden(&WinregRegistry) {
+            Ok(effective_state) => (Some(effective_state), None),
+            Err(error) => (None, Some(format!("{:#}", error))),
+        };
+    StatusReport {
+        file_extensions_hidden: effective_state.is_some_and(|effective_state| effective_state.file_extensions_hidden),
+        setting_source: effective_state
+            .map(|effective_state| format!("{:?}", effective_state.source))
+            .unwrap_or_default(),
+        explorer_restart_pending: false,
+        failing_checks: failing_checks.iter()
+            .filter(|check_id| *check_id != FILE_EXTENSIONS_RULE_ID)
+            .cloned()
+            .collect(),
+        error,
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use tracing::{instrument, trace};
+
+use crate::checks::{CheckStatus, SecurityCheck, Severity};
+use crate::err;
+use crate::i18n::tr;
+use crate::windows_ops::{ExplorerRestartPolicy, FixOutcome};
+use crate::wmi;
+use crate::wmi::WmiObject;
+
+// Where Windows Security Center lists the antivirus products it knows about, Microsoft Defender included
+const SECURITY_CENTER_NAMESPACE: &str = "root\\SecurityCenter2";
+
+const ANTIVIRUS_PRODUCT_QUERY: &str = "SELECT displayName, productState FROM AntiVirusProduct";
+
+// Bit flags describing a product's state. This bit is set while it scans files as they're opened.
+const PRODUCT_STATE_REAL_TIME_PROTECTION_ON: i64 = 0x1000;
+
+// Windows Security settings, where real-time protection can be turned back on
+const WINDOWS_SECURITY_SETTINGS_URI: &str = "windowsdefender://threatsettings";
+
+// Warns when no antivirus product scans files as they're opened, which is what would otherwise catch
+// a malicious attachment the moment the user double-clicks it. Any product will do, not just Defender.
+#[derive(Debug)]
+pub(crate) struct RealTimeProtectionCheck;
+
+impl SecurityCheck for RealTimeProtectionCheck {
+    fn id(&self) -> &str {
+        "real-time-protection"
+    }
+
+    fn name(&self) -> String {
+        tr("check-real-time-protection")
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Critical
+    }
+
+    #[instrument]
+    fn evaluate(&self) -> Result<CheckStatus> {
+        let products: Vec<WmiObject> = wmi::query(
+            SECURITY_CENTER_NAMESPACE, ANTIVIRUS_PRODUCT_QUERY, &["displayName", "productState"]
+        )?;
+        for product in &products {
+            trace!("{:?} has product state {:?}", product.string("displayName"), product.integer("productState"));
+        }
+
+        let is_any_protecting: bool = products.iter()
+            .filter_map(|product| product.integer("productState"))
+            .any(|product_state| product_state & PRODUCT_STATE_REAL_TIME_PROTECTION_ON != 0);
+        return match is_any_protecting {
+            true => Ok(CheckStatus::Pass),
+            false => Ok(CheckStatus::Warn),
+        };
+    }
+
+    // antivirus products guard their settings against other programs, so the user has to do this
+    fn can_remediate(&self) -> bool {
+        false
+    }
+
+    fn remediate(&self, _restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome> {
+        Err(err::CheckError::CannotRemediate(String::from(self.id())).into())
+    }
+
+    fn settings_program(&self) -> Option<&'static str> {
+        Some(WINDOWS_SECURITY_SETTINGS_URI)
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace};
+use winreg::enums::{HKEY_CLASSES_ROOT, HKEY_CURRENT_USER};
+
+use crate::checks::{CheckStatus, SecurityCheck, Severity};
+use crate::err;
+use crate::i18n::tr;
+use crate::paths;
+use crate::registry::WinregRegistry;
+use crate::windows_ops;
+use crate::windows_ops::{ExplorerRestartPolicy, FixOutcome};
+
+// Extensions which Windows runs as scripts out of the box. Attackers mail them around since they do
+// whatever a program could, while looking like a harmless text file to most people.
+pub(crate) const SCRIPT_EXTENSIONS: [&str; 7] = [".js", ".jse", ".vbs", ".vbe", ".wsf", ".wsh", ".hta"];
+
+// Programs which run a script file handed to them, compared against the executable in an open command
+const SCRIPT_HOSTS: [&str; 3] = ["wscript.exe", "cscript.exe", "mshta.exe"];
+
+// Path under HKEY_CURRENT_USER to the keys holding Windows Explorer's per-extension choices
+const FILE_EXTS_REGKEY_SUBPATH: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\FileExts";
+
+// The value under an extension's UserChoice key naming the ProgID the user picked in "Open with"
+const USER_CHOICE_PROG_ID_VALUE_NAME: &str = "ProgId";
+
+// Path under HKEY_CURRENT_USER to the user's own class registrations, which win over the machine's
+const USER_CLASSES_REGKEY_SUBPATH: &str = "Software\\Classes";
+
+// ProgID which ships with Windows and opens files in Notepad
+const NOTEPAD_PROG_ID: &str = "txtfile";
+
+// Name of the file under the app data directory which holds the associations from before the fix
+const ASSOCIATION_BACKUP_FILE_NAME: &str = "association-backup.json";
+
+// Verb Windows falls back on when a ProgID doesn't name a default one
+const DEFAULT_VERB: &str = "open";
+
+// Where the ProgID which an extension opens with came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AssociationSource {
+    // the user picked it through "Open with", which wins over everything else
+    UserChoice,
+    // registered for the extension under HKEY_CLASSES_ROOT, by Windows or by an installer
+    ClassesRoot,
+}
+
+// What double-clicking a file with the extension does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Association {
+    pub(crate) extension: String,
+    // None if nothing is registered, in which case Windows asks the user what to open the file with
+    pub(crate) prog_id: Option<String>,
+    pub(crate) source: AssociationSource,
+    // the command line of the ProgID's default verb, with %1 standing for the file
+    pub(crate) command: Option<String>,
+}
+
+impl Association {
+    // Whether double-clicking a file runs it as a script
+    pub(crate) fn launches_script_host(&self) -> bool {
+        let Some(command) = &self.command else {
+            return false;
+        };
+        let command: String = command.to_lowercase();
+        SCRIPT_HOSTS.iter().any(|script_host| command.contains(script_host))
+    }
+}
+
+// Looks up what double-clicking a file with the given extension, e.g. ".js", does for the current user
+#[instrument]
+pub(crate) fn read_association(extension: &str) -> Result<Association> {
+    let user_choice: Option<String> = windows_ops::get_string_value(
+        &WinregRegistry, HKEY_CURRENT_USER, &user_choice_subpath(extension), USER_CHOICE_PROG_ID_VALUE_NAME
+    )?;
+    let (prog_id, source): (Option<String>, AssociationSource) = match user_choice {
+        Some(prog_id) => (Some(prog_id), AssociationSource::UserChoice),
+        None => (
+            windows_ops::get_string_value(&WinregRegistry, HKEY_CLASSES_ROOT, extension, "")?,
+            AssociationSource::ClassesRoot,
+        ),
+    };
+    // an empty default value registers nothing
+    let prog_id: Option<String> = prog_id.filter(|prog_id| !prog_id.is_empty());
+
+    let command: Option<String> = match &prog_id {
+        Some(prog_id) => read_open_command(prog_id)?,
+        None => None,
+    };
+    trace!("{} opens with {:?} from {:?}, which runs {:?}", extension, prog_id, source, command);
+    Ok(Association { extension: String::from(extension), prog_id, source, command })
+}
+
+// What every script extension currently does, in the order of `SCRIPT_EXTENSIONS`
+pub(crate) fn audit_script_associations() -> Result<Vec<Association>> {
+    SCRIPT_EXTENSIONS.iter().map(|extension| read_association(extension)).collect()
+}
+
+// The command line of a ProgID's default verb, which is what runs on double-click
+fn read_open_command(prog_id: &str) -> Result<Option<String>> {
+    let verb: String = windows_ops::get_string_value(
+        &WinregRegistry, HKEY_CLASSES_ROOT, &format!("{prog_id}\\shell"), ""
+    )?
+        .filter(|verb| !verb.is_empty())
+        .unwrap_or_else(|| String::from(DEFAULT_VERB));
+    windows_ops::get_string_value(
+        &WinregRegistry, HKEY_CLASSES_ROOT, &format!("{prog_id}\\shell\\{verb}\\command"), ""
+    )
+}
+
+// The user's own class registrations from before script files were pointed at Notepad
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AssociationBackup {
+    // ProgID by extension, or None where the user had no registration of their own
+    user_classes: BTreeMap<String, Option<String>>,
+}
+
+// Makes double-clicking any script file open it in Notepad for the current user, after backing up
+// what the user's own registrations said. This method returns whether a change was made.
+#[instrument]
+pub(crate) fn reassociate_scripts_with_notepad() -> Result<bool> {
+    // the first backup holds what the user had before this program stepped in, so fixing again keeps it
+    if load_backup()?.is_none() {
+        let user_classes: BTreeMap<String, Option<String>> = SCRIPT_EXTENSIONS.iter()
+            .map(|extension| Ok((String::from(*extension), read_user_class(extension)?)))
+            .collect::<Result<_>>()?;
+        save_backup(&AssociationBackup { user_classes })?;
+    }
+
+    let mut was_change_made: bool = false;
+    for extension in SCRIPT_EXTENSIONS {
+        was_change_made |= windows_ops::set_or_update_registry_value(
+            &WinregRegistry, HKEY_CURRENT_USER, &user_class_subpath(extension), "", String::from(NOTEPAD_PROG_ID)
+        )?;
+
+        // "Open with" choices win over class registrations. Windows signs them, so they can only be removed
+        // rather than pointed somewhere else, and undoing leaves them removed.
+        let association: Association = read_association(extension)?;
+        if association.source == AssociationSource::UserChoice && association.launches_script_host() {
+            trace!("Removing the choice to open {} with {:?}", extension, association.prog_id);
+            was_change_made |= windows_ops::delete_regkey(
+                &WinregRegistry, HKEY_CURRENT_USER, &user_choice_subpath(extension)
+            )?;
+        }
+    }
+    Ok(was_change_made)
+}
+
+// Puts back the user's own registrations from the backup, then drops the backup.
+// This method returns whether a change was made.
+#[instrument]
+pub(crate) fn restore_script_associations() -> Result<bool> {
+    let Some(backup) = load_backup()? else {
+        trace!("No association backup found, so there is nothing to restore");
+        return Ok(false);
+    };
+
+    let mut was_change_made: bool = false;
+    for (extension, prog_id) in &backup.user_classes {
+        let subkey_path: String = user_class_subpath(extension);
+        was_change_made |= match prog_id {
+            Some(prog_id) => windows_ops::set_or_update_registry_value(
+                &WinregRegistry, HKEY_CURRENT_USER, &subkey_path, "", prog_id.clone()
+            )?,
+            None => windows_ops::delete_registry_value(&WinregRegistry, HKEY_CURRENT_USER, &subkey_path, "")?,
+        };
+    }
+
+    let path: PathBuf = backup_file_path()?;
+    std::fs::remove_file(&path).map_err(|error| err::AssociationBackupError::FailedToWrite { path, source: error })?;
+    Ok(was_change_made)
+}
+
+pub(crate) fn has_backup() -> bool {
+    backup_file_path().map(|path| path.exists()).unwrap_or(false)
+}
+
+fn user_choice_subpath(extension: &str) -> String {
+    format!("{FILE_EXTS_REGKEY_SUBPATH}\\{extension}\\UserChoice")
+}
+
+fn user_class_subpath(extension: &str) -> String {
+    format!("{USER_CLASSES_REGKEY_SUBPATH}\\{extension}")
+}
+
+// The ProgID the user's own registration names for an extension, if there is one
+fn read_user_class(extension: &str) -> Result<Option<String>> {
+    windows_ops::get_string_value(&WinregRegistry, HKEY_CURRENT_USER, &user_class_subpath(extension), "")
+}
+
+fn backup_file_path() -> Result<PathBuf> {
+    Ok(paths::app_data_dir()?.join(ASSOCIATION_BACKUP_FILE_NAME))
+}
+
+fn load_backup() -> Result<Option<AssociationBackup>> {
+    let path: PathBuf = backup_file_path()?;
+    let contents: String = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(err::AssociationBackupError::FailedToRead { path, source: error }.into())
+    };
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|error| err::AssociationBackupError::FailedToParse { path, source: error }.into())
+}
+
+fn save_backup(backup: &AssociationBackup) -> Result<()> {
+    let path: PathBuf = backup_file_path()?;
+    let contents: String = serde_json::to_string_pretty(backup)?;
+    std::fs::write(&path, contents)
+        .map_err(|error| err::AssociationBackupError::FailedToWrite { path, source: error }.into())
+}
+
+// Warns when double-clicking a script file runs it. That's how Windows comes out of the box, so the check
+// only runs once the user turns it on.
+#[derive(Debug)]
+pub(crate) struct ScriptAssociationCheck;
+
+pub(crate) const SCRIPT_ASSOCIATIONS_CHECK_ID: &str = "script-associations";
+
+impl SecurityCheck for ScriptAssociationCheck {
+    fn id(&self) -> &str {
+        SCRIPT_ASSOCIATIONS_CHECK_ID
+    }
+
+    fn name(&self) -> String {
+        tr("check-script-associations")
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    #[instrument]
+    fn evaluate(&self) -> Result<CheckStatus> {
+        return match audit_script_associations()?.iter().any(Association::launches_script_host) {
+            true => Ok(CheckStatus::Warn),
+            false => Ok(CheckStatus::Pass),
+        };
+    }
+
+    fn remediate(&self, restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome> {
+        return match reassociate_scripts_with_notepad()? {
+            true => windows_ops::make_windows_explorer_pick_up_changes(restart_policy),
+            false => Ok(FixOutcome::AlreadyVisible),
+        };
+    }
+
+    // scripts which the user runs on purpose, e.g. login scripts, stop running on double-click
+    fn needs_confirmation(&self) -> bool {
+        true
+    }
+
+    fn can_undo(&self) -> bool {
+        has_backup()
+    }
+
+    fn undo(&self, restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome> {
+        return match restore_script_associations()? {
+            true => windows_ops::make_windows_explorer_pick_up_changes(restart_policy),
+            false => Ok(FixOutcome::AlreadyVisible),
+        };
+    }
+
+    fn managed_values(&self) -> Vec<String> {
+        SCRIPT_EXTENSIONS.iter()
+            .map(|extension| format!("HKEY_CURRENT_USER\\{}\\(Default)", user_class_subpath(extension)))
+            .collect()
+    }
+}
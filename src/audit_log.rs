@@ -0,0 +1,82 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace, warn};
+
+use crate::err;
+use crate::paths;
+use crate::windows_ops::SettingScope;
+
+// Name of the file under the app data directory which holds the audit log, one JSON object per line
+const AUDIT_LOG_FILE_NAME: &str = "hide-file-ext-audit.jsonl";
+
+// One change to HideFileExt. The log is only ever appended to, and unlike the history it's always kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AuditEntry {
+    pub(crate) timestamp: DateTime<Local>,
+    pub(crate) scope: SettingScope,
+    // None where the value didn't exist
+    pub(crate) old_value: Option<u32>,
+    pub(crate) new_value: Option<u32>,
+    // whether this program made the change, rather than something else on the computer
+    pub(crate) remediated_by_app: bool,
+}
+
+impl AuditEntry {
+    // A change which happened just now
+    pub(crate) fn new(scope: SettingScope, old_value: Option<u32>, new_value: Option<u32>, remediated_by_app: bool) -> AuditEntry {
+        AuditEntry { timestamp: Local::now(), scope, old_value, new_value, remediated_by_app }
+    }
+
+    // Something other than this program hid file extensions again
+    pub(crate) fn is_incident(&self) -> bool {
+        !self.remediated_by_app && self.new_value.is_some_and(|new_value| new_value != 0)
+    }
+}
+
+fn audit_log_path() -> Result<PathBuf> {
+    Ok(paths::app_data_dir()?.join(AUDIT_LOG_FILE_NAME))
+}
+
+// Appends a change to the audit log
+#[instrument]
+pub(crate) fn record(entry: &AuditEntry) -> Result<()> {
+    let path: PathBuf = audit_log_path()?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|error| err::AuditLogError { path: path.clone(), source: error })?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)
+        .map_err(|error| err::AuditLogError { path, source: error }.into())
+}
+
+// Every change recorded so far, oldest first
+#[instrument]
+pub(crate) fn load() -> Result<Vec<AuditEntry>> {
+    let path: PathBuf = audit_log_path()?;
+    let file: File = match File::open(&path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            trace!("No audit log found at {:?}", path);
+            return Ok(Vec::new());
+        },
+        Err(error) => return Err(err::AuditLogError { path, source: error }.into())
+    };
+
+    let mut entries: Vec<AuditEntry> = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line: String = line.map_err(|error| err::AuditLogError { path: path.clone(), source: error })?;
+        match serde_json::from_str::<AuditEntry>(&line) {
+            Ok(entry) => entries.push(entry),
+            // one bad line shouldn't make the rest of the log unreadable
+            Err(error) => warn!("Skipping unreadable audit log entry {:?}: {}", line, error),
+        }
+    }
+    Ok(entries)
+}
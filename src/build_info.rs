@@ -0,0 +1,24 @@
+use chrono::NaiveDateTime;
+
+pub(crate) const APPLICATION_DISPLAY_NAME: &str = "no-hidden-extensions";
+
+pub(crate) const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) const LICENSE: &str = env!("CARGO_PKG_LICENSE");
+
+pub(crate) const REPOSITORY: &str = env!("CARGO_PKG_REPOSITORY");
+
+// Whose code signing certificate an update has to be signed with before it replaces this executable
+pub(crate) const UPDATE_SIGNER: &str = "Philosobyte";
+
+// Seconds since the Unix epoch, recorded by build.rs
+const BUILD_TIMESTAMP: &str = env!("BUILD_TIMESTAMP");
+
+// When this executable was built, in UTC
+pub(crate) fn build_timestamp() -> String {
+    BUILD_TIMESTAMP.parse::<i64>()
+        .ok()
+        .and_then(|seconds| NaiveDateTime::from_timestamp_opt(seconds, 0))
+        .map(|timestamp| timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| String::from(BUILD_TIMESTAMP))
+}
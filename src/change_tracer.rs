@@ -0,0 +1,236 @@
+use std::ffi::c_void;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::JoinHandle;
+
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+use windows_sys::core::{GUID, PWSTR};
+use windows_sys::Win32::Foundation::{ERROR_ALREADY_EXISTS, ERROR_SUCCESS, GetLastError};
+use windows_sys::Win32::System::Diagnostics::Etw::{
+    CloseTrace, ControlTraceW, EnableTraceEx2, EVENT_CONTROL_CODE_ENABLE_PROVIDER, EVENT_RECORD, EVENT_TRACE_CONTROL_STOP,
+    EVENT_TRACE_LOGFILEW, EVENT_TRACE_PROPERTIES, EVENT_TRACE_REAL_TIME_MODE, OpenTraceW, PROCESS_TRACE_MODE_EVENT_RECORD,
+    PROCESS_TRACE_MODE_REAL_TIME, ProcessTrace, PROPERTY_DATA_DESCRIPTOR, StartTraceW, TdhGetProperty,
+    TdhGetPropertySize, WNODE_FLAG_TRACED_GUID,
+};
+use windows_sys::Win32::System::Registry::REG_DWORD;
+use windows_sys::Win32::System::Threading::GetCurrentProcessId;
+
+use crate::err;
+use crate::registry_audit::ChangeAttribution;
+use crate::windows_ops;
+
+// Only one session by a name can run at a time, so a session left behind by a crash is taken over
+const SESSION_NAME: &str = "NoHiddenExtensions-RegistryTrace";
+
+// Microsoft-Windows-Kernel-Registry
+const KERNEL_REGISTRY_PROVIDER: GUID = GUID::from_u128(0x70eb4f03_c1de_4f73_a051_33d13d5413bd);
+const SET_VALUE_KEY_EVENT_ID: u16 = 5;
+// TRACE_LEVEL_INFORMATION
+const INFORMATION_LEVEL: u8 = 4;
+// the provider's keywords don't single out value writes, so every event is asked for and the rest dropped
+const ALL_KEYWORDS: u64 = 0;
+
+// OpenTraceW's INVALID_PROCESSTRACE_HANDLE
+const INVALID_TRACE_HANDLE: u64 = u64::MAX;
+
+// Follows every write to a registry value through Event Tracing for Windows, and reports the ones which
+// turn HideFileExt on. Unlike registry auditing, this doesn't change the key's security,
+// but it needs administrator rights for as long as it runs. The session stops when this is dropped.
+#[derive(Debug)]
+pub(crate) struct ChangeTracer {
+    session: u64,
+    trace: u64,
+    changes: Receiver<ChangeAttribution>,
+    // runs ProcessTrace, which only returns once the trace is closed
+    _consumer: JoinHandle<()>,
+}
+
+impl ChangeTracer {
+    #[instrument]
+    pub(crate) fn start() -> Result<ChangeTracer> {
+        let session: u64 = start_session()?;
+        let enable_result: u32 = unsafe {
+            EnableTraceEx2(
+                session,
+                &KERNEL_REGISTRY_PROVIDER,
+                EVENT_CONTROL_CODE_ENABLE_PROVIDER,
+                INFORMATION_LEVEL,
+                ALL_KEYWORDS,
+                0,
+                0,
+                std::ptr::null(),
+            )
+        };
+        if enable_result != ERROR_SUCCESS {
+            stop_session(session);
+            return Err(err::ChangeTraceError::FailedToEnableProvider(enable_result).into());
+        }
+
+        // the callback gets at the sender through the trace's context, so it has to stay put until the trace closes
+        let (sender, changes) = channel();
+        let sender: Box<Sender<ChangeAttribution>> = Box::new(sender);
+        let mut session_name: Vec<u16> = windows_ops::to_wide_string(SESSION_NAME);
+        let mut logfile: EVENT_TRACE_LOGFILEW = unsafe { std::mem::zeroed() };
+        logfile.LoggerName = session_name.as_mut_ptr() as PWSTR;
+        logfile.Anonymous1.ProcessTraceMode = PROCESS_TRACE_MODE_REAL_TIME | PROCESS_TRACE_MODE_EVENT_RECORD;
+        logfile.Anonymous2.EventRecordCallback = Some(on_event);
+        logfile.Context = sender.as_ref() as *const Sender<ChangeAttribution> as *mut c_void;
+
+        let trace: u64 = unsafe { OpenTraceW(&mut logfile) };
+        if trace == INVALID_TRACE_HANDLE {
+            stop_session(session);
+            return Err(err::ChangeTraceError::FailedToOpen(unsafe { GetLastError() }).into());
+        }
+
+        let consumer: JoinHandle<()> = std::thread::spawn(move || {
+            let result: u32 = unsafe { ProcessTrace(&trace, 1, std::ptr::null(), std::ptr::null()) };
+            trace!("Stopped tracing registry changes with result {}", result);
+            drop(sender);
+        });
+        Ok(ChangeTracer { session, trace, changes, _consumer: consumer })
+    }
+
+    // Blocks until something other than this program turns HideFileExt on
+    pub(crate) fn wait_for_change(&self) -> Result<ChangeAttribution> {
+        self.changes.recv().map_err(|_| err::ChangeTraceError::Stopped.into())
+    }
+}
+
+impl Drop for ChangeTracer {
+    fn drop(&mut self) {
+        let result: u32 = unsafe { CloseTrace(self.trace) };
+        if result != ERROR_SUCCESS {
+            warn!("Failed to close the registry trace, error code {}", result);
+        }
+        stop_session(self.session);
+    }
+}
+
+// EVENT_TRACE_PROPERTIES has to be followed by the session's name in the same buffer
+fn session_properties() -> Vec<u64> {
+    let name_offset: usize = std::mem::size_of::<EVENT_TRACE_PROPERTIES>();
+    let buffer_size: usize = name_offset + (SESSION_NAME.len() + 1) * std::mem::size_of::<u16>();
+    // u64 keeps the properties aligned
+    let mut buffer: Vec<u64> = vec![0; buffer_size / std::mem::size_of::<u64>() + 1];
+    let properties: &mut EVENT_TRACE_PROPERTIES = unsafe { &mut *(buffer.as_mut_ptr() as *mut EVENT_TRACE_PROPERTIES) };
+    properties.Wnode.BufferSize = (buffer.len() * std::mem::size_of::<u64>()) as u32;
+    properties.Wnode.Flags = WNODE_FLAG_TRACED_GUID;
+    // timestamps from QueryPerformanceCounter
+    properties.Wnode.ClientContext = 1;
+    properties.LogFileMode = EVENT_TRACE_REAL_TIME_MODE;
+    properties.LoggerNameOffset = name_offset as u32;
+    buffer
+}
+
+fn start_session() -> Result<u64> {
+    let session_name: Vec<u16> = windows_ops::to_wide_string(SESSION_NAME);
+    let mut session: u64 = 0;
+    let mut properties: Vec<u64> = session_properties();
+    let mut result: u32 = unsafe {
+        StartTraceW(&mut session, session_name.as_ptr(), properties.as_mut_ptr() as *mut EVENT_TRACE_PROPERTIES)
+    };
+    if result == ERROR_ALREADY_EXISTS {
+        trace!("Taking over the registry trace session an earlier instance left behind");
+        let mut stale_properties: Vec<u64> = session_properties();
+        unsafe {
+            ControlTraceW(
+                0,
+                session_name.as_ptr(),
+                stale_properties.as_mut_ptr() as *mut EVENT_TRACE_PROPERTIES,
+                EVENT_TRACE_CONTROL_STOP,
+            )
+        };
+        properties = session_properties();
+        result = unsafe {
+            StartTraceW(&mut session, session_name.as_ptr(), properties.as_mut_ptr() as *mut EVENT_TRACE_PROPERTIES)
+        };
+    }
+    match result {
+        ERROR_SUCCESS => Ok(session),
+        error_code => Err(err::ChangeTraceError::FailedToStart(error_code).into()),
+    }
+}
+
+fn stop_session(session: u64) {
+    let mut properties: Vec<u64> = session_properties();
+    let result: u32 = unsafe {
+        ControlTraceW(
+            session,
+            std::ptr::null(),
+            properties.as_mut_ptr() as *mut EVENT_TRACE_PROPERTIES,
+            EVENT_TRACE_CONTROL_STOP,
+        )
+    };
+    if result != ERROR_SUCCESS {
+        warn!("Failed to stop the registry trace session, error code {}", result);
+    }
+}
+
+// Called on the consumer thread for every registry event, so anything but HideFileExt is dropped right away
+unsafe extern "system" fn on_event(event: *mut EVENT_RECORD) {
+    let event: &EVENT_RECORD = &*event;
+    if event.EventHeader.EventDescriptor.Id != SET_VALUE_KEY_EVENT_ID
+        || event.EventHeader.ProcessId == GetCurrentProcessId() {
+        return;
+    }
+    let is_hide_file_ext: bool = read_property(event, "ValueName")
+        .map(|value_name| utf16_string(&value_name).eq_ignore_ascii_case(windows_ops::HIDE_FILE_EXT_VALUE_NAME))
+        .unwrap_or(false);
+    if !is_hide_file_ext {
+        return;
+    }
+
+    // only turning hiding on is worth attributing. Data which wasn't captured is given the benefit of the doubt.
+    let new_data: Option<u32> = match read_dword_property(event, "Type") {
+        Some(REG_DWORD) => read_dword_property(event, "CapturedData"),
+        _ => None,
+    };
+    if new_data == Some(0) {
+        return;
+    }
+
+    let process_id: u32 = event.EventHeader.ProcessId;
+    let change_attribution = ChangeAttribution {
+        process_path: windows_ops::get_process_image_path(process_id).unwrap_or_default(),
+        process_id,
+    };
+    trace!("HideFileExt was set by {:?}", change_attribution);
+    let sender: &Sender<ChangeAttribution> = &*(event.UserContext as *const Sender<ChangeAttribution>);
+    if let Err(error) = sender.send(change_attribution) {
+        warn!("Failed to forward a traced change: {:?}", error);
+    }
+}
+
+// The raw bytes of one of the event's properties, by name
+fn read_property(event: &EVENT_RECORD, name: &str) -> Option<Vec<u8>> {
+    let wide_name: Vec<u16> = windows_ops::to_wide_string(name);
+    let descriptor = PROPERTY_DATA_DESCRIPTOR {
+        PropertyName: wide_name.as_ptr() as u64,
+        // not an array
+        ArrayIndex: u32::MAX,
+        Reserved: 0,
+    };
+    let mut size: u32 = 0;
+    if unsafe { TdhGetPropertySize(event, 0, std::ptr::null(), 1, &descriptor, &mut size) } != ERROR_SUCCESS {
+        return None;
+    }
+    let mut buffer: Vec<u8> = vec![0; size as usize];
+    match unsafe { TdhGetProperty(event, 0, std::ptr::null(), 1, &descriptor, size, buffer.as_mut_ptr()) } {
+        ERROR_SUCCESS => Some(buffer),
+        _ => None,
+    }
+}
+
+fn read_dword_property(event: &EVENT_RECORD, name: &str) -> Option<u32> {
+    let bytes: Vec<u8> = read_property(event, name)?;
+    Some(u32::from_le_bytes(bytes.get(..4)?.try_into().ok()?))
+}
+
+// Registry names are logged as null-terminated UTF-16
+fn utf16_string(bytes: &[u8]) -> String {
+    let wide: Vec<u16> = bytes.chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|character| *character != 0)
+        .collect();
+    String::from_utf16_lossy(&wide)
+}
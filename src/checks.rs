@@ -0,0 +1,203 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::antivirus::RealTimeProtectionCheck;
+use crate::associations::ScriptAssociationCheck;
+use crate::config::Config;
+use crate::err;
+use crate::registry::WinregRegistry;
+use crate::rules;
+use crate::rules::{RegistryRule, WatchedKey};
+// rules declare their severity, so it's defined alongside them
+pub(crate) use crate::rules::Severity;
+use crate::uac::UacCheck;
+use crate::windows_ops::{ExplorerRestartPolicy, FixOutcome};
+
+// What a check found the last time it looked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CheckStatus {
+    Pass,
+    Warn,
+}
+
+// Something about the computer's configuration which can leave the user open to attack.
+// The UI only goes through this trait, so a new check only has to be added to `built_in_checks()`.
+pub(crate) trait SecurityCheck: Debug + Send + Sync {
+    // Stays the same across versions, since the config file and history refer to checks by it
+    fn id(&self) -> &str;
+
+    fn name(&self) -> String;
+
+    fn severity(&self) -> Severity;
+
+    // Whether the check runs before the user has turned it on or off themselves
+    fn enabled_by_default(&self) -> bool {
+        true
+    }
+
+    fn evaluate(&self) -> Result<CheckStatus>;
+
+    // Whether this program knows how to make the check pass, rather than only reporting on it
+    fn can_remediate(&self) -> bool {
+        true
+    }
+
+    fn remediate(&self, restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome>;
+
+    // Whether remediating could get in the user's way enough that they have to confirm it first
+    fn needs_confirmation(&self) -> bool {
+        false
+    }
+
+    fn can_undo(&self) -> bool {
+        false
+    }
+
+    // Reverses what remediating did
+    fn undo(&self, _restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome> {
+        Err(err::CheckError::CannotUndo(String::from(self.id())).into())
+    }
+
+    // A program which lets the user change whatever the check looks at themselves,
+    // for checks this program can't fix on its own
+    fn settings_program(&self) -> Option<&'static str> {
+        None
+    }
+
+    // The registry keys, and the values under them, whose changes may change the outcome. Whatever else a check
+    // looks at is only evaluated again on polls and after the computer wakes.
+    fn watched_keys(&self) -> Vec<WatchedKey> {
+        Vec::new()
+    }
+
+    // Every registry value remediating may write to, for showing the user what this program touches
+    fn managed_values(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+impl SecurityCheck for RegistryRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> String {
+        self.display_name()
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        !self.opt_in
+    }
+
+    fn evaluate(&self) -> Result<CheckStatus> {
+        return match self.is_broken(&WinregRegistry)? {
+            true => Ok(CheckStatus::Warn),
+            false => Ok(CheckStatus::Pass),
+        };
+    }
+
+    fn remediate(&self, restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome> {
+        self.fix(&WinregRegistry, restart_policy)
+    }
+
+    fn needs_confirmation(&self) -> bool {
+        self.advanced
+    }
+
+    fn can_undo(&self) -> bool {
+        self.undoable
+    }
+
+    fn undo(&self, restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome> {
+        match self.undoable {
+            true => RegistryRule::undo(self, &WinregRegistry, restart_policy),
+            false => Err(err::CheckError::CannotUndo(self.id.clone()).into()),
+        }
+    }
+
+    fn watched_keys(&self) -> Vec<WatchedKey> {
+        vec![self.watched_key()]
+    }
+
+    fn managed_values(&self) -> Vec<String> {
+        vec![self.location()]
+    }
+}
+
+// Every check which ships with this program, whether or not it's turned on
+pub(crate) fn built_in_checks() -> Vec<Arc<dyn SecurityCheck>> {
+    let mut checks: Vec<Arc<dyn SecurityCheck>> = rules::built_in_rules().iter()
+        .map(|rule| Arc::new(rule.clone()) as Arc<dyn SecurityCheck>)
+        .collect();
+    checks.push(Arc::new(UacCheck));
+    checks.push(Arc::new(RealTimeProtectionCheck));
+    checks.push(Arc::new(ScriptAssociationCheck));
+    checks
+}
+
+// Every check the user can turn on or off, including the rules of their own from the config file
+pub(crate) fn all_checks(config: &Config) -> Vec<Arc<dyn SecurityCheck>> {
+    let mut checks: Vec<Arc<dyn SecurityCheck>> = built_in_checks();
+    checks.extend(config.custom_rules.iter().map(|rule| Arc::new(rule.clone()) as Arc<dyn SecurityCheck>));
+    checks
+}
+
+// The checks which currently run
+pub(crate) fn active_checks(config: &Config) -> Vec<Arc<dyn SecurityCheck>> {
+    all_checks(config).into_iter()
+        .filter(|check| is_enabled(check.as_ref(), config))
+        .collect()
+}
+
+pub(crate) fn is_enabled(check: &dyn SecurityCheck, config: &Config) -> bool {
+    config.enabled_checks.get(check.id())
+        .copied()
+        .unwrap_or_else(|| check.enabled_by_default())
+}
+
+// Each key the given checks watch, once, along with every value any of them reads from it
+pub(crate) fn watched_keys(checks: &[Arc<dyn SecurityCheck>]) -> Vec<WatchedKey> {
+    let mut watched_keys: Vec<WatchedKey> = Vec::new();
+    for watched_key in checks.iter().flat_map(|check| check.watched_keys()) {
+        match watched_keys.iter_mut().find(|existing| existing.is_same_key(&watched_key)) {
+            Some(existing) => existing.merge(watched_key),
+            None => watched_keys.push(watched_key),
+        }
+    }
+    watched_keys
+}
+
+// Whether any of the values which changed under a key are ones the check reads
+pub(crate) fn is_affected_by(check: &dyn SecurityCheck, changed_key: &WatchedKey, changed_values: &[String]) -> bool {
+    check.watched_keys().iter()
+        .any(|watched_key| watched_key.is_same_key(changed_key) && watched_key.reads_any(changed_values))
+}
+
+// The name of a built-in check. Checks which aren't built in, e.g. rules since removed from the config file,
+// go by their ID.
+pub(crate) fn name_of(check_id: &str) -> String {
+    built_in_checks().iter()
+        .find(|check| check.id() == check_id)
+        .map(|check| check.name())
+        .unwrap_or_else(|| String::from(check_id))
+}
+
+// The share of checks which pass as a percentage, where more severe checks count for more.
+// With nothing to check, there is nothing wrong either.
+pub(crate) fn posture_score(results: &[(Severity, CheckStatus)]) -> u32 {
+    let total_weight: u32 = results.iter().map(|(severity, _)| severity.weight()).sum();
+    let passing_weight: u32 = results.iter()
+        .filter(|(_, status)| *status == CheckStatus::Pass)
+        .map(|(severity, _)| severity.weight())
+        .sum();
+    return match total_weight {
+        0 => 100,
+        _ => passing_weight * 100 / total_weight,
+    };
+}
@@ -0,0 +1,68 @@
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+
+use crate::config::Config;
+use crate::err;
+use crate::event_log;
+use crate::i18n::tr;
+use crate::install;
+use crate::paths;
+use crate::registry::WinregRegistry;
+use crate::registry_audit;
+use crate::startup_task;
+use crate::uri_scheme;
+use crate::windows_ops;
+
+// Something this program may have left on the computer, by the locale key which describes it to the user.
+// Each step returns whether there was anything to remove.
+struct CleanupStep {
+    description_key: &'static str,
+    remove: fn(&Config) -> Result<bool>,
+}
+
+const CLEANUP_STEPS: [CleanupStep; 7] = [
+    CleanupStep {
+        description_key: "cleanup-run-key", remove: |_| windows_ops::remove_startup_registration(&WinregRegistry)
+    },
+    CleanupStep { description_key: "cleanup-scheduled-task", remove: |_| startup_task::unregister() },
+    CleanupStep { description_key: "cleanup-installed-files", remove: |_| install::remove_installed_files() },
+    CleanupStep { description_key: "cleanup-uri-scheme", remove: |_| uri_scheme::unregister() },
+    CleanupStep { description_key: "cleanup-event-source", remove: |_| event_log::remove_source_registration() },
+    CleanupStep { description_key: "cleanup-registry-auditing", remove: turn_off_registry_auditing },
+    CleanupStep { description_key: "cleanup-app-data", remove: |_| paths::remove_app_data_dir() },
+];
+
+// Removes everything this program ever put on the computer, so trying it out leaves nothing behind.
+// Carries on past failures so as much as possible is gone, then reports what is still there.
+// This method returns descriptions of what was removed.
+#[instrument(skip(config))]
+pub(crate) fn clean_up(config: &Config) -> Result<Vec<String>> {
+    // the running instance would write its config again on the way out
+    install::stop_running_instance()?;
+
+    let mut removed: Vec<String> = Vec::new();
+    let mut failed: Vec<String> = Vec::new();
+    for step in CLEANUP_STEPS.iter() {
+        match (step.remove)(config) {
+            Ok(true) => removed.push(tr(step.description_key)),
+            Ok(false) => trace!("Nothing to remove for {}", step.description_key),
+            Err(error) => {
+                warn!("Failed to remove {}: {:?}", step.description_key, error);
+                failed.push(tr(step.description_key));
+            },
+        }
+    }
+    return match failed.is_empty() {
+        true => Ok(removed),
+        false => Err(err::CleanupError(failed).into()),
+    };
+}
+
+// Auditing changes the key's security, which stays behind otherwise. Needs administrator rights.
+fn turn_off_registry_auditing(config: &Config) -> Result<bool> {
+    if !config.attribute_changes {
+        return Ok(false);
+    }
+    registry_audit::turn_off()?;
+    Ok(true)
+}
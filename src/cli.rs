@@ -0,0 +1,344 @@
+use std::path::{Path, PathBuf};
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Subcommand;
+use fluent_bundle::FluentValue;
+use serde::Serialize;
+use tracing::{trace, warn};
+use windows_sys::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+
+use crate::advanced_backup;
+use crate::audit_log;
+use crate::audit_log::AuditEntry;
+use crate::checks;
+use crate::cleanup;
+use crate::checks::{CheckStatus, SecurityCheck};
+use crate::config::Config;
+use crate::effective_state;
+use crate::effective_state::EffectiveState;
+use crate::export;
+use crate::export::ExportFormat;
+use crate::harden;
+use crate::harden::{Hardening, RollbackManifest, RuleResult};
+use crate::i18n::{tr, tr_args};
+use crate::install;
+use crate::ipc;
+use crate::ipc::{IpcCommand, StatusReport};
+use crate::mark_of_the_web;
+use crate::mark_of_the_web::Inspection;
+use crate::registry::WinregRegistry;
+use crate::rules::{FILE_EXTENSIONS_RULE_ID, RefreshAction, RegistryRule};
+use crate::windows_ops;
+use crate::windows_ops::{ExplorerRestartPolicy, SettingScope};
+
+// Shown by `comply --help`. Detection scripts and configuration baselines depend on this output,
+// so it mustn't change.
+const COMPLY_LONG_HELP: &str = "\
+Print one line saying whether this computer meets every active rule, for Intune proactive remediations \
+and Configuration Manager configuration baselines.
+
+Output is either `Compliant` or `NonCompliant: ` followed by the ids of the failing rules, separated by commas. \
+With --json, it's an object instead: {\"compliant\":false,\"failing_rules\":[\"file-extensions\"]}
+
+Exit codes: 0 when compliant, 1 when not, 2 when the state couldn't be read. \
+Run it as the signed-in user, since most rules are per-user settings.";
+
+// Things this program can do from a terminal without opening its window
+#[derive(Debug, Clone, Subcommand)]
+pub(crate) enum CliCommand {
+    // report where a file came from, going by its mark of the web
+    Inspect {
+        path: PathBuf,
+    },
+    // write every recorded change to HideFileExt to a file, as CSV or JSON
+    Export {
+        path: PathBuf,
+        // taken from the file extension when left out
+        #[arg(long, value_enum)]
+        format: Option<ExportFormat>,
+    },
+    // print whether file extensions are hidden, as JSON
+    Status,
+    // make file extensions visible and print where things stand afterward, as JSON
+    Fix,
+    // copy this program to %LOCALAPPDATA%\Programs, start it at sign-in and add it to the Start menu
+    Install,
+    // undo `install`, leaving settings and history alone
+    Uninstall,
+    // remove everything this program ever put on the computer, settings and history included
+    Cleanup,
+    // put the values under Windows Explorer's Advanced key back the way the newest backup has them
+    Restore,
+    // undo the last change this program made, going back one more change each time
+    Undo,
+    // apply the security baseline in one registry transaction and print what happened to each rule,
+    // along with where the manifest for rolling it back went
+    Harden {
+        // also show protected operating system files, which the baseline otherwise leaves alone
+        #[arg(long)]
+        show_super_hidden: bool,
+        // only print what would change
+        #[arg(long)]
+        dry_run: bool,
+        // put back every value an earlier run changed, going by the manifest it wrote
+        #[arg(long, value_name = "MANIFEST", conflicts_with_all = ["show_super_hidden", "dry_run"])]
+        rollback: Option<PathBuf>,
+    },
+    // print a single line saying whether every active rule is met and exit with a matching code
+    #[command(about = "Print whether every active rule is met, for Intune and Configuration Manager")]
+    #[command(long_about = COMPLY_LONG_HELP)]
+    Comply {
+        // print a JSON object instead of a plain line
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+// The output of `comply --json`
+#[derive(Debug, Clone, Serialize)]
+struct ComplianceReport {
+    compliant: bool,
+    failing_rules: Vec<String>,
+}
+
+impl ComplianceReport {
+    fn to_line(&self) -> String {
+        match self.compliant {
+            true => String::from("Compliant"),
+            false => format!("NonCompliant: {}", self.failing_rules.join(",")),
+        }
+    }
+
+    fn exit_code(&self) -> i32 {
+        match self.compliant {
+            true => 0,
+            false => 1,
+        }
+    }
+}
+
+// Carries out a command, printing to the terminal it was run from
+pub(crate) fn run(command: CliCommand, config: &Config) -> Result<()> {
+    // this is a GUI program, so it has no console of its own to print to
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+
+    match command {
+        CliCommand::Inspect { path } => {
+            let inspection: Inspection = mark_of_the_web::inspect(&path)?;
+            println!("{}", inspection.path.display());
+            for finding in inspection.findings() {
+                println!("  {finding}");
+            }
+            if inspection.is_dangerous() {
+                println!("  {}", tr("motw-executable-warning"));
+            }
+        },
+        CliCommand::Export { path, format } => {
+            let format: ExportFormat = format.unwrap_or_else(|| ExportFormat::from_path(&path));
+            let count: usize = export::export(&path, format)?;
+            println!("{}", export::summary(count, &path));
+        },
+        CliCommand::Status => println!("{}", ask_running_instance_or(IpcCommand::Status, || status_report(config))?),
+        CliCommand::Fix => println!("{}", ask_running_instance_or(IpcCommand::Fix, || fix(config))?),
+        CliCommand::Install => {
+            let path: PathBuf = install::install(config)?;
+            println!("{}", tr_args("install-summary", &[("path", FluentValue::from(path.display().to_string()))]));
+        },
+        CliCommand::Uninstall => {
+            install::uninstall()?;
+            println!("{}", tr("uninstall-summary"));
+        },
+        CliCommand::Cleanup => {
+            for removed in cleanup::clean_up(config)? {
+                println!("  {removed}");
+            }
+            println!("{}", tr("cleanup-summary"));
+        },
+        CliCommand::Restore => match advanced_backup::restore_latest()? {
+            Some(backup) => {
+                windows_ops::make_windows_explorer_pick_up_changes(ExplorerRestartPolicy::RestartIfNeeded)?;
+                let time: String = backup.timestamp.format("%Y-%m-%d %H:%M:%S").to_string();
+                println!("{}", tr_args("restore-summary", &[("time", FluentValue::from(time))]));
+            },
+            None => println!("{}", tr("no-backup")),
+        },
+        CliCommand::Undo => match advanced_backup::undo_last_change()? {
+            Some(backup) => {
+                windows_ops::make_windows_explorer_pick_up_changes(ExplorerRestartPolicy::RestartIfNeeded)?;
+                let time: String = backup.timestamp.format("%Y-%m-%d %H:%M:%S").to_string();
+                println!("{}", tr_args("undo-summary", &[("time", FluentValue::from(time))]));
+            },
+            None => println!("{}", tr("undo-nothing")),
+        },
+        CliCommand::Harden { rollback: Some(manifest_path), .. } => roll_back_baseline(&manifest_path)?,
+        CliCommand::Harden { show_super_hidden, dry_run, rollback: None } =>
+            apply_baseline(show_super_hidden, dry_run)?,
+        CliCommand::Comply { json } => {
+            let exit_code: i32 = match compliance_report(config) {
+                Ok(report) => {
+                    match json {
+                        true => println!("{}", serde_json::to_string(&report)?),
+                        false => println!("{}", report.to_line()),
+                    }
+                    report.exit_code()
+                },
+                // returning the error would exit with 1 too, which would read as non-compliant
+                Err(error) => {
+                    warn!("Failed to evaluate compliance: {:?}", error);
+                    println!("Error: {error}");
+                    2
+                },
+            };
+            std::process::exit(exit_code);
+        },
+    }
+    Ok(())
+}
+
+// The running instance carries out the command when there is one, so its window, history and watchers
+// stay in step. Otherwise the command is carried out here.
+fn ask_running_instance_or(command: IpcCommand, run_here: impl FnOnce() -> Result<StatusReport>) -> Result<String> {
+    return match ipc::send_request(command) {
+        Ok(response) => Ok(response),
+        Err(error) => {
+            trace!("Carrying out {:?} without the running instance: {:?}", command, error);
+            Ok(serde_json::to_string(&run_here()?)?)
+        },
+    };
+}
+
+// Like the running instance's own report, minus what only its window knows about
+fn status_report(config: &Config) -> Result<StatusReport> {
+    let effective_state: EffectiveState = effective_state::file_extensions_hidden(&WinregRegistry)?;
+    let mut failing_checks: Vec<String> = Vec::new();
+    let checks: Vec<Arc<dyn SecurityCheck>> = checks::active_checks(config);
+    for check in checks.iter().filter(|check| check.id() != FILE_EXTENSIONS_RULE_ID) {
+        if check.evaluate()? == CheckStatus::Warn {
+            failing_checks.push(String::from(check.id()));
+        }
+    }
+    Ok(StatusReport {
+        file_extensions_hidden: effective_state.file_extensions_hidden,
+        setting_source: format!("{:?}", effective_state.source),
+        explorer_restart_pending: false,
+        failing_checks,
+        error: None,
+    })
+}
+
+// HideFileExt counts as a failing rule alongside the other checks
+fn compliance_report(config: &Config) -> Result<ComplianceReport> {
+    let status: StatusReport = status_report(config)?;
+    let mut failing_rules: Vec<String> = Vec::new();
+    if status.file_extensions_hidden {
+        failing_rules.push(String::from(FILE_EXTENSIONS_RULE_ID));
+    }
+    failing_rules.extend(status.failing_checks);
+    Ok(ComplianceReport { compliant: failing_rules.is_empty(), failing_rules })
+}
+
+fn fix(config: &Config) -> Result<StatusReport> {
+    let old_value: Option<u32> = windows_ops::get_hide_file_ext_value(&WinregRegistry, SettingScope::CurrentUser)?;
+    advanced_backup::back_up_before_change(&[SettingScope::CurrentUser]);
+    windows_ops::turn_off_file_extension_hiding(&WinregRegistry, ExplorerRestartPolicy::RestartIfNeeded)?;
+    let new_value: Option<u32> = windows_ops::get_hide_file_ext_value(&WinregRegistry, SettingScope::CurrentUser)?;
+    if old_value != new_value {
+        record_hide_file_ext_change(old_value, new_value);
+    }
+    status_report(config)
+}
+
+fn apply_baseline(show_super_hidden: bool, dry_run: bool) -> Result<()> {
+    let rules: Vec<RegistryRule> = harden::baseline_rules(show_super_hidden);
+    if !dry_run {
+        advanced_backup::back_up_before_change(&[SettingScope::CurrentUser]);
+    }
+    let hardening: Hardening = harden::harden(&WinregRegistry, &rules, dry_run, &harden::manifest_dir()?)?;
+    print_hardening_results(&hardening.results, dry_run);
+
+    let changed: Vec<&RuleResult> = hardening.results.iter().filter(|result| result.changed).collect();
+    if changed.iter().any(|result| result.rule.refresh == RefreshAction::WindowsExplorer) {
+        windows_ops::make_windows_explorer_pick_up_changes(ExplorerRestartPolicy::RestartIfNeeded)?;
+    }
+    if let Some(result) = changed.iter().find(|result| result.rule.id == FILE_EXTENSIONS_RULE_ID) {
+        record_hide_file_ext_change(result.old_data, Some(result.rule.desired_data));
+    }
+    if let Some(manifest_path) = hardening.manifest_path {
+        let manifest_path: String = manifest_path.display().to_string();
+        println!("{}", tr_args("harden-manifest-written", &[("path", FluentValue::from(manifest_path))]));
+    }
+    Ok(())
+}
+
+fn roll_back_baseline(manifest_path: &Path) -> Result<()> {
+    let old_value: Option<u32> = windows_ops::get_hide_file_ext_value(&WinregRegistry, SettingScope::CurrentUser)?;
+    advanced_backup::back_up_before_change(&[SettingScope::CurrentUser]);
+    let manifest: RollbackManifest = harden::roll_back(&WinregRegistry, manifest_path)?;
+
+    let mut rows: Vec<[String; 2]> = vec![[tr("harden-column-rule"), tr("harden-column-restored")]];
+    for entry in &manifest.entries {
+        rows.push([entry.display_name(), match entry.old_data {
+            Some(old_data) => old_data.to_string(),
+            None => tr("harden-no-value"),
+        }]);
+    }
+    print_table(&rows);
+
+    windows_ops::make_windows_explorer_pick_up_changes(ExplorerRestartPolicy::RestartIfNeeded)?;
+    let new_value: Option<u32> = windows_ops::get_hide_file_ext_value(&WinregRegistry, SettingScope::CurrentUser)?;
+    if old_value != new_value {
+        record_hide_file_ext_change(old_value, new_value);
+    }
+    Ok(())
+}
+
+// One row per rule, with the columns lined up
+fn print_hardening_results(results: &[RuleResult], dry_run: bool) {
+    let describe_data = |data: Option<u32>| match data {
+        Some(data) => data.to_string(),
+        None => tr("harden-no-value"),
+    };
+    let mut rows: Vec<[String; 4]> = vec![[
+        tr("harden-column-rule"), tr("harden-column-before"), tr("harden-column-desired"), tr("harden-column-result"),
+    ]];
+    for result in results {
+        rows.push([
+            result.rule.display_name(),
+            describe_data(result.old_data),
+            describe_data(Some(result.rule.desired_data)),
+            match (result.changed, result.needed_change() && dry_run) {
+                (true, _) => tr("harden-changed"),
+                (false, true) => tr("harden-would-change"),
+                (false, false) => tr("harden-already-set"),
+            },
+        ]);
+    }
+
+    print_table(&rows);
+}
+
+// The first row is the header. Every column but the last is padded to its widest cell.
+fn print_table<const COLUMNS: usize>(rows: &[[String; COLUMNS]]) {
+    let mut widths: [usize; COLUMNS] = [0; COLUMNS];
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in rows {
+        let cells: Vec<String> = row.iter().zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
+
+// No window is watching to record the change in the audit log
+fn record_hide_file_ext_change(old_value: Option<u32>, new_value: Option<u32>) {
+    let entry: AuditEntry = AuditEntry::new(SettingScope::CurrentUser, old_value, new_value, true);
+    if let Err(error) = audit_log::record(&entry) {
+        warn!("Failed to add the change to HideFileExt to the audit log: {:?}", error);
+    }
+}
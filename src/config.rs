@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveTime};
+use fluent_bundle::FluentValue;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace};
+
+use crate::err;
+use crate::i18n::{tr, tr_args};
+use crate::paths;
+use crate::retry::RetryPolicy;
+use crate::rules::RegistryRule;
+use crate::sound::NotificationSound;
+use crate::syslog::SyslogConfig;
+
+// Name of the file under the app data directory which holds user preferences.
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+// User preferences which persist across restarts. Any setting missing from the file takes its default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Config {
+    pub(crate) quiet_hours: Option<QuietHours>,
+    pub(crate) notification_sound: NotificationSound,
+    // keeps the window in front of every other while file extensions are hidden, until the user fixes or
+    // acknowledges it
+    pub(crate) keep_warning_on_top: bool,
+    pub(crate) theme_preference: ThemePreference,
+    pub(crate) text_scale: TextScale,
+    // BCP 47 tag such as "de-DE" which overrides the Windows display language
+    pub(crate) language: Option<String>,
+    // How persistently to retry registry and process operations which fail transiently
+    pub(crate) retry: RetryPolicy,
+    // set once the user asks not to confirm before Windows Explorer may be restarted
+    pub(crate) skip_fix_confirmation: bool,
+    // leave Windows Explorer running when refreshing it isn't enough, and let the user restart it later
+    pub(crate) defer_explorer_restart: bool,
+    // restart Windows Explorer right away rather than first asking it to refresh, for when refreshing isn't enough
+    pub(crate) always_restart_explorer: bool,
+    // how often to re-read the setting in case a change notification was missed
+    pub(crate) poll_interval: PollInterval,
+    // how often to evaluate every check from scratch and record the outcome, even when nothing seems to have changed
+    pub(crate) scan_interval: ScanInterval,
+    // nothing calls for the user's attention until then, e.g. while they're changing settings on purpose
+    pub(crate) paused_until: Option<DateTime<Local>>,
+    // checks the user turned on or off, by ID. Every other check keeps its default.
+    pub(crate) enabled_checks: BTreeMap<String, bool>,
+    // rules of the user's own, checked along with the built-in ones
+    pub(crate) custom_rules: Vec<RegistryRule>,
+    // the Explorer\Advanced key is audited, so the Security event log can tell which program changed HideFileExt
+    pub(crate) attribute_changes: bool,
+    // follow registry writes through event tracing while running as administrator, to tell which program changed HideFileExt
+    pub(crate) trace_changes: bool,
+    // receives a JSON POST whenever HideFileExt changes, e.g. to post into a chat channel
+    pub(crate) webhook_url: Option<String>,
+    // forwards every change to HideFileExt to a syslog server, e.g. for a SIEM to pick up
+    pub(crate) syslog: Option<SyslogConfig>,
+    // writes every change to HideFileExt to the Windows Application event log
+    pub(crate) write_to_event_log: bool,
+    // serves Prometheus metrics at http://127.0.0.1:<port>/metrics, e.g. to alert when file extensions stay hidden
+    pub(crate) metrics_port: Option<u16>,
+    // global hotkeys such as "Ctrl+Alt+E", which work whichever program has focus
+    pub(crate) show_window_hotkey: Option<String>,
+    pub(crate) fix_hotkey: Option<String>,
+    // how this program starts when the user signs in, once they've asked it to
+    pub(crate) startup_method: StartupMethod,
+    // whether the scheduled task starts this program as administrator
+    pub(crate) startup_task_elevated: bool,
+    // how long to wait after the user signs in before starting, while Windows is still busy setting up
+    pub(crate) startup_delay_seconds: u64,
+    // opens only the tray icon, however this program is started, as --start-minimized does for one start
+    pub(crate) start_minimized: bool,
+    // starts with only the tray icon, and creates the window the first time it's needed
+    pub(crate) tray_only: bool,
+    // asks GitHub once a day whether there's a newer release. Off unless the user turns it on.
+    pub(crate) check_for_updates: bool,
+    // logs everything this program does, for when something goes wrong
+    pub(crate) verbose_logging: bool,
+    // how each line in the log is written, in the file and on stdout
+    pub(crate) log_format: LogFormat,
+    // shows actions which reach into Windows' internals, such as opening Registry Editor
+    pub(crate) advanced_mode: bool,
+}
+
+// Whether to follow Windows' light/dark mode or force one of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub(crate) enum ThemePreference {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl ThemePreference {
+    pub(crate) const ALL: [ThemePreference; 3] = [ThemePreference::System, ThemePreference::Light, ThemePreference::Dark];
+}
+
+impl Display for ThemePreference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name: String = match self {
+            ThemePreference::System => tr("theme-system"),
+            ThemePreference::Light => tr("theme-light"),
+            ThemePreference::Dark => tr("theme-dark"),
+        };
+        write!(f, "{name}")
+    }
+}
+
+// Plain text for people to read, or JSON for log pipelines to ingest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub(crate) enum LogFormat {
+    #[default]
+    Text,
+    // one object per line, with the fields of the spans each event happened in
+    Json,
+}
+
+// Where Windows is told to start this program when the user signs in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub(crate) enum StartupMethod {
+    // the Run key under HKEY_CURRENT_USER
+    #[default]
+    RunKey,
+    ScheduledTask,
+}
+
+impl StartupMethod {
+    pub(crate) const ALL: [StartupMethod; 2] = [StartupMethod::RunKey, StartupMethod::ScheduledTask];
+}
+
+impl Display for StartupMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name: String = match self {
+            StartupMethod::RunKey => tr("startup-method-run-key"),
+            StartupMethod::ScheduledTask => tr("startup-method-scheduled-task"),
+        };
+        write!(f, "{name}")
+    }
+}
+
+// A daily window during which state changes are recorded but the window does not pop up.
+// The window may wrap around midnight, e.g. 22:00 to 07:00.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct QuietHours {
+    pub(crate) start: NaiveTime,
+    pub(crate) end: NaiveTime,
+}
+
+impl QuietHours {
+    pub(crate) fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+// Extra scaling applied to the whole UI on top of Windows' own text scaling, as a percentage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct TextScale(pub(crate) u32);
+
+impl TextScale {
+    pub(crate) const PRESETS: [TextScale; 5] = [TextScale(100), TextScale(125), TextScale(150), TextScale(175), TextScale(200)];
+
+    pub(crate) fn factor(&self) -> f64 {
+        f64::from(self.0) / 100.0
+    }
+}
+
+impl Default for TextScale {
+    fn default() -> Self {
+        TextScale(100)
+    }
+}
+
+impl Display for TextScale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+// Minutes between re-reads of the registry which don't wait for a change notification, where 0 turns them off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) struct PollInterval(pub(crate) u32);
+
+impl PollInterval {
+    pub(crate) const PRESETS: [PollInterval; 4] = [PollInterval(0), PollInterval(5), PollInterval(15), PollInterval(60)];
+
+    pub(crate) fn duration(&self) -> Option<Duration> {
+        match self.0 {
+            0 => None,
+            minutes => Some(Duration::from_secs(u64::from(minutes) * 60)),
+        }
+    }
+}
+
+impl Default for PollInterval {
+    fn default() -> Self {
+        PollInterval(15)
+    }
+}
+
+impl Display for PollInterval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name: String = match self.0 {
+            0 => tr("poll-interval-off"),
+            minutes => tr_args("poll-interval-minutes", &[("minutes", FluentValue::from(minutes))]),
+        };
+        write!(f, "{name}")
+    }
+}
+
+// How long the user can pause protection for, in minutes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PauseDuration(pub(crate) u32);
+
+impl PauseDuration {
+    pub(crate) const PRESETS: [PauseDuration; 3] = [PauseDuration(15), PauseDuration(60), PauseDuration(4 * 60)];
+
+    pub(crate) fn duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(i64::from(self.0))
+    }
+}
+
+impl Display for PauseDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name: String = match self.0 % 60 {
+            0 => tr_args("pause-duration-hours", &[("hours", FluentValue::from(self.0 / 60))]),
+            _ => tr_args("pause-duration-minutes", &[("minutes", FluentValue::from(self.0))]),
+        };
+        write!(f, "{name}")
+    }
+}
+
+// Hours between scans which watch every key anew and evaluate every check, where 0 turns them off. Unlike a poll,
+// a scan also catches a key which was deleted and created again, which the watcher on the old key never hears of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) struct ScanInterval(pub(crate) u32);
+
+impl ScanInterval {
+    pub(crate) const PRESETS: [ScanInterval; 4] = [ScanInterval(0), ScanInterval(1), ScanInterval(6), ScanInterval(24)];
+
+    pub(crate) fn duration(&self) -> Option<Duration> {
+        match self.0 {
+            0 => None,
+            hours => Some(Duration::from_secs(u64::from(hours) * 60 * 60)),
+        }
+    }
+}
+
+impl Default for ScanInterval {
+    fn default() -> Self {
+        ScanInterval(1)
+    }
+}
+
+impl Display for ScanInterval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name: String = match self.0 {
+            0 => tr("scan-interval-off"),
+            hours => tr_args("scan-interval-hours", &[("hours", FluentValue::from(hours))]),
+        };
+        write!(f, "{name}")
+    }
+}
+
+fn config_file_path() -> Result<PathBuf> {
+    Ok(paths::app_data_dir()?.join(CONFIG_FILE_NAME))
+}
+
+impl Config {
+    // Whether the user paused protection, and the pause hasn't run out yet
+    pub(crate) fn protection_paused(&self, now: DateTime<Local>) -> bool {
+        self.paused_until.is_some_and(|paused_until| now < paused_until)
+    }
+}
+
+// Reads the config file, falling back to defaults if it does not exist yet.
+#[instrument]
+pub(crate) fn load() -> Result<Config> {
+    let path: PathBuf = config_file_path()?;
+
+    let contents: String = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            trace!("No config file found at {:?}, using defaults", path);
+            return Ok(Config::default());
+        },
+        Err(error) => return Err(err::ConfigError::FailedToRead { path, source: error }.into())
+    };
+
+    toml::from_str(&contents)
+        .map_err(|error| err::ConfigError::FailedToParse { path, source: error }.into())
+}
+
+#[instrument]
+pub(crate) fn save(config: &Config) -> Result<()> {
+    let path: PathBuf = config_file_path()?;
+    let contents: String = toml::to_string_pretty(config)?;
+
+    std::fs::write(&path, contents)
+        .map_err(|error| err::ConfigError::FailedToWrite { path, source: error }.into())
+}
@@ -0,0 +1,80 @@
+use std::backtrace::Backtrace;
+use std::panic::PanicInfo;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use chrono::Local;
+use windows_sys::Win32::UI::WindowsAndMessaging::{MB_ICONERROR, MB_OK, MB_SETFOREGROUND, MessageBoxW};
+
+use crate::build_info;
+use crate::build_info::APPLICATION_DISPLAY_NAME;
+use crate::i18n::tr_args;
+use crate::paths;
+use crate::windows_ops;
+
+// Name of the directory under the app data directory which holds crash reports.
+const CRASH_REPORT_DIR_NAME: &str = "crashes";
+
+// A description of what the app was doing, kept up to date so a crash report can include it
+static STATE_SNAPSHOT: Mutex<String> = Mutex::new(String::new());
+
+// Replaces what the crash report says about the app's state
+pub(crate) fn record_state_snapshot(snapshot: String) {
+    // a poisoned lock means a panic is already being reported
+    if let Ok(mut state_snapshot) = STATE_SNAPSHOT.lock() {
+        *state_snapshot = snapshot;
+    }
+}
+
+// There's no console to print to with windows_subsystem = "windows", so a panic would otherwise
+// close the app without a trace. Write a crash report and tell the user where to find it instead.
+pub(crate) fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        default_hook(panic_info);
+
+        let report: String = build_crash_report(panic_info);
+        let message: String = match write_crash_report(&report) {
+            Ok(path) => tr_args("crash-dialog-message", &[("path", path.display().to_string().into())]),
+            Err(error) => tr_args("crash-dialog-message-unsaved", &[("error", format!("{:#}", error).into())]),
+        };
+        show_crash_dialog(&message);
+    }));
+}
+
+fn build_crash_report(panic_info: &PanicInfo) -> String {
+    let state_snapshot: String = match STATE_SNAPSHOT.try_lock() {
+        Ok(state_snapshot) => state_snapshot.clone(),
+        Err(_) => String::from("unavailable"),
+    };
+
+    format!(
+        "{} {} (built {})\nTime: {}\nThread: {}\nPanic: {}\n\nState:\n{}\n\nBacktrace:\n{}\n",
+        APPLICATION_DISPLAY_NAME,
+        build_info::VERSION,
+        build_info::build_timestamp(),
+        Local::now().format("%Y-%m-%d %H:%M:%S %z"),
+        std::thread::current().name().unwrap_or("unnamed"),
+        panic_info,
+        state_snapshot,
+        Backtrace::force_capture(),
+    )
+}
+
+fn write_crash_report(report: &str) -> Result<PathBuf> {
+    let crash_report_dir: PathBuf = paths::app_data_dir()?.join(CRASH_REPORT_DIR_NAME);
+    std::fs::create_dir_all(&crash_report_dir)?;
+
+    let path: PathBuf = crash_report_dir.join(format!("crash-{}.txt", Local::now().format("%Y%m%d-%H%M%S")));
+    std::fs::write(&path, report)?;
+    Ok(path)
+}
+
+fn show_crash_dialog(message: &str) {
+    let wide_message: Vec<u16> = windows_ops::to_wide_string(message);
+    let wide_title: Vec<u16> = windows_ops::to_wide_string(APPLICATION_DISPLAY_NAME);
+    unsafe {
+        MessageBoxW(0, wide_message.as_ptr(), wide_title.as_ptr(), MB_OK | MB_ICONERROR | MB_SETFOREGROUND);
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use tracing::{instrument, trace};
+use windows::Win32::Foundation::E_ACCESSDENIED;
+use windows_sys::Win32::Foundation::{ERROR_ACCESS_DENIED, GetLastError};
+use windows_sys::Win32::UI::Shell::ShellExecuteW;
+use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+use crate::err;
+use crate::windows_ops;
+
+// The command line flag which carries a `PendingAction` over to the elevated instance
+const PENDING_ACTION_FLAG: &str = "--pending-action";
+
+// Something the user asked for which needs administrator rights, to be finished by the elevated instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum PendingAction {
+    TurnOffFileExtensionHiding,
+    TurnOffFileExtensionHidingForAllUsers,
+    TurnOffFileExtensionHidingForLoadedUsers,
+    TurnOffFileExtensionHidingForNewAccounts,
+    RunAtStartup,
+    TurnOnChangeAttribution,
+    TurnOffChangeAttribution,
+    TurnOnChangeTracing,
+}
+
+// Whether retrying as administrator could get past the error
+pub(crate) fn is_access_denied(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        let io_access_denied: bool = cause.downcast_ref::<std::io::Error>()
+            .is_some_and(|io_error| io_error.raw_os_error() == Some(ERROR_ACCESS_DENIED as i32));
+        // COM APIs such as Task Scheduler's report it as an HRESULT instead
+        let com_access_denied: bool = cause.downcast_ref::<windows::core::Error>()
+            .is_some_and(|com_error| com_error.code() == E_ACCESSDENIED);
+        io_access_denied || com_access_denied
+    })
+}
+
+// Starts another instance of this program as administrator, which Windows asks the user to confirm.
+// The new instance carries out the pending action once it starts. The caller should exit afterward.
+#[instrument]
+pub(crate) fn relaunch_elevated(pending_action: PendingAction) -> Result<()> {
+    let current_exe_path: PathBuf = std::env::current_exe()?;
+    let current_exe_path_str: &str = current_exe_path.to_str()
+        .ok_or(err::NonUtf8ExecutablePathError)?;
+
+    let pending_action_name: String = pending_action.to_possible_value()
+        .map(|value| String::from(value.get_name()))
+        .ok_or(err::ElevationError::UnnamedPendingAction)?;
+    let parameters: String = build_parameters(&pending_action_name);
+    trace!("Relaunching as administrator with parameters {}", parameters);
+
+    let verb: Vec<u16> = windows_ops::to_wide_string("runas");
+    let file: Vec<u16> = windows_ops::to_wide_string(current_exe_path_str);
+    let parameters: Vec<u16> = windows_ops::to_wide_string(&parameters);
+    let result: isize = unsafe {
+        ShellExecuteW(0, verb.as_ptr(), file.as_ptr(), parameters.as_ptr(), std::ptr::null(), SW_SHOWNORMAL as i32)
+    };
+
+    // anything at or below 32 is an error code, e.g. when the user declines the UAC prompt
+    match result {
+        0..=32 => Err(err::ElevationError::FailedToRelaunch(unsafe { GetLastError() }).into()),
+        _ => Ok(())
+    }
+}
+
+// Passes through this instance's own arguments, minus any earlier pending action.
+// The elevated instance starts long after sign-in, so it shouldn't wait out the startup delay again.
+fn build_parameters(pending_action_name: &str) -> String {
+    let mut arguments: Vec<String> = Vec::new();
+    let mut original_arguments = std::env::args().skip(1);
+    while let Some(argument) = original_arguments.next() {
+        if argument == PENDING_ACTION_FLAG {
+            original_arguments.next();
+        } else if argument == windows_ops::AT_LOGON_FLAG {
+            continue;
+        } else if !argument.starts_with(&format!("{PENDING_ACTION_FLAG}=")) {
+            arguments.push(argument);
+        }
+    }
+    arguments.push(String::from(PENDING_ACTION_FLAG));
+    arguments.push(String::from(pending_action_name));
+
+    arguments.iter()
+        .map(|argument| match argument.contains(' ') {
+            true => format!("\"{argument}\""),
+            false => argument.clone(),
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -1,35 +1,434 @@
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-#[error("The current executable path could not be converted into a UTF-8 string")]
-pub(crate) struct NonUtf8ExecutablePathError;
-
-#[derive(Error, Debug)]
-pub(crate) enum RegistryOpsError {
-    #[error("Failed to look up registry data for key {key} and value {value} with error")]
-    FailedToGetValueData {
-        key: String,
-        value: String,
-        source: std::io::Error,
-    }
-}
-
-#[derive(Error, Debug)]
-#[error("Failed to find a running process with name {0}")]
-pub(crate) struct ProcessNotFoundError(pub(crate) String);
-
-#[derive(Error, Debug)]
-#[error("Failed to restart Windows Explorer in order for it to pick up registry changes")]
-pub(crate) struct UnableToRestartWindowsExplorer;
-
-#[derive(Error, Debug)]
-pub(crate) enum IconLoadingError {
-    #[error("Failed to load this program's icon")]
-    FailedToLoadIconBytes(#[source] anyhow::Error),
-
-    #[error("Failed to construct this program's tray icon")]
-    FailedToConstructTrayIcon(#[source] Box<dyn std::error::Error + Send + Sync>),
-
-    #[error("Failed to construct this program's window icon")]
-    FailedToConstructWindowIcon(#[source] Box<dyn std::error::Error + Send + Sync>)
-}
+use thiserror::Error;
+
+// errors of the registry, process and localization logic, which lives in the core library
+pub(crate) use no_hidden_extensions_core::err::*;
+
+#[derive(Error, Debug)]
+pub(crate) enum ElevationError {
+    #[error("The pending action has no command line name")]
+    UnnamedPendingAction,
+
+    #[error("Failed to restart this program as administrator, error code {0}")]
+    FailedToRelaunch(u32),
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum IconLoadingError {
+    #[error("Failed to construct this program's tray icon")]
+    FailedToConstructTrayIcon(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Failed to construct this program's window icon")]
+    FailedToConstructWindowIcon(#[source] Box<dyn std::error::Error + Send + Sync>)
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum AppDataDirError {
+    #[error("The LOCALAPPDATA environment variable is not set")]
+    LocalAppDataNotSet,
+
+    #[error("Failed to create this program's data directory at {path}")]
+    FailedToCreate {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to delete this program's data directory at {path}")]
+    FailedToRemove {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    }
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum HistoryStorageError {
+    #[error("Failed to read the notification history file at {path}")]
+    FailedToRead {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to write to the notification history file at {path}")]
+    FailedToWrite {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    }
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum ConfigError {
+    #[error("Failed to read the config file at {path}")]
+    FailedToRead {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse the config file at {path}")]
+    FailedToParse {
+        path: std::path::PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error("Failed to write the config file at {path}")]
+    FailedToWrite {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to play the system sound {0}")]
+pub(crate) struct SoundError(pub(crate) String);
+
+#[derive(Error, Debug)]
+pub(crate) enum AccessibilityError {
+    #[error("The channel carrying screen reader actions was poisoned")]
+    ActionChannelPoisoned,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum PowerNotificationError {
+    #[error("Failed to register for sleep and resume notifications, error code {0}")]
+    FailedToRegister(u32),
+    #[error("The channel carrying resume notifications was poisoned")]
+    ResumeChannelPoisoned,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum CheckError {
+    #[error("The {0} check can only report what it finds, not fix it")]
+    CannotRemediate(String),
+
+    #[error("The {0} check has no way to undo its fix")]
+    CannotUndo(String),
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum WmiError {
+    #[error("Failed to connect to the WMI namespace {namespace}")]
+    FailedToConnect {
+        namespace: String,
+        source: windows::core::Error,
+    },
+
+    #[error("Failed to run the WMI query {query}")]
+    FailedToQuery {
+        query: String,
+        source: windows::core::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum AssociationBackupError {
+    #[error("Failed to read the association backup at {path}")]
+    FailedToRead {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse the association backup at {path}")]
+    FailedToParse {
+        path: std::path::PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error("Failed to write the association backup at {path}")]
+    FailedToWrite {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum FolderWatchError {
+    #[error("Failed to find the Downloads folder, error code {0:#x}")]
+    FailedToFindDownloads(i32),
+
+    #[error("Failed to open {path} for watching, error code {error_code}")]
+    FailedToOpen {
+        path: std::path::PathBuf,
+        error_code: u32,
+    },
+
+    #[error("Failed to wait for new files, error code {0}")]
+    FailedToWait(u32),
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to read the mark of the web on {path}")]
+pub(crate) struct MarkOfTheWebError {
+    pub(crate) path: std::path::PathBuf,
+    pub(crate) source: std::io::Error,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum DefuseError {
+    #[error("{0} already exists")]
+    AlreadyExists(std::path::PathBuf),
+
+    #[error("Failed to rename {from}")]
+    FailedToRename {
+        from: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to record the rename in {path}")]
+    FailedToLog {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to access the audit log at {path}")]
+pub(crate) struct AuditLogError {
+    pub(crate) path: std::path::PathBuf,
+    pub(crate) source: std::io::Error,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum RegistryAuditError {
+    #[error("Failed to turn on auditing of registry changes, error code {0}")]
+    FailedToEnableAuditPolicy(u32),
+
+    #[error("Failed to build the audit entries for the registry key, error code {0}")]
+    FailedToBuildSacl(u32),
+
+    #[error("Failed to set the audit entries on the registry key, error code {0}")]
+    FailedToSetSacl(u32),
+
+    #[error("Failed to search the Security event log, error code {0}")]
+    FailedToQueryEvents(u32),
+
+    #[error("Failed to read an event from the Security event log, error code {0}")]
+    FailedToReadEvent(u32),
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum ChangeTraceError {
+    #[error("Failed to start the registry trace session, error code {0}")]
+    FailedToStart(u32),
+
+    #[error("Failed to enable the kernel registry provider, error code {0}")]
+    FailedToEnableProvider(u32),
+
+    #[error("Failed to open the registry trace, error code {0}")]
+    FailedToOpen(u32),
+
+    #[error("The registry trace stopped")]
+    Stopped,
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to export the audit log to {path}")]
+pub(crate) struct ExportError {
+    pub(crate) path: std::path::PathBuf,
+    pub(crate) source: std::io::Error,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum HttpError {
+    #[error("{0} is not a valid URL")]
+    InvalidUrl(String),
+
+    #[error("Failed to reach {url}, error code {error_code}")]
+    FailedToSend {
+        url: String,
+        error_code: u32,
+    },
+
+    #[error("{url} answered with status {status_code}")]
+    Rejected {
+        url: String,
+        status_code: u32,
+    },
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to send to the syslog server {host}")]
+pub(crate) struct SyslogError {
+    pub(crate) host: String,
+    pub(crate) source: std::io::Error,
+}
+
+#[derive(Error, Debug)]
+#[error("Failed to write to the Windows event log, error code {0}")]
+pub(crate) struct EventLogError(pub(crate) u32);
+
+#[derive(Error, Debug)]
+#[error("Failed to serve metrics on port {port}")]
+pub(crate) struct MetricsError {
+    pub(crate) port: u16,
+    pub(crate) source: std::io::Error,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum IpcError {
+    #[error("Failed to create the named pipe, error code {0}")]
+    FailedToCreatePipe(u32),
+    #[error("Failed to wait for a client on the named pipe, error code {0}")]
+    FailedToConnect(u32),
+    #[error("Failed to read a request from the named pipe, error code {0}")]
+    FailedToRead(u32),
+    #[error("Failed to write a response to the named pipe, error code {0}")]
+    FailedToWrite(u32),
+    #[error("The channel carrying requests from the named pipe was poisoned")]
+    RequestChannelPoisoned,
+    #[error("Failed to send a request to the running instance, error code {0}")]
+    FailedToSend(u32),
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum SingleInstanceError {
+    #[error("Failed to create the mutex which keeps this program to one instance, error code {0}")]
+    FailedToCreateMutex(u32),
+    #[error("Failed to wait for the running instance to exit, error code {0}")]
+    FailedToWait(u32),
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum HotkeyError {
+    #[error("\"{0}\" is not a hotkey. Use e.g. Ctrl+Alt+E.")]
+    Invalid(String),
+    #[error("Failed to register the hotkey {hotkey}, which another program may already use, error code {error_code}")]
+    FailedToRegister {
+        hotkey: String,
+        error_code: u32,
+    },
+    #[error("Stopped listening for hotkeys")]
+    Stopped,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum StartupTaskError {
+    #[error("Failed to connect to Task Scheduler")]
+    FailedToConnect(#[source] windows::core::Error),
+    #[error("Failed to create the scheduled task which starts this program")]
+    FailedToRegister(#[source] windows::core::Error),
+    #[error("Failed to delete the scheduled task which starts this program")]
+    FailedToDelete(#[source] windows::core::Error),
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum InstallError {
+    #[error("The APPDATA environment variable is not set")]
+    AppDataNotSet,
+    #[error("The running instance didn't exit")]
+    InstanceStillRunning,
+    #[error("Failed to copy this program to {path}")]
+    FailedToCopy {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to create the Start menu shortcut at {path}")]
+    FailedToCreateShortcut {
+        path: std::path::PathBuf,
+        source: windows::core::Error,
+    },
+    #[error("Failed to start {path}")]
+    FailedToLaunch {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to delete {path}")]
+    FailedToDelete {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+#[error("Some of what this program created is still there: {}", .0.join(", "))]
+pub(crate) struct CleanupError(pub(crate) Vec<String>);
+
+#[derive(Error, Debug)]
+#[error("\"{0}\" is not a version number")]
+pub(crate) struct InvalidVersionError(pub(crate) String);
+
+#[derive(Error, Debug)]
+pub(crate) enum SelfUpdateError {
+    #[error("Version {0} doesn't include an executable and its hash to update to")]
+    MissingAssets(String),
+    #[error("The downloaded update's hash {actual} doesn't match the published hash {expected}")]
+    HashMismatch {
+        expected: String,
+        actual: String,
+    },
+    #[error("Failed to hash the downloaded update, status {0:#x}")]
+    FailedToHash(i32),
+    #[error("The downloaded update isn't validly signed, status {0:#x}")]
+    UntrustedSignature(i32),
+    #[error("The downloaded update is signed by {actual} rather than {expected}")]
+    UnexpectedSigner {
+        expected: String,
+        actual: String,
+    },
+    #[error("Failed to replace {path}")]
+    FailedToReplace {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to start the updated executable")]
+    FailedToRelaunch(#[source] std::io::Error),
+}
+
+#[derive(Error, Debug)]
+#[error("Logging hasn't been set up yet")]
+pub(crate) struct LoggingNotInitializedError;
+
+#[derive(Error, Debug)]
+pub(crate) enum AdvancedKeyBackupError {
+    #[error("Failed to read the Advanced key backup at {path}")]
+    FailedToRead {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse the Advanced key backup at {path}")]
+    FailedToParse {
+        path: std::path::PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error("Failed to write the Advanced key backup at {path}")]
+    FailedToWrite {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum RollbackManifestError {
+    #[error("Failed to read the rollback manifest at {path}")]
+    FailedToRead {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse the rollback manifest at {path}")]
+    FailedToParse {
+        path: std::path::PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error("Failed to write the rollback manifest at {path}")]
+    FailedToWrite {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+#[error("This build doesn't include the {0} feature")]
+pub(crate) struct FeatureDisabledError(pub(crate) &'static str);
+
+#[derive(Error, Debug)]
+pub(crate) enum SessionNotificationError {
+    #[error("Failed to create the window which receives session changes, error code {0}")]
+    FailedToCreateWindow(u32),
+    #[error("Failed to register for session lock and unlock notifications, error code {0}")]
+    FailedToRegister(u32),
+    #[error("The thread listening for session changes stopped")]
+    ListenerStopped,
+    #[error("The channel carrying session changes was poisoned")]
+    SessionChannelPoisoned,
+}
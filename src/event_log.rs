@@ -0,0 +1,81 @@
+use anyhow::Result;
+use tracing::{instrument, warn};
+use windows_sys::Win32::Foundation::{GetLastError, HANDLE};
+use windows_sys::Win32::System::EventLog::{
+    DeregisterEventSource, EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE, RegisterEventSourceW, ReportEventW,
+};
+
+use winreg::RegKey;
+use winreg::enums::HKEY_LOCAL_MACHINE;
+
+use crate::audit_log::AuditEntry;
+use crate::err;
+use crate::event_sinks;
+use crate::event_sinks::EventSink;
+use crate::windows_ops;
+
+// Shows up as the source in Event Viewer. Without a message file registered for it, Event Viewer says it can't
+// find the description, but still shows the text after that.
+const EVENT_SOURCE_NAME: &str = "no-hidden-extensions";
+const SETTING_CHANGED_EVENT_ID: u32 = 1;
+const EVENT_SOURCE_REGKEY_SUBPATH: &str = "SYSTEM\\CurrentControlSet\\Services\\EventLog\\Application\\no-hidden-extensions";
+
+// Writes every change to the Windows Application event log, where Windows Event Forwarding can pick it up
+#[derive(Debug)]
+pub(crate) struct EventLogSink;
+
+impl EventSink for EventLogSink {
+    fn name(&self) -> &'static str {
+        "Windows event log"
+    }
+
+    #[instrument]
+    fn send(&self, entry: &AuditEntry) -> Result<()> {
+        let source_name: Vec<u16> = windows_ops::to_wide_string(EVENT_SOURCE_NAME);
+        let event_source: HANDLE = unsafe { RegisterEventSourceW(std::ptr::null(), source_name.as_ptr()) };
+        if event_source == 0 {
+            return Err(err::EventLogError(unsafe { GetLastError() }).into());
+        }
+
+        let event_type = match entry.is_incident() {
+            true => EVENTLOG_WARNING_TYPE,
+            false => EVENTLOG_INFORMATION_TYPE,
+        };
+        let message: Vec<u16> = windows_ops::to_wide_string(&event_sinks::describe(entry));
+        let strings: [*const u16; 1] = [message.as_ptr()];
+        let reported = unsafe {
+            ReportEventW(
+                event_source,
+                event_type,
+                0,
+                SETTING_CHANGED_EVENT_ID,
+                std::ptr::null_mut(),
+                strings.len() as u16,
+                0,
+                strings.as_ptr(),
+                std::ptr::null(),
+            )
+        };
+        let result: Result<()> = match reported {
+            0 => Err(err::EventLogError(unsafe { GetLastError() }).into()),
+            _ => Ok(()),
+        };
+
+        if unsafe { DeregisterEventSource(event_source) } == 0 {
+            warn!("Failed to close the event log, error code {}", unsafe { GetLastError() });
+        }
+        result
+    }
+}
+
+// Deletes the event source's registration, which this program doesn't create itself, but which an administrator
+// may have added to give Event Viewer a message file. Needs administrator rights if there is one.
+// This method returns whether a change was made.
+#[instrument]
+pub(crate) fn remove_source_registration() -> Result<bool> {
+    return match RegKey::predef(HKEY_LOCAL_MACHINE).delete_subkey_all(EVENT_SOURCE_REGKEY_SUBPATH) {
+        Ok(()) => Ok(true),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(error.into()),
+    };
+}
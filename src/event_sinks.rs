@@ -0,0 +1,71 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use anyhow::Result;
+use tracing::warn;
+
+use crate::audit_log::AuditEntry;
+use crate::config::Config;
+use crate::event_log::EventLogSink;
+use crate::syslog::SyslogSink;
+use crate::webhook;
+use crate::webhook::WebhookSink;
+
+// Somewhere outside this program which hears about every change to HideFileExt, such as a SIEM.
+// A new destination only has to implement this and be added to `configured_sinks()`.
+pub(crate) trait EventSink: Debug + Send + Sync {
+    // Shown in the log when sending fails
+    fn name(&self) -> &'static str;
+
+    fn send(&self, entry: &AuditEntry) -> Result<()>;
+}
+
+// Every sink the config file turns on
+pub(crate) fn configured_sinks(config: &Config) -> Vec<Arc<dyn EventSink>> {
+    let mut sinks: Vec<Arc<dyn EventSink>> = Vec::new();
+    if let Some(webhook_url) = &config.webhook_url {
+        // the config file may have been edited by hand, so the URL is checked here as well as in the window
+        match webhook::parse_url(webhook_url) {
+            Ok(webhook_url) => sinks.push(Arc::new(WebhookSink::new(&webhook_url))),
+            Err(error) => warn!("Not posting changes to the webhook: {:?}", error),
+        }
+    }
+    if let Some(syslog) = &config.syslog {
+        sinks.push(Arc::new(SyslogSink::new(syslog.clone())));
+    }
+    if config.write_to_event_log {
+        sinks.push(Arc::new(EventLogSink));
+    }
+    sinks
+}
+
+// Sends the change on a thread of its own, so a slow or unreachable server can't hold up the UI.
+// Failures are only logged, since the change itself is already in the audit log.
+pub(crate) fn forward_in_background(sinks: Vec<Arc<dyn EventSink>>, entry: AuditEntry) {
+    if sinks.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        for sink in sinks {
+            if let Err(error) = sink.send(&entry) {
+                warn!("Failed to forward the change to the {}: {:?}", sink.name(), error);
+            }
+        }
+    });
+}
+
+// One line about the change for logs which are read by people, e.g. "HideFileExt for CurrentUser changed from 1 to 0"
+pub(crate) fn describe(entry: &AuditEntry) -> String {
+    let describe_value = |value: Option<u32>| match value {
+        Some(value) => value.to_string(),
+        None => String::from("not set"),
+    };
+    let changed_by: &str = match entry.remediated_by_app {
+        true => "by no-hidden-extensions",
+        false => "by another program",
+    };
+    format!(
+        "HideFileExt for {:?} changed from {} to {} {}",
+        entry.scope, describe_value(entry.old_value), describe_value(entry.new_value), changed_by
+    )
+}
@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::ValueEnum;
+use fluent_bundle::FluentValue;
+use tracing::{instrument, trace, warn};
+use windows_sys::Win32::UI::Controls::Dialogs::{
+    CommDlgExtendedError, GetSaveFileNameW, OFN_OVERWRITEPROMPT, OFN_PATHMUSTEXIST, OPENFILENAMEW,
+};
+
+use crate::audit_log;
+use crate::audit_log::AuditEntry;
+use crate::err;
+use crate::i18n::{tr, tr_args};
+use crate::windows_ops;
+
+// Offered by the save dialog in this order, which its filter index counts from 1
+const SAVE_DIALOG_FILTER: &str = "CSV (*.csv)\0*.csv\0JSON (*.json)\0*.json\0";
+const CSV_FILTER_INDEX: u32 = 1;
+
+// Longest path the save dialog can hand back
+const MAX_PATH_LENGTH: usize = 32 * 1024;
+
+const CSV_HEADER: &str = "timestamp,scope,old_value,new_value,remediated_by_app";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    // Goes by the file extension, falling back to CSV since spreadsheets open it
+    pub(crate) fn from_path(path: &Path) -> ExportFormat {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => ExportFormat::Json,
+            _ => ExportFormat::Csv,
+        }
+    }
+}
+
+// Writes every change in the audit log to a file, replacing whatever was there.
+// This method returns how many changes were written.
+#[instrument]
+pub(crate) fn export(path: &Path, format: ExportFormat) -> Result<usize> {
+    let entries: Vec<AuditEntry> = audit_log::load()?;
+    let contents: String = match format {
+        ExportFormat::Csv => to_csv(&entries),
+        ExportFormat::Json => serde_json::to_string_pretty(&entries)?,
+    };
+    std::fs::write(path, contents)
+        .map_err(|error| err::ExportError { path: path.to_path_buf(), source: error })?;
+    trace!("Exported {} audit log entries to {:?}", entries.len(), path);
+    Ok(entries.len())
+}
+
+// Tells the user how many changes went where
+pub(crate) fn summary(count: usize, path: &Path) -> String {
+    tr_args("export-summary", &[
+        ("count", FluentValue::from(count)),
+        ("path", FluentValue::from(path.display().to_string())),
+    ])
+}
+
+// None of the fields can hold a comma or quote, so nothing has to be escaped
+fn to_csv(entries: &[AuditEntry]) -> String {
+    let optional_value = |value: Option<u32>| value.map(|value| value.to_string()).unwrap_or_default();
+    let mut csv: String = format!("{CSV_HEADER}\r\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{:?},{},{},{}\r\n",
+            entry.timestamp.to_rfc3339(),
+            entry.scope,
+            optional_value(entry.old_value),
+            optional_value(entry.new_value),
+            entry.remediated_by_app,
+        ));
+    }
+    csv
+}
+
+// Asks the user where to export to, with Windows' own save dialog. Blocks until they pick a file or cancel.
+pub(crate) fn choose_export_path() -> Option<(PathBuf, ExportFormat)> {
+    let filter: Vec<u16> = windows_ops::to_wide_string(SAVE_DIALOG_FILTER);
+    let title: Vec<u16> = windows_ops::to_wide_string(&tr("export-dialog-title"));
+    let default_extension: Vec<u16> = windows_ops::to_wide_string("csv");
+    let mut file_name: Vec<u16> = vec![0; MAX_PATH_LENGTH];
+
+    let mut dialog: OPENFILENAMEW = unsafe { std::mem::zeroed() };
+    dialog.lStructSize = std::mem::size_of::<OPENFILENAMEW>() as u32;
+    dialog.hwndOwner = windows_ops::find_own_window().unwrap_or(0);
+    dialog.lpstrFilter = filter.as_ptr();
+    dialog.nFilterIndex = CSV_FILTER_INDEX;
+    dialog.lpstrFile = file_name.as_mut_ptr();
+    dialog.nMaxFile = file_name.len() as u32;
+    dialog.lpstrTitle = title.as_ptr();
+    dialog.lpstrDefExt = default_extension.as_ptr();
+    dialog.Flags = OFN_OVERWRITEPROMPT | OFN_PATHMUSTEXIST;
+
+    if unsafe { GetSaveFileNameW(&mut dialog) } == 0 {
+        // no extended error means the user cancelled
+        let error_code: u32 = unsafe { CommDlgExtendedError() };
+        if error_code != 0 {
+            warn!("The save dialog failed, error code {:#x}", error_code);
+        }
+        return None;
+    }
+
+    let length: usize = file_name.iter().position(|character| *character == 0).unwrap_or(file_name.len());
+    let path: PathBuf = PathBuf::from(String::from_utf16_lossy(&file_name[..length]));
+    let format: ExportFormat = match dialog.nFilterIndex {
+        CSV_FILTER_INDEX => ExportFormat::Csv,
+        _ => ExportFormat::Json,
+    };
+    Some((path, format))
+}
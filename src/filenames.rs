@@ -0,0 +1,189 @@
+// Spots file names which use Unicode to make a program look like a document, even with extensions visible
+
+// Extensions which Windows runs as code on double-click
+const EXECUTABLE_EXTENSIONS: [&str; 21] = [
+    "exe", "scr", "com", "pif", "bat", "cmd", "msi", "msp", "lnk", "cpl", "hta", "js", "jse", "vbs", "vbe", "wsf",
+    "wsh", "ps1", "jar", "reg", "url",
+];
+
+// Characters which reorder the text after them. U+202E, RIGHT-TO-LEFT OVERRIDE, is the classic:
+// "invoice\u{202E}fdp.exe" shows as "invoiceexe.pdf".
+const BIDI_CONTROLS: [char; 12] = [
+    '\u{200E}', '\u{200F}', '\u{061C}', '\u{202A}', '\u{202B}', '\u{202C}', '\u{202D}', '\u{202E}',
+    '\u{2066}', '\u{2067}', '\u{2068}', '\u{2069}',
+];
+
+// Characters which take up no space or show as blank, e.g. to pad a fake extension
+const INVISIBLE_CHARACTERS: [char; 8] = [
+    '\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}', '\u{180E}', '\u{115F}', '\u{3164}',
+];
+
+// Characters which look like a dot, so "invoice\u{2024}pdf.exe" passes for a PDF with its real extension tacked on
+const LOOKALIKE_DOTS: [char; 6] = ['\u{2024}', '\u{FE52}', '\u{FF0E}', '\u{3002}', '\u{0701}', '\u{A4F8}'];
+
+// Cyrillic and Greek letters which are drawn like Latin ones, e.g. to spell "pdf" without writing an extension
+const LOOKALIKE_LETTERS: [char; 20] = [
+    'а', 'с', 'ԁ', 'е', 'һ', 'і', 'ј', 'к', 'о', 'р', 'ѕ', 'х', 'у', 'ԝ', 'α', 'ο', 'ρ', 'ν', 'τ', 'ι',
+];
+
+// Extensions of files people expect to open safely, which a program's name may end in before its real extension
+const DOCUMENT_EXTENSIONS: [&str; 18] = [
+    "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "txt", "rtf", "csv", "jpg", "jpeg", "png", "gif", "mp3",
+    "mp4", "zip", "html",
+];
+
+// This many spaces in a row push the real extension out of view in most columns
+const PADDING_RUN_LENGTH: usize = 5;
+
+// One way a file name hides what it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FilenameTrick {
+    BidiControl(char),
+    InvisibleCharacter(char),
+    LookalikeDot(char),
+    // only counted in names which mix it with Latin letters, since a name written in Cyrillic is just that
+    LookalikeLetter(char),
+    // a long run of spaces before the real extension
+    Padding,
+    // a document's extension right before the real one, e.g. "invoice.pdf.exe"
+    DoubleExtension,
+}
+
+// The extension Windows goes by, lowercased, e.g. "exe" for "invoice.pdf.exe"
+pub(crate) fn real_extension(file_name: &str) -> Option<String> {
+    let (_, extension) = without_trailing_dots_and_spaces(file_name).rsplit_once('.')?;
+    Some(extension.to_lowercase())
+}
+
+// Windows drops trailing dots and spaces when it opens a file, so "invoice.exe. " runs as "invoice.exe"
+fn without_trailing_dots_and_spaces(file_name: &str) -> &str {
+    file_name.trim_end_matches(['.', ' '])
+}
+
+pub(crate) fn is_executable(file_name: &str) -> bool {
+    real_extension(file_name)
+        .map(|extension| EXECUTABLE_EXTENSIONS.contains(&extension.as_str()))
+        .unwrap_or(false)
+}
+
+// Every trick in a file name, in the order they appear. Each trick is listed once, however often it's used.
+pub(crate) fn find_tricks(file_name: &str) -> Vec<FilenameTrick> {
+    let has_latin_letters: bool = file_name.chars().any(|character| character.is_ascii_alphabetic());
+
+    let mut tricks: Vec<FilenameTrick> = Vec::new();
+    for character in file_name.chars() {
+        let trick: Option<FilenameTrick> = if BIDI_CONTROLS.contains(&character) {
+            Some(FilenameTrick::BidiControl(character))
+        } else if INVISIBLE_CHARACTERS.contains(&character) {
+            Some(FilenameTrick::InvisibleCharacter(character))
+        } else if LOOKALIKE_DOTS.contains(&character) {
+            Some(FilenameTrick::LookalikeDot(character))
+        } else if has_latin_letters && LOOKALIKE_LETTERS.contains(&character) {
+            Some(FilenameTrick::LookalikeLetter(character))
+        } else {
+            None
+        };
+        if let Some(trick) = trick.filter(|trick| !tricks.contains(trick)) {
+            tricks.push(trick);
+        }
+    }
+
+    if let Some((name_without_extension, _)) = without_trailing_dots_and_spaces(file_name).rsplit_once('.') {
+        if name_without_extension.contains(&" ".repeat(PADDING_RUN_LENGTH)) {
+            tricks.push(FilenameTrick::Padding);
+        }
+        if real_extension(name_without_extension).map_or(false, |extension| DOCUMENT_EXTENSIONS.contains(&extension.as_str())) {
+            tricks.push(FilenameTrick::DoubleExtension);
+        }
+    }
+    tricks
+}
+
+// The file name with every invisible or reordering character written out as its code point, e.g. "[U+202E]",
+// so showing it doesn't fall for the same trick
+pub(crate) fn reveal(file_name: &str) -> String {
+    file_name.chars()
+        .map(|character| match BIDI_CONTROLS.contains(&character) || INVISIBLE_CHARACTERS.contains(&character) {
+            true => format!("[U+{:04X}]", u32::from(character)),
+            false => String::from(character),
+        })
+        .collect()
+}
+
+// A program whose name uses tricks to pass for something else. Tricks in the names of documents are
+// left alone, since opening those can't run anything.
+pub(crate) fn is_disguised(file_name: &str) -> bool {
+    is_executable(file_name) && !find_tricks(file_name).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filenames::{find_tricks, FilenameTrick, is_disguised, is_executable, real_extension, reveal};
+
+    #[test]
+    fn a_document_extension_before_the_real_one_is_a_double_extension() {
+        assert_eq!(find_tricks("invoice.pdf.exe"), vec![FilenameTrick::DoubleExtension]);
+        assert_eq!(find_tricks("INVOICE.PDF.EXE"), vec![FilenameTrick::DoubleExtension]);
+        assert!(is_disguised("invoice.pdf.exe"));
+    }
+
+    #[test]
+    fn dots_within_ordinary_names_are_no_double_extension() {
+        assert!(find_tricks("setup.v2.exe").is_empty());
+        assert!(find_tricks("archive.tar.gz").is_empty());
+        assert!(!is_disguised("setup.v2.exe"));
+    }
+
+    #[test]
+    fn a_right_to_left_override_is_found_and_revealed() {
+        let file_name: &str = "invoice\u{202E}fdp.exe";
+        assert_eq!(find_tricks(file_name), vec![FilenameTrick::BidiControl('\u{202E}')]);
+        assert!(is_disguised(file_name));
+        assert_eq!(reveal(file_name), "invoice[U+202E]fdp.exe");
+    }
+
+    #[test]
+    fn other_bidi_controls_are_found_once_each() {
+        let file_name: &str = "photo\u{2067}gpj\u{2069}\u{2067}.scr";
+        assert_eq!(
+            find_tricks(file_name),
+            vec![FilenameTrick::BidiControl('\u{2067}'), FilenameTrick::BidiControl('\u{2069}')]
+        );
+        assert!(is_disguised(file_name));
+    }
+
+    #[test]
+    fn trailing_dots_and_spaces_are_dropped_like_windows_does() {
+        assert_eq!(real_extension("invoice.exe."), Some(String::from("exe")));
+        assert_eq!(real_extension("invoice.exe . ."), Some(String::from("exe")));
+        assert!(is_executable("invoice.exe "));
+        assert_eq!(find_tricks("invoice.pdf.exe. "), vec![FilenameTrick::DoubleExtension]);
+    }
+
+    #[test]
+    fn a_long_run_of_spaces_is_padding() {
+        let file_name: String = format!("invoice.pdf{}.exe", " ".repeat(40));
+        assert_eq!(find_tricks(&file_name), vec![FilenameTrick::Padding, FilenameTrick::DoubleExtension]);
+        assert!(is_disguised(&file_name));
+    }
+
+    #[test]
+    fn a_few_spaces_are_no_padding() {
+        assert!(find_tricks("my holiday    photos.exe").is_empty());
+    }
+
+    #[test]
+    fn names_without_an_extension_have_none() {
+        assert_eq!(real_extension("README"), None);
+        assert_eq!(real_extension("README..."), None);
+        assert!(!is_executable("README"));
+        assert!(find_tricks("README").is_empty());
+    }
+
+    #[test]
+    fn tricks_in_documents_are_left_alone() {
+        let file_name: &str = "report\u{202E}txt.pdf";
+        assert!(!find_tricks(file_name).is_empty());
+        assert!(!is_disguised(file_name));
+    }
+}
@@ -0,0 +1,112 @@
+use std::ffi::c_void;
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+use windows_sys::core::PWSTR;
+use windows_sys::Win32::Foundation::GetLastError;
+use windows_sys::Win32::Storage::FileSystem::{
+    CreateFileW, FILE_ACTION_ADDED, FILE_ACTION_RENAMED_NEW_NAME, FILE_FLAG_BACKUP_SEMANTICS, FILE_LIST_DIRECTORY,
+    FILE_NOTIFY_CHANGE_FILE_NAME, FILE_NOTIFY_INFORMATION, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE,
+    OPEN_EXISTING, ReadDirectoryChangesW,
+};
+use windows_sys::Win32::System::Com::CoTaskMemFree;
+use windows_sys::Win32::UI::Shell::{FOLDERID_Downloads, KF_FLAG_DEFAULT, SHGetKnownFolderPath};
+
+use crate::err;
+use crate::handle::Win32Handle;
+
+// How many bytes of change records one wait can hand back. Changes beyond that are dropped.
+const CHANGE_BUFFER_SIZE: usize = 16 * 1024;
+
+// Watches a folder, but not its subfolders, for files which appear in it
+#[derive(Debug)]
+pub(crate) struct FolderWatcher {
+    folder: PathBuf,
+    directory: Win32Handle,
+}
+
+impl FolderWatcher {
+    #[instrument]
+    pub(crate) fn new(folder: PathBuf) -> Result<FolderWatcher> {
+        let wide_path: Vec<u16> = folder.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+        // sharing everything keeps the folder usable by everything else while it's watched
+        let directory: Win32Handle = Win32Handle::from_invalid_on_failure(unsafe {
+            CreateFileW(
+                wide_path.as_ptr(),
+                FILE_LIST_DIRECTORY,
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                std::ptr::null(),
+                OPEN_EXISTING,
+                FILE_FLAG_BACKUP_SEMANTICS,
+                0,
+            )
+        }).map_err(|error_code| err::FolderWatchError::FailedToOpen { path: folder.clone(), error_code })?;
+        Ok(FolderWatcher { folder, directory })
+    }
+
+    // Blocks until files are created in or renamed into the folder, and returns their paths
+    pub(crate) fn wait_for_new_files(&self) -> Result<Vec<PathBuf>> {
+        // the records have to be aligned to a DWORD
+        let mut buffer: Vec<u32> = vec![0; CHANGE_BUFFER_SIZE / std::mem::size_of::<u32>()];
+        let mut bytes_returned: u32 = 0;
+        let succeeded: i32 = unsafe {
+            ReadDirectoryChangesW(
+                self.directory.raw(),
+                buffer.as_mut_ptr().cast::<c_void>(),
+                CHANGE_BUFFER_SIZE as u32,
+                0,
+                FILE_NOTIFY_CHANGE_FILE_NAME,
+                &mut bytes_returned,
+                std::ptr::null_mut(),
+                None,
+            )
+        };
+        if succeeded == 0 {
+            return Err(err::FolderWatchError::FailedToWait(unsafe { GetLastError() }).into());
+        }
+        if bytes_returned == 0 {
+            warn!("Too many changes in {:?} at once, some new files were missed", self.folder);
+            return Ok(Vec::new());
+        }
+
+        let mut new_files: Vec<PathBuf> = Vec::new();
+        let mut offset: usize = 0;
+        loop {
+            let record: &FILE_NOTIFY_INFORMATION = unsafe {
+                &*buffer.as_ptr().cast::<u8>().add(offset).cast::<FILE_NOTIFY_INFORMATION>()
+            };
+            if matches!(record.Action, FILE_ACTION_ADDED | FILE_ACTION_RENAMED_NEW_NAME) {
+                let name_length: usize = record.FileNameLength as usize / std::mem::size_of::<u16>();
+                let name: &[u16] = unsafe { std::slice::from_raw_parts(record.FileName.as_ptr(), name_length) };
+                new_files.push(self.folder.join(std::ffi::OsString::from_wide(name)));
+            }
+            if record.NextEntryOffset == 0 {
+                break;
+            }
+            offset += record.NextEntryOffset as usize;
+        }
+        trace!("New files in {:?}: {:?}", self.folder, new_files);
+        Ok(new_files)
+    }
+}
+
+// The current user's Downloads folder, wherever they moved it
+#[instrument]
+pub(crate) fn downloads_folder() -> Result<PathBuf> {
+    let mut path: PWSTR = std::ptr::null_mut();
+    let result: i32 = unsafe { SHGetKnownFolderPath(&FOLDERID_Downloads, KF_FLAG_DEFAULT, 0, &mut path) };
+    if result < 0 {
+        // the path has to be freed even on failure
+        unsafe { CoTaskMemFree(path.cast::<c_void>()) };
+        return Err(err::FolderWatchError::FailedToFindDownloads(result).into());
+    }
+
+    let length: usize = (0..).take_while(|index| unsafe { *path.add(*index) } != 0).count();
+    let downloads_folder: PathBuf = PathBuf::from(std::ffi::OsString::from_wide(unsafe {
+        std::slice::from_raw_parts(path, length)
+    }));
+    unsafe { CoTaskMemFree(path.cast::<c_void>()) };
+    Ok(downloads_folder)
+}
@@ -0,0 +1,262 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace};
+use winreg::HKEY;
+
+use crate::err;
+use crate::paths;
+use crate::registry::RegistryProvider;
+use crate::rules;
+use crate::rules::{RegistryRule, RuleHive};
+use crate::windows_ops;
+
+// The rule which shows protected operating system files. It's only part of the baseline when asked for,
+// since most people are better off never seeing those files.
+const SHOW_SUPER_HIDDEN_RULE_ID: &str = "protected-system-files";
+
+// Name of the directory under the app data directory which holds one rollback manifest per hardening
+const MANIFEST_DIR_NAME: &str = "rollback";
+
+// What hardening found and did for one rule
+#[derive(Debug, Clone)]
+pub(crate) struct RuleResult {
+    pub(crate) rule: RegistryRule,
+    // None where there was no value
+    pub(crate) old_data: Option<u32>,
+    // false where the value already held the desired data, and always on a dry run
+    pub(crate) changed: bool,
+}
+
+impl RuleResult {
+    pub(crate) fn needed_change(&self) -> bool {
+        self.old_data != Some(self.rule.desired_data)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Hardening {
+    pub(crate) results: Vec<RuleResult>,
+    // where the manifest for rolling the changes back went, if anything changed
+    pub(crate) manifest_path: Option<PathBuf>,
+}
+
+// Every value one hardening changed, as it was before
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct RollbackManifest {
+    pub(crate) timestamp: DateTime<Local>,
+    pub(crate) entries: Vec<RollbackEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct RollbackEntry {
+    pub(crate) rule_id: String,
+    pub(crate) hive: RuleHive,
+    pub(crate) key: String,
+    pub(crate) value: String,
+    // None where there was no value, so rolling back deletes it
+    pub(crate) old_data: Option<u32>,
+}
+
+impl RollbackEntry {
+    // The rule's name where it's still a built-in rule, or its id otherwise
+    pub(crate) fn display_name(&self) -> String {
+        match rules::built_in_rules().iter().find(|rule| rule.id == self.rule_id) {
+            Some(rule) => rule.display_name(),
+            None => self.rule_id.clone(),
+        }
+    }
+}
+
+// The built-in rules marked as part of the baseline, plus ShowSuperHidden if the user chose it
+pub(crate) fn baseline_rules(show_super_hidden: bool) -> Vec<RegistryRule> {
+    rules::built_in_rules().iter()
+        .filter(|rule| rule.baseline || (show_super_hidden && rule.id == SHOW_SUPER_HIDDEN_RULE_ID))
+        .cloned()
+        .collect()
+}
+
+// Where the rollback manifests go, under the app data directory
+pub(crate) fn manifest_dir() -> Result<PathBuf> {
+    Ok(paths::app_data_dir()?.join(MANIFEST_DIR_NAME))
+}
+
+// Applies every rule in one registry transaction, so either all of the values change or none do.
+// The rollback manifest is written into `manifest_dir` before the transaction is committed, so no change is made
+// without one. Windows isn't told about the changes, and nothing is written on a dry run.
+#[instrument]
+pub(crate) fn harden(
+    registry: &dyn RegistryProvider, rules: &[RegistryRule], dry_run: bool, manifest_dir: &Path
+) -> Result<Hardening> {
+    let mut results: Vec<RuleResult> = Vec::new();
+    let mut manifest_path: Option<PathBuf> = None;
+    registry.transacted(&mut |registry: &dyn RegistryProvider| {
+        results.clear();
+        for rule in rules {
+            let old_data: Option<u32> = rule.current_data(registry)?;
+            let changed: bool = old_data != Some(rule.desired_data) && !dry_run && rule.apply(registry)?;
+            results.push(RuleResult { rule: rule.clone(), old_data, changed });
+        }
+        manifest_path = write_manifest(&results, manifest_dir)?;
+        Ok(manifest_path.is_some())
+    })?;
+    trace!("Hardened {} rules, changing {}", results.len(), results.iter().filter(|result| result.changed).count());
+    Ok(Hardening { results, manifest_path })
+}
+
+// Puts back every value the manifest lists in one registry transaction. Windows isn't told about the changes.
+#[instrument]
+pub(crate) fn roll_back(registry: &dyn RegistryProvider, manifest_path: &Path) -> Result<RollbackManifest> {
+    let contents: String = std::fs::read_to_string(manifest_path).map_err(|error| {
+        err::RollbackManifestError::FailedToRead { path: manifest_path.to_path_buf(), source: error }
+    })?;
+    let manifest: RollbackManifest = serde_json::from_str(&contents).map_err(|error| {
+        err::RollbackManifestError::FailedToParse { path: manifest_path.to_path_buf(), source: error }
+    })?;
+
+    registry.transacted(&mut |registry: &dyn RegistryProvider| {
+        for entry in &manifest.entries {
+            let predefined_key: HKEY = entry.hive.predefined_key();
+            match entry.old_data {
+                Some(old_data) => windows_ops::set_or_update_registry_value(
+                    registry, predefined_key, &entry.key, &entry.value, old_data
+                )?,
+                None => windows_ops::delete_registry_value(registry, predefined_key, &entry.key, &entry.value)?,
+            };
+        }
+        Ok(true)
+    })?;
+    trace!("Rolled back {} values from {:?}", manifest.entries.len(), manifest_path);
+    Ok(manifest)
+}
+
+// Nothing is written when nothing changed. This method returns the manifest's path otherwise.
+fn write_manifest(results: &[RuleResult], manifest_dir: &Path) -> Result<Option<PathBuf>> {
+    let entries: Vec<RollbackEntry> = results.iter()
+        .filter(|result| result.changed)
+        .map(|result| RollbackEntry {
+            rule_id: result.rule.id.clone(),
+            hive: result.rule.hive,
+            key: result.rule.key.clone(),
+            value: result.rule.value.clone(),
+            old_data: result.old_data,
+        })
+        .collect();
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    std::fs::create_dir_all(manifest_dir).map_err(|error| {
+        err::RollbackManifestError::FailedToWrite { path: manifest_dir.to_path_buf(), source: error }
+    })?;
+    let timestamp: DateTime<Local> = Local::now();
+    let path: PathBuf = manifest_dir.join(format!("harden-{}.json", timestamp.format("%Y%m%d-%H%M%S")));
+    let contents: String = serde_json::to_string_pretty(&RollbackManifest { timestamp, entries })?;
+    std::fs::write(&path, contents)
+        .map_err(|error| err::RollbackManifestError::FailedToWrite { path: path.clone(), source: error })?;
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use anyhow::Result;
+    use winreg::enums::HKEY_CURRENT_USER;
+
+    use crate::harden;
+    use crate::harden::{Hardening, RollbackManifest};
+    use crate::registry::InMemoryRegistry;
+    use crate::rules::{RefreshAction, RegistryRule, RuleHive, Severity};
+    use crate::windows_ops;
+
+    const TEST_REGKEY_SUBPATH: &str = "Software\\NoHiddenExtensions\\Test";
+
+    fn rule(id: &str, value: &str, desired_data: u32) -> RegistryRule {
+        RegistryRule {
+            id: String::from(id),
+            name: None,
+            hive: RuleHive::CurrentUser,
+            key: String::from(TEST_REGKEY_SUBPATH),
+            value: String::from(value),
+            desired_data,
+            severity: Severity::Warning,
+            refresh: RefreshAction::None,
+            opt_in: false,
+            advanced: false,
+            undoable: true,
+            baseline: true,
+        }
+    }
+
+    // A directory of the test's own, so no manifest lands among the user's
+    fn manifest_dir(test_name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("no-hidden-extensions-{}-{}", std::process::id(), test_name))
+    }
+
+    fn read(registry: &InMemoryRegistry, value: &str) -> Result<Option<u32>> {
+        windows_ops::get_dword_value(registry, HKEY_CURRENT_USER, TEST_REGKEY_SUBPATH, value)
+    }
+
+    #[test]
+    fn rolling_back_restores_every_changed_value() -> Result<()> {
+        let manifest_dir: PathBuf = manifest_dir("roll-back");
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_CURRENT_USER, TEST_REGKEY_SUBPATH, "Changed", 0u32)
+            .with_value(HKEY_CURRENT_USER, TEST_REGKEY_SUBPATH, "AlreadySet", 1u32);
+        let rules: Vec<RegistryRule> = vec![
+            rule("changed", "Changed", 1), rule("missing", "Missing", 0), rule("already-set", "AlreadySet", 1),
+        ];
+
+        let hardening: Hardening = harden::harden(&registry, &rules, false, &manifest_dir)?;
+        assert_eq!(read(&registry, "Changed")?, Some(1));
+        assert_eq!(read(&registry, "Missing")?, Some(0));
+        let manifest_path: PathBuf = hardening.manifest_path.expect("Hardening changed values without a manifest");
+
+        let manifest: RollbackManifest = harden::roll_back(&registry, &manifest_path)?;
+        let rolled_back_rule_ids: Vec<&str> = manifest.entries.iter().map(|entry| entry.rule_id.as_str()).collect();
+        assert_eq!(rolled_back_rule_ids, vec!["changed", "missing"]);
+        assert_eq!(read(&registry, "Changed")?, Some(0));
+        // the value didn't exist before, so it's gone again rather than holding 0
+        assert_eq!(read(&registry, "Missing")?, None);
+        // hardening didn't change it, so rolling back leaves it alone
+        assert_eq!(read(&registry, "AlreadySet")?, Some(1));
+
+        std::fs::remove_dir_all(&manifest_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn nothing_is_written_when_nothing_changes() -> Result<()> {
+        let manifest_dir: PathBuf = manifest_dir("unchanged");
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_CURRENT_USER, TEST_REGKEY_SUBPATH, "AlreadySet", 1u32);
+
+        let rules: Vec<RegistryRule> = vec![rule("already-set", "AlreadySet", 1)];
+
+        let hardening: Hardening = harden::harden(&registry, &rules, false, &manifest_dir)?;
+
+        assert!(!hardening.results[0].changed);
+        assert_eq!(hardening.manifest_path, None);
+        assert!(!manifest_dir.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn a_dry_run_changes_nothing() -> Result<()> {
+        let manifest_dir: PathBuf = manifest_dir("dry-run");
+        let registry = InMemoryRegistry::new()
+            .with_value(HKEY_CURRENT_USER, TEST_REGKEY_SUBPATH, "Changed", 0u32);
+        let rules: Vec<RegistryRule> = vec![rule("changed", "Changed", 1), rule("missing", "Missing", 0)];
+
+        let hardening: Hardening = harden::harden(&registry, &rules, true, &manifest_dir)?;
+
+        assert!(hardening.results.iter().all(|result| result.needed_change() && !result.changed));
+        assert_eq!(hardening.manifest_path, None);
+        assert_eq!(read(&registry, "Changed")?, Some(0));
+        assert_eq!(read(&registry, "Missing")?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use fluent_bundle::FluentValue;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace, warn};
+
+use crate::checks;
+use crate::err;
+use crate::i18n::{tr, tr_args};
+use crate::paths;
+
+// Name of the file under the app data directory which holds the persisted history.
+const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+// Only keep this many events around so a misbehaving program can't grow memory without bound.
+const MAX_EVENTS_IN_MEMORY: usize = 500;
+
+// Something worth telling the user about after the fact
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum HistoryEventKind {
+    // File extension hiding was turned on by something other than this program
+    FileExtensionsHidden,
+    // File extension hiding was turned on by the program with this name, according to the Security event log
+    FileExtensionsHiddenBy(String),
+    // File extension hiding was turned off by something other than this program
+    FileExtensionsVisible,
+    // The user asked this program to turn file extension hiding off
+    RemediatedByUser,
+    // The check with this ID started failing
+    CheckFailed(String),
+    // The check with this ID started passing, through something other than this program
+    CheckPassed(String),
+    // The user asked this program to fix whatever the check with this ID found
+    CheckRemediatedByUser(String),
+    // The user asked this program to undo its fix for the check with this ID
+    CheckUndoneByUser(String),
+    // A program with a disguised name, given here, showed up in the Downloads folder
+    DisguisedFileFound(String),
+    // The user had this program rename the disguised file with this name so it can't run
+    DisguisedFileDefused(String),
+    // The user had this program give the defused file with this name its original name back
+    DefusedFileRestored(String),
+    // This program had moved away from the path given here, so the Run key was pointed at its new location
+    StartupEntryRepaired(String),
+    // The user had this program put the Advanced key back the way its newest backup has it
+    BackupRestored,
+    // The user had this program undo the last change it made
+    ChangeUndone,
+    // A scheduled scan found this many checks failing, HideFileExt's included
+    ScanCompleted(usize),
+}
+
+impl HistoryEventKind {
+    pub(crate) fn description(&self) -> String {
+        match self {
+            HistoryEventKind::FileExtensionsHidden => tr("history-extensions-hidden"),
+            HistoryEventKind::FileExtensionsHiddenBy(process_name) =>
+                tr_args("history-extensions-hidden-by", &[("process", FluentValue::from(process_name.as_str()))]),
+            HistoryEventKind::FileExtensionsVisible => tr("history-extensions-visible"),
+            HistoryEventKind::RemediatedByUser => tr("history-remediated-by-user"),
+            HistoryEventKind::CheckFailed(check_id) =>
+                tr_args("history-check-failed", &[("check", FluentValue::from(checks::name_of(check_id)))]),
+            HistoryEventKind::CheckPassed(check_id) =>
+                tr_args("history-check-passed", &[("check", FluentValue::from(checks::name_of(check_id)))]),
+            HistoryEventKind::CheckRemediatedByUser(check_id) =>
+                tr_args("history-check-remediated-by-user", &[("check", FluentValue::from(checks::name_of(check_id)))]),
+            HistoryEventKind::CheckUndoneByUser(check_id) =>
+                tr_args("history-check-undone-by-user", &[("check", FluentValue::from(checks::name_of(check_id)))]),
+            HistoryEventKind::DisguisedFileFound(file_name) =>
+                tr_args("history-disguised-file-found", &[("file", FluentValue::from(file_name.as_str()))]),
+            HistoryEventKind::DisguisedFileDefused(file_name) =>
+                tr_args("history-disguised-file-defused", &[("file", FluentValue::from(file_name.as_str()))]),
+            HistoryEventKind::DefusedFileRestored(file_name) =>
+                tr_args("history-defused-file-restored", &[("file", FluentValue::from(file_name.as_str()))]),
+            HistoryEventKind::StartupEntryRepaired(old_path) =>
+                tr_args("history-startup-entry-repaired", &[("path", FluentValue::from(old_path.as_str()))]),
+            HistoryEventKind::BackupRestored => tr("history-backup-restored"),
+            HistoryEventKind::ChangeUndone => tr("history-change-undone"),
+            HistoryEventKind::ScanCompleted(0) => tr("history-scan-passed"),
+            HistoryEventKind::ScanCompleted(failing_checks) =>
+                tr_args("history-scan-failed", &[("count", FluentValue::from(*failing_checks))]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct HistoryEvent {
+    pub(crate) timestamp: DateTime<Local>,
+    pub(crate) kind: HistoryEventKind,
+}
+
+// Chronological record of warning and remediation events, optionally mirrored to disk
+// as one JSON object per line.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct History {
+    events: VecDeque<HistoryEvent>,
+    file_path: Option<PathBuf>,
+}
+
+impl History {
+    // A history which is lost when the program exits
+    pub(crate) fn in_memory() -> History {
+        History::default()
+    }
+
+    // A history backed by a file in the app data directory. Existing events are loaded from it.
+    #[instrument]
+    pub(crate) fn persisted() -> Result<History> {
+        let file_path: PathBuf = paths::app_data_dir()?.join(HISTORY_FILE_NAME);
+        let mut history = History { events: VecDeque::new(), file_path: Some(file_path.clone()) };
+
+        let file: File = match File::open(&file_path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                trace!("No existing history file found at {:?}", file_path);
+                return Ok(history);
+            },
+            Err(error) => return Err(err::HistoryStorageError::FailedToRead { path: file_path, source: error }.into())
+        };
+
+        for line in BufReader::new(file).lines() {
+            let line: String = line
+                .map_err(|error| err::HistoryStorageError::FailedToRead { path: file_path.clone(), source: error })?;
+            match serde_json::from_str::<HistoryEvent>(&line) {
+                Ok(event) => history.push(event),
+                // one bad line shouldn't make the rest of the history unreadable
+                Err(error) => warn!("Skipping unreadable history entry {:?}: {}", line, error),
+            }
+        }
+        Ok(history)
+    }
+
+    // Records an event as happening right now
+    #[instrument(skip(self))]
+    pub(crate) fn record(&mut self, kind: HistoryEventKind) -> Result<()> {
+        let event = HistoryEvent { timestamp: Local::now(), kind };
+
+        if let Some(file_path) = &self.file_path {
+            let mut file: File = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(file_path)
+                .map_err(|error| err::HistoryStorageError::FailedToWrite { path: file_path.clone(), source: error })?;
+            writeln!(file, "{}", serde_json::to_string(&event)?)
+                .map_err(|error| err::HistoryStorageError::FailedToWrite { path: file_path.clone(), source: error })?;
+        }
+
+        self.push(event);
+        Ok(())
+    }
+
+    // Events from newest to oldest
+    pub(crate) fn newest_first(&self) -> impl Iterator<Item = &HistoryEvent> {
+        self.events.iter().rev()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    fn push(&mut self, event: HistoryEvent) {
+        if self.events.len() == MAX_EVENTS_IN_MEMORY {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+}
@@ -0,0 +1,161 @@
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+use windows_sys::Win32::Foundation::GetLastError;
+use windows_sys::Win32::System::Threading::GetCurrentThreadId;
+use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+    MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN, RegisterHotKey, UnregisterHotKey, VK_F1,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    GetMessageW, MSG, PeekMessageW, PM_NOREMOVE, PostThreadMessageW, WM_HOTKEY, WM_QUIT, WM_USER,
+};
+
+use crate::err;
+
+// What a global hotkey does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum HotkeyAction {
+    ShowWindow,
+    Fix,
+}
+
+// A key combination such as Ctrl+Alt+E, which works whichever program has focus
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Hotkey {
+    // as written in the config file, to name it in errors
+    text: String,
+    modifiers: u32,
+    virtual_key: u32,
+}
+
+impl Hotkey {
+    // Takes any of Ctrl, Alt, Shift and Win, then a letter, a digit or F1 to F24, joined by "+"
+    pub(crate) fn parse(text: &str) -> Result<Hotkey> {
+        let invalid = || err::HotkeyError::Invalid(String::from(text));
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let key: &str = parts.pop().filter(|key| !key.is_empty()).ok_or_else(invalid)?;
+
+        let mut modifiers: u32 = 0;
+        for modifier in parts {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => MOD_CONTROL,
+                "alt" => MOD_ALT,
+                "shift" => MOD_SHIFT,
+                "win" => MOD_WIN,
+                _ => return Err(invalid().into()),
+            };
+        }
+        // a bare key would swallow ordinary typing
+        if modifiers == 0 {
+            return Err(invalid().into());
+        }
+
+        let key: String = key.to_ascii_uppercase();
+        let virtual_key: u32 = match key.as_bytes() {
+            // letters and digits share their virtual key codes with ASCII
+            [character] if character.is_ascii_alphanumeric() => u32::from(*character),
+            [b'F', number @ ..] => match std::str::from_utf8(number).ok().and_then(|number| number.parse::<u32>().ok()) {
+                Some(number @ 1..=24) => u32::from(VK_F1) + number - 1,
+                _ => return Err(invalid().into()),
+            },
+            _ => return Err(invalid().into()),
+        };
+        Ok(Hotkey { text: String::from(text), modifiers, virtual_key })
+    }
+
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+// Listens for global hotkeys on a thread of its own, since Windows delivers them to the message queue
+// of the thread which registered them. The hotkeys are released when this is dropped.
+#[derive(Debug)]
+pub(crate) struct HotkeyListener {
+    thread_id: u32,
+    presses: Receiver<HotkeyAction>,
+    _thread: JoinHandle<()>,
+}
+
+impl HotkeyListener {
+    // Registers every hotkey it can. Another program may already have taken some of them, which are
+    // returned along with why they couldn't be registered.
+    #[instrument]
+    pub(crate) fn start(hotkeys: Vec<(HotkeyAction, Hotkey)>) -> (HotkeyListener, Vec<(Hotkey, anyhow::Error)>) {
+        let (setup_sender, setup_receiver) = channel();
+        let (press_sender, presses) = channel();
+        let thread: JoinHandle<()> = std::thread::spawn(move || listen(hotkeys, setup_sender, press_sender));
+        // the thread only hangs up early if it panicked, which leaves nothing registered
+        let (thread_id, failures) = setup_receiver.recv().unwrap_or_default();
+        (HotkeyListener { thread_id, presses, _thread: thread }, failures)
+    }
+
+    // Blocks until one of the hotkeys is pressed, or returns None once the timeout passes without a press
+    pub(crate) fn wait_for_press(&self, timeout: Duration) -> Result<Option<HotkeyAction>> {
+        match self.presses.recv_timeout(timeout) {
+            Ok(action) => Ok(Some(action)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(err::HotkeyError::Stopped.into()),
+        }
+    }
+}
+
+impl Drop for HotkeyListener {
+    fn drop(&mut self) {
+        if unsafe { PostThreadMessageW(self.thread_id, WM_QUIT, 0, 0) } == 0 {
+            warn!("Failed to stop listening for hotkeys, error code {}", unsafe { GetLastError() });
+        }
+    }
+}
+
+fn listen(
+    hotkeys: Vec<(HotkeyAction, Hotkey)>,
+    setup_sender: Sender<(u32, Vec<(Hotkey, anyhow::Error)>)>,
+    press_sender: Sender<HotkeyAction>,
+) {
+    // gives this thread a message queue, so WM_QUIT can reach it even if no hotkey gets registered
+    let mut message: MSG = unsafe { std::mem::zeroed() };
+    unsafe { PeekMessageW(&mut message, 0, WM_USER, WM_USER, PM_NOREMOVE) };
+
+    // each hotkey is identified to Windows by its index
+    let mut registered: Vec<(i32, HotkeyAction)> = Vec::new();
+    let mut failures: Vec<(Hotkey, anyhow::Error)> = Vec::new();
+    for (id, (action, hotkey)) in (0..).zip(hotkeys) {
+        match unsafe { RegisterHotKey(0, id, hotkey.modifiers | MOD_NOREPEAT, hotkey.virtual_key) } {
+            0 => {
+                let error: anyhow::Error = err::HotkeyError::FailedToRegister {
+                    hotkey: hotkey.text.clone(), error_code: unsafe { GetLastError() }
+                }.into();
+                failures.push((hotkey, error));
+            },
+            _ => {
+                trace!("Registered {} to {:?}", hotkey.text, action);
+                registered.push((id, action));
+            },
+        }
+    }
+    if setup_sender.send((unsafe { GetCurrentThreadId() }, failures)).is_err() {
+        return;
+    }
+
+    while unsafe { GetMessageW(&mut message, 0, 0, 0) } > 0 {
+        if message.message != WM_HOTKEY {
+            continue;
+        }
+        let action: Option<HotkeyAction> = registered.iter()
+            .find(|(id, _)| *id as usize == message.wParam)
+            .map(|(_, action)| *action);
+        if let Some(action) = action {
+            if press_sender.send(action).is_err() {
+                break;
+            }
+        }
+    }
+
+    for (id, _) in registered {
+        unsafe { UnregisterHotKey(0, id) };
+    }
+}
@@ -0,0 +1,166 @@
+use std::ffi::c_void;
+
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+use windows_sys::core::PWSTR;
+use windows_sys::Win32::Foundation::GetLastError;
+use windows_sys::Win32::Networking::WinHttp::{
+    INTERNET_SCHEME_HTTPS, URL_COMPONENTS, WINHTTP_ACCESS_TYPE_DEFAULT_PROXY, WINHTTP_FLAG_SECURE,
+    WINHTTP_QUERY_FLAG_NUMBER, WINHTTP_QUERY_STATUS_CODE, WinHttpCloseHandle, WinHttpConnect, WinHttpCrackUrl,
+    WinHttpOpen, WinHttpOpenRequest, WinHttpQueryDataAvailable, WinHttpQueryHeaders, WinHttpReadData,
+    WinHttpReceiveResponse, WinHttpSendRequest,
+};
+
+use crate::err;
+use crate::windows_ops;
+
+// Some servers, GitHub's API among them, turn away requests without a user agent
+const USER_AGENT: &str = concat!("no-hidden-extensions/", env!("CARGO_PKG_VERSION"));
+const JSON_CONTENT_TYPE_HEADER: &str = "Content-Type: application/json\r\n";
+
+// A WinHTTP handle, which is closed when this is dropped
+struct InternetHandle(*mut c_void);
+
+impl InternetHandle {
+    fn from_null_on_failure(handle: *mut c_void, url: &str) -> Result<InternetHandle> {
+        match handle.is_null() {
+            true => Err(err::HttpError::FailedToSend { url: String::from(url), error_code: unsafe { GetLastError() } }.into()),
+            false => Ok(InternetHandle(handle)),
+        }
+    }
+}
+
+impl Drop for InternetHandle {
+    fn drop(&mut self) {
+        if unsafe { WinHttpCloseHandle(self.0) } == 0 {
+            warn!("Failed to close a WinHTTP handle, error code {}", unsafe { GetLastError() });
+        }
+    }
+}
+
+// Fetches a URL and returns the body of the response, as long as the status says it succeeded
+#[instrument]
+pub(crate) fn get(url: &str, headers: &str) -> Result<String> {
+    let body: Vec<u8> = download(url, headers)?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+// Like `get()`, for files which aren't text. Redirects are followed.
+#[instrument]
+pub(crate) fn download(url: &str, headers: &str) -> Result<Vec<u8>> {
+    send("GET", url, headers, &[])
+}
+
+// Posts JSON to a URL. The response's body is ignored.
+#[instrument]
+pub(crate) fn post_json(url: &str, body: &str) -> Result<()> {
+    send("POST", url, JSON_CONTENT_TYPE_HEADER, body.as_bytes())?;
+    Ok(())
+}
+
+// Through WinHTTP, which picks up the system's proxy settings and certificate store.
+// `headers` are separated and ended by \r\n.
+fn send(verb: &str, url: &str, headers: &str, body: &[u8]) -> Result<Vec<u8>> {
+    let to_send_error = |error_code: u32| err::HttpError::FailedToSend { url: String::from(url), error_code };
+
+    // the URL's parts point back into this buffer
+    let wide_url: Vec<u16> = windows_ops::to_wide_string(url);
+    let mut components: URL_COMPONENTS = unsafe { std::mem::zeroed() };
+    components.dwStructSize = std::mem::size_of::<URL_COMPONENTS>() as u32;
+    components.dwHostNameLength = u32::MAX;
+    components.dwUrlPathLength = u32::MAX;
+    components.dwExtraInfoLength = u32::MAX;
+    if unsafe { WinHttpCrackUrl(wide_url.as_ptr(), 0, 0, &mut components) } == 0 {
+        return Err(err::HttpError::InvalidUrl(String::from(url)).into());
+    }
+    let host_name: Vec<u16> = to_wide_part(components.lpszHostName, components.dwHostNameLength);
+    // the query string is sent along with the path
+    let path_length: u32 = components.dwUrlPathLength + components.dwExtraInfoLength;
+    let path: Vec<u16> = to_wide_part(components.lpszUrlPath, path_length);
+
+    let user_agent: Vec<u16> = windows_ops::to_wide_string(USER_AGENT);
+    let session: InternetHandle = InternetHandle::from_null_on_failure(unsafe {
+        WinHttpOpen(user_agent.as_ptr(), WINHTTP_ACCESS_TYPE_DEFAULT_PROXY, std::ptr::null(), std::ptr::null(), 0)
+    }, url)?;
+    let connection: InternetHandle = InternetHandle::from_null_on_failure(unsafe {
+        WinHttpConnect(session.0, host_name.as_ptr(), components.nPort, 0)
+    }, url)?;
+
+    let wide_verb: Vec<u16> = windows_ops::to_wide_string(verb);
+    let flags: u32 = match components.nScheme {
+        INTERNET_SCHEME_HTTPS => WINHTTP_FLAG_SECURE,
+        _ => 0,
+    };
+    let request: InternetHandle = InternetHandle::from_null_on_failure(unsafe {
+        WinHttpOpenRequest(
+            connection.0, wide_verb.as_ptr(), path.as_ptr(), std::ptr::null(), std::ptr::null(), std::ptr::null_mut(), flags
+        )
+    }, url)?;
+
+    let wide_headers: Vec<u16> = windows_ops::to_wide_string(headers);
+    let sent = unsafe {
+        WinHttpSendRequest(
+            request.0,
+            wide_headers.as_ptr(),
+            u32::MAX,
+            body.as_ptr() as *const c_void,
+            body.len() as u32,
+            body.len() as u32,
+            0,
+        )
+    };
+    if sent == 0 || unsafe { WinHttpReceiveResponse(request.0, std::ptr::null_mut()) } == 0 {
+        return Err(to_send_error(unsafe { GetLastError() }).into());
+    }
+
+    let mut status_code: u32 = 0;
+    let mut status_code_size: u32 = std::mem::size_of::<u32>() as u32;
+    let queried = unsafe {
+        WinHttpQueryHeaders(
+            request.0,
+            WINHTTP_QUERY_STATUS_CODE | WINHTTP_QUERY_FLAG_NUMBER,
+            std::ptr::null(),
+            &mut status_code as *mut u32 as *mut c_void,
+            &mut status_code_size,
+            std::ptr::null_mut(),
+        )
+    };
+    if queried == 0 {
+        return Err(to_send_error(unsafe { GetLastError() }).into());
+    }
+    trace!("{} answered with status {}", url, status_code);
+    if !(200..=299).contains(&status_code) {
+        return Err(err::HttpError::Rejected { url: String::from(url), status_code }.into());
+    }
+    read_response_body(&request).map_err(|error_code| to_send_error(error_code).into())
+}
+
+// Reads whatever the server sends until it's done. Returns the error code when reading fails.
+fn read_response_body(request: &InternetHandle) -> std::result::Result<Vec<u8>, u32> {
+    let mut body: Vec<u8> = Vec::new();
+    loop {
+        let mut available: u32 = 0;
+        if unsafe { WinHttpQueryDataAvailable(request.0, &mut available) } == 0 {
+            return Err(unsafe { GetLastError() });
+        }
+        if available == 0 {
+            return Ok(body);
+        }
+        let mut chunk: Vec<u8> = vec![0; available as usize];
+        let mut bytes_read: u32 = 0;
+        if unsafe { WinHttpReadData(request.0, chunk.as_mut_ptr().cast(), available, &mut bytes_read) } == 0 {
+            return Err(unsafe { GetLastError() });
+        }
+        body.extend_from_slice(&chunk[..bytes_read as usize]);
+    }
+}
+
+// A null-terminated copy of part of a URL which WinHttpCrackUrl pointed out
+fn to_wide_part(start: PWSTR, length: u32) -> Vec<u16> {
+    let mut part: Vec<u16> = match start.is_null() {
+        true => Vec::new(),
+        false => unsafe { std::slice::from_raw_parts(start, length as usize) }.to_vec(),
+    };
+    part.push(0);
+    part
+}
@@ -0,0 +1,186 @@
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::{instrument, trace};
+use windows::core::{ComInterface, HSTRING};
+use windows::Win32::Foundation::TRUE;
+use windows::Win32::System::Com::{CLSCTX_INPROC_SERVER, CoCreateInstance, IPersistFile};
+use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
+use windows_sys::Win32::System::Threading::CREATE_NO_WINDOW;
+
+use crate::build_info::APPLICATION_DISPLAY_NAME;
+use crate::com::ComApartment;
+use crate::config::{Config, StartupMethod};
+use crate::err;
+use crate::ipc;
+use crate::ipc::IpcCommand;
+use crate::registry::WinregRegistry;
+use crate::single_instance;
+use crate::single_instance::InstanceLock;
+use crate::startup_task;
+use crate::windows_ops;
+
+// Where per-user programs go, e.g. %LOCALAPPDATA%\Programs\no-hidden-extensions\no-hidden-extensions.exe
+const INSTALL_DIR_NAME: &str = "no-hidden-extensions";
+const EXECUTABLE_NAME: &str = "no-hidden-extensions.exe";
+const START_MENU_SUBPATH: &str = "Microsoft\\Windows\\Start Menu\\Programs";
+
+// How long the running instance gets to exit before its executable is replaced or deleted
+const EXIT_TIMEOUT: Duration = Duration::from_secs(10);
+// Gives this process time to exit before the helper deletes its executable
+const SELF_DELETE_DELAY_SECONDS: u32 = 3;
+
+// Copies this executable to the user's programs directory, has it start at sign-in the way the config says,
+// adds it to the Start menu and starts it from there. Installing again replaces the earlier copy.
+// This method returns where it was installed.
+#[instrument(skip(config))]
+pub(crate) fn install(config: &Config) -> Result<PathBuf> {
+    let install_dir: PathBuf = install_dir()?;
+    let installed_executable_path: PathBuf = install_dir.join(EXECUTABLE_NAME);
+    let current_executable_path: PathBuf = std::env::current_exe()?;
+
+    stop_running_instance()?;
+    std::fs::create_dir_all(&install_dir)
+        .map_err(|error| err::InstallError::FailedToCopy { path: install_dir.clone(), source: error })?;
+    if !is_same_file(&current_executable_path, &installed_executable_path) {
+        std::fs::copy(&current_executable_path, &installed_executable_path)
+            .map_err(|error| err::InstallError::FailedToCopy { path: installed_executable_path.clone(), source: error })?;
+        trace!("Copied {:?} to {:?}", current_executable_path, installed_executable_path);
+    }
+
+    // the other way is taken away so the program can't start twice
+    match config.startup_method {
+        StartupMethod::RunKey => {
+            windows_ops::run_program_at_startup(&WinregRegistry, &installed_executable_path)?;
+            startup_task::unregister()?;
+        },
+        StartupMethod::ScheduledTask => {
+            startup_task::register(&installed_executable_path, config.startup_task_elevated)?;
+            windows_ops::remove_startup_registration(&WinregRegistry)?;
+        },
+    }
+    create_shortcut(&installed_executable_path)?;
+
+    Command::new(&installed_executable_path)
+        .spawn()
+        .map_err(|error| err::InstallError::FailedToLaunch { path: installed_executable_path.clone(), source: error })?;
+    Ok(installed_executable_path)
+}
+
+// Undoes `install()`: stops the running instance, stops this program from starting at sign-in, and deletes
+// the Start menu shortcut and the installed executable. Settings and history are left alone.
+#[instrument]
+pub(crate) fn uninstall() -> Result<()> {
+    stop_running_instance()?;
+    windows_ops::remove_startup_registration(&WinregRegistry)?;
+    startup_task::unregister()?;
+    remove_installed_files()?;
+    Ok(())
+}
+
+// Deletes the Start menu shortcut and the installed executable, once nothing runs from it.
+// This method returns whether a change was made.
+#[instrument]
+pub(crate) fn remove_installed_files() -> Result<bool> {
+    let install_dir: PathBuf = install_dir()?;
+    let shortcut_removed: bool = remove_file_if_exists(&shortcut_path()?)?;
+
+    let current_executable_path: PathBuf = std::env::current_exe()?;
+    if current_executable_path.starts_with(&install_dir) {
+        // Windows won't delete an executable while it runs, so that's left to a helper
+        delete_after_exit(&current_executable_path, &install_dir)?;
+        return Ok(true);
+    }
+    return match std::fs::remove_dir_all(&install_dir) {
+        Ok(()) => {
+            trace!("Deleted {:?}", install_dir);
+            Ok(true)
+        },
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(shortcut_removed),
+        Err(error) => Err(err::InstallError::FailedToDelete { path: install_dir, source: error }.into()),
+    };
+}
+
+fn install_dir() -> Result<PathBuf> {
+    let local_app_data: PathBuf = std::env::var_os("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .ok_or(err::AppDataDirError::LocalAppDataNotSet)?;
+    Ok(local_app_data.join("Programs").join(INSTALL_DIR_NAME))
+}
+
+fn shortcut_path() -> Result<PathBuf> {
+    let app_data: PathBuf = std::env::var_os("APPDATA")
+        .map(PathBuf::from)
+        .ok_or(err::InstallError::AppDataNotSet)?;
+    Ok(app_data.join(START_MENU_SUBPATH).join(format!("{APPLICATION_DISPLAY_NAME}.lnk")))
+}
+
+// Asks the instance running in this session to exit and waits until it has
+pub(crate) fn stop_running_instance() -> Result<()> {
+    // there's usually no instance running, in which case no one answers
+    if let Err(error) = ipc::send_request(IpcCommand::Exit) {
+        trace!("No running instance to stop: {:?}", error);
+    }
+    let lock: Option<InstanceLock> = single_instance::acquire(EXIT_TIMEOUT)?;
+    return match lock {
+        // released right away, so the installed copy can claim the session
+        Some(_) => Ok(()),
+        None => Err(err::InstallError::InstanceStillRunning.into()),
+    };
+}
+
+fn is_same_file(first: &Path, second: &Path) -> bool {
+    match (first.canonicalize(), second.canonicalize()) {
+        (Ok(first), Ok(second)) => first == second,
+        _ => false,
+    }
+}
+
+fn create_shortcut(executable_path: &Path) -> Result<()> {
+    let _com: ComApartment = ComApartment::enter()?;
+    let shortcut_path: PathBuf = shortcut_path()?;
+    save_shortcut(executable_path, &shortcut_path)
+        .map_err(|error| err::InstallError::FailedToCreateShortcut { path: shortcut_path.clone(), source: error })?;
+    trace!("Created the Start menu shortcut {:?}", shortcut_path);
+    Ok(())
+}
+
+fn save_shortcut(executable_path: &Path, shortcut_path: &Path) -> windows::core::Result<()> {
+    let working_dir: &Path = executable_path.parent().unwrap_or(executable_path);
+    unsafe {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(&HSTRING::from(executable_path.as_os_str()))?;
+        link.SetWorkingDirectory(&HSTRING::from(working_dir.as_os_str()))?;
+        link.SetDescription(&HSTRING::from(APPLICATION_DISPLAY_NAME))?;
+        let file: IPersistFile = link.cast()?;
+        file.Save(&HSTRING::from(shortcut_path.as_os_str()), TRUE)
+    }
+}
+
+fn remove_file_if_exists(path: &Path) -> Result<bool> {
+    return match std::fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(err::InstallError::FailedToDelete { path: path.to_path_buf(), source: error }.into()),
+    };
+}
+
+// Leaves behind a hidden command prompt which waits for this process to exit, then deletes the directory
+fn delete_after_exit(executable_path: &Path, install_dir: &Path) -> Result<()> {
+    // ping is the usual way to sleep in cmd, at one ping per second after the first
+    let script: String = format!(
+        "/C ping -n {} 127.0.0.1 > NUL & del /F /Q \"{}\" & rmdir /S /Q \"{}\"",
+        SELF_DELETE_DELAY_SECONDS + 1, executable_path.display(), install_dir.display()
+    );
+    // cmd has its own quoting rules, so the script is passed as is
+    Command::new("cmd")
+        .raw_arg(script)
+        .creation_flags(CREATE_NO_WINDOW)
+        .spawn()
+        .map_err(|error| err::InstallError::FailedToDelete { path: install_dir.to_path_buf(), source: error })?;
+    trace!("{:?} will be deleted once this process exits", install_dir);
+    Ok(())
+}
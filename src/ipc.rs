@@ -0,0 +1,241 @@
+use std::sync::{Mutex, OnceLock};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Serialize;
+use tracing::{instrument, trace, warn};
+use windows_sys::Win32::Foundation::{ERROR_PIPE_CONNECTED, GetLastError};
+use windows_sys::Win32::Storage::FileSystem::{
+    FILE_FLAG_FIRST_PIPE_INSTANCE, FlushFileBuffers, PIPE_ACCESS_DUPLEX, ReadFile, WriteFile,
+};
+use windows_sys::Win32::System::Pipes::{
+    CallNamedPipeW, ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, PIPE_READMODE_MESSAGE, PIPE_REJECT_REMOTE_CLIENTS,
+    PIPE_TYPE_MESSAGE, PIPE_WAIT,
+};
+
+use crate::err;
+use crate::handle::Win32Handle;
+use crate::process::{ProcessManager, WindowsProcessManager};
+use crate::windows_ops;
+
+// Other local processes talk to this program through a pipe of this name, one request per connection. Each
+// sign-in session has an instance of its own, so the session id is appended, as in `session_pipe_name()`.
+pub(crate) const PIPE_NAME_PREFIX: &str = r"\\.\pipe\no-hidden-extensions";
+
+// Requests are a single command name, so this is plenty
+const REQUEST_BUFFER_SIZE: usize = 512;
+const RESPONSE_BUFFER_SIZE: usize = 4096;
+
+// How long a client waits for the pipe to be free, while the running instance answers someone else
+const CONNECT_TIMEOUT_MILLIS: u32 = 5000;
+
+// The UI answers between two frames, so anything slower means it's stuck
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+// What another process can ask of this program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IpcCommand {
+    Status,
+    // makes file extensions visible, as the button in the window does
+    Fix,
+    ShowWindow,
+    // closes the running instance, e.g. so its executable can be replaced
+    Exit,
+}
+
+impl IpcCommand {
+    const ALL: [IpcCommand; 4] = [IpcCommand::Status, IpcCommand::Fix, IpcCommand::ShowWindow, IpcCommand::Exit];
+
+    // What is sent over the pipe
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            IpcCommand::Status => "status",
+            IpcCommand::Fix => "fix",
+            IpcCommand::ShowWindow => "show-window",
+            IpcCommand::Exit => "exit",
+        }
+    }
+
+    fn parse(request: &str) -> Option<IpcCommand> {
+        IpcCommand::ALL.into_iter().find(|command| command.name() == request.trim())
+    }
+}
+
+// A command from another process, which the UI answers through `respond()`
+#[derive(Debug, Clone)]
+pub(crate) struct IpcRequest {
+    pub(crate) command: IpcCommand,
+    reply: Sender<String>,
+}
+
+impl IpcRequest {
+    pub(crate) fn respond(&self, status_report: &StatusReport) {
+        let response: String = serde_json::to_string(status_report)
+            .unwrap_or_else(|error| error_response(&error.to_string()));
+        // the pipe server may have given up waiting
+        if self.reply.send(response).is_err() {
+            warn!("The response to {:?} came too late", self.command);
+        }
+    }
+}
+
+// Every command is answered with where things stand once it's been carried out
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct StatusReport {
+    pub(crate) file_extensions_hidden: bool,
+    pub(crate) setting_source: String,
+    pub(crate) explorer_restart_pending: bool,
+    // IDs of the other checks which currently fail
+    pub(crate) failing_checks: Vec<String>,
+    // the failure the window currently shows, if any
+    pub(crate) error: Option<String>,
+}
+
+// The pipe server hands requests to the UI over a channel, since only the UI knows and changes the state
+static REQUEST_CHANNEL: OnceLock<(Sender<IpcRequest>, Mutex<Receiver<IpcRequest>>)> = OnceLock::new();
+
+fn request_channel() -> &'static (Sender<IpcRequest>, Mutex<Receiver<IpcRequest>>) {
+    REQUEST_CHANNEL.get_or_init(|| {
+        let (sender, receiver) = channel();
+        (sender, Mutex::new(receiver))
+    })
+}
+
+// Blocks until another process sends a command
+pub(crate) fn wait_for_request() -> Result<IpcRequest> {
+    let receiver = request_channel().1.lock()
+        .map_err(|_| err::IpcError::RequestChannelPoisoned)?;
+    Ok(receiver.recv()?)
+}
+
+// Takes the next command another process sent, without waiting for one
+pub(crate) fn take_request() -> Result<Option<IpcRequest>> {
+    let receiver = request_channel().1.lock()
+        .map_err(|_| err::IpcError::RequestChannelPoisoned)?;
+    match receiver.try_recv() {
+        Ok(request) => Ok(Some(request)),
+        Err(TryRecvError::Empty) => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+// Puts a request back for whoever waits for requests next, e.g. a window which hasn't opened yet
+pub(crate) fn requeue(request: IpcRequest) {
+    // the receiver lives as long as the process, so this can't fail
+    let _ = request_channel().0.send(request);
+}
+
+// e.g. \\.\pipe\no-hidden-extensions-2. Without the session id, the instance in one session would take requests
+// meant for the instance in another, such as another user's command to exit.
+fn session_pipe_name() -> Result<String> {
+    let session_id: u32 = WindowsProcessManager.current_session_id()?;
+    Ok(format!("{}-{}", PIPE_NAME_PREFIX, session_id))
+}
+
+// Sends a command to this session's running instance and returns its JSON response
+#[instrument]
+pub(crate) fn send_request(command: IpcCommand) -> Result<String> {
+    let pipe_name: Vec<u16> = windows_ops::to_wide_string(&session_pipe_name()?);
+    let request: &str = command.name();
+    let mut buffer: Vec<u8> = vec![0; RESPONSE_BUFFER_SIZE];
+    let mut bytes_read: u32 = 0;
+    // connects, sends, reads the response and disconnects in one go
+    let called = unsafe {
+        CallNamedPipeW(
+            pipe_name.as_ptr(),
+            request.as_ptr().cast(),
+            request.len() as u32,
+            buffer.as_mut_ptr().cast(),
+            buffer.len() as u32,
+            &mut bytes_read,
+            CONNECT_TIMEOUT_MILLIS,
+        )
+    };
+    if called == 0 {
+        return Err(err::IpcError::FailedToSend(unsafe { GetLastError() }).into());
+    }
+    buffer.truncate(bytes_read as usize);
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+// Opens this session's pipe and answers requests on it until the program exits. Fails if another process already
+// has a pipe by this name. Remote computers are turned away, and Windows' default security only lets
+// this user and administrators send requests.
+#[instrument]
+pub(crate) fn serve_in_background() -> Result<()> {
+    let pipe_name: Vec<u16> = windows_ops::to_wide_string(&session_pipe_name()?);
+    let pipe: Win32Handle = Win32Handle::from_invalid_on_failure(unsafe {
+        CreateNamedPipeW(
+            pipe_name.as_ptr(),
+            PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE,
+            PIPE_TYPE_MESSAGE | PIPE_READMODE_MESSAGE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            1,
+            RESPONSE_BUFFER_SIZE as u32,
+            REQUEST_BUFFER_SIZE as u32,
+            0,
+            std::ptr::null(),
+        )
+    }).map_err(err::IpcError::FailedToCreatePipe)?;
+
+    std::thread::spawn(move || loop {
+        if let Err(error) = answer_next_client(&pipe) {
+            warn!("Failed to answer a request over the pipe: {:?}", error);
+        }
+        // frees the pipe for the next client
+        unsafe { DisconnectNamedPipe(pipe.raw()) };
+    });
+    Ok(())
+}
+
+fn answer_next_client(pipe: &Win32Handle) -> Result<()> {
+    if unsafe { ConnectNamedPipe(pipe.raw(), std::ptr::null_mut()) } == 0 {
+        // the client may connect between creating the pipe and waiting for it
+        let error_code: u32 = unsafe { GetLastError() };
+        if error_code != ERROR_PIPE_CONNECTED {
+            return Err(err::IpcError::FailedToConnect(error_code).into());
+        }
+    }
+
+    let mut buffer: [u8; REQUEST_BUFFER_SIZE] = [0; REQUEST_BUFFER_SIZE];
+    let mut bytes_read: u32 = 0;
+    let read = unsafe {
+        ReadFile(pipe.raw(), buffer.as_mut_ptr().cast(), buffer.len() as u32, &mut bytes_read, std::ptr::null_mut())
+    };
+    if read == 0 {
+        return Err(err::IpcError::FailedToRead(unsafe { GetLastError() }).into());
+    }
+    let request: String = String::from_utf8_lossy(&buffer[..bytes_read as usize]).into_owned();
+    trace!("Received {:?} over the pipe", request);
+
+    let response: String = match IpcCommand::parse(&request) {
+        Some(command) => forward_to_ui(command),
+        None => error_response(&format!("Unknown command {:?}", request.trim())),
+    };
+    let mut bytes_written: u32 = 0;
+    let written = unsafe {
+        WriteFile(pipe.raw(), response.as_ptr().cast(), response.len() as u32, &mut bytes_written, std::ptr::null_mut())
+    };
+    if written == 0 {
+        return Err(err::IpcError::FailedToWrite(unsafe { GetLastError() }).into());
+    }
+    // so the client has the whole response before the pipe is disconnected
+    unsafe { FlushFileBuffers(pipe.raw()) };
+    Ok(())
+}
+
+fn forward_to_ui(command: IpcCommand) -> String {
+    let (reply, response) = channel();
+    if request_channel().0.send(IpcRequest { command, reply }).is_err() {
+        return error_response("The window has closed");
+    }
+    match response.recv_timeout(RESPONSE_TIMEOUT) {
+        Ok(response) => response,
+        Err(RecvTimeoutError::Timeout) => error_response("The window didn't respond in time"),
+        Err(RecvTimeoutError::Disconnected) => error_response("The window dropped the request"),
+    }
+}
+
+fn error_response(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
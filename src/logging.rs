@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+use tracing::{trace, warn, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::config::LogFormat;
+use crate::err;
+use crate::paths;
+
+// Under the data directory, e.g. logs\no-hidden-extensions.2023-04-01.log
+const LOG_DIR_NAME: &str = "logs";
+const LOG_FILE_PREFIX: &str = "no-hidden-extensions";
+const LOG_FILE_SUFFIX: &str = "log";
+// a new file is started every day, and only the last week's are kept
+const MAX_LOG_FILES: usize = 7;
+
+// Used unless --log-level or RUST_LOG says otherwise
+const DEFAULT_FILTER: &str = "info";
+// Everything this program logs, without the flood from the libraries under it
+const VERBOSE_FILTER: &str = "info,no_hidden_extensions=trace";
+
+// Swaps the filter while the program runs, along with the filter to go back to when verbose logging is turned off
+static FILTER: OnceLock<(reload::Handle<EnvFilter, Registry>, String)> = OnceLock::new();
+
+// Logs to stdout, and to a file when `to_file` is set, since a windowed program's stdout goes nowhere unless it's
+// run from a terminal. `log_level` is a filter in RUST_LOG's syntax, e.g. "debug" or "no_hidden_extensions=trace".
+// The returned guard writes out what's still buffered when it's dropped, so it has to live until the program exits.
+pub(crate) fn init(to_file: bool, log_level: Option<&str>, log_format: LogFormat) -> Result<Option<WorkerGuard>> {
+    let requested_filter: Option<String> = log_level.map(String::from)
+        .or_else(|| std::env::var(EnvFilter::DEFAULT_ENV).ok());
+    let (filter, base_filter, filter_error) = match requested_filter.as_deref().map(EnvFilter::try_new) {
+        Some(Ok(filter)) => (filter, requested_filter.unwrap_or_default(), None),
+        Some(Err(error)) => (EnvFilter::new(DEFAULT_FILTER), String::from(DEFAULT_FILTER), Some(error)),
+        None => (EnvFilter::new(DEFAULT_FILTER), String::from(DEFAULT_FILTER), None),
+    };
+    let (filter_layer, filter_handle) = reload::Layer::new(filter);
+    let _ = FILTER.set((filter_handle, base_filter));
+
+    let log_file: Option<Result<RollingFileAppender>> = to_file.then(open_log_file);
+    let (file_layer, guard, file_error) = match log_file {
+        Some(Ok(appender)) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (Some(format_layer(log_format, writer, false)), Some(guard), None)
+        },
+        Some(Err(error)) => (None, None, Some(error)),
+        None => (None, None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(format_layer(log_format, std::io::stdout, true))
+        .with(file_layer)
+        .try_init()
+        .map_err(|e| anyhow!(e))?;
+    // only now is there somewhere to report these
+    if let Some(error) = filter_error {
+        warn!("Ignoring the log level {:?}, which isn't a valid filter: {}", requested_filter, error);
+    }
+    if let Some(error) = file_error {
+        warn!("Failed to open the log file, logging to stdout only: {:?}", error);
+    }
+    Ok(guard)
+}
+
+// Logs everything this program does, or goes back to the filter it started with
+pub(crate) fn set_verbose(verbose: bool) -> Result<()> {
+    let (handle, base_filter) = FILTER.get().ok_or(err::LoggingNotInitializedError)?;
+    let filter: &str = match verbose {
+        true => VERBOSE_FILTER,
+        false => base_filter,
+    };
+    handle.reload(EnvFilter::new(filter))?;
+    trace!("Now logging with the filter {}", filter);
+    Ok(())
+}
+
+// Colors only make sense in a terminal
+fn format_layer<S, W>(log_format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    match log_format {
+        LogFormat::Text => fmt::layer().with_ansi(ansi).with_writer(writer).boxed(),
+        LogFormat::Json => fmt::layer().json().with_current_span(true).with_span_list(true).with_writer(writer).boxed(),
+    }
+}
+
+fn open_log_file() -> Result<RollingFileAppender> {
+    let log_dir: PathBuf = paths::app_data_dir()?.join(LOG_DIR_NAME);
+    let appender: RollingFileAppender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir)?;
+    Ok(appender)
+}
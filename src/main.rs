@@ -1,80 +1,364 @@
 #![windows_subsystem = "windows"]
+// much of what the window uses has no other caller in a headless build
+#![cfg_attr(not(feature = "gui"), allow(dead_code, unused_imports))]
+
+use std::time::Duration;
 
 use clap::{Parser, command, arg};
+#[cfg(feature = "gui")]
 use iced::{Application, Settings, Theme};
-use tray_icon::{TrayIcon, TrayIconBuilder};
 use anyhow::{anyhow, Result};
-use image::RgbaImage;
-use tracing::instrument;
+use tracing::{trace, warn};
+use tracing_appender::non_blocking::WorkerGuard;
 
+#[cfg(feature = "cli")]
+use crate::cli::CliCommand;
+use crate::config::Config;
+use crate::elevation::PendingAction;
 use crate::err::IconLoadingError;
-use crate::ui::{APPLICATION_DISPLAY_NAME, NoHiddenExtensionsState, UiOptions};
+use crate::history::History;
+use crate::ipc::IpcCommand;
+use crate::registry::WinregRegistry;
+#[cfg(feature = "tray")]
+use crate::tray_only::TrayOnlyExit;
+#[cfg(feature = "gui")]
+use crate::ui::{DEFAULT_WINDOW_SIZE, NoHiddenExtensionsState, UiOptions};
+// these live in the core library, and are imported here so the rest of the program reaches them through crate::
+use no_hidden_extensions_core::{
+    com, effective_state, handle, i18n, process, registry, registry_watcher, retry, rules, shell_windows, user_hives,
+    windows_ops,
+};
 
-mod windows_ops;
+#[cfg(feature = "gui")]
 mod ui;
+#[cfg(any(not(feature = "gui"), feature = "tray"))]
+mod agent;
 mod err;
+mod history;
+mod paths;
+mod config;
+#[cfg(feature = "gui")]
+mod notifications;
+// the stand-in has the same functions, and never manages to add an icon
+#[cfg_attr(not(feature = "tray"), path = "tray_disabled.rs")]
+mod tray;
+mod sound;
+#[cfg(feature = "gui")]
+mod accessibility;
+#[cfg(feature = "gui")]
+mod spoofing_demo;
+mod build_info;
+mod crash;
+mod elevation;
+mod power;
+mod session;
+mod checks;
+mod uac;
+mod wmi;
+mod antivirus;
+mod associations;
+mod filenames;
+mod folder_watcher;
+mod mark_of_the_web;
+#[cfg(feature = "cli")]
+mod cli;
+mod quarantine;
+mod audit_log;
+mod registry_audit;
+#[cfg(feature = "watcher-etw")]
+mod change_tracer;
+mod stats;
+mod export;
+mod http;
+mod webhook;
+mod event_sinks;
+mod syslog;
+mod event_log;
+mod metrics;
+mod ipc;
+mod single_instance;
+mod uri_scheme;
+#[cfg(feature = "gui")]
+mod hotkey;
+mod startup_task;
+mod install;
+mod cleanup;
+mod update_check;
+mod self_update;
+mod logging;
+mod advanced_backup;
+mod harden;
+#[cfg(feature = "gui")]
+mod worker;
+#[cfg(feature = "tray")]
+mod tray_only;
+
+// How long an instance relaunched as administrator or after an update waits for the instance it replaces to close
+const HANDOVER_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     #[arg(short, long, default_value_t = false)]
     start_minimized: bool,
-}
 
-#[instrument]
-fn load_visual_data_for_tray_and_window_icon() -> Result<(Vec<u8>, u32, u32)> {
-    // embed bytes into the executable at compile-time
-    let image_bytes = include_bytes!("..\\resources\\tray_and_window_icon.png");
-    let image: RgbaImage = image::load_from_memory(image_bytes)?
-        .into_rgba8();
+    // keep the notification history in %LOCALAPPDATA% so it survives restarts
+    #[arg(long, default_value_t = false)]
+    persist_history: bool,
+
+    // also fix the setting for every user on this machine, which needs administrator rights
+    #[arg(long, default_value_t = false)]
+    all_users: bool,
+
+    // set when this program restarts itself as administrator to finish what the user asked for
+    #[arg(long, value_enum)]
+    pending_action: Option<PendingAction>,
+
+    // make file extensions visible right away, through the running instance if there is one
+    #[arg(long, default_value_t = false)]
+    fix_now: bool,
 
-    let (width, height) = image.dimensions();
-    let rgba: Vec<u8> = image.into_raw();
+    // set by the Run key and the scheduled task, so the startup delay only applies when the user signs in
+    #[arg(long, default_value_t = false, hide = true)]
+    at_logon: bool,
 
-    Ok((rgba, width, height))
+    // seconds to wait after the user signs in before starting, remembered for later sign-ins
+    #[arg(long, value_name = "SECONDS")]
+    startup_delay: Option<u64>,
+
+    // set when an update starts the new executable
+    #[arg(long, default_value_t = false, hide = true)]
+    after_update: bool,
+
+    // which log messages to keep, in RUST_LOG's syntax, e.g. "debug"
+    #[arg(long, value_name = "FILTER")]
+    log_level: Option<String>,
+
+    // set by Windows when the user follows a link such as nhe://fix
+    #[arg(long)]
+    uri: Option<String>,
+
+    // run a command in the terminal instead of opening the window
+    #[cfg(feature = "cli")]
+    #[command(subcommand)]
+    command: Option<CliCommand>,
 }
 
+// The tray and window icon's raw RGBA pixels and dimensions, which build.rs decodes from its PNG
+include!(concat!(env!("OUT_DIR"), "/icon.rs"));
+
 
 pub fn main() -> Result<()> {
-    // log to stdout
-    tracing_subscriber::fmt::fmt()
-        .try_init()
-        .map_err(|e| anyhow!(e))?;
+    // installed before anything else can go wrong. Messages are only looked up once a panic happens, and ones
+    // looked up before a language is chosen show their ids rather than nothing at all.
+    crash::install_panic_hook();
 
-    let (rgba, width, height) = load_visual_data_for_tray_and_window_icon()
-        .map_err(|error| IconLoadingError::FailedToLoadIconBytes(error))?;
+    let executable_args: Args = Args::parse();
+    // the config says how to log, so it's read first. Nothing is logged yet, so a failure is only reported once
+    // logging is set up.
+    let (mut config, config_error): (Config, Option<anyhow::Error>) = match config::load() {
+        Ok(config) => (config, None),
+        Err(error) => (Config::default(), Some(error)),
+    };
+    // log to stdout, and to %LOCALAPPDATA%\no-hidden-extensions\logs unless a command prints to the terminal.
+    // Commands such as cleanup may also delete the log directory, which an open log file would get in the way of.
+    #[cfg(feature = "cli")]
+    let runs_command: bool = executable_args.command.is_some();
+    #[cfg(not(feature = "cli"))]
+    let runs_command: bool = false;
+    let _log_guard: Option<WorkerGuard> = logging::init(
+        !runs_command, executable_args.log_level.as_deref(), config.log_format
+    )?;
+    // the startup delay isn't saved over a config file which couldn't be read, which would replace it with defaults
+    let config_readable: bool = config_error.is_none();
+    if let Some(error) = config_error {
+        warn!("Failed to read the config, using the defaults: {:?}", error);
+    }
+    if config.verbose_logging {
+        if let Err(error) = logging::set_verbose(true) {
+            warn!("Failed to turn on verbose logging: {:?}", error);
+        }
+    }
+    retry::init(config.retry);
+    if let Err(error) = i18n::init(config.language.as_deref()) {
+        warn!("Failed to choose a language, showing messages in {}: {:?}", i18n::FALLBACK_LANGUAGE, error);
+        if let Err(error) = i18n::init_fallback() {
+            warn!("Failed to load the built-in messages: {:?}", error);
+        }
+    }
+    // without the built-in rules there is nothing to check, but the window can still say so and offer an update
+    if let Err(error) = rules::init() {
+        warn!("Failed to load the built-in rules: {:?}", error);
+    }
 
-    // add to the Windows system tray
-    let tray_ic: tray_icon::icon::Icon = tray_icon::icon::Icon::from_rgba(rgba.clone(), width.clone(), height.clone())
-        .map_err(|bad_icon| IconLoadingError::FailedToConstructTrayIcon(Box::new(bad_icon)))?;
+    if let Some(startup_delay) = executable_args.startup_delay {
+        config.startup_delay_seconds = startup_delay;
+        if !config_readable {
+            trace!("Not remembering the startup delay in the config file which couldn't be read");
+        } else if let Err(error) = config::save(&config) {
+            warn!("Failed to remember the startup delay: {:?}", error);
+        }
+    }
+    #[cfg(feature = "cli")]
+    if let Some(command) = executable_args.command {
+        return cli::run(command, &config);
+    }
 
-    let _tray_ic: TrayIcon = TrayIconBuilder::new()
-        .with_tooltip(APPLICATION_DISPLAY_NAME)
-        .with_icon(tray_ic)
-        .build()
-        .map_err(|error| IconLoadingError::FailedToConstructTrayIcon(Box::new(error)))?;
+    // a second instance would fight the first over the tray icon and the watchers, so it brings up the first one's
+    // window instead. An instance relaunched as administrator or after an update waits for the one it takes over
+    // from to exit.
+    let handover_timeout: Duration = match executable_args.pending_action.is_some() || executable_args.after_update {
+        true => HANDOVER_TIMEOUT,
+        false => Duration::ZERO,
+    };
+    // nhe://open only needs the window, which every start brings up anyway
+    let link_command: Option<IpcCommand> = executable_args.uri.as_deref().and_then(|uri| {
+        let link_command: Option<IpcCommand> = uri_scheme::parse(uri);
+        if link_command.is_none() {
+            warn!("Ignoring the unknown link {}", uri);
+        }
+        link_command
+    });
+    let fix_now: bool = executable_args.fix_now || link_command == Some(IpcCommand::Fix);
 
-    let main_window_ic: iced::window::Icon = iced::window::Icon::from_rgba(rgba, width, height)
+    let Some(_instance_lock) = single_instance::acquire(handover_timeout)? else {
+        #[cfg(feature = "cli")]
+        if fix_now {
+            return cli::run(CliCommand::Fix, &config);
+        }
+        #[cfg(not(feature = "cli"))]
+        if fix_now {
+            return ipc::send_request(IpcCommand::Fix).map(|_| ());
+        }
+        if let Err(error) = ipc::send_request(IpcCommand::ShowWindow) {
+            warn!("Failed to bring up the running instance's window: {:?}", error);
+        }
+        return Ok(());
+    };
+
+    // right after signing in, the shell may not be ready for tray icons yet and everything competes for the disk
+    if executable_args.at_logon && config.startup_delay_seconds > 0 {
+        trace!("Waiting {} seconds after sign-in before starting", config.startup_delay_seconds);
+        std::thread::sleep(Duration::from_secs(config.startup_delay_seconds));
+    }
+
+    if let Err(error) = self_update::remove_previous_executable() {
+        warn!("Failed to delete the executable from before the last update: {:?}", error);
+    }
+
+    if let Err(error) = uri_scheme::register() {
+        warn!("Failed to register the {}:// link scheme: {:?}", uri_scheme::URI_SCHEME, error);
+    }
+
+    // without this, file extensions hidden while the computer slept could go unnoticed
+    if let Err(error) = power::register_for_resume_notifications() {
+        warn!("Failed to register for resume notifications: {:?}", error);
+    }
+
+    if let Err(error) = ipc::serve_in_background() {
+        warn!("Failed to start answering requests from other programs: {:?}", error);
+    }
+
+    if let Some(metrics_port) = config.metrics_port {
+        if let Err(error) = metrics::serve_in_background(metrics_port) {
+            warn!("Failed to start the metrics endpoint: {:?}", error);
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    let result: Result<()> = run_window(executable_args, config, fix_now);
+    #[cfg(not(feature = "gui"))]
+    let result: Result<()> = agent::run(&config);
+    // wake the watcher threads, which would otherwise stay blocked until the process is torn down
+    registry_watcher::stop_all();
+    result
+}
+
+// Opens the window, or only adds the tray icon when starting minimized. This method returns once the window closes.
+#[cfg(feature = "gui")]
+fn run_window(
+    executable_args: Args, #[cfg_attr(not(feature = "tray"), allow(unused_mut))] mut config: Config, fix_now: bool
+) -> Result<()> {
+    // grabbing the user's attention waits while the workstation is locked, since nobody would see it
+    if let Err(error) = session::register_for_session_notifications() {
+        warn!("Failed to register for session lock and unlock notifications: {:?}", error);
+    }
+
+    // add to the Windows system tray. The shell may not be ready for tray icons yet, e.g. right after signing in
+    // or in some remote desktop sessions, so the window carries on without one until a retry succeeds.
+    #[cfg(feature = "tray")]
+    let tray_created: bool = match tray::create(config.verbose_logging) {
+        Ok(()) => true,
+        Err(error) => {
+            warn!("Failed to add the tray icon, will try again: {:?}", error);
+            false
+        },
+    };
+    #[cfg(not(feature = "tray"))]
+    let tray_created: bool = false;
+
+    // in tray-only mode, the window is only created once something calls for it. Something left to finish needs it
+    // right away.
+    #[cfg(feature = "tray")]
+    let tray_only: bool = config.tray_only && tray_created && !fix_now && executable_args.pending_action.is_none();
+    #[cfg(feature = "tray")]
+    let opened_from_tray: bool = match tray_only {
+        true => match tray_only::run_until_window_needed(&mut config)? {
+            TrayOnlyExit::OpenWindow => true,
+            TrayOnlyExit::FileExtensionsHidden => false,
+            TrayOnlyExit::Exit => return Ok(()),
+        },
+        false => false,
+    };
+    #[cfg(not(feature = "tray"))]
+    let opened_from_tray: bool = false;
+    // without a tray icon, a hidden window couldn't be brought back. The Run key and the scheduled task don't pass
+    // --start-minimized, so a start from either follows the config like a start from the Start menu does.
+    let start_minimized: bool = (executable_args.start_minimized || config.start_minimized || config.tray_only)
+        && tray_created
+        && !opened_from_tray;
+
+    let main_window_ic: iced::window::Icon = iced::window::Icon::from_rgba(ICON_RGBA.to_vec(), ICON_WIDTH, ICON_HEIGHT)
         .map_err(|error| IconLoadingError::FailedToConstructWindowIcon(Box::new(error)))?;
 
-    let theme: Theme = match dark_light::detect() {
-        dark_light::Mode::Dark => Theme::Dark,
-        dark_light::Mode::Light => Theme::Light,
-        dark_light::Mode::Default => Theme::default()
+    let theme: Theme = ui::detect_system_theme();
+
+    let windows_text_scale: f64 = windows_ops::get_windows_text_scale_factor(&WinregRegistry)
+        .unwrap_or_else(|error| {
+            warn!("Failed to read the Windows text size, using the default: {:?}", error);
+            1.0
+        });
+    let window_size: (u32, u32) = ui::scale_window_size(
+        DEFAULT_WINDOW_SIZE, windows_text_scale * config.text_scale.factor()
+    );
+
+    let history: History = match executable_args.persist_history {
+        true => History::persisted().unwrap_or_else(|error| {
+            warn!("Failed to load the saved history, keeping it in memory only: {:?}", error);
+            History::in_memory()
+        }),
+        false => History::in_memory(),
     };
 
-    let executable_args: Args = Args::parse();
     let mut settings: Settings<UiOptions> = Settings::with_flags(
         UiOptions {
-            start_minimized: executable_args.start_minimized,
-            theme
+            start_minimized,
+            theme,
+            history,
+            config,
+            windows_text_scale,
+            machine_wide_enforcement: executable_args.all_users,
+            // fixing right after starting is what an elevated instance does with its pending action
+            pending_action: match fix_now {
+                true => Some(PendingAction::TurnOffFileExtensionHiding),
+                false => executable_args.pending_action,
+            },
         }
     );
 
     settings.window.icon = Some(main_window_ic);
-    settings.window.size = (475, 175);
-    settings.window.visible = !executable_args.start_minimized;
+    settings.window.size = window_size;
+    settings.window.visible = !start_minimized;
 
-    NoHiddenExtensionsState::run(settings)
-        .map_err(|e| anyhow!(e))
+    NoHiddenExtensionsState::run(settings).map_err(|e| anyhow!(e))
 }
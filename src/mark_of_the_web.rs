@@ -0,0 +1,156 @@
+use std::ffi::OsString;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use fluent_bundle::FluentValue;
+use tracing::{instrument, trace};
+
+use crate::err;
+use crate::filenames;
+use crate::i18n::{tr, tr_args};
+
+// The alternate data stream in which browsers and mail clients record where a file came from
+const ZONE_IDENTIFIER_STREAM_NAME: &str = "Zone.Identifier";
+
+// Which of Internet Explorer's security zones a file came from. Windows still goes by them, e.g. to decide
+// whether SmartScreen checks a program and whether Office opens a document in Protected View.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SecurityZone {
+    LocalMachine,
+    LocalIntranet,
+    TrustedSites,
+    Internet,
+    RestrictedSites,
+    // a zone a program made up for itself
+    Other(u32),
+}
+
+impl SecurityZone {
+    fn from_id(zone_id: u32) -> SecurityZone {
+        match zone_id {
+            0 => SecurityZone::LocalMachine,
+            1 => SecurityZone::LocalIntranet,
+            2 => SecurityZone::TrustedSites,
+            3 => SecurityZone::Internet,
+            4 => SecurityZone::RestrictedSites,
+            other => SecurityZone::Other(other),
+        }
+    }
+
+    // Whether the file came from somewhere outside the user's control
+    pub(crate) fn is_untrusted(&self) -> bool {
+        matches!(self, SecurityZone::Internet | SecurityZone::RestrictedSites)
+    }
+}
+
+impl Display for SecurityZone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name: String = match self {
+            SecurityZone::LocalMachine => tr("zone-local-machine"),
+            SecurityZone::LocalIntranet => tr("zone-local-intranet"),
+            SecurityZone::TrustedSites => tr("zone-trusted-sites"),
+            SecurityZone::Internet => tr("zone-internet"),
+            SecurityZone::RestrictedSites => tr("zone-restricted-sites"),
+            SecurityZone::Other(zone_id) => tr_args("zone-other", &[("zone", FluentValue::from(*zone_id))]),
+        };
+        write!(f, "{name}")
+    }
+}
+
+// What a Zone.Identifier stream says about a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MarkOfTheWeb {
+    pub(crate) zone: SecurityZone,
+    // the page which linked to the download
+    pub(crate) referrer_url: Option<String>,
+    // where the file itself was downloaded from
+    pub(crate) host_url: Option<String>,
+}
+
+// Everything the inspector found out about one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Inspection {
+    pub(crate) path: PathBuf,
+    // None if nothing marked the file, e.g. because it was made on this computer or copied off a USB stick
+    pub(crate) mark: Option<MarkOfTheWeb>,
+    pub(crate) is_executable: bool,
+}
+
+impl Inspection {
+    // A program from the internet is exactly what an attachment posing as a document turns out to be
+    pub(crate) fn is_dangerous(&self) -> bool {
+        self.is_executable && self.mark.as_ref().map_or(false, |mark| mark.zone.is_untrusted())
+    }
+
+    // What to tell the user, one line each
+    pub(crate) fn findings(&self) -> Vec<String> {
+        let Some(mark) = &self.mark else {
+            return vec![tr("motw-none")];
+        };
+
+        let mut findings: Vec<String> = vec![tr_args("motw-zone", &[("zone", FluentValue::from(mark.zone.to_string()))])];
+        if let Some(host_url) = &mark.host_url {
+            findings.push(tr_args("motw-host-url", &[("url", FluentValue::from(host_url.as_str()))]));
+        }
+        if let Some(referrer_url) = &mark.referrer_url {
+            findings.push(tr_args("motw-referrer-url", &[("url", FluentValue::from(referrer_url.as_str()))]));
+        }
+        findings
+    }
+}
+
+// Reads the mark of the web on a file, if it has one
+#[instrument]
+pub(crate) fn inspect(path: &Path) -> Result<Inspection> {
+    let file_name: String = path.file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mark: Option<MarkOfTheWeb> = read_alternate_data_stream(path, ZONE_IDENTIFIER_STREAM_NAME)?
+        .map(|contents| parse_zone_identifier(&contents));
+    trace!("{:?} is marked with {:?}", path, mark);
+
+    Ok(Inspection { path: path.to_path_buf(), mark, is_executable: filenames::is_executable(&file_name) })
+}
+
+// Reads an NTFS alternate data stream as text, or None if the file has no stream by that name.
+// Windows' file APIs open a stream when it's named after the file and a colon.
+fn read_alternate_data_stream(path: &Path, stream_name: &str) -> Result<Option<String>> {
+    let mut stream_path: OsString = path.as_os_str().to_os_string();
+    stream_path.push(":");
+    stream_path.push(stream_name);
+
+    return match std::fs::read(&stream_path) {
+        // some programs write the stream as UTF-16, but every key and value in it is ASCII
+        Ok(contents) => Ok(Some(String::from_utf8_lossy(&contents).replace('\0', ""))),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(err::MarkOfTheWebError { path: path.to_path_buf(), source: error }.into())
+    };
+}
+
+// The stream is an INI file. Anything besides ZoneId is optional, and unknown keys are ignored.
+fn parse_zone_identifier(contents: &str) -> MarkOfTheWeb {
+    let mut mark: MarkOfTheWeb = MarkOfTheWeb {
+        // Windows treats a stream without a zone as coming from the internet
+        zone: SecurityZone::Internet,
+        referrer_url: None,
+        host_url: None,
+    };
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value: &str = value.trim();
+        match key.trim() {
+            "ZoneId" => {
+                if let Ok(zone_id) = value.parse::<u32>() {
+                    mark.zone = SecurityZone::from_id(zone_id);
+                }
+            },
+            "ReferrerUrl" => mark.referrer_url = Some(String::from(value)),
+            "HostUrl" => mark.host_url = Some(String::from(value)),
+            _ => {}
+        }
+    }
+    mark
+}
@@ -0,0 +1,103 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use tracing::{instrument, trace, warn};
+
+use crate::err;
+
+const METRICS_PATH: &str = "/metrics";
+// the version of Prometheus' text format which every scraper understands
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+// A scraper which stops talking shouldn't hold up the next one
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// What the last scrape sees, kept apart from the UI so the server never waits on it
+static FILE_EXTENSIONS_HIDDEN: AtomicBool = AtomicBool::new(false);
+// Unix time at which file extensions were last found hidden, or 0 while they're visible
+static HIDDEN_SINCE: AtomicI64 = AtomicI64::new(0);
+static REMEDIATIONS: AtomicU64 = AtomicU64::new(0);
+// Unix time at which HideFileExt was last read, so a stuck watcher can be told apart from a quiet one
+static LAST_CHECKED: AtomicI64 = AtomicI64::new(0);
+
+// Called whenever the UI settles on whether file extensions are hidden
+pub(crate) fn record_state(file_extensions_hidden: bool) {
+    let was_hidden: bool = FILE_EXTENSIONS_HIDDEN.swap(file_extensions_hidden, Ordering::Relaxed);
+    match (was_hidden, file_extensions_hidden) {
+        (false, true) => HIDDEN_SINCE.store(Utc::now().timestamp(), Ordering::Relaxed),
+        (true, false) => HIDDEN_SINCE.store(0, Ordering::Relaxed),
+        _ => {},
+    }
+}
+
+pub(crate) fn record_remediation() {
+    REMEDIATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_check() {
+    LAST_CHECKED.store(Utc::now().timestamp(), Ordering::Relaxed);
+}
+
+// Serves the metrics at http://127.0.0.1:<port>/metrics until the program exits.
+// Only this computer can connect, since nothing about the request is authenticated.
+#[instrument]
+pub(crate) fn serve_in_background(port: u16) -> Result<()> {
+    let listener: TcpListener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .map_err(|error| err::MetricsError { port, source: error })?;
+    trace!("Serving metrics on port {}", port);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let result: std::io::Result<()> = stream.and_then(answer);
+            if let Err(error) = result {
+                warn!("Failed to answer a metrics request: {:?}", error);
+            }
+        }
+    });
+    Ok(())
+}
+
+// Just enough HTTP for a scraper: the request line decides the answer, and the headers are ignored
+fn answer(mut stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut request_line: String = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let mut parts = request_line.split_whitespace();
+    let (status, body): (&str, String) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(METRICS_PATH)) => ("200 OK", render()),
+        (Some("GET"), _) => ("404 Not Found", String::from("Not found\n")),
+        _ => ("405 Method Not Allowed", String::from("Method not allowed\n")),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, METRICS_CONTENT_TYPE, body.len(), body
+    )
+}
+
+fn render() -> String {
+    let file_extensions_hidden: u8 = FILE_EXTENSIONS_HIDDEN.load(Ordering::Relaxed).into();
+    format!(
+        "# HELP no_hidden_extensions_file_extensions_hidden Whether Windows Explorer currently hides file extensions.\n\
+        # TYPE no_hidden_extensions_file_extensions_hidden gauge\n\
+        no_hidden_extensions_file_extensions_hidden {}\n\
+        # HELP no_hidden_extensions_hidden_since_timestamp_seconds When file extensions were last found hidden, or 0 while they're visible.\n\
+        # TYPE no_hidden_extensions_hidden_since_timestamp_seconds gauge\n\
+        no_hidden_extensions_hidden_since_timestamp_seconds {}\n\
+        # HELP no_hidden_extensions_remediations_total How often this program made file extensions visible again since it started.\n\
+        # TYPE no_hidden_extensions_remediations_total counter\n\
+        no_hidden_extensions_remediations_total {}\n\
+        # HELP no_hidden_extensions_last_check_timestamp_seconds When the watcher last read HideFileExt.\n\
+        # TYPE no_hidden_extensions_last_check_timestamp_seconds gauge\n\
+        no_hidden_extensions_last_check_timestamp_seconds {}\n",
+        file_extensions_hidden,
+        HIDDEN_SINCE.load(Ordering::Relaxed),
+        REMEDIATIONS.load(Ordering::Relaxed),
+        LAST_CHECKED.load(Ordering::Relaxed),
+    )
+}
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Local};
+use fluent_bundle::FluentValue;
+use tracing::trace;
+
+use crate::config::QuietHours;
+use crate::i18n::tr_args;
+
+// Reasons this program would grab the user's attention
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Notification {
+    FileExtensionsHidden,
+    // holds the ID of the check which started failing
+    CheckFailed(String),
+    // holds the name of the disguised file
+    DisguisedFileFound(String),
+    // holds the newer version
+    UpdateAvailable(String),
+}
+
+// A notification which was held back during quiet hours
+#[derive(Debug, Clone)]
+struct DeferredNotification {
+    timestamp: DateTime<Local>,
+    notification: Notification,
+}
+
+// Summary of everything which happened during quiet hours, delivered once they end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DeferredSummary {
+    pub(crate) times_hidden: usize,
+    pub(crate) first: DateTime<Local>,
+    pub(crate) last: DateTime<Local>,
+}
+
+impl DeferredSummary {
+    pub(crate) fn message(&self) -> String {
+        tr_args("quiet-hours-summary", &[
+            ("count", FluentValue::from(self.times_hidden)),
+            ("first", FluentValue::from(self.first.format("%H:%M").to_string())),
+            ("last", FluentValue::from(self.last.format("%H:%M").to_string())),
+        ])
+    }
+}
+
+// Decides whether a notification may be delivered right away, and holds the rest until it may.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NotificationScheduler {
+    quiet_hours: Option<QuietHours>,
+    deferred: Vec<DeferredNotification>,
+}
+
+impl NotificationScheduler {
+    pub(crate) fn new(quiet_hours: Option<QuietHours>) -> NotificationScheduler {
+        NotificationScheduler { quiet_hours, deferred: Vec::new() }
+    }
+
+    pub(crate) fn is_quiet(&self, now: DateTime<Local>) -> bool {
+        self.quiet_hours
+            .map(|quiet_hours| quiet_hours.contains(now.time()))
+            .unwrap_or(false)
+    }
+
+    // Returns whether the notification should be delivered now. If not, it is queued for later.
+    pub(crate) fn submit(&mut self, notification: Notification, now: DateTime<Local>) -> bool {
+        if !self.is_quiet(now) {
+            return true;
+        }
+        trace!("Deferring {:?} until quiet hours end", notification);
+        self.deferred.push(DeferredNotification { timestamp: now, notification });
+        false
+    }
+
+    // Should be called periodically. Once quiet hours are over, drains the queue into a summary.
+    pub(crate) fn poll(&mut self, now: DateTime<Local>) -> Option<DeferredSummary> {
+        if self.deferred.is_empty() || self.is_quiet(now) {
+            return None;
+        }
+
+        let deferred: Vec<DeferredNotification> = std::mem::take(&mut self.deferred);
+        let times_hidden: usize = deferred.iter()
+            .filter(|deferred| deferred.notification == Notification::FileExtensionsHidden)
+            .count();
+
+        Some(DeferredSummary {
+            times_hidden,
+            first: deferred.first()?.timestamp,
+            last: deferred.last()?.timestamp,
+        })
+    }
+}
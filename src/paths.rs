@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::err;
+
+// Name of the directory under %LOCALAPPDATA% which holds every file this program writes.
+const APP_DATA_DIR_NAME: &str = "no-hidden-extensions";
+
+// Returns the directory under %LOCALAPPDATA% for this program's files, creating it if necessary.
+pub(crate) fn app_data_dir() -> Result<PathBuf> {
+    let local_app_data: PathBuf = std::env::var_os("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .ok_or(err::AppDataDirError::LocalAppDataNotSet)?;
+
+    let app_data_dir: PathBuf = local_app_data.join(APP_DATA_DIR_NAME);
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|error| err::AppDataDirError::FailedToCreate { path: app_data_dir.clone(), source: error })?;
+    Ok(app_data_dir)
+}
+
+// Deletes the directory under %LOCALAPPDATA% with everything in it.
+// This method returns whether a change was made.
+pub(crate) fn remove_app_data_dir() -> Result<bool> {
+    let local_app_data: PathBuf = std::env::var_os("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .ok_or(err::AppDataDirError::LocalAppDataNotSet)?;
+
+    let app_data_dir: PathBuf = local_app_data.join(APP_DATA_DIR_NAME);
+    return match std::fs::remove_dir_all(&app_data_dir) {
+        Ok(()) => Ok(true),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(err::AppDataDirError::FailedToRemove { path: app_data_dir, source: error }.into()),
+    };
+}
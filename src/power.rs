@@ -0,0 +1,78 @@
+use std::ffi::c_void;
+use std::sync::{Mutex, OnceLock};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+use windows_sys::Win32::System::Power::{
+    DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS, HPOWERNOTIFY, PowerRegisterSuspendResumeNotification,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::{DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC};
+
+use crate::err;
+
+// Sent once each time the computer wakes up
+#[derive(Debug)]
+pub(crate) struct ResumeNotification;
+
+// Windows calls back on its own threads, so resumes are forwarded to the UI over a channel
+static RESUME_CHANNEL: OnceLock<(Sender<ResumeNotification>, Mutex<Receiver<ResumeNotification>>)> = OnceLock::new();
+
+fn resume_channel() -> &'static (Sender<ResumeNotification>, Mutex<Receiver<ResumeNotification>>) {
+    RESUME_CHANNEL.get_or_init(|| {
+        let (sender, receiver) = channel();
+        (sender, Mutex::new(receiver))
+    })
+}
+
+// Asks Windows to report whenever the computer wakes from sleep or hibernation.
+// Registry change notifications can be lost meanwhile, so watching has to start over afterward.
+#[instrument]
+pub(crate) fn register_for_resume_notifications() -> Result<()> {
+    // Windows holds on to these for as long as the registration lasts, which is the life of the process
+    let parameters: &'static mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS = Box::leak(Box::new(
+        DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS { Callback: Some(on_power_event), Context: std::ptr::null_mut() }
+    ));
+    let mut registration: HPOWERNOTIFY = 0;
+    let result: u32 = unsafe {
+        PowerRegisterSuspendResumeNotification(
+            DEVICE_NOTIFY_CALLBACK,
+            parameters as *mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS as isize,
+            &mut registration,
+        )
+    };
+    match result {
+        ERROR_SUCCESS => Ok(()),
+        error_code => Err(err::PowerNotificationError::FailedToRegister(error_code).into()),
+    }
+}
+
+// Blocks until the computer wakes from sleep or hibernation
+pub(crate) fn wait_for_resume() -> Result<ResumeNotification> {
+    let receiver = resume_channel().1.lock()
+        .map_err(|_| err::PowerNotificationError::ResumeChannelPoisoned)?;
+    Ok(receiver.recv()?)
+}
+
+// Takes the next resume, without waiting for one
+pub(crate) fn take_resume() -> Result<Option<ResumeNotification>> {
+    let receiver = resume_channel().1.lock()
+        .map_err(|_| err::PowerNotificationError::ResumeChannelPoisoned)?;
+    match receiver.try_recv() {
+        Ok(resume_notification) => Ok(Some(resume_notification)),
+        Err(TryRecvError::Empty) => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+unsafe extern "system" fn on_power_event(_context: *const c_void, event_type: u32, _setting: *const c_void) -> u32 {
+    // sent on every resume, whether or not a user is around to notice
+    if event_type == PBT_APMRESUMEAUTOMATIC {
+        trace!("The computer woke from sleep");
+        if let Err(error) = resume_channel().0.send(ResumeNotification) {
+            warn!("Failed to forward a resume notification: {:?}", error);
+        }
+    }
+    ERROR_SUCCESS
+}
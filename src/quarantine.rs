@@ -0,0 +1,92 @@
+use std::ffi::OsString;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace, warn};
+
+use crate::err;
+use crate::paths;
+
+// Appended to a disguised file's name so Windows opens it as text rather than running it
+const DEFUSED_SUFFIX: &str = ".dangerous.txt";
+
+// Name of the file under the app data directory which records every rename, one JSON object per line
+const DEFUSE_LOG_FILE_NAME: &str = "defused-files.jsonl";
+
+// A disguised file which was renamed so it can't run, and where it was before
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DefusedFile {
+    pub(crate) original_path: PathBuf,
+    pub(crate) defused_path: PathBuf,
+}
+
+// What happened to a file, as recorded in the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum DefuseAction {
+    Defused,
+    Restored,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DefuseLogEntry {
+    timestamp: DateTime<Local>,
+    action: DefuseAction,
+    #[serde(flatten)]
+    file: DefusedFile,
+}
+
+// Renames a file so double-clicking it opens it in a text editor, e.g. "invoice.pdf.exe" becomes
+// "invoice.pdf.exe.dangerous.txt". Nothing is deleted, so a false alarm costs nothing.
+#[instrument]
+pub(crate) fn defuse(path: &Path) -> Result<DefusedFile> {
+    let mut defused_name: OsString = path.as_os_str().to_os_string();
+    defused_name.push(DEFUSED_SUFFIX);
+    let defused_file: DefusedFile = DefusedFile { original_path: path.to_path_buf(), defused_path: PathBuf::from(defused_name) };
+
+    rename(&defused_file.original_path, &defused_file.defused_path)?;
+    trace!("Defused {:?}", defused_file);
+    record(DefuseAction::Defused, &defused_file);
+    Ok(defused_file)
+}
+
+// Gives a defused file its original name back
+#[instrument]
+pub(crate) fn restore(defused_file: &DefusedFile) -> Result<()> {
+    rename(&defused_file.defused_path, &defused_file.original_path)?;
+    trace!("Restored {:?}", defused_file);
+    record(DefuseAction::Restored, defused_file);
+    Ok(())
+}
+
+// Refuses to replace a file which already has the new name
+fn rename(from: &Path, to: &Path) -> Result<()> {
+    if to.exists() {
+        return Err(err::DefuseError::AlreadyExists(to.to_path_buf()).into());
+    }
+    std::fs::rename(from, to)
+        .map_err(|error| err::DefuseError::FailedToRename { from: from.to_path_buf(), source: error }.into())
+}
+
+// The log is only ever appended to, so it shows everything which was renamed and back again.
+// The rename already happened, so failing to log it is only worth a warning.
+fn record(action: DefuseAction, file: &DefusedFile) {
+    if let Err(error) = append_to_log(action, file) {
+        warn!("Failed to log that {:?} was {:?}: {:?}", file, action, error);
+    }
+}
+
+fn append_to_log(action: DefuseAction, file: &DefusedFile) -> Result<()> {
+    let path: PathBuf = paths::app_data_dir()?.join(DEFUSE_LOG_FILE_NAME);
+    let entry: DefuseLogEntry = DefuseLogEntry { timestamp: Local::now(), action, file: file.clone() };
+    let mut log_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|error| err::DefuseError::FailedToLog { path: path.clone(), source: error })?;
+    writeln!(log_file, "{}", serde_json::to_string(&entry)?)
+        .map_err(|error| err::DefuseError::FailedToLog { path, source: error }.into())
+}
@@ -0,0 +1,226 @@
+use std::ffi::c_void;
+use std::path::Path;
+
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+use windows_sys::core::GUID;
+use windows_sys::Win32::Foundation::{BOOL, ERROR_INSUFFICIENT_BUFFER, ERROR_NO_MORE_ITEMS, ERROR_SUCCESS, GetLastError};
+use windows_sys::Win32::Security::{ACL, GetSecurityDescriptorSacl, PSECURITY_DESCRIPTOR, SACL_SECURITY_INFORMATION, SE_SECURITY_NAME};
+use windows_sys::Win32::Security::Authentication::Identity::{
+    AUDIT_POLICY_INFORMATION, AuditSetSystemPolicy, POLICY_AUDIT_EVENT_SUCCESS,
+};
+use windows_sys::Win32::Security::Authorization::{
+    ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1, SE_REGISTRY_KEY, SetNamedSecurityInfoW,
+};
+use windows_sys::Win32::System::EventLog::{
+    EVT_HANDLE, EvtClose, EvtNext, EvtQuery, EvtQueryChannelPath, EvtQueryReverseDirection, EvtRender,
+    EvtRenderEventXml,
+};
+use windows_sys::Win32::System::Memory::LocalFree;
+
+use crate::err;
+use crate::windows_ops;
+
+// The "Registry" subcategory of "Object Access" in the advanced audit policy. Without it, Windows ignores
+// the audit entries on registry keys.
+const REGISTRY_AUDIT_SUBCATEGORY: GUID = GUID::from_u128(0x0cce921e_69ae_11d9_bed3_505054503030);
+const OBJECT_ACCESS_AUDIT_CATEGORY: GUID = GUID::from_u128(0x6997984a_797a_11d9_bed3_505054503030);
+
+// Has Windows log whenever anyone (WD) successfully (SA) writes a value (0x2, KEY_SET_VALUE) to the key
+const AUDIT_VALUE_WRITES_SACL: &str = "S:(AU;SA;0x2;;;WD)";
+const EMPTY_SACL: &str = "S:";
+
+// "A registry value was modified"
+const REGISTRY_VALUE_MODIFIED_EVENT_ID: u32 = 4657;
+const SECURITY_CHANNEL: &str = "Security";
+
+// An event older than this is taken to be about an earlier change, not the one just noticed
+const RECENT_EVENT_WINDOW_MILLIS: u32 = 5 * 60 * 1000;
+
+// The process which last changed HideFileExt, according to the Security event log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ChangeAttribution {
+    // the full path of its executable
+    pub(crate) process_path: String,
+    pub(crate) process_id: u32,
+}
+
+impl ChangeAttribution {
+    // e.g. FooInstaller.exe
+    pub(crate) fn process_name(&self) -> String {
+        Path::new(&self.process_path)
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.process_path.clone())
+    }
+}
+
+// A handle from the event log API, which is closed when this is dropped
+struct EventLogHandle(EVT_HANDLE);
+
+impl Drop for EventLogHandle {
+    fn drop(&mut self) {
+        if unsafe { EvtClose(self.0) } == 0 {
+            warn!("Failed to close event log handle {}, error code {}", self.0, unsafe { GetLastError() });
+        }
+    }
+}
+
+// Has Windows record which process writes to the Explorer\Advanced key of the user this program runs as.
+// Needs administrator rights. Replaces whatever auditing was set on that key before.
+#[instrument]
+pub(crate) fn turn_on() -> Result<()> {
+    windows_ops::enable_privilege(SE_SECURITY_NAME)?;
+    enable_registry_auditing()?;
+    set_explorer_key_sacl(AUDIT_VALUE_WRITES_SACL)
+}
+
+// Stops auditing the key again. The audit policy itself is left on, since something else may rely on it.
+// Needs administrator rights.
+#[instrument]
+pub(crate) fn turn_off() -> Result<()> {
+    windows_ops::enable_privilege(SE_SECURITY_NAME)?;
+    set_explorer_key_sacl(EMPTY_SACL)
+}
+
+fn enable_registry_auditing() -> Result<()> {
+    let policy = AUDIT_POLICY_INFORMATION {
+        AuditSubCategoryGuid: REGISTRY_AUDIT_SUBCATEGORY,
+        AuditingInformation: POLICY_AUDIT_EVENT_SUCCESS,
+        AuditCategoryGuid: OBJECT_ACCESS_AUDIT_CATEGORY,
+    };
+    match unsafe { AuditSetSystemPolicy(&policy, 1) } {
+        0 => Err(err::RegistryAuditError::FailedToEnableAuditPolicy(unsafe { GetLastError() }).into()),
+        _ => Ok(())
+    }
+}
+
+fn set_explorer_key_sacl(sddl: &str) -> Result<()> {
+    let wide_sddl: Vec<u16> = windows_ops::to_wide_string(sddl);
+    let mut security_descriptor: PSECURITY_DESCRIPTOR = std::ptr::null_mut();
+    let converted: BOOL = unsafe {
+        ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            wide_sddl.as_ptr(), SDDL_REVISION_1, &mut security_descriptor, std::ptr::null_mut()
+        )
+    };
+    if converted == 0 {
+        return Err(err::RegistryAuditError::FailedToBuildSacl(unsafe { GetLastError() }).into());
+    }
+
+    let mut sacl_present: BOOL = 0;
+    let mut sacl: *mut ACL = std::ptr::null_mut();
+    let mut sacl_defaulted: BOOL = 0;
+    let result: Result<()> = match unsafe {
+        GetSecurityDescriptorSacl(security_descriptor, &mut sacl_present, &mut sacl, &mut sacl_defaulted)
+    } {
+        0 => Err(err::RegistryAuditError::FailedToBuildSacl(unsafe { GetLastError() }).into()),
+        _ => {
+            // the object name of a key in the current user's hive, as the security API expects it
+            let object_name: Vec<u16> = windows_ops::to_wide_string(
+                &format!("CURRENT_USER\\{}", windows_ops::WINDOWS_EXPLORER_REGKEY_SUBPATH)
+            );
+            let error_code: u32 = unsafe {
+                SetNamedSecurityInfoW(
+                    object_name.as_ptr(),
+                    SE_REGISTRY_KEY,
+                    SACL_SECURITY_INFORMATION,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                    std::ptr::null(),
+                    sacl,
+                )
+            };
+            match error_code {
+                ERROR_SUCCESS => Ok(()),
+                error_code => Err(err::RegistryAuditError::FailedToSetSacl(error_code).into()),
+            }
+        },
+    };
+    unsafe { LocalFree(security_descriptor as isize) };
+    result
+}
+
+// The process behind the latest change to HideFileExt from the last few minutes, if auditing caught one.
+// Reading the Security event log needs administrator rights.
+#[instrument]
+pub(crate) fn find_latest_change() -> Result<Option<ChangeAttribution>> {
+    let query: String = format!(
+        "*[System[EventID={} and TimeCreated[timediff(@SystemTime) <= {}]]] \
+        and *[EventData[Data[@Name='ObjectValueName']='{}']]",
+        REGISTRY_VALUE_MODIFIED_EVENT_ID, RECENT_EVENT_WINDOW_MILLIS, windows_ops::HIDE_FILE_EXT_VALUE_NAME
+    );
+    let wide_channel: Vec<u16> = windows_ops::to_wide_string(SECURITY_CHANNEL);
+    let wide_query: Vec<u16> = windows_ops::to_wide_string(&query);
+    let results: EventLogHandle = match unsafe {
+        EvtQuery(0, wide_channel.as_ptr(), wide_query.as_ptr(), EvtQueryChannelPath | EvtQueryReverseDirection)
+    } {
+        0 => return Err(err::RegistryAuditError::FailedToQueryEvents(unsafe { GetLastError() }).into()),
+        handle => EventLogHandle(handle),
+    };
+
+    // newest first, so only the first event matters
+    let mut event: EVT_HANDLE = 0;
+    let mut returned_count: u32 = 0;
+    if unsafe { EvtNext(results.0, 1, &mut event, 0, 0, &mut returned_count) } == 0 {
+        return match unsafe { GetLastError() } {
+            ERROR_NO_MORE_ITEMS => {
+                trace!("No recent change to HideFileExt was audited");
+                Ok(None)
+            },
+            error_code => Err(err::RegistryAuditError::FailedToQueryEvents(error_code).into()),
+        };
+    }
+    let event: EventLogHandle = EventLogHandle(event);
+
+    let xml: String = render_event_xml(&event)?;
+    trace!("Latest audited change to HideFileExt: {}", xml);
+    let process_path: Option<String> = event_data(&xml, "ProcessName");
+    let process_id: Option<u32> = event_data(&xml, "ProcessId")
+        .and_then(|process_id| u32::from_str_radix(process_id.trim_start_matches("0x"), 16).ok());
+    return match (process_path, process_id) {
+        (Some(process_path), Some(process_id)) => Ok(Some(ChangeAttribution { process_path, process_id })),
+        _ => {
+            warn!("The audited change to HideFileExt didn't name a process");
+            Ok(None)
+        },
+    };
+}
+
+fn render_event_xml(event: &EventLogHandle) -> Result<String> {
+    let mut buffer_used: u32 = 0;
+    let mut property_count: u32 = 0;
+    // the first call only finds out how big the buffer has to be
+    let rendered: BOOL = unsafe {
+        EvtRender(0, event.0, EvtRenderEventXml, 0, std::ptr::null_mut(), &mut buffer_used, &mut property_count)
+    };
+    let last_error: u32 = unsafe { GetLastError() };
+    if rendered == 0 && last_error != ERROR_INSUFFICIENT_BUFFER {
+        return Err(err::RegistryAuditError::FailedToReadEvent(last_error).into());
+    }
+
+    // the size is in bytes
+    let mut buffer: Vec<u16> = vec![0; (buffer_used as usize + 1) / 2];
+    let rendered: BOOL = unsafe {
+        EvtRender(
+            0,
+            event.0,
+            EvtRenderEventXml,
+            (buffer.len() * 2) as u32,
+            buffer.as_mut_ptr() as *mut c_void,
+            &mut buffer_used,
+            &mut property_count,
+        )
+    };
+    if rendered == 0 {
+        return Err(err::RegistryAuditError::FailedToReadEvent(unsafe { GetLastError() }).into());
+    }
+    Ok(String::from_utf16_lossy(&buffer).trim_end_matches('\0').to_string())
+}
+
+// Picks a named field out of an event's XML, e.g. <Data Name='ProcessName'>C:\Foo.exe</Data>
+fn event_data(xml: &str, name: &str) -> Option<String> {
+    let start_tag: String = format!("<Data Name='{name}'>");
+    let start: usize = xml.find(&start_tag)? + start_tag.len();
+    let length: usize = xml[start..].find("</Data>")?;
+    Some(String::from(&xml[start..start + length]))
+}
@@ -0,0 +1,240 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+use windows_sys::Win32::Security::Cryptography::{
+    BCRYPT_ALG_HANDLE, BCRYPT_SHA256_ALGORITHM, BCryptCloseAlgorithmProvider, BCryptHash, BCryptOpenAlgorithmProvider,
+    CERT_CONTEXT, CERT_NAME_SIMPLE_DISPLAY_TYPE, CertGetNameStringW,
+};
+use windows_sys::Win32::Security::WinTrust::{
+    WINTRUST_ACTION_GENERIC_VERIFY_V2, WINTRUST_DATA, WINTRUST_DATA_0, WINTRUST_FILE_INFO, WTD_CHOICE_FILE,
+    WTD_REVOKE_WHOLECHAIN, WTD_STATEACTION_CLOSE, WTD_STATEACTION_VERIFY, WTD_UI_NONE, WTHelperGetProvCertFromChain,
+    WTHelperGetProvSignerFromChain, WTHelperProvDataFromStateData, WinVerifyTrust,
+};
+
+use crate::build_info;
+use crate::err;
+use crate::http;
+use crate::update_check::Release;
+use crate::windows_ops;
+
+// Lets the new instance wait for this one to exit, as an instance relaunched as administrator does
+pub(crate) const AFTER_UPDATE_FLAG: &str = "--after-update";
+
+const SHA256_LENGTH: usize = 32;
+// NTSTATUS for success
+const STATUS_SUCCESS: i32 = 0;
+
+// Downloads the release's executable, checks it against the hash published with it and that it's signed by
+// `build_info::UPDATE_SIGNER`, puts it in place of this executable and starts it. The hash only shows the download
+// arrived intact, since whoever can publish a release can publish both, so the signature is what proves who made
+// it. Windows won't overwrite a running executable but will rename it, so this one is moved aside and deleted by
+// the new one once it starts. The caller should exit right after this succeeds.
+#[instrument]
+pub(crate) fn install(release: &Release) -> Result<()> {
+    let (Some(executable_url), Some(checksum_url)) = (&release.executable_url, &release.checksum_url) else {
+        return Err(err::SelfUpdateError::MissingAssets(release.version.to_string()).into());
+    };
+
+    let executable: Vec<u8> = http::download(executable_url, "")?;
+    let checksum: String = http::get(checksum_url, "")?;
+    // e.g. "<hash>  no-hidden-extensions.exe"
+    let expected_hash: String = checksum.split_whitespace().next().unwrap_or_default().to_ascii_lowercase();
+    let actual_hash: String = sha256_hex(&executable)?;
+    if expected_hash != actual_hash {
+        return Err(err::SelfUpdateError::HashMismatch { expected: expected_hash, actual: actual_hash }.into());
+    }
+    trace!("Downloaded version {}, whose hash matches", release.version);
+
+    let download_path: PathBuf = std::env::temp_dir().join(format!("no-hidden-extensions-{}.exe", release.version));
+    std::fs::write(&download_path, &executable)
+        .map_err(|error| err::SelfUpdateError::FailedToReplace { path: download_path.clone(), source: error })?;
+
+    let current_executable_path: PathBuf = std::env::current_exe()?;
+    let replace_result: Result<()> = verify_signer(&download_path, build_info::UPDATE_SIGNER)
+        .and_then(|()| replace_executable(&download_path, &current_executable_path));
+    if let Err(error) = std::fs::remove_file(&download_path) {
+        warn!("Failed to delete the downloaded update at {:?}: {:?}", download_path, error);
+    }
+    replace_result?;
+
+    // the user's own arguments carry over, but the new instance starts long after sign-in
+    let arguments: Vec<String> = std::env::args().skip(1)
+        .filter(|argument| argument != windows_ops::AT_LOGON_FLAG && argument != AFTER_UPDATE_FLAG)
+        .collect();
+    Command::new(&current_executable_path)
+        .args(arguments)
+        .arg(AFTER_UPDATE_FLAG)
+        .spawn()
+        .map_err(err::SelfUpdateError::FailedToRelaunch)?;
+    Ok(())
+}
+
+// Deletes the executable which an update moved aside. Called on every start, since only the new executable
+// can do it once the old one has exited.
+#[instrument]
+pub(crate) fn remove_previous_executable() -> Result<()> {
+    let previous_executable_path: PathBuf = previous_executable_path(&std::env::current_exe()?);
+    return match std::fs::remove_file(&previous_executable_path) {
+        Ok(()) => {
+            trace!("Deleted the executable from before the update at {:?}", previous_executable_path);
+            Ok(())
+        },
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error.into()),
+    };
+}
+
+// e.g. no-hidden-extensions.exe.old
+fn previous_executable_path(executable_path: &Path) -> PathBuf {
+    let mut previous_executable_path = executable_path.as_os_str().to_os_string();
+    previous_executable_path.push(".old");
+    PathBuf::from(previous_executable_path)
+}
+
+fn replace_executable(new_executable_path: &Path, current_executable_path: &Path) -> Result<()> {
+    let previous_executable_path: PathBuf = previous_executable_path(current_executable_path);
+    // an earlier update's leftover would be in the way
+    remove_previous_executable()?;
+    std::fs::rename(current_executable_path, &previous_executable_path)
+        .map_err(|error| err::SelfUpdateError::FailedToReplace { path: current_executable_path.to_path_buf(), source: error })?;
+
+    // copied rather than renamed, since the temp directory may be on another drive
+    if let Err(error) = std::fs::copy(new_executable_path, current_executable_path) {
+        if let Err(restore_error) = std::fs::rename(&previous_executable_path, current_executable_path) {
+            warn!("Failed to move the old executable back into place: {:?}", restore_error);
+        }
+        return Err(err::SelfUpdateError::FailedToReplace { path: current_executable_path.to_path_buf(), source: error }.into());
+    }
+    trace!("Replaced {:?}", current_executable_path);
+    Ok(())
+}
+
+// Checks the executable's Authenticode signature through Windows, including whether the certificate was revoked,
+// and that the certificate it was signed with was issued to the expected signer
+fn verify_signer(executable_path: &Path, expected_signer: &str) -> Result<()> {
+    let wide_path: Vec<u16> = windows_ops::to_wide_string(&executable_path.to_string_lossy());
+    let mut file_info = WINTRUST_FILE_INFO {
+        cbStruct: std::mem::size_of::<WINTRUST_FILE_INFO>() as u32,
+        pcwszFilePath: wide_path.as_ptr(),
+        hFile: 0,
+        pgKnownSubject: std::ptr::null_mut(),
+    };
+    let mut trust_data = WINTRUST_DATA {
+        cbStruct: std::mem::size_of::<WINTRUST_DATA>() as u32,
+        pPolicyCallbackData: std::ptr::null_mut(),
+        pSIPClientData: std::ptr::null_mut(),
+        dwUIChoice: WTD_UI_NONE,
+        fdwRevocationChecks: WTD_REVOKE_WHOLECHAIN,
+        dwUnionChoice: WTD_CHOICE_FILE,
+        Anonymous: WINTRUST_DATA_0 { pFile: &mut file_info },
+        // keeps the verified signature around, so the signer can be read from it
+        dwStateAction: WTD_STATEACTION_VERIFY,
+        hWVTStateData: 0,
+        pwszURLReference: std::ptr::null_mut(),
+        dwProvFlags: 0,
+        dwUIContext: 0,
+        pSignatureSettings: std::ptr::null_mut(),
+    };
+    let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+    let status: i32 = unsafe {
+        WinVerifyTrust(0, &mut action, &mut trust_data as *mut WINTRUST_DATA as *mut _)
+    };
+    let signer: Option<String> = match status {
+        0 => signer_name(&trust_data),
+        _ => None,
+    };
+
+    trust_data.dwStateAction = WTD_STATEACTION_CLOSE;
+    let close_status: i32 = unsafe {
+        WinVerifyTrust(0, &mut action, &mut trust_data as *mut WINTRUST_DATA as *mut _)
+    };
+    if close_status != 0 {
+        warn!("Failed to release the signature's verification state, status {:#x}", close_status);
+    }
+
+    if status != 0 {
+        return Err(err::SelfUpdateError::UntrustedSignature(status).into());
+    }
+    let actual_signer: String = signer.unwrap_or_default();
+    if actual_signer != expected_signer {
+        return Err(err::SelfUpdateError::UnexpectedSigner {
+            expected: String::from(expected_signer),
+            actual: actual_signer,
+        }.into());
+    }
+    trace!("The update is signed by {}", actual_signer);
+    Ok(())
+}
+
+// The name on the leaf certificate of the first signature, which WinVerifyTrust has just verified
+fn signer_name(trust_data: &WINTRUST_DATA) -> Option<String> {
+    let certificate: *const CERT_CONTEXT = unsafe {
+        let provider_data = WTHelperProvDataFromStateData(trust_data.hWVTStateData);
+        if provider_data.is_null() {
+            return None;
+        }
+        let signer = WTHelperGetProvSignerFromChain(provider_data, 0, 0, 0);
+        if signer.is_null() {
+            return None;
+        }
+        let provider_certificate = WTHelperGetProvCertFromChain(signer, 0);
+        if provider_certificate.is_null() {
+            return None;
+        }
+        (*provider_certificate).pCert
+    };
+    if certificate.is_null() {
+        return None;
+    }
+
+    let mut name: Vec<u16> = vec![0; 256];
+    let length: u32 = unsafe {
+        CertGetNameStringW(
+            certificate,
+            CERT_NAME_SIMPLE_DISPLAY_TYPE,
+            0,
+            std::ptr::null(),
+            name.as_mut_ptr(),
+            name.len() as u32,
+        )
+    };
+    // the length includes the terminating null, and is 1 when the certificate has no name
+    match length {
+        0 | 1 => None,
+        length => Some(String::from_utf16_lossy(&name[..length as usize - 1])),
+    }
+}
+
+// Through Windows' own cryptography, in lowercase hex
+fn sha256_hex(bytes: &[u8]) -> Result<String> {
+    let mut algorithm: BCRYPT_ALG_HANDLE = 0;
+    let status: i32 = unsafe {
+        BCryptOpenAlgorithmProvider(&mut algorithm, BCRYPT_SHA256_ALGORITHM, std::ptr::null(), 0)
+    };
+    if status != STATUS_SUCCESS {
+        return Err(err::SelfUpdateError::FailedToHash(status).into());
+    }
+
+    let mut hash: [u8; SHA256_LENGTH] = [0; SHA256_LENGTH];
+    let status: i32 = unsafe {
+        BCryptHash(
+            algorithm,
+            std::ptr::null(),
+            0,
+            bytes.as_ptr(),
+            bytes.len() as u32,
+            hash.as_mut_ptr(),
+            hash.len() as u32,
+        )
+    };
+    let close_status: i32 = unsafe { BCryptCloseAlgorithmProvider(algorithm, 0) };
+    if close_status != STATUS_SUCCESS {
+        warn!("Failed to close the SHA-256 provider, status {:#x}", close_status);
+    }
+    if status != STATUS_SUCCESS {
+        return Err(err::SelfUpdateError::FailedToHash(status).into());
+    }
+    Ok(hash.iter().map(|byte| format!("{byte:02x}")).collect())
+}
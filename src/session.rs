@@ -0,0 +1,106 @@
+use std::sync::{Mutex, OnceLock};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+use windows_sys::Win32::Foundation::{GetLastError, HWND, LPARAM, LRESULT, WPARAM};
+use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows_sys::Win32::System::RemoteDesktop::{NOTIFY_FOR_THIS_SESSION, WTSRegisterSessionNotification};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, HWND_MESSAGE, MSG, RegisterClassW, WNDCLASSW,
+    WM_WTSSESSION_CHANGE, WTS_SESSION_LOCK, WTS_SESSION_UNLOCK,
+};
+
+use crate::err;
+use crate::windows_ops;
+
+// Windows only reports session changes to a window, so a hidden one of this class receives them
+const WINDOW_CLASS_NAME: &str = "no-hidden-extensions-session-listener";
+
+// Sent each time the user locks or unlocks the workstation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SessionChange {
+    Locked,
+    Unlocked,
+}
+
+// The window procedure runs on the listener's thread, so changes are forwarded to the UI over a channel
+static SESSION_CHANNEL: OnceLock<(Sender<SessionChange>, Mutex<Receiver<SessionChange>>)> = OnceLock::new();
+
+fn session_channel() -> &'static (Sender<SessionChange>, Mutex<Receiver<SessionChange>>) {
+    SESSION_CHANNEL.get_or_init(|| {
+        let (sender, receiver) = channel();
+        (sender, Mutex::new(receiver))
+    })
+}
+
+// Asks Windows to report whenever this session is locked or unlocked. The hidden window which receives the
+// reports lives on a thread of its own for the life of the process.
+#[instrument]
+pub(crate) fn register_for_session_notifications() -> Result<()> {
+    let (setup_sender, setup_receiver) = channel();
+    std::thread::Builder::new()
+        .name(String::from("session listener"))
+        .spawn(move || listen(setup_sender))?;
+    // the thread only hangs up early if it panicked
+    setup_receiver.recv().unwrap_or(Err(err::SessionNotificationError::ListenerStopped))?;
+    Ok(())
+}
+
+// Blocks until the workstation is locked or unlocked
+pub(crate) fn wait_for_session_change() -> Result<SessionChange> {
+    let receiver = session_channel().1.lock()
+        .map_err(|_| err::SessionNotificationError::SessionChannelPoisoned)?;
+    Ok(receiver.recv()?)
+}
+
+fn listen(setup_sender: Sender<Result<(), err::SessionNotificationError>>) {
+    let class_name: Vec<u16> = windows_ops::to_wide_string(WINDOW_CLASS_NAME);
+    let instance = unsafe { GetModuleHandleW(std::ptr::null()) };
+    let mut window_class: WNDCLASSW = unsafe { std::mem::zeroed() };
+    window_class.lpfnWndProc = Some(on_window_message);
+    window_class.hInstance = instance;
+    window_class.lpszClassName = class_name.as_ptr();
+    // failing here shows up as failing to create the window
+    unsafe { RegisterClassW(&window_class) };
+
+    // a message-only window is never shown and receives no broadcasts, only what is sent to it directly
+    let window: HWND = unsafe {
+        CreateWindowExW(
+            0, class_name.as_ptr(), std::ptr::null(), 0, 0, 0, 0, 0, HWND_MESSAGE, 0, instance, std::ptr::null(),
+        )
+    };
+    if window == 0 {
+        let _ = setup_sender.send(Err(err::SessionNotificationError::FailedToCreateWindow(unsafe { GetLastError() })));
+        return;
+    }
+    if unsafe { WTSRegisterSessionNotification(window, NOTIFY_FOR_THIS_SESSION) } == 0 {
+        let _ = setup_sender.send(Err(err::SessionNotificationError::FailedToRegister(unsafe { GetLastError() })));
+        return;
+    }
+    if setup_sender.send(Ok(())).is_err() {
+        return;
+    }
+
+    let mut message: MSG = unsafe { std::mem::zeroed() };
+    while unsafe { GetMessageW(&mut message, 0, 0, 0) } > 0 {
+        unsafe { DispatchMessageW(&message) };
+    }
+}
+
+unsafe extern "system" fn on_window_message(window: HWND, message: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if message != WM_WTSSESSION_CHANGE {
+        return DefWindowProcW(window, message, wparam, lparam);
+    }
+    // remote connections and sign-outs are reported too, and don't change whether anyone can see the screen
+    let change: SessionChange = match wparam as u32 {
+        WTS_SESSION_LOCK => SessionChange::Locked,
+        WTS_SESSION_UNLOCK => SessionChange::Unlocked,
+        _ => return 0,
+    };
+    trace!("The session changed: {:?}", change);
+    if let Err(error) = session_channel().0.send(change) {
+        warn!("Failed to forward a session change: {:?}", error);
+    }
+    0
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::{instrument, trace};
+use windows_sys::Win32::Foundation::{ERROR_ALREADY_EXISTS, GetLastError, WAIT_ABANDONED, WAIT_OBJECT_0, WAIT_TIMEOUT};
+use windows_sys::Win32::System::Threading::{CreateMutexW, WaitForSingleObject};
+
+use crate::err;
+use crate::handle::Win32Handle;
+use crate::windows_ops;
+
+// One instance per signed-in session, since each session has its own tray and registry hive
+const INSTANCE_MUTEX_NAME: &str = "Local\\no-hidden-extensions";
+
+// Proof that this is the only instance running in the session. Windows releases it when the process exits.
+#[derive(Debug)]
+pub(crate) struct InstanceLock {
+    _mutex: Win32Handle,
+}
+
+// Claims the session for this instance, waiting up to `timeout` for an earlier instance to exit.
+// Returns None when another instance keeps running.
+#[instrument]
+pub(crate) fn acquire(timeout: Duration) -> Result<Option<InstanceLock>> {
+    let mutex_name: Vec<u16> = windows_ops::to_wide_string(INSTANCE_MUTEX_NAME);
+    let mutex: Win32Handle = Win32Handle::from_null_on_failure(unsafe {
+        CreateMutexW(std::ptr::null(), 1, mutex_name.as_ptr())
+    }).map_err(err::SingleInstanceError::FailedToCreateMutex)?;
+    if unsafe { GetLastError() } != ERROR_ALREADY_EXISTS {
+        return Ok(Some(InstanceLock { _mutex: mutex }));
+    }
+
+    trace!("Another instance is running, waiting up to {:?} for it to exit", timeout);
+    let timeout_millis: u32 = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+    return match unsafe { WaitForSingleObject(mutex.raw(), timeout_millis) } {
+        // an instance which crashed leaves the mutex abandoned, which still hands it over
+        WAIT_OBJECT_0 | WAIT_ABANDONED => Ok(Some(InstanceLock { _mutex: mutex })),
+        WAIT_TIMEOUT => Ok(None),
+        _ => Err(err::SingleInstanceError::FailedToWait(unsafe { GetLastError() }).into()),
+    };
+}
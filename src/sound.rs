@@ -0,0 +1,79 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace};
+use windows_sys::Win32::Media::Audio::{PlaySoundW, SND_ALIAS, SND_ASYNC, SND_NODEFAULT, SND_SYSTEM};
+
+use crate::err;
+use crate::i18n::tr;
+use crate::windows_ops;
+
+// System sounds which can accompany the hidden-extensions warning.
+// These follow whatever sound scheme the user has configured in Windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub(crate) enum NotificationSound {
+    #[default]
+    Silent,
+    DefaultBeep,
+    Exclamation,
+    Asterisk,
+    CriticalStop,
+    Notification,
+}
+
+impl NotificationSound {
+    pub(crate) const ALL: [NotificationSound; 6] = [
+        NotificationSound::Silent,
+        NotificationSound::DefaultBeep,
+        NotificationSound::Exclamation,
+        NotificationSound::Asterisk,
+        NotificationSound::CriticalStop,
+        NotificationSound::Notification,
+    ];
+
+    // The registry alias of the sound under HKCU\AppEvents\Schemes\Apps\.Default
+    fn alias(&self) -> Option<&'static str> {
+        match self {
+            NotificationSound::Silent => None,
+            NotificationSound::DefaultBeep => Some(".Default"),
+            NotificationSound::Exclamation => Some("SystemExclamation"),
+            NotificationSound::Asterisk => Some("SystemAsterisk"),
+            NotificationSound::CriticalStop => Some("SystemHand"),
+            NotificationSound::Notification => Some("Notification.Default"),
+        }
+    }
+}
+
+impl Display for NotificationSound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name: String = match self {
+            NotificationSound::Silent => tr("sound-silent"),
+            NotificationSound::DefaultBeep => tr("sound-default-beep"),
+            NotificationSound::Exclamation => tr("sound-exclamation"),
+            NotificationSound::Asterisk => tr("sound-asterisk"),
+            NotificationSound::CriticalStop => tr("sound-critical-stop"),
+            NotificationSound::Notification => tr("sound-notification"),
+        };
+        write!(f, "{name}")
+    }
+}
+
+// Plays the sound without blocking. Playing `Silent` does nothing.
+#[instrument]
+pub(crate) fn play(sound: NotificationSound) -> Result<()> {
+    let Some(alias) = sound.alias() else {
+        trace!("Notification sound is turned off");
+        return Ok(());
+    };
+
+    let wide_alias: Vec<u16> = windows_ops::to_wide_string(alias);
+    // SND_SYSTEM ties the sound to the system volume so muting system sounds also mutes this
+    let was_played = unsafe {
+        PlaySoundW(wide_alias.as_ptr(), 0, SND_ALIAS | SND_ASYNC | SND_NODEFAULT | SND_SYSTEM)
+    };
+    match was_played {
+        0 => Err(err::SoundError(String::from(alias)).into()),
+        _ => Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+// A made-up download folder showing how a program can pass for a document
+// once Windows Explorer hides file extensions.
+
+pub(crate) struct DemoFile {
+    pub(crate) real_name: &'static str,
+    // whether double-clicking the file would run code instead of opening a document
+    pub(crate) is_dangerous: bool,
+}
+
+pub(crate) const DEMO_FILES: [DemoFile; 5] = [
+    DemoFile { real_name: "invoice.pdf.exe", is_dangerous: true },
+    DemoFile { real_name: "quarterly-report.docx", is_dangerous: false },
+    DemoFile { real_name: "holiday-photo.jpg.scr", is_dangerous: true },
+    DemoFile { real_name: "meeting-notes.txt", is_dangerous: false },
+    DemoFile { real_name: "payment-details.xlsx.js", is_dangerous: true },
+];
+
+// Mimics Windows Explorer, which only strips the last extension when extensions are hidden
+pub(crate) fn name_as_shown_by_explorer(real_name: &str, extensions_hidden: bool) -> &str {
+    if !extensions_hidden {
+        return real_name;
+    }
+    match real_name.rsplit_once('.') {
+        Some((name_without_extension, _)) => name_without_extension,
+        None => real_name
+    }
+}
@@ -0,0 +1,138 @@
+use std::path::Path;
+
+use anyhow::Result;
+use tracing::{instrument, trace};
+use windows::core::{BSTR, ComInterface};
+use windows::Win32::Foundation::{ERROR_FILE_NOT_FOUND, VARIANT_FALSE};
+use windows::Win32::System::Com::{CLSCTX_INPROC_SERVER, CoCreateInstance, VARIANT};
+use windows::Win32::System::TaskScheduler::{
+    IAction, IExecAction, ILogonTrigger, IPrincipal, ITaskDefinition, ITaskFolder, ITaskService, ITaskSettings,
+    ITrigger, TASK_ACTION_EXEC, TASK_CREATE_OR_UPDATE, TASK_LOGON_INTERACTIVE_TOKEN, TASK_RUNLEVEL_HIGHEST,
+    TASK_RUNLEVEL_LUA, TASK_RUNLEVEL_TYPE, TASK_TRIGGER_LOGON, TaskScheduler,
+};
+
+use crate::com::ComApartment;
+use crate::err;
+use crate::windows_ops;
+
+// Tasks go in the root of the Task Scheduler Library, where users look for them
+const TASK_FOLDER_PATH: &str = "\\";
+const TASK_NAME_PREFIX: &str = "NoHiddenExtensions";
+// Task Scheduler stops tasks after 72 hours unless told otherwise, and this one runs for the whole session
+const UNLIMITED_EXECUTION_TIME: &str = "PT0S";
+
+// Starts the executable whenever the current user signs in, through a scheduled task rather than the Run key.
+// Unlike the Run key, optimizer tools tend to leave it alone, and it can start this program as administrator
+// without a UAC prompt, though creating such a task needs administrator rights itself.
+// Replaces whatever task this program created before.
+#[instrument]
+pub(crate) fn register(executable_path: &Path, run_elevated: bool) -> Result<()> {
+    let _com: ComApartment = ComApartment::enter()?;
+
+    let service: ITaskService = connect_service().map_err(err::StartupTaskError::FailedToConnect)?;
+    let folder: ITaskFolder = unsafe { service.GetFolder(&BSTR::from(TASK_FOLDER_PATH)) }
+        .map_err(err::StartupTaskError::FailedToConnect)?;
+    let definition: ITaskDefinition = unsafe { service.NewTask(0) }
+        .map_err(err::StartupTaskError::FailedToRegister)?;
+    describe_task(&definition, executable_path, run_elevated)
+        .map_err(err::StartupTaskError::FailedToRegister)?;
+
+    unsafe {
+        folder.RegisterTaskDefinition(
+            &BSTR::from(task_name()),
+            &definition,
+            TASK_CREATE_OR_UPDATE.0,
+            VARIANT::default(),
+            VARIANT::default(),
+            TASK_LOGON_INTERACTIVE_TOKEN,
+            VARIANT::default(),
+        )
+    }.map_err(err::StartupTaskError::FailedToRegister)?;
+    trace!("Registered the scheduled task {}", task_name());
+    Ok(())
+}
+
+fn describe_task(
+    definition: &ITaskDefinition, executable_path: &Path, run_elevated: bool
+) -> windows::core::Result<()> {
+    unsafe {
+        let trigger: ITrigger = definition.Triggers()?.Create(TASK_TRIGGER_LOGON)?;
+        let logon_trigger: ILogonTrigger = trigger.cast()?;
+        // only when this user signs in, not anyone
+        logon_trigger.SetUserId(&BSTR::from(current_user()))?;
+
+        let action: IAction = definition.Actions()?.Create(TASK_ACTION_EXEC)?;
+        let exec_action: IExecAction = action.cast()?;
+        exec_action.SetPath(&BSTR::from(executable_path.to_string_lossy().as_ref()))?;
+        exec_action.SetArguments(&BSTR::from(windows_ops::AT_LOGON_FLAG))?;
+
+        let principal: IPrincipal = definition.Principal()?;
+        principal.SetLogonType(TASK_LOGON_INTERACTIVE_TOKEN)?;
+        let run_level: TASK_RUNLEVEL_TYPE = match run_elevated {
+            true => TASK_RUNLEVEL_HIGHEST,
+            false => TASK_RUNLEVEL_LUA,
+        };
+        principal.SetRunLevel(run_level)?;
+
+        // laptops are the machines most likely to pick up a misbehaving download
+        let settings: ITaskSettings = definition.Settings()?;
+        settings.SetDisallowStartIfOnBatteries(VARIANT_FALSE)?;
+        settings.SetStopIfGoingOnBatteries(VARIANT_FALSE)?;
+        settings.SetExecutionTimeLimit(&BSTR::from(UNLIMITED_EXECUTION_TIME))
+    }
+}
+
+// Deletes the task again. This method returns whether a change was made.
+#[instrument]
+pub(crate) fn unregister() -> Result<bool> {
+    let _com: ComApartment = ComApartment::enter()?;
+    if !is_registered()? {
+        trace!("No scheduled task to delete");
+        return Ok(false);
+    }
+    let folder: ITaskFolder = connect()?;
+    unsafe { folder.DeleteTask(&BSTR::from(task_name()), 0) }
+        .map_err(err::StartupTaskError::FailedToDelete)?;
+    Ok(true)
+}
+
+#[instrument]
+pub(crate) fn is_registered() -> Result<bool> {
+    let _com: ComApartment = ComApartment::enter()?;
+    let folder: ITaskFolder = connect()?;
+    return match unsafe { folder.GetTask(&BSTR::from(task_name())) } {
+        Ok(_) => Ok(true),
+        Err(error) if error.code() == ERROR_FILE_NOT_FOUND.to_hresult() => Ok(false),
+        Err(error) => Err(err::StartupTaskError::FailedToConnect(error).into()),
+    };
+}
+
+fn connect_service() -> windows::core::Result<ITaskService> {
+    unsafe {
+        let service: ITaskService = CoCreateInstance(&TaskScheduler, None, CLSCTX_INPROC_SERVER)?;
+        // empty arguments connect to this computer as the current user
+        service.Connect(VARIANT::default(), VARIANT::default(), VARIANT::default(), VARIANT::default())?;
+        Ok(service)
+    }
+}
+
+fn connect() -> Result<ITaskFolder> {
+    let service: ITaskService = connect_service().map_err(err::StartupTaskError::FailedToConnect)?;
+    let folder: ITaskFolder = unsafe { service.GetFolder(&BSTR::from(TASK_FOLDER_PATH)) }
+        .map_err(err::StartupTaskError::FailedToConnect)?;
+    Ok(folder)
+}
+
+// One task per user, since every user who signs in to the computer may want this program to start
+fn task_name() -> String {
+    format!("{} ({})", TASK_NAME_PREFIX, std::env::var("USERNAME").unwrap_or_default())
+}
+
+// e.g. CONTOSO\jdoe, as Task Scheduler expects it
+fn current_user() -> String {
+    format!(
+        "{}\\{}",
+        std::env::var("USERDOMAIN").unwrap_or_default(),
+        std::env::var("USERNAME").unwrap_or_default()
+    )
+}
@@ -0,0 +1,130 @@
+use chrono::{DateTime, Local};
+
+use crate::audit_log::AuditEntry;
+use crate::windows_ops::SettingScope;
+
+// How often something other than this program hid file extensions again, from the audit log
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ProtectionStats {
+    current_user_incident_count: usize,
+    all_users_incident_count: usize,
+    pub(crate) last_incident: Option<DateTime<Local>>,
+    // when the current streak started: the last incident, or the first change this program saw without one
+    protected_since: Option<DateTime<Local>>,
+}
+
+impl ProtectionStats {
+    pub(crate) fn from_entries(entries: &[AuditEntry]) -> ProtectionStats {
+        let incidents: Vec<&AuditEntry> = entries.iter().filter(|entry| entry.is_incident()).collect();
+        let incident_count_in = |scope: SettingScope| -> usize {
+            incidents.iter().filter(|entry| entry.scope == scope).count()
+        };
+        let last_incident: Option<DateTime<Local>> = incidents.iter().map(|entry| entry.timestamp).max();
+        let first_entry: Option<DateTime<Local>> = entries.iter().map(|entry| entry.timestamp).min();
+        ProtectionStats {
+            current_user_incident_count: incident_count_in(SettingScope::CurrentUser),
+            all_users_incident_count: incident_count_in(SettingScope::AllUsers),
+            last_incident,
+            protected_since: last_incident.or(first_entry),
+        }
+    }
+
+    pub(crate) fn incident_count(&self) -> usize {
+        self.current_user_incident_count + self.all_users_incident_count
+    }
+
+    pub(crate) fn incident_count_in(&self, scope: SettingScope) -> usize {
+        match scope {
+            SettingScope::CurrentUser => self.current_user_incident_count,
+            SettingScope::AllUsers => self.all_users_incident_count,
+        }
+    }
+
+    // Calendar days without an incident up to now, or None before anything was recorded. An incident late
+    // yesterday counts as a day ago, the way people count days.
+    pub(crate) fn days_protected(&self, now: DateTime<Local>) -> Option<i64> {
+        self.protected_since
+            .map(|protected_since| (now.date_naive() - protected_since.date_naive()).num_days().max(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local, TimeZone};
+
+    use crate::audit_log::AuditEntry;
+    use crate::stats::ProtectionStats;
+    use crate::windows_ops::SettingScope;
+
+    // A day in June, far from any daylight saving switch
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 6, day, hour, minute, 0).unwrap()
+    }
+
+    fn entry(
+        timestamp: DateTime<Local>, scope: SettingScope, new_value: Option<u32>, remediated_by_app: bool
+    ) -> AuditEntry {
+        AuditEntry { timestamp, scope, old_value: Some(0), new_value, remediated_by_app }
+    }
+
+    #[test]
+    fn an_empty_history_has_no_stats() {
+        let stats: ProtectionStats = ProtectionStats::from_entries(&[]);
+        assert_eq!(stats, ProtectionStats::default());
+        assert_eq!(stats.incident_count(), 0);
+        assert_eq!(stats.last_incident, None);
+        assert_eq!(stats.days_protected(at(1, 12, 0)), None);
+    }
+
+    #[test]
+    fn the_streak_starts_with_the_first_change_when_nothing_hid_file_extensions() {
+        let stats: ProtectionStats = ProtectionStats::from_entries(&[
+            entry(at(3, 9, 0), SettingScope::CurrentUser, Some(0), true),
+            entry(at(5, 9, 0), SettingScope::CurrentUser, Some(0), false),
+        ]);
+        assert_eq!(stats.incident_count(), 0);
+        assert_eq!(stats.last_incident, None);
+        assert_eq!(stats.days_protected(at(10, 8, 0)), Some(7));
+    }
+
+    #[test]
+    fn days_are_counted_across_midnight() {
+        let stats: ProtectionStats = ProtectionStats::from_entries(&[
+            entry(at(1, 23, 30), SettingScope::CurrentUser, Some(1), false),
+        ]);
+        assert_eq!(stats.days_protected(at(1, 23, 59)), Some(0));
+        assert_eq!(stats.days_protected(at(2, 0, 15)), Some(1));
+        assert_eq!(stats.days_protected(at(8, 0, 0)), Some(7));
+        // a clock set back doesn't make the streak negative
+        assert_eq!(stats.days_protected(at(1, 8, 0)), Some(0));
+    }
+
+    #[test]
+    fn the_latest_incident_restarts_the_streak() {
+        let stats: ProtectionStats = ProtectionStats::from_entries(&[
+            entry(at(1, 10, 0), SettingScope::CurrentUser, Some(1), false),
+            entry(at(1, 10, 1), SettingScope::CurrentUser, Some(0), true),
+            entry(at(4, 18, 0), SettingScope::CurrentUser, Some(1), false),
+            entry(at(4, 18, 1), SettingScope::CurrentUser, Some(0), true),
+        ]);
+        assert_eq!(stats.last_incident, Some(at(4, 18, 0)));
+        assert_eq!(stats.days_protected(at(6, 9, 0)), Some(2));
+    }
+
+    #[test]
+    fn incidents_are_counted_per_scope() {
+        let stats: ProtectionStats = ProtectionStats::from_entries(&[
+            entry(at(1, 10, 0), SettingScope::CurrentUser, Some(1), false),
+            entry(at(2, 10, 0), SettingScope::CurrentUser, Some(1), false),
+            entry(at(3, 10, 0), SettingScope::AllUsers, Some(1), false),
+            // none of these hid file extensions
+            entry(at(4, 10, 0), SettingScope::AllUsers, Some(0), true),
+            entry(at(5, 10, 0), SettingScope::CurrentUser, Some(0), false),
+            entry(at(6, 10, 0), SettingScope::AllUsers, None, false),
+        ]);
+        assert_eq!(stats.incident_count_in(SettingScope::CurrentUser), 2);
+        assert_eq!(stats.incident_count_in(SettingScope::AllUsers), 1);
+        assert_eq!(stats.incident_count(), 3);
+        assert_eq!(stats.last_incident, Some(at(3, 10, 0)));
+    }
+}
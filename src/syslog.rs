@@ -0,0 +1,127 @@
+use std::io::Write;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, trace};
+
+use crate::audit_log::AuditEntry;
+use crate::err;
+use crate::event_sinks;
+use crate::event_sinks::EventSink;
+
+const APP_NAME: &str = "no-hidden-extensions";
+const MESSAGE_ID: &str = "HideFileExtChanged";
+// the enterprise number RFC 5612 sets aside for documentation, since this program has none of its own
+const STRUCTURED_DATA_ID: &str = "hideFileExt@32473";
+
+// How long to wait for a syslog server over TCP before giving up on a message
+const TCP_TIMEOUT: Duration = Duration::from_secs(10);
+
+// RFC 5424 severities
+const WARNING_SEVERITY: u8 = 4;
+const NOTICE_SEVERITY: u8 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub(crate) enum SyslogTransport {
+    #[default]
+    Udp,
+    // framed by octet counting, as RFC 6587 describes
+    Tcp,
+}
+
+// Where to forward changes to over syslog, as set in the config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SyslogConfig {
+    pub(crate) host: String,
+    #[serde(default = "default_port")]
+    pub(crate) port: u16,
+    #[serde(default)]
+    pub(crate) transport: SyslogTransport,
+    // from 0 (kernel) to 23 (local7). Defaults to 13, log audit.
+    #[serde(default = "default_facility")]
+    pub(crate) facility: u8,
+}
+
+fn default_port() -> u16 {
+    514
+}
+
+fn default_facility() -> u8 {
+    13
+}
+
+// Forwards every change as an RFC 5424 message, e.g. to a SIEM
+#[derive(Debug)]
+pub(crate) struct SyslogSink {
+    config: SyslogConfig,
+}
+
+impl SyslogSink {
+    pub(crate) fn new(config: SyslogConfig) -> SyslogSink {
+        SyslogSink { config }
+    }
+
+    fn format(&self, entry: &AuditEntry) -> String {
+        // something else hiding file extensions is what the receiver should look into
+        let severity: u8 = match entry.is_incident() {
+            true => WARNING_SEVERITY,
+            false => NOTICE_SEVERITY,
+        };
+        let priority: u16 = u16::from(self.config.facility.min(23)) * 8 + u16::from(severity);
+        let hostname: String = std::env::var("COMPUTERNAME").unwrap_or_else(|_| String::from("-"));
+        let optional_value = |value: Option<u32>| value.map(|value| value.to_string()).unwrap_or_default();
+        format!(
+            "<{}>1 {} {} {} {} {} [{} scope=\"{:?}\" oldValue=\"{}\" newValue=\"{}\" remediatedByApp=\"{}\"] {}",
+            priority,
+            entry.timestamp.to_rfc3339(),
+            hostname,
+            APP_NAME,
+            std::process::id(),
+            MESSAGE_ID,
+            STRUCTURED_DATA_ID,
+            entry.scope,
+            optional_value(entry.old_value),
+            optional_value(entry.new_value),
+            entry.remediated_by_app,
+            event_sinks::describe(entry),
+        )
+    }
+}
+
+impl EventSink for SyslogSink {
+    fn name(&self) -> &'static str {
+        "syslog server"
+    }
+
+    #[instrument]
+    fn send(&self, entry: &AuditEntry) -> Result<()> {
+        let message: String = self.format(entry);
+        let to_send_error = |error: std::io::Error| err::SyslogError { host: self.config.host.clone(), source: error };
+        let address: SocketAddr = (self.config.host.as_str(), self.config.port)
+            .to_socket_addrs()
+            .map_err(to_send_error)?
+            .next()
+            .ok_or_else(|| to_send_error(std::io::ErrorKind::NotFound.into()))?;
+        trace!("Sending {} to {}", message, address);
+
+        match self.config.transport {
+            SyslogTransport::Udp => {
+                // any local port, on whichever IP version the server uses
+                let local_address: SocketAddr = match address {
+                    SocketAddr::V4(_) => SocketAddr::from(([0, 0, 0, 0], 0)),
+                    SocketAddr::V6(_) => SocketAddr::from(([0u16; 8], 0)),
+                };
+                let socket: UdpSocket = UdpSocket::bind(local_address).map_err(to_send_error)?;
+                socket.send_to(message.as_bytes(), address).map_err(to_send_error)?;
+            },
+            SyslogTransport::Tcp => {
+                let mut stream: TcpStream = TcpStream::connect_timeout(&address, TCP_TIMEOUT).map_err(to_send_error)?;
+                stream.set_write_timeout(Some(TCP_TIMEOUT)).map_err(to_send_error)?;
+                write!(stream, "{} {}", message.len(), message).map_err(to_send_error)?;
+            },
+        }
+        Ok(())
+    }
+}
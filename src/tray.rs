@@ -0,0 +1,261 @@
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use fluent_bundle::FluentValue;
+use tracing::{instrument, trace, warn};
+use tray_icon::{TrayIcon, TrayIconBuilder};
+use tray_icon::icon::Icon;
+use tray_icon::menu::{CheckMenuItem, Menu, MenuItem};
+
+use crate::build_info::APPLICATION_DISPLAY_NAME;
+use crate::err::IconLoadingError;
+use crate::i18n::{tr, tr_args};
+use crate::{ICON_HEIGHT, ICON_RGBA, ICON_WIDTH};
+
+// Color of the dot painted over the tray icon in the warning state
+const WARNING_DOT_RGBA: [u8; 4] = [220, 20, 20, 255];
+
+thread_local! {
+    // The tray icon is created on the main thread, which is also the thread iced runs `update()` on,
+    // so the UI can reach it without any synchronization.
+    static TRAY: RefCell<Option<Tray>> = RefCell::new(None);
+}
+
+// Menu item ids are only known once the menu is created, and menu events arrive on other threads
+static MENU_ITEM_ACTIONS: OnceLock<Vec<(u32, TrayMenuAction)>> = OnceLock::new();
+
+// Actions offered by the tray icon's context menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TrayMenuAction {
+    Open,
+    About,
+    RestartExplorer,
+    ToggleVerboseLogging,
+}
+
+struct Tray {
+    tray_icon: TrayIcon,
+    normal_icon: Icon,
+    warning_icon: Icon,
+    // only enabled while a restart is pending
+    restart_explorer_item: MenuItem,
+    verbose_logging_item: CheckMenuItem,
+    showing_warning: bool,
+    explorer_restart_pending: bool,
+    // minutes until paused protection resumes, rounded up
+    pause_minutes_left: Option<i64>,
+    pulsing: bool,
+    // while pulsing, whether the normal icon currently stands in for the warning icon
+    pulse_dimmed: bool,
+}
+
+impl Tray {
+    // hidden file extensions matter more than a pause, which matters more than a pending restart
+    fn tooltip(&self) -> String {
+        match (self.showing_warning, self.pause_minutes_left, self.explorer_restart_pending) {
+            (true, _, _) => tr("tray-warning-tooltip"),
+            (false, Some(minutes_left), _) =>
+                tr_args("tray-paused-tooltip", &[("minutes", FluentValue::from(minutes_left))]),
+            (false, None, true) => tr("tray-restart-pending-tooltip"),
+            (false, None, false) => String::from(APPLICATION_DISPLAY_NAME),
+        }
+    }
+
+    fn update_tooltip(&self) {
+        if let Err(error) = self.tray_icon.set_tooltip(Some(self.tooltip())) {
+            warn!("Failed to change the tray tooltip: {:?}", error);
+        }
+    }
+}
+
+// Adds this program to the Windows system tray. Can be called again after failing.
+#[instrument]
+pub(crate) fn create(verbose_logging: bool) -> Result<()> {
+    let warning_rgba: Vec<u8> = add_warning_dot(ICON_RGBA, ICON_WIDTH, ICON_HEIGHT);
+
+    let normal_icon: Icon = Icon::from_rgba(ICON_RGBA.to_vec(), ICON_WIDTH, ICON_HEIGHT)
+        .map_err(|bad_icon| IconLoadingError::FailedToConstructTrayIcon(Box::new(bad_icon)))?;
+    let warning_icon: Icon = Icon::from_rgba(warning_rgba, ICON_WIDTH, ICON_HEIGHT)
+        .map_err(|bad_icon| IconLoadingError::FailedToConstructTrayIcon(Box::new(bad_icon)))?;
+
+    let open_item = MenuItem::new(tr("tray-menu-open"), true, None);
+    let about_item = MenuItem::new(tr("tray-menu-about"), true, None);
+    let restart_explorer_item = MenuItem::new(tr("tray-menu-restart-explorer"), false, None);
+    // so support can ask for detailed logs without walking the user through the settings
+    let verbose_logging_item = CheckMenuItem::new(tr("tray-menu-verbose-logging"), true, verbose_logging, None);
+    let menu = Menu::new();
+    menu.append_items(&[&open_item, &about_item, &restart_explorer_item, &verbose_logging_item]);
+
+    let tray_icon: TrayIcon = TrayIconBuilder::new()
+        .with_tooltip(APPLICATION_DISPLAY_NAME)
+        .with_icon(normal_icon.clone())
+        .with_menu(Box::new(menu))
+        .build()
+        .map_err(|error| IconLoadingError::FailedToConstructTrayIcon(Box::new(error)))?;
+    // only once building succeeded, since a retry creates the items again with new ids
+    let _ = MENU_ITEM_ACTIONS.set(vec![
+        (open_item.id(), TrayMenuAction::Open),
+        (about_item.id(), TrayMenuAction::About),
+        (restart_explorer_item.id(), TrayMenuAction::RestartExplorer),
+        (verbose_logging_item.id(), TrayMenuAction::ToggleVerboseLogging),
+    ]);
+
+    TRAY.with(|tray| {
+        *tray.borrow_mut() = Some(Tray {
+            tray_icon,
+            normal_icon,
+            warning_icon,
+            restart_explorer_item,
+            verbose_logging_item,
+            showing_warning: false,
+            explorer_restart_pending: false,
+            pause_minutes_left: None,
+            pulsing: false,
+            pulse_dimmed: false,
+        });
+    });
+    Ok(())
+}
+
+// Whether the tray icon has been added yet
+pub(crate) fn exists() -> bool {
+    TRAY.with(|tray| tray.borrow().is_some())
+}
+
+pub(crate) fn menu_action_for(menu_item_id: u32) -> Option<TrayMenuAction> {
+    MENU_ITEM_ACTIONS.get()?
+        .iter()
+        .find(|(id, _)| *id == menu_item_id)
+        .map(|(_, action)| *action)
+}
+
+// Silently flags (or stops flagging) hidden file extensions through the tray icon and its tooltip.
+// This is the fallback for when popping up the window would interrupt the user.
+#[instrument]
+pub(crate) fn set_warning_indicator(show_warning: bool) {
+    TRAY.with(|tray| {
+        let mut tray = tray.borrow_mut();
+        let Some(tray) = tray.as_mut() else {
+            trace!("No tray icon exists yet");
+            return;
+        };
+        if tray.showing_warning == show_warning {
+            return;
+        }
+
+        let icon: Icon = match show_warning {
+            true => tray.warning_icon.clone(),
+            false => tray.normal_icon.clone(),
+        };
+        if let Err(error) = tray.tray_icon.set_icon(Some(icon)) {
+            warn!("Failed to change the tray icon: {:?}", error);
+        }
+        tray.showing_warning = show_warning;
+        tray.pulse_dimmed = false;
+        tray.update_tooltip();
+    });
+}
+
+// Alternates between the warning icon and the normal one, to catch the eye of a user who has the window hidden.
+// Each call is one step, so how fast the icon blinks is up to the caller.
+pub(crate) fn pulse() {
+    TRAY.with(|tray| {
+        let mut tray = tray.borrow_mut();
+        let Some(tray) = tray.as_mut() else {
+            return;
+        };
+        tray.pulsing = true;
+        tray.pulse_dimmed = !tray.pulse_dimmed;
+        let icon: Icon = match tray.pulse_dimmed {
+            true => tray.normal_icon.clone(),
+            false => tray.warning_icon.clone(),
+        };
+        if let Err(error) = tray.tray_icon.set_icon(Some(icon)) {
+            warn!("Failed to change the tray icon: {:?}", error);
+        }
+    });
+}
+
+// Puts back the icon `set_warning_indicator()` last asked for, after pulsing
+pub(crate) fn stop_pulsing() {
+    TRAY.with(|tray| {
+        let mut tray = tray.borrow_mut();
+        let Some(tray) = tray.as_mut().filter(|tray| tray.pulsing) else {
+            return;
+        };
+        // the bright step of a pulse shows the warning icon, which may not be what's wanted either
+        let icon: Icon = match tray.showing_warning {
+            true => tray.warning_icon.clone(),
+            false => tray.normal_icon.clone(),
+        };
+        if let Err(error) = tray.tray_icon.set_icon(Some(icon)) {
+            warn!("Failed to change the tray icon: {:?}", error);
+        }
+        tray.pulsing = false;
+        tray.pulse_dimmed = false;
+    });
+}
+
+// Offers to restart Windows Explorer from the tray menu while a fix is waiting on it
+#[instrument]
+pub(crate) fn set_explorer_restart_pending(explorer_restart_pending: bool) {
+    TRAY.with(|tray| {
+        let mut tray = tray.borrow_mut();
+        let Some(tray) = tray.as_mut() else {
+            trace!("No tray icon exists yet");
+            return;
+        };
+        if tray.explorer_restart_pending == explorer_restart_pending {
+            return;
+        }
+
+        tray.restart_explorer_item.set_enabled(explorer_restart_pending);
+        tray.explorer_restart_pending = explorer_restart_pending;
+        tray.update_tooltip();
+    });
+}
+
+// Counts down to the end of a pause in the tooltip, or stops once protection resumes
+pub(crate) fn set_pause_remaining(pause_minutes_left: Option<i64>) {
+    TRAY.with(|tray| {
+        let mut tray = tray.borrow_mut();
+        let Some(tray) = tray.as_mut() else {
+            return;
+        };
+        if tray.pause_minutes_left == pause_minutes_left {
+            return;
+        }
+        tray.pause_minutes_left = pause_minutes_left;
+        tray.update_tooltip();
+    });
+}
+
+// Keeps the menu's checkmark in step with the setting, which can also change from the settings screen
+pub(crate) fn set_verbose_logging(verbose_logging: bool) {
+    TRAY.with(|tray| {
+        if let Some(tray) = tray.borrow().as_ref() {
+            tray.verbose_logging_item.set_checked(verbose_logging);
+        }
+    });
+}
+
+// Paints a dot over the bottom-right corner of the icon so the warning state is visible at a glance
+fn add_warning_dot(rgba: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let mut rgba: Vec<u8> = rgba.to_vec();
+    let radius: f32 = width.min(height) as f32 / 4.0;
+    let center_x: f32 = width as f32 - radius;
+    let center_y: f32 = height as f32 - radius;
+
+    for y in 0..height {
+        for x in 0..width {
+            let distance_x: f32 = x as f32 + 0.5 - center_x;
+            let distance_y: f32 = y as f32 + 0.5 - center_y;
+            if distance_x * distance_x + distance_y * distance_y <= radius * radius {
+                let offset: usize = ((y * width + x) * 4) as usize;
+                rgba[offset..offset + 4].copy_from_slice(&WARNING_DOT_RGBA);
+            }
+        }
+    }
+    rgba
+}
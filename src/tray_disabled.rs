@@ -0,0 +1,40 @@
+// Stands in for tray.rs in builds without the tray feature, so the window carries on as it does whenever
+// the tray icon can't be added
+#![allow(dead_code)]
+
+use anyhow::Result;
+
+use crate::err;
+
+// Actions offered by the tray icon's context menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TrayMenuAction {
+    Open,
+    About,
+    RestartExplorer,
+    ToggleVerboseLogging,
+}
+
+pub(crate) fn create(_verbose_logging: bool) -> Result<()> {
+    Err(err::FeatureDisabledError("tray").into())
+}
+
+pub(crate) fn exists() -> bool {
+    false
+}
+
+pub(crate) fn menu_action_for(_menu_item_id: u32) -> Option<TrayMenuAction> {
+    None
+}
+
+pub(crate) fn set_warning_indicator(_show_warning: bool) {}
+
+pub(crate) fn pulse() {}
+
+pub(crate) fn stop_pulsing() {}
+
+pub(crate) fn set_explorer_restart_pending(_explorer_restart_pending: bool) {}
+
+pub(crate) fn set_pause_remaining(_pause_minutes_left: Option<i64>) {}
+
+pub(crate) fn set_verbose_logging(_verbose_logging: bool) {}
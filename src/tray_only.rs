@@ -0,0 +1,142 @@
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::Local;
+use tracing::{instrument, trace, warn};
+use tray_icon::{ClickEvent, TrayEvent};
+use tray_icon::menu::MenuEvent;
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    DispatchMessageW, MSG, MsgWaitForMultipleObjects, PeekMessageW, PM_REMOVE, QS_ALLINPUT, TranslateMessage,
+};
+
+use crate::agent;
+use crate::agent::AgentEvent;
+use crate::checks;
+use crate::checks::SecurityCheck;
+use crate::config;
+use crate::config::Config;
+use crate::ipc;
+use crate::ipc::IpcCommand;
+use crate::logging;
+use crate::power;
+use crate::registry_watcher;
+use crate::rules::FILE_EXTENSIONS_RULE_ID;
+use crate::tray;
+use crate::tray::TrayMenuAction;
+
+// How long to sleep between looks at the tray, other processes' requests and the watchers, unless a window message
+// arrives first
+const PUMP_INTERVAL: Duration = Duration::from_millis(100);
+
+// Why the tray-only mode ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TrayOnlyExit {
+    OpenWindow,
+    // file extensions are hidden. The window starts hidden, and shows itself unless e.g. it's quiet hours.
+    FileExtensionsHidden,
+    // another process asked this program to exit
+    Exit,
+}
+
+// Keeps only the tray icon until the window is needed: when the user opens it from the tray, another process asks
+// for it, or file extensions are hidden. The window does the fixing and the notifying, as it would have without this
+// mode. The tray icon lives on this thread, so this method pumps its window messages until it returns.
+#[instrument(skip(config))]
+pub(crate) fn run_until_window_needed(config: &mut Config) -> Result<TrayOnlyExit> {
+    let checks: Vec<Arc<dyn SecurityCheck>> = checks::active_checks(config);
+    let (event_sender, event_receiver): (Sender<AgentEvent>, Receiver<AgentEvent>) = channel();
+    for watched_key in checks::watched_keys(&checks) {
+        let event_sender: Sender<AgentEvent> = event_sender.clone();
+        std::thread::Builder::new()
+            .name(String::from("registry watcher"))
+            .spawn(move || agent::watch(watched_key, event_sender))?;
+    }
+
+    let exit: Result<TrayOnlyExit> = wait_until_window_needed(config, &checks, &event_receiver);
+    // the window starts watchers of its own
+    registry_watcher::stop_all();
+    exit
+}
+
+fn wait_until_window_needed(
+    config: &mut Config, checks: &[Arc<dyn SecurityCheck>], event_receiver: &Receiver<AgentEvent>
+) -> Result<TrayOnlyExit> {
+    let mut failing_checks: Vec<String> = agent::failing_checks_of(checks);
+    let mut last_evaluated: Instant = Instant::now();
+    loop {
+        // the window only ever calls attention to the other checks on its dashboard
+        if failing_checks.iter().any(|check_id| check_id == FILE_EXTENSIONS_RULE_ID)
+            && !config.protection_paused(Local::now())
+        {
+            trace!("Creating the window, since file extensions are hidden");
+            return Ok(TrayOnlyExit::FileExtensionsHidden);
+        }
+        pump_window_messages();
+
+        while let Ok(tray_event) = TrayEvent::receiver().try_recv() {
+            if matches!(tray_event.event, ClickEvent::Left | ClickEvent::Double) {
+                return Ok(TrayOnlyExit::OpenWindow);
+            }
+        }
+        while let Ok(menu_event) = MenuEvent::receiver().try_recv() {
+            match tray::menu_action_for(menu_event.id) {
+                Some(TrayMenuAction::Open | TrayMenuAction::About) => return Ok(TrayOnlyExit::OpenWindow),
+                Some(TrayMenuAction::ToggleVerboseLogging) => toggle_verbose_logging(config),
+                // nothing has been fixed yet, so no restart is pending
+                Some(TrayMenuAction::RestartExplorer) => {},
+                None => trace!("Ignoring event for unknown menu item {}", menu_event.id),
+            }
+        }
+        while let Some(request) = ipc::take_request()? {
+            match request.command {
+                IpcCommand::Status => request.respond(&agent::status_report(&failing_checks)),
+                IpcCommand::Exit => {
+                    request.respond(&agent::status_report(&failing_checks));
+                    return Ok(TrayOnlyExit::Exit);
+                },
+                // the window answers once it's open
+                IpcCommand::Fix | IpcCommand::ShowWindow => {
+                    ipc::requeue(request);
+                    return Ok(TrayOnlyExit::OpenWindow);
+                },
+            }
+        }
+        if power::take_resume()?.is_some() {
+            registry_watcher::rearm_all();
+        }
+
+        let values_changed: bool = event_receiver.try_iter().count() > 0;
+        let poll_due: bool = config.poll_interval.duration()
+            .is_some_and(|poll_interval| last_evaluated.elapsed() >= poll_interval);
+        if values_changed || poll_due {
+            failing_checks = agent::failing_checks_of(checks);
+            last_evaluated = Instant::now();
+        }
+
+        unsafe { MsgWaitForMultipleObjects(0, std::ptr::null(), 0, PUMP_INTERVAL.as_millis() as u32, QS_ALLINPUT) };
+    }
+}
+
+fn toggle_verbose_logging(config: &mut Config) {
+    config.verbose_logging = !config.verbose_logging;
+    if let Err(error) = config::save(config) {
+        warn!("Failed to save the config: {:?}", error);
+    }
+    if let Err(error) = logging::set_verbose(config.verbose_logging) {
+        warn!("Failed to change how much is logged: {:?}", error);
+    }
+    tray::set_verbose_logging(config.verbose_logging);
+}
+
+// Handles whatever Windows sent the tray icon's hidden window, such as clicks and menu picks
+fn pump_window_messages() {
+    let mut message: MSG = unsafe { std::mem::zeroed() };
+    while unsafe { PeekMessageW(&mut message, 0, 0, 0, PM_REMOVE) } != 0 {
+        unsafe {
+            TranslateMessage(&message);
+            DispatchMessageW(&message);
+        }
+    }
+}
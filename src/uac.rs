@@ -0,0 +1,81 @@
+use anyhow::Result;
+use tracing::{instrument, trace};
+use winreg::enums::HKEY_LOCAL_MACHINE;
+
+use crate::checks::{CheckStatus, SecurityCheck, Severity};
+use crate::err;
+use crate::i18n::tr;
+use crate::rules::{RuleHive, WatchedKey};
+use crate::registry::WinregRegistry;
+use crate::windows_ops;
+use crate::windows_ops::{ExplorerRestartPolicy, FixOutcome};
+
+// Path under HKEY_LOCAL_MACHINE to the key holding User Account Control's settings
+const UAC_POLICY_REGKEY_SUBPATH: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Policies\\System";
+
+// 0 turns User Account Control off entirely, so every program an administrator runs has full control.
+// Missing means on.
+const ENABLE_LUA_VALUE_NAME: &str = "EnableLUA";
+
+// How administrators are asked before a program gets full control, where 0 means never asking.
+// That's the "Never notify" end of the slider. Missing means Windows' default, which asks.
+const CONSENT_PROMPT_BEHAVIOR_ADMIN_VALUE_NAME: &str = "ConsentPromptBehaviorAdmin";
+
+// Where the user can change User Account Control's settings. Windows asks for administrator rights to open it.
+const UAC_SETTINGS_PROGRAM: &str = "UserAccountControlSettings.exe";
+
+// Warns when User Account Control is off or never asks, since then a disguised program which the user
+// opens can take over the machine without any prompt giving it away.
+// Changing this is left to the user, since it takes administrator rights and a restart.
+#[derive(Debug)]
+pub(crate) struct UacCheck;
+
+impl SecurityCheck for UacCheck {
+    fn id(&self) -> &str {
+        "uac"
+    }
+
+    fn name(&self) -> String {
+        tr("check-uac")
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    #[instrument]
+    fn evaluate(&self) -> Result<CheckStatus> {
+        let enable_lua: Option<u32> = windows_ops::get_dword_value(
+            &WinregRegistry, HKEY_LOCAL_MACHINE, UAC_POLICY_REGKEY_SUBPATH, ENABLE_LUA_VALUE_NAME
+        )?;
+        let consent_prompt_behavior: Option<u32> = windows_ops::get_dword_value(
+            &WinregRegistry, HKEY_LOCAL_MACHINE, UAC_POLICY_REGKEY_SUBPATH, CONSENT_PROMPT_BEHAVIOR_ADMIN_VALUE_NAME
+        )?;
+        trace!("EnableLUA is {:?} and ConsentPromptBehaviorAdmin is {:?}", enable_lua, consent_prompt_behavior);
+
+        return match (enable_lua, consent_prompt_behavior) {
+            (Some(0), _) | (_, Some(0)) => Ok(CheckStatus::Warn),
+            _ => Ok(CheckStatus::Pass),
+        };
+    }
+
+    fn can_remediate(&self) -> bool {
+        false
+    }
+
+    fn remediate(&self, _restart_policy: ExplorerRestartPolicy) -> Result<FixOutcome> {
+        Err(err::CheckError::CannotRemediate(String::from(self.id())).into())
+    }
+
+    fn settings_program(&self) -> Option<&'static str> {
+        Some(UAC_SETTINGS_PROGRAM)
+    }
+
+    fn watched_keys(&self) -> Vec<WatchedKey> {
+        vec![WatchedKey::new(
+            RuleHive::LocalMachine,
+            UAC_POLICY_REGKEY_SUBPATH,
+            &[ENABLE_LUA_VALUE_NAME, CONSENT_PROMPT_BEHAVIOR_ADMIN_VALUE_NAME],
+        )]
+    }
+}
@@ -1,257 +1,3674 @@
-use iced::{Alignment, Application, Command, Element, executor, Length, subscription, Subscription, Theme, window};
-use iced::alignment::{Horizontal, Vertical};
-use iced::widget::{button, checkbox, column, container, text, Text};
-use iced::window::{Event, Mode, UserAttention};
-use tracing::{instrument, trace};
-use tray_icon::TrayEvent;
-
-use crate::ui::Message::{Backend, Ui, User};
-use crate::windows_ops;
-
-pub(crate) const APPLICATION_DISPLAY_NAME: &str = "no-hidden-extensions";
-
-// Notification of user input
-#[derive(Debug, Clone)]
-pub(crate) enum UserMessage {
-    RunAtStartup,
-    DontRunAtStartup,
-    HideFileExtensions,
-}
-
-// Notification of change in system state
-#[derive(Debug, Clone)]
-pub(crate) enum BackendMessage {
-    FileExtensionsAreNowHidden,
-    FileExtensionsAreNoLongerHidden,
-}
-
-// Notification of change in UI windowing
-#[derive(Debug, Clone)]
-pub(crate) enum UiMessage {
-    MinimizeToTray,
-    RestoreFromTray
-}
-
-// Used for communication between components
-#[derive(Debug, Clone)]
-pub(crate) enum Message {
-    User(UserMessage),
-    Backend(BackendMessage),
-    Ui(UiMessage),
-}
-
-#[derive(Debug, Clone)]
-pub(crate) struct UiOptions {
-    pub(crate) start_minimized: bool,
-    pub(crate) theme: Theme,
-}
-
-// primary application state
-#[derive(Debug, Clone)]
-pub(crate) struct NoHiddenExtensionsState {
-    run_at_startup: bool,
-    file_extensions_hidden: bool,
-    system_theme: Theme,
-}
-
-impl Application for NoHiddenExtensionsState {
-    type Executor = executor::Default;
-    type Message = Message;
-    type Theme = Theme;
-    type Flags = UiOptions;
-
-    #[instrument]
-    fn new(ui_options: UiOptions) -> (NoHiddenExtensionsState, Command<Message>) {
-        let file_extensions_hidden: &bool = &windows_ops::are_file_extensions_hidden()
-            .expect("Could not determine whether file extensions are hidden");
-
-        let run_at_startup: &bool = &windows_ops::will_app_run_at_startup()
-            .expect("Could not determine whether app will run at startup");
-
-        let no_hidden_extensions_state = NoHiddenExtensionsState {
-            run_at_startup: *run_at_startup,
-            file_extensions_hidden: *file_extensions_hidden,
-            system_theme: ui_options.theme,
-        };
-
-        let commands: Command<Message> = if *file_extensions_hidden {
-            // file extensions are already hidden, so we need to tell the user regardless of
-            // whether we're supposed to start minimized
-            get_commands_which_notify_user()
-        } else if ui_options.start_minimized {
-            window::change_mode(Mode::Hidden)
-        } else {
-            Command::none()
-        };
-
-        return (no_hidden_extensions_state, commands);
-    }
-
-    fn title(&self) -> String {
-        String::from(APPLICATION_DISPLAY_NAME)
-    }
-
-    #[instrument]
-    fn update(&mut self, message: Message) -> Command<Message> {
-        return match message {
-            User(user_message) => {
-                match user_message {
-                    UserMessage::RunAtStartup => {
-                        windows_ops::run_this_program_at_startup()
-                            .expect("Unable to make this program run at startup");
-                        self.run_at_startup = true;
-                        Command::none()
-                    },
-                    UserMessage::DontRunAtStartup => {
-                        windows_ops::dont_run_this_program_at_startup()
-                            .expect("Unable to stop making this program run at startup");
-                        self.run_at_startup = false;
-                        Command::none()
-                    },
-                    UserMessage::HideFileExtensions => {
-                        windows_ops::turn_off_file_extension_hiding()
-                            .expect("Unable to turn off file extension hiding");
-                        Command::none()
-                    },
-                }
-            },
-            Backend(backend_message) => {
-                match backend_message {
-                    BackendMessage::FileExtensionsAreNowHidden => {
-                        self.file_extensions_hidden = true;
-                        get_commands_which_notify_user()
-                    },
-                    BackendMessage::FileExtensionsAreNoLongerHidden => {
-                        self.file_extensions_hidden = false;
-                        Command::none()
-                    },
-                }
-            },
-            Ui(ui_message) => {
-                match ui_message {
-                    UiMessage::RestoreFromTray => {
-                        Command::batch(vec![
-                            window::change_mode(Mode::Windowed),
-                            window::minimize(false),
-                            window::gain_focus(),
-                        ])
-                    },
-                    UiMessage::MinimizeToTray => {
-                        window::change_mode::<Message>(Mode::Hidden)
-                    }
-                }
-            }
-        };
-    }
-
-    #[instrument]
-    fn view(&self) -> Element<Message> {
-        let body_text: Text = match self.file_extensions_hidden {
-            true => text(
-                "Warning - file extensions are hidden in Windows Explorer. This means a higher risk \
-                 of falling for a phishing attack."
-            ),
-            false => text(
-                "File extensions are visible in Windows Explorer, which is great! \
-                 It is harder for you to fall for a phishing attack."
-            )
-        }.horizontal_alignment(Horizontal::Center)
-        .vertical_alignment(Vertical::Center);
-
-        let stop_hiding_file_extensions_button = match self.file_extensions_hidden {
-            true => button("Stop hiding file extensions and restart Windows Explorer").on_press(User(UserMessage::HideFileExtensions)),
-            false => button("Stop hiding file extensions and restart Windows Explorer")
-        };
-
-        let run_at_startup_checkbox = checkbox(
-            "Run at Windows startup",
-            self.run_at_startup,
-            |run_at_startup| match run_at_startup {
-                true => User(UserMessage::RunAtStartup),
-                false => User(UserMessage::DontRunAtStartup)
-            }
-        );
-
-        let content = column![body_text, stop_hiding_file_extensions_button, run_at_startup_checkbox]
-            .align_items(Alignment::Center)
-            .spacing(20)
-            .padding(20);
-
-        container(content)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .center_x()
-            .center_y()
-            .into()
-    }
-
-    fn theme(&self) -> Theme {
-        self.system_theme.clone()
-    }
-
-    #[instrument]
-    fn subscription(&self) -> Subscription<Message> {
-        return Subscription::batch(vec![
-            get_listener_for_backend_messages(),
-            get_listener_for_ui_messages(),
-            get_listener_for_window_resize_messages(),
-        ]);
-    }
-}
-
-fn get_listener_for_backend_messages() -> Subscription<Message> {
-    subscription::unfold(
-        std::any::TypeId::of::<BackendMessage>(),
-        0,
-        |_| async {
-            trace!("Waiting for a change in the Windows Explorer registry key");
-            windows_ops::wait_for_any_change_in_windows_explorer_regkey()
-                .expect("Failed to wait for a change in the Windows Explorer Advanced registry key");
-            trace!("Received a change in the Windows Explorer registry key");
-
-            match windows_ops::are_file_extensions_hidden().expect("Failed to check whether file extensions are currently being hidden") {
-                true => (Some(Backend(BackendMessage::FileExtensionsAreNowHidden)), 0),
-                false => (Some(Backend(BackendMessage::FileExtensionsAreNoLongerHidden)), 0)
-            }
-        }
-    )
-}
-
-fn get_listener_for_ui_messages() -> Subscription<Message> {
-    subscription::events_with(|event, _status|
-        match event {
-            iced::Event::Window(window_event) => {
-                match window_event {
-                    // these are typical values when user clicks on the minimize button
-                    Event::Resized {width: 0, height: 0} => {
-                        Some(Ui(UiMessage::MinimizeToTray))
-                    },
-                    _ => None
-                }
-            },
-            _ => None
-        }
-    )
-}
-
-fn get_listener_for_window_resize_messages() -> Subscription<Message> {
-    subscription::unfold(
-        std::any::TypeId::of::<UiMessage>(),
-        0,
-        |_| async {
-            let _: TrayEvent = TrayEvent::receiver().recv()
-                .expect("Unable to listen for tray events");
-            // We don't have a menu, so allow any tray event to restore the window
-            (Some(Ui(UiMessage::RestoreFromTray)), 0)
-        }
-    )
-}
-
-fn get_commands_which_notify_user() -> Command<Message> {
-    Command::batch(vec![
-        window::change_mode(Mode::Windowed),
-        window::minimize(false),
-        window::request_user_attention(Some(UserAttention::Informational)),
-        window::gain_focus(),
-    ])
-}
+use std::ffi::OsStr;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use fluent_bundle::FluentValue;
+use iced::{Alignment, Application, Color, Command, Element, executor, Length, subscription, Subscription, Theme, window};
+use iced::alignment::Horizontal;
+use iced::futures::channel::oneshot;
+use iced::keyboard::{Event as KeyboardEvent, KeyCode, Modifiers};
+use iced::theme;
+use iced::widget::{
+    button, checkbox, container, pick_list, scrollable, svg, text, text_input, tooltip, Button, Column, Row, Svg, Text,
+};
+use iced::widget::tooltip::Position;
+use iced::window::{Event, Mode, UserAttention};
+use tracing::{instrument, trace, warn};
+#[cfg(feature = "tray")]
+use tray_icon::{ClickEvent, TrayEvent};
+#[cfg(feature = "tray")]
+use tray_icon::menu::MenuEvent;
+
+use crate::accessibility;
+use crate::accessibility::{AccessibleConfirmation, AccessibleControl, AccessibleView};
+use crate::advanced_backup;
+use crate::advanced_backup::AdvancedKeyBackup;
+use crate::associations;
+use crate::associations::{Association, SCRIPT_ASSOCIATIONS_CHECK_ID};
+use crate::audit_log;
+use crate::audit_log::AuditEntry;
+use crate::build_info;
+use crate::build_info::APPLICATION_DISPLAY_NAME;
+#[cfg(feature = "watcher-etw")]
+use crate::change_tracer::ChangeTracer;
+use crate::checks;
+use crate::checks::{CheckStatus, SecurityCheck, Severity};
+use crate::config;
+use crate::crash;
+use crate::effective_state;
+use crate::effective_state::{EffectiveState, SettingSource};
+use crate::elevation;
+use crate::elevation::PendingAction;
+use crate::event_sinks;
+use crate::export;
+use crate::filenames;
+use crate::folder_watcher;
+use crate::folder_watcher::FolderWatcher;
+use crate::config::{Config, PauseDuration, PollInterval, ScanInterval, StartupMethod, TextScale, ThemePreference};
+use crate::history::{History, HistoryEventKind};
+use crate::hotkey::{Hotkey, HotkeyAction, HotkeyListener};
+use crate::i18n;
+use crate::logging;
+use crate::ipc;
+use crate::ipc::{IpcCommand, IpcRequest, StatusReport};
+use crate::i18n::{LayoutDirection, tr, tr_args};
+use crate::mark_of_the_web;
+use crate::mark_of_the_web::Inspection;
+use crate::metrics;
+use crate::notifications::{DeferredSummary, Notification, NotificationScheduler};
+use crate::power;
+use crate::quarantine;
+use crate::quarantine::DefusedFile;
+use crate::registry_audit;
+use crate::registry_audit::ChangeAttribution;
+use crate::process::WindowsProcessManager;
+use crate::registry::WinregRegistry;
+use crate::registry_watcher;
+use crate::registry_watcher::{RegistryWatcher, WatchEvent};
+use crate::rules::{FILE_EXTENSIONS_RULE_ID, ValueSnapshot, WatchedKey};
+use crate::sound;
+use crate::sound::NotificationSound;
+use crate::spoofing_demo::{DEMO_FILES, name_as_shown_by_explorer};
+use crate::self_update;
+use crate::session;
+use crate::session::SessionChange;
+use crate::startup_task;
+use crate::stats::ProtectionStats;
+use crate::tray;
+use crate::user_hives;
+use crate::user_hives::UserHiveResult;
+use crate::tray::TrayMenuAction;
+use crate::update_check;
+use crate::update_check::{Release, Version};
+use crate::webhook;
+use crate::ui::Message::{Backend, Ui, User};
+use crate::windows_ops;
+use crate::windows_ops::{ExplorerRestartPolicy, FixOutcome, SettingScope, STARTUP_REGISTRY_VALUE};
+use crate::worker;
+use crate::worker::WorkerSender;
+
+// Window size when only the dashboard and controls are shown, before any scaling
+pub(crate) const DEFAULT_WINDOW_SIZE: (u32, u32) = (475, 300);
+
+// Window size when a panel is expanded below the controls, before any scaling
+const WINDOW_SIZE_WITH_EXPANDED_PANEL: (u32, u32) = (475, 525);
+
+// Window size while settings are shown, before any scaling
+const SETTINGS_WINDOW_SIZE: (u32, u32) = (475, 400);
+
+// Used to call out files which are really programs
+const DANGER_COLOR: Color = Color::from_rgb(0.85, 0.1, 0.1);
+
+// How long each step of the tray icon's pulse lasts, while it calls attention to hidden file extensions
+const TRAY_PULSE_INTERVAL: Duration = Duration::from_millis(700);
+
+// Used for the headline while every check passes
+const SAFE_COLOR: Color = Color::from_rgb(0.1, 0.62, 0.29);
+
+// The glyphs at the start of the status row, drawn in the same colors as the headline
+const STATUS_OK_SVG: &[u8] = include_bytes!("../resources/status_ok.svg");
+const STATUS_WARNING_SVG: &[u8] = include_bytes!("../resources/status_warning.svg");
+
+// How wide and tall the status glyph is, before any scaling
+const STATUS_GLYPH_SIZE: u16 = 48;
+
+// How long to wait before watching the registry again after watching it failed
+const WATCH_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+// How often time-based work such as ending quiet hours is checked
+const CLOCK_TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+// How often adding the tray icon is tried again while there is none
+#[cfg(feature = "tray")]
+const TRAY_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+// How much of the history goes into the status copied for support
+const EVENTS_IN_STATUS_REPORT: usize = 5;
+
+// Notification of user input
+#[derive(Debug, Clone)]
+pub(crate) enum UserMessage {
+    RunAtStartup,
+    DontRunAtStartup,
+    RequestFix,
+    ConfirmFix,
+    CancelFix,
+    ToggleDontAskAgain(bool),
+    ChangeDeferExplorerRestart(bool),
+    ChangeAlwaysRestartExplorer(bool),
+    ChangeTraceChanges(bool),
+    RestartExplorerNow,
+    HideFileExtensions,
+    HideFileExtensionsForAllUsers,
+    HideFileExtensionsForLoadedUsers,
+    HideFileExtensionsForNewAccounts,
+    TurnOnChangeAttribution,
+    TurnOffChangeAttribution,
+    // each of these names a check by its ID
+    RemediateCheck(String),
+    UndoCheck(String),
+    OpenCheckSettings(String),
+    ToggleCheck(String, bool),
+    ConfirmRemediateCheck,
+    CancelRemediateCheck,
+    ExpandPanel(Panel),
+    CollapsePanel,
+    DismissDeferredSummary,
+    DismissDisguisedFiles,
+    ExportEvents,
+    // a file was dropped onto the window
+    InspectFile(PathBuf),
+    DefuseFile(PathBuf),
+    RestoreDefusedFile(DefusedFile),
+    OpenSettings,
+    CloseSettings,
+    SelectSettingsTab(SettingsTab),
+    ChangeThemePreference(ThemePreference),
+    ChangeTextScale(TextScale),
+    ChangeNotificationSound(NotificationSound),
+    // the webhook field was edited, which is only saved once the user submits it
+    ChangeWebhookUrl(String),
+    SaveWebhookUrl,
+    ChangePollInterval(PollInterval),
+    ChangeScanInterval(ScanInterval),
+    ChangeStartupMethod(StartupMethod),
+    ChangeStartupTaskElevated(bool),
+    ChangeStartMinimized(bool),
+    PauseProtection(PauseDuration),
+    ResumeProtection,
+    ChangeKeepWarningOnTop(bool),
+    AcknowledgeWarning,
+    ChangeTrayOnly(bool),
+    CopyStatus,
+    OpenFolderOptions,
+    OpenRegistryEditor,
+    ChangeAdvancedMode(bool),
+    RestoreAdvancedKeyBackup,
+    UndoLastChange,
+    ChangeVerboseLogging(bool),
+    // from the tray menu, which doesn't know the current setting
+    ToggleVerboseLogging,
+    ChangeCheckForUpdates(bool),
+    CheckForUpdates,
+    OpenReleasePage,
+    InstallUpdate,
+    DismissUpdate,
+    ScreenReaderAction(AccessibleControl),
+    RetryFailedOperation,
+    RelaunchElevated(PendingAction),
+    DismissError,
+    RefreshState,
+}
+
+// Extra content which can be expanded below the controls, one at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Panel {
+    History,
+    SpoofingDemo,
+    ScriptAssociations,
+    MarkOfTheWeb,
+    About,
+}
+
+// Sections of the settings view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SettingsTab {
+    Notifications,
+    Startup,
+    Enforcement,
+    Advanced,
+}
+
+impl SettingsTab {
+    const ALL: [SettingsTab; 4] = [
+        SettingsTab::Notifications,
+        SettingsTab::Startup,
+        SettingsTab::Enforcement,
+        SettingsTab::Advanced,
+    ];
+}
+
+impl Display for SettingsTab {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name: String = match self {
+            SettingsTab::Notifications => tr("settings-tab-notifications"),
+            SettingsTab::Startup => tr("settings-tab-startup"),
+            SettingsTab::Enforcement => tr("settings-tab-enforcement"),
+            SettingsTab::Advanced => tr("settings-tab-advanced"),
+        };
+        write!(f, "{name}")
+    }
+}
+
+// Which screen the window is showing, along with the state which only that screen needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ActiveView {
+    Main { expanded_panel: Option<Panel> },
+    Settings { tab: SettingsTab },
+}
+
+// Notification of change in system state
+#[derive(Debug, Clone)]
+pub(crate) enum BackendMessage {
+    // something under a key which a check watches changed, which may or may not matter
+    // the lowercase names of the values whose data changed
+    WatchedValuesChanged(WatchedKey, Vec<String>),
+    FileExtensionsAreNowHidden(SettingSource),
+    FileExtensionsAreNoLongerHidden(SettingSource),
+    // each of these names a check by its ID
+    CheckStartedFailing(String),
+    CheckStartedPassing(String),
+    // programs whose names pass for something else showed up in the Downloads folder
+    DisguisedFilesFound(Vec<PathBuf>),
+    // event tracing saw a program turn HideFileExt on
+    HideFileExtTurnedOnBy(ChangeAttribution),
+    // another process sent a command over the named pipe
+    IpcRequestReceived(IpcRequest),
+    HotkeyPressed(HotkeyAction),
+    NewerReleaseFound(Release),
+    // the check the user asked for finished, with the error's details if it failed
+    UpdateCheckFinished(Result<Option<Release>, String>),
+    // the update the user asked for was installed and started, or failed to be
+    UpdateInstallFinished(Result<(), String>),
+    ClockTick,
+    SystemThemeChanged(Theme),
+    ResumedFromSleep,
+    SessionChanged(SessionChange),
+    PollTick,
+    // time for a scheduled scan, which evaluates every check whether or not anything changed
+    ScanDue,
+}
+
+// Notification of change in UI windowing
+#[derive(Debug, Clone)]
+pub(crate) enum UiMessage {
+    MinimizeToTray,
+    RestoreFromTray,
+    OpenPanelFromTray(Panel),
+    // try adding the tray icon again, after it couldn't be added before
+    RetryTrayCreation,
+    PulseTrayIcon,
+    // from the keyboard, acting on whatever is shown
+    FocusNext,
+    FocusPrevious,
+    Confirm,
+    GoBack,
+    FixFromKeyboard,
+    MinimizeFromKeyboard,
+}
+
+// Operations whose failure is shown to the user rather than ending the program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum FailedOperation {
+    CheckState,
+    RunAtStartup,
+    DontRunAtStartup,
+    TurnOffFileExtensionHiding,
+    TurnOffFileExtensionHidingForAllUsers,
+    TurnOffFileExtensionHidingForLoadedUsers,
+    TurnOffFileExtensionHidingForNewAccounts,
+    TurnOnChangeAttribution,
+    TurnOffChangeAttribution,
+    RemediateCheck { check_id: String, check_name: String },
+    UndoCheck { check_id: String, check_name: String },
+    OpenCheckSettings { check_id: String, check_name: String },
+    AuditScriptAssociations,
+    ExportEvents(PathBuf),
+    InspectFile(PathBuf),
+    DefuseFile(PathBuf),
+    RestoreDefusedFile(DefusedFile),
+    RestartWindowsExplorer,
+    WatchForChanges,
+    // names the hotkey as written in the config file
+    RegisterHotkey(String),
+    CheckForUpdates,
+    InstallUpdate,
+    RestoreAdvancedKeyBackup,
+    UndoLastChange,
+    OpenFolderOptions,
+    OpenRegistryEditor,
+}
+
+impl FailedOperation {
+    fn description(&self) -> String {
+        match self {
+            FailedOperation::CheckState => tr("error-check-state"),
+            FailedOperation::RunAtStartup => tr("error-run-at-startup"),
+            FailedOperation::DontRunAtStartup => tr("error-dont-run-at-startup"),
+            FailedOperation::TurnOffFileExtensionHiding => tr("error-turn-off-hiding"),
+            FailedOperation::TurnOffFileExtensionHidingForAllUsers => tr("error-turn-off-hiding-for-all-users"),
+            FailedOperation::TurnOffFileExtensionHidingForLoadedUsers => tr("error-turn-off-hiding-for-loaded-users"),
+            FailedOperation::TurnOffFileExtensionHidingForNewAccounts => tr("error-turn-off-hiding-for-new-accounts"),
+            FailedOperation::TurnOnChangeAttribution => tr("error-turn-on-change-attribution"),
+            FailedOperation::TurnOffChangeAttribution => tr("error-turn-off-change-attribution"),
+            FailedOperation::RemediateCheck { check_name, .. } =>
+                tr_args("error-remediate-check", &[("check", FluentValue::from(check_name.as_str()))]),
+            FailedOperation::UndoCheck { check_name, .. } =>
+                tr_args("error-undo-check", &[("check", FluentValue::from(check_name.as_str()))]),
+            FailedOperation::OpenCheckSettings { check_name, .. } =>
+                tr_args("error-open-check-settings", &[("check", FluentValue::from(check_name.as_str()))]),
+            FailedOperation::AuditScriptAssociations => tr("error-audit-script-associations"),
+            FailedOperation::ExportEvents(path) =>
+                tr_args("error-export-events", &[("path", FluentValue::from(path.display().to_string()))]),
+            FailedOperation::InspectFile(path) =>
+                tr_args("error-inspect-file", &[("file", FluentValue::from(path.display().to_string()))]),
+            FailedOperation::DefuseFile(path) =>
+                tr_args("error-defuse-file", &[("file", FluentValue::from(filenames::reveal(&file_name_of(path))))]),
+            FailedOperation::RestoreDefusedFile(defused_file) => tr_args("error-restore-defused-file", &[
+                ("file", FluentValue::from(filenames::reveal(&file_name_of(&defused_file.original_path))))
+            ]),
+            FailedOperation::RestartWindowsExplorer => tr("error-restart-explorer"),
+            FailedOperation::WatchForChanges => tr("error-watch-for-changes"),
+            FailedOperation::RegisterHotkey(hotkey) =>
+                tr_args("error-register-hotkey", &[("hotkey", FluentValue::from(hotkey.as_str()))]),
+            FailedOperation::CheckForUpdates => tr("error-check-for-updates"),
+            FailedOperation::InstallUpdate => tr("error-install-update"),
+            FailedOperation::RestoreAdvancedKeyBackup => tr("error-restore-backup"),
+            FailedOperation::UndoLastChange => tr("error-undo-last-change"),
+            FailedOperation::OpenFolderOptions => tr("error-open-folder-options"),
+            FailedOperation::OpenRegistryEditor => tr("error-open-registry-editor"),
+        }
+    }
+
+    // The message which attempts the operation again, if the user can retry it
+    fn retry_message(&self) -> Option<Message> {
+        match self {
+            FailedOperation::CheckState => Some(User(UserMessage::RefreshState)),
+            FailedOperation::RunAtStartup => Some(User(UserMessage::RunAtStartup)),
+            FailedOperation::DontRunAtStartup => Some(User(UserMessage::DontRunAtStartup)),
+            FailedOperation::TurnOffFileExtensionHiding => Some(User(UserMessage::HideFileExtensions)),
+            FailedOperation::TurnOffFileExtensionHidingForAllUsers => Some(User(UserMessage::HideFileExtensionsForAllUsers)),
+            FailedOperation::TurnOffFileExtensionHidingForLoadedUsers => Some(User(UserMessage::HideFileExtensionsForLoadedUsers)),
+            FailedOperation::TurnOffFileExtensionHidingForNewAccounts => Some(User(UserMessage::HideFileExtensionsForNewAccounts)),
+            FailedOperation::TurnOnChangeAttribution => Some(User(UserMessage::TurnOnChangeAttribution)),
+            FailedOperation::TurnOffChangeAttribution => Some(User(UserMessage::TurnOffChangeAttribution)),
+            FailedOperation::RemediateCheck { check_id, .. } => Some(User(UserMessage::RemediateCheck(check_id.clone()))),
+            FailedOperation::UndoCheck { check_id, .. } => Some(User(UserMessage::UndoCheck(check_id.clone()))),
+            FailedOperation::OpenCheckSettings { check_id, .. } => Some(User(UserMessage::OpenCheckSettings(check_id.clone()))),
+            FailedOperation::AuditScriptAssociations => Some(User(UserMessage::ExpandPanel(Panel::ScriptAssociations))),
+            FailedOperation::ExportEvents(_) => Some(User(UserMessage::ExportEvents)),
+            FailedOperation::InspectFile(path) => Some(User(UserMessage::InspectFile(path.clone()))),
+            FailedOperation::DefuseFile(path) => Some(User(UserMessage::DefuseFile(path.clone()))),
+            FailedOperation::RestoreDefusedFile(defused_file) =>
+                Some(User(UserMessage::RestoreDefusedFile(defused_file.clone()))),
+            FailedOperation::RestartWindowsExplorer => Some(User(UserMessage::RestartExplorerNow)),
+            // the watcher keeps retrying on its own
+            FailedOperation::WatchForChanges => None,
+            // only a different hotkey in the config file can help
+            FailedOperation::RegisterHotkey(_) => None,
+            FailedOperation::CheckForUpdates => Some(User(UserMessage::CheckForUpdates)),
+            FailedOperation::InstallUpdate => Some(User(UserMessage::InstallUpdate)),
+            FailedOperation::RestoreAdvancedKeyBackup => Some(User(UserMessage::RestoreAdvancedKeyBackup)),
+            FailedOperation::UndoLastChange => Some(User(UserMessage::UndoLastChange)),
+            FailedOperation::OpenFolderOptions => Some(User(UserMessage::OpenFolderOptions)),
+            FailedOperation::OpenRegistryEditor => Some(User(UserMessage::OpenRegistryEditor)),
+        }
+    }
+
+    // What an elevated instance would have to do to finish the operation
+    fn pending_action(&self) -> Option<PendingAction> {
+        match self {
+            FailedOperation::RunAtStartup => Some(PendingAction::RunAtStartup),
+            FailedOperation::TurnOffFileExtensionHiding => Some(PendingAction::TurnOffFileExtensionHiding),
+            FailedOperation::TurnOffFileExtensionHidingForAllUsers => Some(PendingAction::TurnOffFileExtensionHidingForAllUsers),
+            FailedOperation::TurnOffFileExtensionHidingForLoadedUsers => Some(PendingAction::TurnOffFileExtensionHidingForLoadedUsers),
+            FailedOperation::TurnOffFileExtensionHidingForNewAccounts => Some(PendingAction::TurnOffFileExtensionHidingForNewAccounts),
+            FailedOperation::TurnOnChangeAttribution => Some(PendingAction::TurnOnChangeAttribution),
+            FailedOperation::TurnOffChangeAttribution => Some(PendingAction::TurnOffChangeAttribution),
+            _ => None,
+        }
+    }
+}
+
+impl PendingAction {
+    fn user_message(&self) -> UserMessage {
+        match self {
+            PendingAction::TurnOffFileExtensionHiding => UserMessage::HideFileExtensions,
+            PendingAction::TurnOffFileExtensionHidingForAllUsers => UserMessage::HideFileExtensionsForAllUsers,
+            PendingAction::TurnOffFileExtensionHidingForLoadedUsers => UserMessage::HideFileExtensionsForLoadedUsers,
+            PendingAction::TurnOffFileExtensionHidingForNewAccounts => UserMessage::HideFileExtensionsForNewAccounts,
+            PendingAction::RunAtStartup => UserMessage::RunAtStartup,
+            PendingAction::TurnOnChangeAttribution => UserMessage::TurnOnChangeAttribution,
+            PendingAction::TurnOffChangeAttribution => UserMessage::TurnOffChangeAttribution,
+            PendingAction::TurnOnChangeTracing => UserMessage::ChangeTraceChanges(true),
+        }
+    }
+}
+
+// Shown before fixing, since Windows Explorer may have to restart in the middle of the user's work
+#[derive(Debug, Clone, Copy, Default)]
+struct FixConfirmation {
+    dont_ask_again: bool,
+}
+
+// A failure the user is currently being shown
+#[derive(Debug, Clone)]
+struct ErrorBanner {
+    operation: FailedOperation,
+    details: String,
+    // set when the operation was refused for lack of administrator rights
+    elevated_retry: Option<PendingAction>,
+}
+
+// Used for communication between components
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    User(UserMessage),
+    Backend(BackendMessage),
+    Ui(UiMessage),
+    Error(FailedOperation, String),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct UiOptions {
+    pub(crate) start_minimized: bool,
+    pub(crate) theme: Theme,
+    pub(crate) history: History,
+    pub(crate) config: Config,
+    pub(crate) windows_text_scale: f64,
+    // whether fixing the setting should also fix it for every user on the machine
+    pub(crate) machine_wide_enforcement: bool,
+    // what to do right after starting, when relaunched as administrator
+    pub(crate) pending_action: Option<PendingAction>,
+}
+
+// The raw HideFileExt value in each hive this program can write to, or None where it isn't set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct HiveValues {
+    current_user: Option<u32>,
+    all_users: Option<u32>,
+}
+
+// primary application state
+#[derive(Debug, Clone)]
+pub(crate) struct NoHiddenExtensionsState {
+    run_at_startup: bool,
+    file_extensions_hidden: bool,
+    file_extensions_setting_source: SettingSource,
+    hive_values: HiveValues,
+    running_elevated: bool,
+    machine_wide_enforcement: bool,
+    // what happened the last time the setting was applied for every signed-in user
+    loaded_user_results: Option<Vec<UserHiveResult>>,
+    // whether the last attempt to fix the Default User profile changed anything
+    new_accounts_result: Option<bool>,
+    // the registry was fixed, but Windows Explorer won't show it until it restarts
+    explorer_restart_pending: bool,
+    // Windows Explorer was only asked to refresh, so it may or may not show the fix yet
+    explorer_only_refreshed: bool,
+    // the fix needed another Windows Explorer restart soon after the last one
+    setting_keeps_changing: bool,
+    // the program which last hid file extensions, when auditing or tracing is on and caught it
+    change_attribution: Option<ChangeAttribution>,
+    // the program event tracing last saw turn HideFileExt on, since file extensions were last visible
+    traced_change: Option<ChangeAttribution>,
+    // how often file extensions were hidden again, according to the audit log
+    protection_stats: ProtectionStats,
+    // what the last export of the audit log wrote, to confirm it to the user
+    export_summary: Option<String>,
+    // where this program was before the Run key had to be pointed at its new location on this start
+    startup_repaired_from: Option<String>,
+    // the checks which are turned on
+    checks: Vec<Arc<dyn SecurityCheck>>,
+    // IDs of the checks which currently fail
+    failing_checks: Vec<String>,
+    // disguised programs found in the Downloads folder which the user hasn't dismissed yet
+    disguised_files: Vec<PathBuf>,
+    // disguised programs renamed since the user last dismissed them, which can still be renamed back
+    defused_files: Vec<DefusedFile>,
+    // the file last dropped onto the window and what its mark of the web says
+    inspection: Option<Inspection>,
+    // what each script extension did when the user last looked, read whenever its panel opens
+    script_associations: Vec<Association>,
+    system_theme: Theme,
+    windows_text_scale: f64,
+    layout_direction: LayoutDirection,
+    history: History,
+    active_view: ActiveView,
+    config: Config,
+    notification_scheduler: NotificationScheduler,
+    deferred_summary: Option<DeferredSummary>,
+    // nobody can see the window while the workstation is locked, so grabbing attention waits for the unlock
+    session_locked: bool,
+    attention_pending_unlock: bool,
+    // whether the window is kept in front of every other until the user fixes or acknowledges the warning
+    warning_kept_on_top: bool,
+    // hidden in the tray, rather than shown or minimized to the taskbar
+    window_hidden: bool,
+    // set once the user has seen the window since file extensions were last hidden, which stops the tray icon's pulse
+    warning_acknowledged: bool,
+    // set while the watchdog recently had to restart a thread which watches for changes
+    monitoring_degraded: bool,
+    // the newest release on GitHub, if it's newer than this executable
+    available_update: Option<Release>,
+    // the release whose banner the user closed, so the daily check doesn't bring it back
+    dismissed_update: Option<Version>,
+    // set when the user checked for updates and there were none
+    confirmed_up_to_date: bool,
+    // set while the update check the user asked for is running, so it isn't started twice
+    update_check_in_progress: bool,
+    // set while the update is downloaded and put in place, so it isn't installed twice
+    update_install_in_progress: bool,
+    // set for a little while after the status was copied, so the user can tell it worked
+    status_copied: bool,
+    // what's in the webhook field, which may not have been saved yet
+    webhook_url_draft: String,
+    // set when the user tried to save a webhook URL which isn't a web address
+    webhook_url_invalid: bool,
+    // when the Advanced key was last backed up before a change, if ever
+    latest_backup_time: Option<DateTime<Local>>,
+    error_banner: Option<ErrorBanner>,
+    fix_confirmation: Option<FixConfirmation>,
+    // ID of the check whose fix the user is being asked to confirm
+    check_confirmation: Option<String>,
+}
+
+impl Application for NoHiddenExtensionsState {
+    type Executor = executor::Default;
+    type Message = Message;
+    type Theme = Theme;
+    type Flags = UiOptions;
+
+    #[instrument]
+    fn new(ui_options: UiOptions) -> (NoHiddenExtensionsState, Command<Message>) {
+        let mut no_hidden_extensions_state = NoHiddenExtensionsState {
+            run_at_startup: false,
+            file_extensions_hidden: false,
+            file_extensions_setting_source: SettingSource::UserPreference,
+            // read here rather than in `refresh_state()`, so starting up doesn't look like a change in the audit log
+            hive_values: read_hive_values(),
+            running_elevated: windows_ops::is_running_elevated().unwrap_or_else(|error| {
+                warn!("Assuming this program isn't running as administrator: {:?}", error);
+                false
+            }),
+            machine_wide_enforcement: ui_options.machine_wide_enforcement,
+            loaded_user_results: None,
+            new_accounts_result: None,
+            explorer_restart_pending: false,
+            explorer_only_refreshed: false,
+            setting_keeps_changing: false,
+            change_attribution: None,
+            traced_change: None,
+            protection_stats: load_protection_stats(),
+            export_summary: None,
+            // before `refresh_state()`, so the startup checkbox reflects the repaired Run key
+            startup_repaired_from: windows_ops::repair_startup_registration(&WinregRegistry).unwrap_or_else(|error| {
+                warn!("Failed to check whether the Run key still names this program: {:?}", error);
+                None
+            }),
+            checks: checks::active_checks(&ui_options.config),
+            failing_checks: Vec::new(),
+            disguised_files: Vec::new(),
+            defused_files: Vec::new(),
+            inspection: None,
+            script_associations: Vec::new(),
+            system_theme: ui_options.theme,
+            windows_text_scale: ui_options.windows_text_scale,
+            layout_direction: i18n::layout_direction(),
+            history: ui_options.history,
+            active_view: ActiveView::Main { expanded_panel: None },
+            notification_scheduler: NotificationScheduler::new(ui_options.config.quiet_hours),
+            config: ui_options.config,
+            deferred_summary: None,
+            session_locked: false,
+            attention_pending_unlock: false,
+            warning_kept_on_top: false,
+            window_hidden: ui_options.start_minimized,
+            warning_acknowledged: false,
+            monitoring_degraded: false,
+            available_update: None,
+            dismissed_update: None,
+            confirmed_up_to_date: false,
+            update_check_in_progress: false,
+            update_install_in_progress: false,
+            status_copied: false,
+            webhook_url_draft: ui_options.config.webhook_url.clone().unwrap_or_default(),
+            webhook_url_invalid: false,
+            latest_backup_time: latest_backup_time(),
+            error_banner: None,
+            fix_confirmation: None,
+            check_confirmation: None,
+        };
+
+        // a pause from before the restart carries on
+        tray::set_pause_remaining(no_hidden_extensions_state.pause_minutes_left());
+
+        if let Some(old_path) = no_hidden_extensions_state.startup_repaired_from.clone() {
+            no_hidden_extensions_state.record_history_event(HistoryEventKind::StartupEntryRepaired(old_path));
+        }
+
+        if let Err(error) = no_hidden_extensions_state.refresh_state() {
+            no_hidden_extensions_state.report_error(FailedOperation::CheckState, error);
+            // the user has to see the error, so don't hide in the tray
+            return (no_hidden_extensions_state, Command::none());
+        }
+
+        let paused: bool = no_hidden_extensions_state.config.protection_paused(Local::now());
+        let should_notify_user: bool = if no_hidden_extensions_state.file_extensions_hidden && !paused {
+            no_hidden_extensions_state.change_attribution = no_hidden_extensions_state.attribute_change();
+            let history_event: HistoryEventKind = no_hidden_extensions_state.file_extensions_hidden_event();
+            no_hidden_extensions_state.record_history_event(history_event);
+            no_hidden_extensions_state.notification_scheduler
+                .submit(Notification::FileExtensionsHidden, Local::now())
+        } else {
+            false
+        };
+
+        // finish what the user asked for before this instance was relaunched as administrator
+        if let Some(pending_action) = ui_options.pending_action {
+            trace!("Carrying out {:?} after elevation", pending_action);
+            let pending_commands: Command<Message> = no_hidden_extensions_state
+                .handle_message(User(pending_action.user_message()));
+            return (no_hidden_extensions_state, pending_commands);
+        }
+
+        let commands: Command<Message> = if should_notify_user {
+            // file extensions are already hidden, so we need to tell the user regardless of
+            // whether we're supposed to start minimized
+            no_hidden_extensions_state.get_commands_which_notify_user_unless_busy()
+        } else if ui_options.start_minimized {
+            window::change_mode(Mode::Hidden)
+        } else {
+            Command::none()
+        };
+
+        return (no_hidden_extensions_state, commands);
+    }
+
+    fn title(&self) -> String {
+        String::from(APPLICATION_DISPLAY_NAME)
+    }
+
+    #[instrument]
+    fn update(&mut self, message: Message) -> Command<Message> {
+        let commands: Command<Message> = self.handle_message(message);
+        // keep screen readers in sync with whatever just changed
+        accessibility::sync(&self.accessible_view());
+        metrics::record_state(self.file_extensions_hidden);
+        if !self.tray_icon_pulses() {
+            tray::stop_pulsing();
+        }
+        crash::record_state_snapshot(self.state_snapshot());
+        commands
+    }
+
+    #[instrument]
+    fn view(&self) -> Element<Message> {
+        let active_view: Element<Message> = match (self.fix_confirmation, &self.check_confirmation, self.active_view) {
+            // iced has no overlays yet, so a confirmation takes the place of everything else
+            (Some(fix_confirmation), _, _) => self.view_fix_confirmation(fix_confirmation),
+            (None, Some(check_id), _) => self.view_check_confirmation(check_id),
+            (None, None, ActiveView::Main { expanded_panel }) => self.view_main(expanded_panel),
+            (None, None, ActiveView::Settings { tab }) => self.view_settings(tab),
+        };
+
+        let content: Element<Message> = match &self.error_banner {
+            Some(error_banner) => Column::new()
+                .push(self.view_error_banner(error_banner))
+                .push(active_view)
+                .into(),
+            None => active_view,
+        };
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    // Everything in the window, including text, is scaled together so layouts don't clip
+    fn scale_factor(&self) -> f64 {
+        self.windows_text_scale * self.config.text_scale.factor()
+    }
+
+    fn theme(&self) -> Theme {
+        match self.config.theme_preference {
+            ThemePreference::System => self.system_theme.clone(),
+            ThemePreference::Light => Theme::Light,
+            ThemePreference::Dark => Theme::Dark,
+        }
+    }
+
+    #[instrument]
+    fn subscription(&self) -> Subscription<Message> {
+        let poll_listener: Subscription<Message> = match self.config.poll_interval.duration() {
+            Some(poll_interval) => get_listener_for_polls(poll_interval),
+            None => Subscription::none(),
+        };
+        let scan_listener: Subscription<Message> = match self.config.scan_interval.duration() {
+            Some(scan_interval) => get_listener_for_scans(scan_interval),
+            None => Subscription::none(),
+        };
+
+        let registry_listeners: Vec<Subscription<Message>> = checks::watched_keys(&self.checks).into_iter()
+            .map(get_listener_for_registry_changes)
+            .collect();
+
+        // only administrators can start a trace session
+        #[cfg(feature = "watcher-etw")]
+        let change_tracing_listener: Subscription<Message> = match self.config.trace_changes && self.running_elevated {
+            true => get_listener_for_traced_changes(),
+            false => Subscription::none(),
+        };
+        #[cfg(not(feature = "watcher-etw"))]
+        let change_tracing_listener: Subscription<Message> = Subscription::none();
+
+        let hotkeys: Vec<(HotkeyAction, String)> = [
+            (HotkeyAction::ShowWindow, &self.config.show_window_hotkey),
+            (HotkeyAction::Fix, &self.config.fix_hotkey),
+        ].into_iter()
+            .filter_map(|(action, hotkey)| hotkey.clone().map(|hotkey| (action, hotkey)))
+            .collect();
+        let update_check_listener: Subscription<Message> = match self.config.check_for_updates {
+            true => get_listener_for_update_checks(),
+            false => Subscription::none(),
+        };
+
+        #[cfg(feature = "tray")]
+        let tray_listeners: Subscription<Message> = Subscription::batch(vec![
+            get_listener_for_window_resize_messages(),
+            get_listener_for_tray_menu_events(),
+            match tray::exists() {
+                true => Subscription::none(),
+                false => get_listener_for_tray_retries(),
+            },
+        ]);
+        #[cfg(not(feature = "tray"))]
+        let tray_listeners: Subscription<Message> = Subscription::none();
+
+        let tray_pulse_listener: Subscription<Message> = match self.tray_icon_pulses() {
+            true => get_listener_for_tray_pulses(),
+            false => Subscription::none(),
+        };
+
+        // the clock ticks too rarely to end the pause on time
+        let pause_end_listener: Subscription<Message> = match self.config.paused_until {
+            Some(paused_until) => get_listener_for_pause_end(paused_until),
+            None => Subscription::none(),
+        };
+
+        let hotkey_listener: Subscription<Message> = match hotkeys.is_empty() {
+            true => Subscription::none(),
+            false => get_listener_for_hotkeys(hotkeys),
+        };
+
+        return Subscription::batch(vec![
+            Subscription::batch(registry_listeners),
+            get_listener_for_ui_messages(),
+            tray_listeners,
+            tray_pulse_listener,
+            get_listener_for_clock_ticks(),
+            pause_end_listener,
+            get_listener_for_system_theme_changes(),
+            get_listener_for_screen_reader_actions(),
+            get_listener_for_resume_from_sleep(),
+            get_listener_for_session_changes(),
+            get_listener_for_disguised_downloads(),
+            get_listener_for_ipc_requests(),
+            hotkey_listener,
+            update_check_listener,
+            change_tracing_listener,
+            poll_listener,
+            scan_listener,
+        ]);
+    }
+}
+
+pub(crate) fn scale_window_size(base_size: (u32, u32), scale_factor: f64) -> (u32, u32) {
+    (
+        (f64::from(base_size.0) * scale_factor).round() as u32,
+        (f64::from(base_size.1) * scale_factor).round() as u32,
+    )
+}
+
+// Maps Windows' app light/dark mode preference onto an iced theme
+pub(crate) fn detect_system_theme() -> Theme {
+    match dark_light::detect() {
+        dark_light::Mode::Dark => Theme::Dark,
+        dark_light::Mode::Light => Theme::Light,
+        dark_light::Mode::Default => Theme::default()
+    }
+}
+
+impl NoHiddenExtensionsState {
+    fn handle_message(&mut self, message: Message) -> Command<Message> {
+        return match message {
+            User(user_message) => {
+                match user_message {
+                    UserMessage::RunAtStartup => {
+                        self.back_up_before_change(&[SettingScope::CurrentUser]);
+                        match self.register_startup() {
+                            Ok(_) => self.run_at_startup = true,
+                            Err(error) => self.report_error(FailedOperation::RunAtStartup, error),
+                        }
+                        Command::none()
+                    },
+                    UserMessage::DontRunAtStartup => {
+                        self.back_up_before_change(&[SettingScope::CurrentUser]);
+                        match windows_ops::dont_run_this_program_at_startup(&WinregRegistry)
+                            .and_then(|_| startup_task::unregister()) {
+                            Ok(_) => self.run_at_startup = false,
+                            Err(error) => self.report_error(FailedOperation::DontRunAtStartup, error),
+                        }
+                        Command::none()
+                    },
+                    UserMessage::RequestFix => {
+                        // nothing gets interrupted when the restart is left to the user
+                        match self.config.skip_fix_confirmation
+                            || self.explorer_restart_policy() == ExplorerRestartPolicy::Defer
+                        {
+                            true => self.handle_message(User(UserMessage::HideFileExtensions)),
+                            false => {
+                                self.fix_confirmation = Some(FixConfirmation::default());
+                                Command::none()
+                            }
+                        }
+                    },
+                    UserMessage::ToggleDontAskAgain(dont_ask_again) => {
+                        if let Some(fix_confirmation) = self.fix_confirmation.as_mut() {
+                            fix_confirmation.dont_ask_again = dont_ask_again;
+                        }
+                        Command::none()
+                    },
+                    UserMessage::ConfirmFix => {
+                        if let Some(fix_confirmation) = self.fix_confirmation.take() {
+                            if fix_confirmation.dont_ask_again {
+                                self.config.skip_fix_confirmation = true;
+                                self.save_config();
+                            }
+                        }
+                        self.handle_message(User(UserMessage::HideFileExtensions))
+                    },
+                    UserMessage::CancelFix => {
+                        self.fix_confirmation = None;
+                        Command::none()
+                    },
+                    UserMessage::ChangeDeferExplorerRestart(defer_explorer_restart) => {
+                        self.config.defer_explorer_restart = defer_explorer_restart;
+                        self.save_config();
+                        Command::none()
+                    },
+                    UserMessage::ChangeAlwaysRestartExplorer(always_restart_explorer) => {
+                        self.config.always_restart_explorer = always_restart_explorer;
+                        self.save_config();
+                        Command::none()
+                    },
+                    UserMessage::ExportEvents => {
+                        if let Some((path, format)) = export::choose_export_path() {
+                            match export::export(&path, format) {
+                                Ok(count) => self.export_summary = Some(export::summary(count, &path)),
+                                Err(error) => self.report_error(FailedOperation::ExportEvents(path), error),
+                            }
+                        }
+                        Command::none()
+                    },
+                    UserMessage::ChangeTraceChanges(trace_changes) => {
+                        self.config.trace_changes = trace_changes;
+                        self.save_config();
+                        Command::none()
+                    },
+                    UserMessage::TurnOnChangeAttribution => {
+                        match registry_audit::turn_on() {
+                            Ok(()) => {
+                                self.config.attribute_changes = true;
+                                self.save_config();
+                            },
+                            Err(error) => self.report_error(FailedOperation::TurnOnChangeAttribution, error),
+                        }
+                        Command::none()
+                    },
+                    UserMessage::TurnOffChangeAttribution => {
+                        match registry_audit::turn_off() {
+                            Ok(()) => {
+                                self.config.attribute_changes = false;
+                                self.change_attribution = None;
+                                self.save_config();
+                            },
+                            Err(error) => self.report_error(FailedOperation::TurnOffChangeAttribution, error),
+                        }
+                        Command::none()
+                    },
+                    UserMessage::RestartExplorerNow => {
+                        match windows_ops::restart_windows_explorer(&WindowsProcessManager) {
+                            Ok(()) => self.set_explorer_restart_pending(false),
+                            Err(error) => self.report_error(FailedOperation::RestartWindowsExplorer, error),
+                        }
+                        Command::none()
+                    },
+                    UserMessage::HideFileExtensions if self.file_extensions_setting_source.is_group_policy() => {
+                        // screen readers and retries can still ask, but the value would just be reverted
+                        trace!("Not touching a setting which Group Policy controls");
+                        Command::none()
+                    },
+                    UserMessage::HideFileExtensions if self.machine_wide_enforcement => {
+                        self.handle_message(User(UserMessage::HideFileExtensionsForAllUsers))
+                    },
+                    UserMessage::HideFileExtensionsForAllUsers => {
+                        self.back_up_before_change(&[SettingScope::AllUsers, SettingScope::CurrentUser]);
+                        // the current user's own value takes precedence over the machine-wide one,
+                        // so both have to be set to fix things for the current user too
+                        match windows_ops::turn_off_file_extension_hiding_in(
+                            &WinregRegistry,
+                            &[SettingScope::AllUsers, SettingScope::CurrentUser],
+                            self.explorer_restart_policy(),
+                        ) {
+                            Ok(fix_outcome) => self.record_fix_outcome(fix_outcome, HistoryEventKind::RemediatedByUser),
+                            Err(error) => self.report_error(FailedOperation::TurnOffFileExtensionHidingForAllUsers, error),
+                        }
+                        self.refresh_hive_values(true);
+                        Command::none()
+                    },
+                    UserMessage::HideFileExtensionsForLoadedUsers => {
+                        match user_hives::turn_off_file_extension_hiding_for_loaded_users() {
+                            Ok(results) => {
+                                if results.iter().any(|result| result.outcome == Ok(true)) {
+                                    self.record_history_event(HistoryEventKind::RemediatedByUser);
+                                    // the current user is likely one of them, and their Explorer needs to catch up
+                                    match windows_ops::make_windows_explorer_pick_up_changes(self.explorer_restart_policy()) {
+                                        Ok(fix_outcome) => self.note_explorer_state_after(fix_outcome),
+                                        Err(error) => warn!("Failed to update Windows Explorer: {:?}", error),
+                                    }
+                                }
+                                self.loaded_user_results = Some(results);
+                                self.refresh_hive_values(true);
+                                Command::none()
+                            },
+                            Err(error) => {
+                                self.report_error(FailedOperation::TurnOffFileExtensionHidingForLoadedUsers, error);
+                                Command::none()
+                            }
+                        }
+                    },
+                    UserMessage::HideFileExtensionsForNewAccounts => {
+                        match user_hives::turn_off_file_extension_hiding_for_new_accounts() {
+                            Ok(was_change_made) => self.new_accounts_result = Some(was_change_made),
+                            Err(error) => self.report_error(FailedOperation::TurnOffFileExtensionHidingForNewAccounts, error),
+                        }
+                        Command::none()
+                    },
+                    UserMessage::HideFileExtensions => {
+                        self.back_up_before_change(&[SettingScope::CurrentUser]);
+                        let fix_result: Result<FixOutcome> = windows_ops::turn_off_file_extension_hiding(
+                            &WinregRegistry, self.explorer_restart_policy()
+                        );
+                        match fix_result {
+                            Ok(fix_outcome) => self.record_fix_outcome(fix_outcome, HistoryEventKind::RemediatedByUser),
+                            Err(error) => self.report_error(FailedOperation::TurnOffFileExtensionHiding, error),
+                        }
+                        // before the change notification arrives, so the audit log credits this program
+                        self.refresh_hive_values(true);
+                        Command::none()
+                    },
+                    UserMessage::RemediateCheck(check_id) => {
+                        match self.find_any_check(&check_id).map(|check| check.needs_confirmation()) {
+                            Some(true) => self.check_confirmation = Some(check_id),
+                            _ => self.remediate_check(check_id),
+                        }
+                        Command::none()
+                    },
+                    UserMessage::ConfirmRemediateCheck => {
+                        if let Some(check_id) = self.check_confirmation.take() {
+                            self.remediate_check(check_id);
+                        }
+                        Command::none()
+                    },
+                    UserMessage::CancelRemediateCheck => {
+                        self.check_confirmation = None;
+                        Command::none()
+                    },
+                    UserMessage::UndoCheck(check_id) => {
+                        let Some(check) = self.find_any_check(&check_id) else {
+                            trace!("Not undoing {}, which no longer exists", check_id);
+                            return Command::none();
+                        };
+                        self.back_up_before_change(&[SettingScope::CurrentUser]);
+                        match check.undo(self.explorer_restart_policy()) {
+                            Ok(fix_outcome) => {
+                                self.record_fix_outcome(fix_outcome, HistoryEventKind::CheckUndoneByUser(check_id.clone()));
+                                // otherwise the check would start warning about what the user just asked for
+                                self.config.enabled_checks.insert(check_id, false);
+                                self.save_config();
+                                self.checks = checks::active_checks(&self.config);
+                            },
+                            Err(error) => self.report_error(
+                                FailedOperation::UndoCheck { check_id, check_name: check.name() }, error
+                            ),
+                        }
+                        self.refresh_script_associations_if_shown();
+                        Command::none()
+                    },
+                    UserMessage::OpenCheckSettings(check_id) => {
+                        let Some(check) = self.find_check(&check_id) else {
+                            trace!("Not opening settings for {}, which is no longer turned on", check_id);
+                            return Command::none();
+                        };
+                        let Some(settings_program) = check.settings_program() else {
+                            trace!("{} has no settings to open", check_id);
+                            return Command::none();
+                        };
+                        if let Err(error) = windows_ops::open_with_shell(settings_program) {
+                            self.report_error(FailedOperation::OpenCheckSettings { check_id, check_name: check.name() }, error);
+                        }
+                        Command::none()
+                    },
+                    UserMessage::ToggleCheck(check_id, enabled) => {
+                        self.failing_checks.retain(|failing_check_id| *failing_check_id != check_id);
+                        self.config.enabled_checks.insert(check_id, enabled);
+                        self.save_config();
+                        self.checks = checks::active_checks(&self.config);
+                        // a newly enabled check which already fails is reported like any other
+                        self.reconcile_with_registry()
+                    },
+                    UserMessage::RefreshState => {
+                        if let Err(error) = self.refresh_state() {
+                            self.report_error(FailedOperation::CheckState, error);
+                        }
+                        Command::none()
+                    },
+                    UserMessage::RetryFailedOperation => {
+                        // a repeated failure puts the banner right back
+                        match self.error_banner.take().and_then(|banner| banner.operation.retry_message()) {
+                            Some(retry_message) => self.handle_message(retry_message),
+                            None => Command::none()
+                        }
+                    },
+                    UserMessage::RelaunchElevated(pending_action) => {
+                        match elevation::relaunch_elevated(pending_action) {
+                            // the elevated instance takes over from here
+                            Ok(()) => window::close(),
+                            Err(error) => {
+                                warn!("Failed to relaunch as administrator: {:?}", error);
+                                Command::none()
+                            }
+                        }
+                    },
+                    UserMessage::DismissError => {
+                        self.error_banner = None;
+                        Command::none()
+                    },
+                    UserMessage::ExpandPanel(panel) => {
+                        if panel == Panel::ScriptAssociations {
+                            self.refresh_script_associations();
+                        }
+                        self.active_view = ActiveView::Main { expanded_panel: Some(panel) };
+                        self.resize_window_to_fit()
+                    },
+                    UserMessage::CollapsePanel => {
+                        self.active_view = ActiveView::Main { expanded_panel: None };
+                        self.resize_window_to_fit()
+                    },
+                    UserMessage::OpenSettings => {
+                        self.active_view = ActiveView::Settings { tab: SettingsTab::Notifications };
+                        self.resize_window_to_fit()
+                    },
+                    UserMessage::CloseSettings => {
+                        self.active_view = ActiveView::Main { expanded_panel: None };
+                        self.resize_window_to_fit()
+                    },
+                    UserMessage::SelectSettingsTab(tab) => {
+                        self.active_view = ActiveView::Settings { tab };
+                        Command::none()
+                    },
+                    UserMessage::ScreenReaderAction(control) => {
+                        let user_message: UserMessage = match control {
+                            // the same confirmation as for a click, since a restart interrupts screen reader
+                            // users as much as anyone
+                            AccessibleControl::FixButton => UserMessage::RequestFix,
+                            AccessibleControl::RunAtStartupCheckbox => match self.run_at_startup {
+                                true => UserMessage::DontRunAtStartup,
+                                false => UserMessage::RunAtStartup,
+                            },
+                            AccessibleControl::ConfirmButton => UserMessage::ConfirmFix,
+                            AccessibleControl::CancelButton => UserMessage::CancelFix,
+                        };
+                        self.handle_message(User(user_message))
+                    },
+                    UserMessage::DismissDeferredSummary => {
+                        self.deferred_summary = None;
+                        Command::none()
+                    },
+                    UserMessage::DismissDisguisedFiles => {
+                        self.disguised_files.clear();
+                        self.defused_files.clear();
+                        Command::none()
+                    },
+                    UserMessage::DefuseFile(path) => {
+                        match quarantine::defuse(&path) {
+                            Ok(defused_file) => {
+                                self.record_history_event(HistoryEventKind::DisguisedFileDefused(
+                                    filenames::reveal(&file_name_of(&path))
+                                ));
+                                self.disguised_files.retain(|disguised_file| *disguised_file != path);
+                                self.defused_files.push(defused_file);
+                            },
+                            Err(error) => self.report_error(FailedOperation::DefuseFile(path), error),
+                        }
+                        Command::none()
+                    },
+                    UserMessage::RestoreDefusedFile(defused_file) => {
+                        match quarantine::restore(&defused_file) {
+                            Ok(()) => {
+                                self.record_history_event(HistoryEventKind::DefusedFileRestored(
+                                    filenames::reveal(&file_name_of(&defused_file.original_path))
+                                ));
+                                self.defused_files.retain(|other| *other != defused_file);
+                                self.disguised_files.push(defused_file.original_path);
+                            },
+                            Err(error) => self.report_error(FailedOperation::RestoreDefusedFile(defused_file), error),
+                        }
+                        Command::none()
+                    },
+                    UserMessage::InspectFile(path) => {
+                        match mark_of_the_web::inspect(&path) {
+                            Ok(inspection) => self.inspection = Some(inspection),
+                            Err(error) => self.report_error(FailedOperation::InspectFile(path), error),
+                        }
+                        self.active_view = ActiveView::Main { expanded_panel: Some(Panel::MarkOfTheWeb) };
+                        self.resize_window_to_fit()
+                    },
+                    UserMessage::ChangeThemePreference(theme_preference) => {
+                        self.config.theme_preference = theme_preference;
+                        self.save_config();
+                        Command::none()
+                    },
+                    UserMessage::ChangeTextScale(text_scale) => {
+                        self.config.text_scale = text_scale;
+                        self.save_config();
+                        self.resize_window_to_fit()
+                    },
+                    UserMessage::ChangePollInterval(poll_interval) => {
+                        self.config.poll_interval = poll_interval;
+                        self.save_config();
+                        Command::none()
+                    },
+                    UserMessage::ChangeScanInterval(scan_interval) => {
+                        self.config.scan_interval = scan_interval;
+                        self.save_config();
+                        Command::none()
+                    },
+                    UserMessage::ChangeStartupMethod(startup_method) => {
+                        self.config.startup_method = startup_method;
+                        self.save_config();
+                        self.reregister_startup()
+                    },
+                    UserMessage::ChangeStartupTaskElevated(startup_task_elevated) => {
+                        self.config.startup_task_elevated = startup_task_elevated;
+                        self.save_config();
+                        self.reregister_startup()
+                    },
+                    UserMessage::PauseProtection(pause_duration) => {
+                        self.config.paused_until = Some(Local::now() + pause_duration.duration());
+                        self.save_config();
+                        tray::set_pause_remaining(self.pause_minutes_left());
+                        Command::none()
+                    },
+                    UserMessage::ResumeProtection => self.resume_protection(),
+                    UserMessage::ChangeStartMinimized(start_minimized) => {
+                        // takes effect from the next start
+                        self.config.start_minimized = start_minimized;
+                        self.save_config();
+                        Command::none()
+                    },
+                    UserMessage::ChangeTrayOnly(tray_only) => {
+                        // takes effect from the next start
+                        self.config.tray_only = tray_only;
+                        self.save_config();
+                        Command::none()
+                    },
+                    UserMessage::ChangeKeepWarningOnTop(keep_warning_on_top) => {
+                        self.config.keep_warning_on_top = keep_warning_on_top;
+                        self.save_config();
+                        match keep_warning_on_top {
+                            true => Command::none(),
+                            false => self.stop_keeping_warning_on_top(),
+                        }
+                    },
+                    UserMessage::AcknowledgeWarning => {
+                        self.warning_acknowledged = true;
+                        self.stop_keeping_warning_on_top()
+                    },
+                    UserMessage::CopyStatus => {
+                        self.status_copied = true;
+                        iced::clipboard::write(self.plain_text_status())
+                    },
+                    UserMessage::OpenFolderOptions => {
+                        // whatever the user changes there is picked up by the registry watchers like any other change
+                        if let Err(error) = windows_ops::open_folder_options() {
+                            self.report_error(FailedOperation::OpenFolderOptions, error);
+                        }
+                        Command::none()
+                    },
+                    UserMessage::OpenRegistryEditor => {
+                        if let Err(error) = windows_ops::open_in_registry_editor(&WinregRegistry) {
+                            self.report_error(FailedOperation::OpenRegistryEditor, error);
+                        }
+                        Command::none()
+                    },
+                    UserMessage::ChangeAdvancedMode(advanced_mode) => {
+                        self.config.advanced_mode = advanced_mode;
+                        self.save_config();
+                        Command::none()
+                    },
+                    UserMessage::RestoreAdvancedKeyBackup => {
+                        match advanced_backup::restore_latest() {
+                            Ok(Some(_)) => {
+                                let restart_policy: ExplorerRestartPolicy = self.explorer_restart_policy();
+                                match windows_ops::make_windows_explorer_pick_up_changes(restart_policy) {
+                                    Ok(fix_outcome) =>
+                                        self.record_fix_outcome(fix_outcome, HistoryEventKind::BackupRestored),
+                                    Err(error) => warn!("Failed to update Windows Explorer: {:?}", error),
+                                }
+                                self.refresh_hive_values(true);
+                            },
+                            Ok(None) => self.latest_backup_time = None,
+                            Err(error) => self.report_error(FailedOperation::RestoreAdvancedKeyBackup, error),
+                        }
+                        Command::none()
+                    },
+                    UserMessage::UndoLastChange => {
+                        match advanced_backup::undo_last_change() {
+                            Ok(Some(_)) => {
+                                let restart_policy: ExplorerRestartPolicy = self.explorer_restart_policy();
+                                match windows_ops::make_windows_explorer_pick_up_changes(restart_policy) {
+                                    Ok(fix_outcome) =>
+                                        self.record_fix_outcome(fix_outcome, HistoryEventKind::ChangeUndone),
+                                    Err(error) => warn!("Failed to update Windows Explorer: {:?}", error),
+                                }
+                                // before `refresh_state()`, so the audit log puts the change down to this program
+                                self.refresh_hive_values(true);
+                                if let Err(error) = self.refresh_state() {
+                                    self.report_error(FailedOperation::CheckState, error);
+                                }
+                            },
+                            Ok(None) => trace!("There was no change to undo"),
+                            Err(error) => self.report_error(FailedOperation::UndoLastChange, error),
+                        }
+                        // the backup which was just used up is gone
+                        self.latest_backup_time = latest_backup_time();
+                        Command::none()
+                    },
+                    UserMessage::ChangeVerboseLogging(verbose_logging) => {
+                        self.config.verbose_logging = verbose_logging;
+                        self.save_config();
+                        if let Err(error) = logging::set_verbose(verbose_logging) {
+                            warn!("Failed to change how much is logged: {:?}", error);
+                        }
+                        tray::set_verbose_logging(verbose_logging);
+                        Command::none()
+                    },
+                    UserMessage::ToggleVerboseLogging => {
+                        let verbose_logging: bool = !self.config.verbose_logging;
+                        self.handle_message(User(UserMessage::ChangeVerboseLogging(verbose_logging)))
+                    },
+                    UserMessage::ChangeCheckForUpdates(check_for_updates) => {
+                        self.config.check_for_updates = check_for_updates;
+                        self.save_config();
+                        // the subscription starts checking right away
+                        Command::none()
+                    },
+                    UserMessage::CheckForUpdates => {
+                        if self.update_check_in_progress {
+                            return Command::none();
+                        }
+                        self.confirmed_up_to_date = false;
+                        self.update_check_in_progress = true;
+                        check_for_updates_in_background()
+                    },
+                    UserMessage::OpenReleasePage => {
+                        if let Some(release) = &self.available_update {
+                            if let Err(error) = windows_ops::open_with_shell(&release.url) {
+                                warn!("Failed to open the release page: {:?}", error);
+                            }
+                        }
+                        Command::none()
+                    },
+                    UserMessage::InstallUpdate => {
+                        if self.update_install_in_progress {
+                            return Command::none();
+                        }
+                        let Some(release) = self.available_update.clone() else {
+                            return Command::none();
+                        };
+                        self.update_install_in_progress = true;
+                        install_update_in_background(release)
+                    },
+                    UserMessage::DismissUpdate => {
+                        self.dismissed_update = self.available_update.take().map(|release| release.version);
+                        Command::none()
+                    },
+                    UserMessage::ChangeNotificationSound(notification_sound) => {
+                        self.config.notification_sound = notification_sound;
+                        self.save_config();
+                        // let the user hear what they picked
+                        if let Err(error) = sound::play(notification_sound) {
+                            warn!("Failed to play the notification sound: {:?}", error);
+                        }
+                        Command::none()
+                    },
+                    UserMessage::ChangeWebhookUrl(webhook_url) => {
+                        self.webhook_url_draft = webhook_url;
+                        self.webhook_url_invalid = false;
+                        Command::none()
+                    },
+                    UserMessage::SaveWebhookUrl => {
+                        // an empty field turns the webhook off
+                        let webhook_url: Option<String> = match self.webhook_url_draft.trim().is_empty() {
+                            true => None,
+                            false => match webhook::parse_url(&self.webhook_url_draft) {
+                                Ok(webhook_url) => Some(webhook_url),
+                                Err(error) => {
+                                    trace!("Not saving the webhook URL: {:?}", error);
+                                    self.webhook_url_invalid = true;
+                                    return Command::none();
+                                },
+                            },
+                        };
+                        self.webhook_url_draft = webhook_url.clone().unwrap_or_default();
+                        self.config.webhook_url = webhook_url;
+                        self.save_config();
+                        Command::none()
+                    },
+                }
+            },
+            Backend(backend_message) => {
+                match backend_message {
+                    BackendMessage::WatchedValuesChanged(watched_key, changed_values) =>
+                        self.reconcile_changed_values(&watched_key, &changed_values),
+                    BackendMessage::FileExtensionsAreNowHidden(source) if self.file_extensions_hidden => {
+                        // only the change from visible to hidden is worth a warning, not every re-read which finds
+                        // file extensions still hidden
+                        trace!("File extensions were already known to be hidden");
+                        self.file_extensions_setting_source = source;
+                        Command::none()
+                    },
+                    BackendMessage::FileExtensionsAreNowHidden(source) => {
+                        self.file_extensions_hidden = true;
+                        self.warning_acknowledged = false;
+                        self.file_extensions_setting_source = source;
+                        self.refresh_hive_values(false);
+                        self.change_attribution = self.attribute_change();
+                        self.record_history_event(self.file_extensions_hidden_event());
+                        self.notify_user(Notification::FileExtensionsHidden)
+                    },
+                    BackendMessage::FileExtensionsAreNoLongerHidden(source) => {
+                        self.file_extensions_setting_source = source;
+                        self.refresh_hive_values(false);
+                        // this notification also fires after the user fixes things from this app,
+                        // which has already been recorded
+                        if self.file_extensions_hidden {
+                            self.record_history_event(HistoryEventKind::FileExtensionsVisible);
+                        }
+                        self.file_extensions_hidden = false;
+                        self.change_attribution = None;
+                        self.traced_change = None;
+                        tray::set_warning_indicator(false);
+                        self.stop_keeping_warning_on_top()
+                    },
+                    BackendMessage::CheckStartedFailing(check_id) if self.failing_checks.contains(&check_id) => {
+                        trace!("{} was already known to fail", check_id);
+                        Command::none()
+                    },
+                    BackendMessage::CheckStartedFailing(check_id) => {
+                        let severity: Severity = self.find_check(&check_id)
+                            .map(|check| check.severity())
+                            .unwrap_or(Severity::Info);
+                        self.failing_checks.push(check_id.clone());
+                        self.record_history_event(HistoryEventKind::CheckFailed(check_id.clone()));
+                        match severity {
+                            Severity::Info => Command::none(),
+                            Severity::Warning | Severity::Critical => self.notify_user(Notification::CheckFailed(check_id)),
+                        }
+                    },
+                    BackendMessage::CheckStartedPassing(check_id) => {
+                        self.failing_checks.retain(|failing_check_id| *failing_check_id != check_id);
+                        self.record_history_event(HistoryEventKind::CheckPassed(check_id));
+                        Command::none()
+                    },
+                    BackendMessage::DisguisedFilesFound(disguised_files) => {
+                        let mut commands: Vec<Command<Message>> = Vec::new();
+                        for disguised_file in disguised_files {
+                            // e.g. a defused file which the user just renamed back
+                            if self.disguised_files.contains(&disguised_file) {
+                                continue;
+                            }
+                            let file_name: String = filenames::reveal(&file_name_of(&disguised_file));
+                            warn!("Found a disguised program at {:?}", disguised_file);
+                            self.record_history_event(HistoryEventKind::DisguisedFileFound(file_name.clone()));
+                            commands.push(self.notify_user(Notification::DisguisedFileFound(file_name)));
+                            self.disguised_files.push(disguised_file);
+                        }
+                        Command::batch(commands)
+                    },
+                    BackendMessage::HideFileExtTurnedOnBy(change_attribution) => {
+                        // the registry change notification usually arrives first, and the change was unattributed then
+                        if self.file_extensions_hidden && self.change_attribution.is_none() {
+                            self.change_attribution = Some(change_attribution.clone());
+                        }
+                        self.traced_change = Some(change_attribution);
+                        Command::none()
+                    },
+                    BackendMessage::SystemThemeChanged(theme) => {
+                        trace!("Switching to the new system theme {:?}", theme);
+                        self.system_theme = theme;
+                        Command::none()
+                    },
+                    BackendMessage::IpcRequestReceived(request) => {
+                        trace!("Carrying out {:?} for another process", request.command);
+                        let commands: Command<Message> = match request.command {
+                            IpcCommand::Status => Command::none(),
+                            // the process asking already decided, so there's nothing to confirm.
+                            // The registry is read again right away, so the response shows how the fix went.
+                            IpcCommand::Fix => Command::batch(vec![
+                                self.handle_message(User(UserMessage::HideFileExtensions)),
+                                self.reconcile_with_registry(),
+                            ]),
+                            IpcCommand::ShowWindow => self.handle_message(Ui(UiMessage::RestoreFromTray)),
+                            IpcCommand::Exit => window::close(),
+                        };
+                        request.respond(&self.status_report());
+                        commands
+                    },
+                    BackendMessage::HotkeyPressed(action) => {
+                        trace!("The hotkey for {:?} was pressed", action);
+                        match action {
+                            HotkeyAction::ShowWindow => self.handle_message(Ui(UiMessage::RestoreFromTray)),
+                            // pressing the hotkey is as deliberate as confirming the fix
+                            HotkeyAction::Fix => Command::batch(vec![
+                                self.handle_message(User(UserMessage::HideFileExtensions)),
+                                self.reconcile_with_registry(),
+                            ]),
+                        }
+                    },
+                    BackendMessage::NewerReleaseFound(release) => {
+                        // the daily check finds the same release until the user updates
+                        if self.available_update.as_ref() == Some(&release) || self.dismissed_update == Some(release.version) {
+                            return Command::none();
+                        }
+                        trace!("Version {} is available at {}", release.version, release.url);
+                        let version: String = release.version.to_string();
+                        self.available_update = Some(release);
+                        self.notify_user(Notification::UpdateAvailable(version))
+                    },
+                    BackendMessage::UpdateCheckFinished(result) => {
+                        self.update_check_in_progress = false;
+                        match result {
+                            Ok(Some(release)) => self.handle_message(Backend(BackendMessage::NewerReleaseFound(release))),
+                            Ok(None) => {
+                                self.confirmed_up_to_date = true;
+                                Command::none()
+                            },
+                            Err(details) => {
+                                self.report_error(FailedOperation::CheckForUpdates, anyhow::anyhow!(details));
+                                Command::none()
+                            },
+                        }
+                    },
+                    BackendMessage::UpdateInstallFinished(result) => {
+                        self.update_install_in_progress = false;
+                        match result {
+                            // the new executable takes over from here
+                            Ok(()) => window::close(),
+                            Err(details) => {
+                                self.report_error(FailedOperation::InstallUpdate, anyhow::anyhow!(details));
+                                Command::none()
+                            },
+                        }
+                    },
+                    BackendMessage::PollTick => {
+                        trace!("Re-reading the setting in case a change notification was missed");
+                        self.reconcile_with_registry()
+                    },
+                    BackendMessage::ScanDue => {
+                        // a watcher on a key which was deleted and created again waits on the old key forever
+                        registry_watcher::rearm_all();
+                        let commands: Command<Message> = self.reconcile_with_registry();
+                        let failing_checks: usize = self.check_results().iter()
+                            .filter(|(_, status)| *status == CheckStatus::Warn)
+                            .count();
+                        trace!("The scheduled scan found {} checks failing", failing_checks);
+                        self.record_history_event(HistoryEventKind::ScanCompleted(failing_checks));
+                        commands
+                    },
+                    BackendMessage::ResumedFromSleep => {
+                        // change notifications may have been lost while the computer slept
+                        registry_watcher::rearm_all();
+                        self.reconcile_with_registry()
+                    },
+                    BackendMessage::SessionChanged(SessionChange::Locked) => {
+                        self.session_locked = true;
+                        Command::none()
+                    },
+                    BackendMessage::SessionChanged(SessionChange::Unlocked) => {
+                        self.session_locked = false;
+                        // whatever happened while locked may already be over, and what isn't is warned about anew
+                        let reconcile_commands: Command<Message> = self.reconcile_with_registry();
+                        match std::mem::take(&mut self.attention_pending_unlock) {
+                            true => {
+                                trace!("Delivering the warning held back while the workstation was locked");
+                                Command::batch(vec![
+                                    reconcile_commands, self.get_commands_which_notify_user_unless_busy()
+                                ])
+                            },
+                            false => reconcile_commands,
+                        }
+                    },
+                    BackendMessage::ClockTick if self.config.paused_until.is_some()
+                        && !self.config.protection_paused(Local::now()) =>
+                    {
+                        trace!("The pause is over, resuming protection");
+                        let resume_commands: Command<Message> = self.resume_protection();
+                        Command::batch(vec![resume_commands, self.handle_message(Backend(BackendMessage::ClockTick))])
+                    },
+                    BackendMessage::ClockTick => {
+                        self.status_copied = false;
+                        tray::set_pause_remaining(self.pause_minutes_left());
+                        let degraded_workers: Vec<&str> = worker::degraded_workers();
+                        // a restarted watcher may have missed changes while it was down
+                        let reconcile_commands: Command<Message> = match self.monitoring_degraded {
+                            false if !degraded_workers.is_empty() => {
+                                warn!("Monitoring is degraded, restarted {:?}", degraded_workers);
+                                self.reconcile_with_registry()
+                            },
+                            _ => Command::none(),
+                        };
+                        self.monitoring_degraded = !degraded_workers.is_empty();
+                        let summary_commands: Command<Message> = match self.notification_scheduler.poll(Local::now()) {
+                            Some(summary) => {
+                                trace!("Quiet hours ended, delivering summary: {:?}", summary);
+                                self.deferred_summary = Some(summary);
+                                self.get_commands_which_notify_user_unless_busy()
+                            },
+                            None => Command::none()
+                        };
+                        Command::batch(vec![reconcile_commands, summary_commands])
+                    },
+                }
+            },
+            Message::Error(operation, details) => {
+                warn!("{:?} failed: {}", operation, details);
+                self.error_banner = Some(ErrorBanner { operation, details, elevated_retry: None });
+                Command::none()
+            },
+            Ui(ui_message) => {
+                match ui_message {
+                    UiMessage::RestoreFromTray => {
+                        tray::set_warning_indicator(false);
+                        self.window_hidden = false;
+                        self.warning_acknowledged = self.file_extensions_hidden;
+                        Command::batch(vec![
+                            window::change_mode(Mode::Windowed),
+                            window::minimize(false),
+                            window::gain_focus(),
+                        ])
+                    },
+                    // without a tray icon, the window stays minimized to the taskbar so it can be brought back
+                    UiMessage::MinimizeToTray if !tray::exists() => Command::none(),
+                    UiMessage::MinimizeToTray => {
+                        self.window_hidden = true;
+                        window::change_mode::<Message>(Mode::Hidden)
+                    },
+                    UiMessage::RetryTrayCreation => {
+                        match tray::create(self.config.verbose_logging) {
+                            Ok(()) => {
+                                trace!("Added the tray icon after all");
+                                tray::set_explorer_restart_pending(self.explorer_restart_pending);
+                            },
+                            Err(error) => trace!("Still failed to add the tray icon: {:?}", error),
+                        }
+                        Command::none()
+                    },
+                    UiMessage::OpenPanelFromTray(panel) => {
+                        self.active_view = ActiveView::Main { expanded_panel: Some(panel) };
+                        Command::batch(vec![
+                            self.handle_message(Ui(UiMessage::RestoreFromTray)),
+                            self.resize_window_to_fit(),
+                        ])
+                    },
+                    UiMessage::PulseTrayIcon => {
+                        tray::pulse();
+                        Command::none()
+                    },
+                    UiMessage::FocusNext => iced::widget::focus_next(),
+                    UiMessage::FocusPrevious => iced::widget::focus_previous(),
+                    // Enter picks the highlighted choice of a confirmation, as it would in a Windows dialog
+                    UiMessage::Confirm => match (self.fix_confirmation, &self.check_confirmation) {
+                        (Some(_), _) => self.handle_message(User(UserMessage::ConfirmFix)),
+                        (None, Some(_)) => self.handle_message(User(UserMessage::ConfirmRemediateCheck)),
+                        (None, None) => Command::none(),
+                    },
+                    // Escape backs out of whatever was opened last
+                    UiMessage::GoBack => match (self.fix_confirmation, &self.check_confirmation, self.active_view) {
+                        (Some(_), _, _) => self.handle_message(User(UserMessage::CancelFix)),
+                        (None, Some(_), _) => self.handle_message(User(UserMessage::CancelRemediateCheck)),
+                        (None, None, ActiveView::Settings { .. }) =>
+                            self.handle_message(User(UserMessage::CloseSettings)),
+                        (None, None, ActiveView::Main { expanded_panel: Some(_) }) =>
+                            self.handle_message(User(UserMessage::CollapsePanel)),
+                        (None, None, ActiveView::Main { expanded_panel: None }) if self.error_banner.is_some() =>
+                            self.handle_message(User(UserMessage::DismissError)),
+                        (None, None, ActiveView::Main { expanded_panel: None }) => Command::none(),
+                    },
+                    // only does what pressing the fix button could
+                    UiMessage::FixFromKeyboard => match self.can_turn_off_file_extension_hiding()
+                        && !self.file_extensions_setting_source.is_group_policy()
+                    {
+                        true => self.handle_message(User(UserMessage::RequestFix)),
+                        false => Command::none(),
+                    },
+                    // the window's resize to nothing then hides it in the tray, as the minimize button would
+                    UiMessage::MinimizeFromKeyboard => window::minimize(true),
+                }
+            }
+        };
+    }
+
+    fn accessible_view(&self) -> AccessibleView {
+        AccessibleView {
+            status_text: match self.file_extensions_hidden {
+                true => tr("extensions-hidden-warning"),
+                false => tr("extensions-visible-status"),
+            },
+            fix_button_label: tr("stop-hiding-button"),
+            fix_button_enabled: self.can_turn_off_file_extension_hiding(),
+            run_at_startup_label: tr("run-at-startup-checkbox"),
+            run_at_startup: self.run_at_startup,
+            fix_confirmation: self.fix_confirmation.map(|_| AccessibleConfirmation {
+                message: self.fix_confirmation_message(),
+                confirm_label: tr("continue-button"),
+                cancel_label: tr("cancel-button"),
+            }),
+        }
+    }
+
+    fn view_error_banner(&self, error_banner: &ErrorBanner) -> Element<Message> {
+        let mut buttons: Vec<Element<Message>> = Vec::new();
+        if let Some(pending_action) = error_banner.elevated_retry {
+            buttons.push(
+                button(text(tr("retry-as-administrator-button")))
+                    .on_press(User(UserMessage::RelaunchElevated(pending_action)))
+                    .into()
+            );
+        } else if error_banner.operation.retry_message().is_some() {
+            buttons.push(button(text(tr("retry-button"))).on_press(User(UserMessage::RetryFailedOperation)).into());
+        }
+        buttons.push(button(text(tr("dismiss-button"))).on_press(User(UserMessage::DismissError)).into());
+
+        let description: Column<Message> = Column::new()
+            .spacing(2)
+            .width(Length::Fill)
+            .push(text(error_banner.operation.description()).style(DANGER_COLOR))
+            .push(text(&error_banner.details).size(14));
+
+        let mut children: Vec<Element<Message>> = vec![description.into()];
+        children.extend(buttons);
+        self.directional_row(children)
+            .spacing(10)
+            .padding(10)
+            .align_items(Alignment::Center)
+            .into()
+    }
+
+    fn view_fix_confirmation(&self, fix_confirmation: FixConfirmation) -> Element<Message> {
+        Column::new()
+            .spacing(20)
+            .padding(20)
+            .align_items(Alignment::Center)
+            .push(text(self.fix_confirmation_message()).horizontal_alignment(Horizontal::Center))
+            .push(checkbox(
+                tr("dont-ask-again-checkbox"),
+                fix_confirmation.dont_ask_again,
+                |dont_ask_again| User(UserMessage::ToggleDontAskAgain(dont_ask_again))
+            ))
+            .push(
+                self.directional_row(vec![
+                    button(text(tr("continue-button"))).on_press(User(UserMessage::ConfirmFix)).into(),
+                    button(text(tr("cancel-button"))).on_press(User(UserMessage::CancelFix)).into(),
+                ]).spacing(10)
+            )
+            .into()
+    }
+
+    fn view_check_confirmation(&self, check_id: &str) -> Element<Message> {
+        let check_name: String = checks::name_of(check_id);
+        Column::new()
+            .spacing(20)
+            .padding(20)
+            .align_items(Alignment::Center)
+            .push(
+                text(tr_args("check-confirmation-message", &[("check", FluentValue::from(check_name))]))
+                    .horizontal_alignment(Horizontal::Center)
+            )
+            .push(
+                self.directional_row(vec![
+                    button(text(tr("continue-button"))).on_press(User(UserMessage::ConfirmRemediateCheck)).into(),
+                    button(text(tr("cancel-button"))).on_press(User(UserMessage::CancelRemediateCheck)).into(),
+                ]).spacing(10)
+            )
+            .into()
+    }
+
+    fn view_main(&self, expanded_panel: Option<Panel>) -> Element<Message> {
+        let history_button = self.panel_toggle_button(Panel::History, "show-history-button", "hide-history-button");
+        let spoofing_demo_button = self.panel_toggle_button(
+            Panel::SpoofingDemo, "demo-show-button", "demo-hide-button"
+        );
+        let script_associations_button = self.panel_toggle_button(
+            Panel::ScriptAssociations, "script-associations-show-button", "script-associations-hide-button"
+        );
+        let mark_of_the_web_button = self.panel_toggle_button(
+            Panel::MarkOfTheWeb, "motw-show-button", "motw-hide-button"
+        );
+        let about_button = self.panel_toggle_button(Panel::About, "about-show-button", "about-hide-button");
+        let settings_button = button(text(tr("settings-button"))).on_press(User(UserMessage::OpenSettings));
+        let undo_last_change_button = match self.latest_backup_time {
+            Some(_) => button(text(tr("undo-last-change-button"))).on_press(User(UserMessage::UndoLastChange)),
+            None => button(text(tr("undo-last-change-button"))),
+        };
+        let folder_options_button = button(text(tr("folder-options-button")))
+            .on_press(User(UserMessage::OpenFolderOptions));
+        let mut windows_tool_buttons: Vec<Element<Message>> = vec![folder_options_button.into()];
+        if self.config.advanced_mode {
+            windows_tool_buttons.push(
+                button(text(tr("registry-editor-button"))).on_press(User(UserMessage::OpenRegistryEditor)).into()
+            );
+        }
+        let copy_status_button = match self.status_copied {
+            true => button(text(tr("status-copied"))).on_press(User(UserMessage::CopyStatus)),
+            false => button(text(tr("copy-status-button"))).on_press(User(UserMessage::CopyStatus)),
+        };
+
+        let mut content: Column<Message> = Column::new()
+            .align_items(Alignment::Center)
+            .spacing(20)
+            .padding(20);
+
+        if let Some(deferred_summary) = &self.deferred_summary {
+            content = content.push(
+                self.directional_row(vec![
+                    text(deferred_summary.message()).into(),
+                    button(text(tr("dismiss-button"))).on_press(User(UserMessage::DismissDeferredSummary)).into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            );
+        }
+
+        if let Some(release) = &self.available_update {
+            content = content.push(
+                self.directional_row(vec![
+                    text(tr_args("update-available", &[("version", FluentValue::from(release.version.to_string()))])).into(),
+                    match self.update_install_in_progress {
+                        true => button(text(tr("update-installing-button"))),
+                        false if release.executable_url.is_some() && release.checksum_url.is_some() =>
+                            button(text(tr("update-install-button"))).on_press(User(UserMessage::InstallUpdate)),
+                        false => button(text(tr("update-install-button"))),
+                    }.into(),
+                    button(text(tr("update-download-button"))).on_press(User(UserMessage::OpenReleasePage)).into(),
+                    button(text(tr("dismiss-button"))).on_press(User(UserMessage::DismissUpdate)).into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            );
+        }
+
+        for disguised_file in &self.disguised_files {
+            content = content.push(
+                self.directional_row(vec![
+                    text(tr_args("disguised-file-warning", &[
+                        ("file", FluentValue::from(filenames::reveal(&file_name_of(disguised_file))))
+                    ]))
+                        .style(DANGER_COLOR)
+                        .width(Length::Fill)
+                        .into(),
+                    button(text(tr("defuse-button")))
+                        .on_press(User(UserMessage::DefuseFile(disguised_file.clone())))
+                        .into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            );
+        }
+        for defused_file in &self.defused_files {
+            content = content.push(
+                self.directional_row(vec![
+                    text(tr_args("defused-file-status", &[
+                        ("file", FluentValue::from(filenames::reveal(&file_name_of(&defused_file.defused_path))))
+                    ]))
+                        .width(Length::Fill)
+                        .into(),
+                    button(text(tr("undo-check-button")))
+                        .on_press(User(UserMessage::RestoreDefusedFile(defused_file.clone())))
+                        .into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            );
+        }
+        if !self.disguised_files.is_empty() || !self.defused_files.is_empty() {
+            content = content.push(button(text(tr("dismiss-button"))).on_press(User(UserMessage::DismissDisguisedFiles)));
+        }
+
+        content = content.push(self.view_dashboard());
+
+        content = content.push(match self.pause_minutes_left() {
+            Some(minutes_left) => self.directional_row(vec![
+                text(tr_args("protection-paused", &[("minutes", FluentValue::from(minutes_left))]))
+                    .style(DANGER_COLOR)
+                    .width(Length::Fill)
+                    .into(),
+                button(text(tr("resume-protection-button"))).on_press(User(UserMessage::ResumeProtection)).into(),
+            ]),
+            None => self.directional_row(vec![
+                text(tr("pause-protection-label")).into(),
+                pick_list(&PauseDuration::PRESETS[..], None, |pause_duration| {
+                    User(UserMessage::PauseProtection(pause_duration))
+                }).placeholder(tr("pause-protection-placeholder")).into(),
+            ]),
+        }.spacing(10).align_items(Alignment::Center));
+
+        if self.warning_kept_on_top {
+            content = content.push(
+                self.directional_row(vec![
+                    text(tr("warning-kept-on-top")).size(14).width(Length::Fill).into(),
+                    button(text(tr("acknowledge-button"))).on_press(User(UserMessage::AcknowledgeWarning)).into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            );
+        }
+
+        // builds without the tray feature never have an icon to wait for
+        if cfg!(feature = "tray") && !tray::exists() {
+            content = content.push(
+                text(tr("tray-unavailable-warning")).size(14).horizontal_alignment(Horizontal::Center)
+            );
+        }
+        if self.monitoring_degraded {
+            content = content.push(
+                text(tr("monitoring-degraded-warning")).style(DANGER_COLOR).horizontal_alignment(Horizontal::Center)
+            );
+        }
+        if self.setting_keeps_changing {
+            content = content.push(
+                text(tr("setting-keeps-changing-warning")).style(DANGER_COLOR).horizontal_alignment(Horizontal::Center)
+            );
+        }
+        if let (true, Some(change_attribution)) = (self.file_extensions_hidden, &self.change_attribution) {
+            content = content.push(
+                text(tr_args("extensions-hidden-by", &[
+                    ("process", FluentValue::from(change_attribution.process_name())),
+                    ("process_id", FluentValue::from(change_attribution.process_id)),
+                ])).size(14).horizontal_alignment(Horizontal::Center)
+            );
+        }
+        if self.explorer_restart_pending {
+            let restart_reason: String = match self.explorer_only_refreshed {
+                true => tr("explorer-only-refreshed"),
+                false => tr("explorer-restart-pending"),
+            };
+            content = content.push(
+                self.directional_row(vec![
+                    text(restart_reason).size(14).into(),
+                    button(text(tr("restart-explorer-button"))).on_press(User(UserMessage::RestartExplorerNow)).into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            );
+        }
+
+        // the user's own setting is the usual case, so only call out the others
+        if self.file_extensions_setting_source != SettingSource::UserPreference {
+            content = content.push(
+                text(tr_args("setting-source", &[("source", FluentValue::from(self.file_extensions_setting_source.to_string()))]))
+                    .size(14)
+            );
+        }
+
+        // split over three rows so they still fit at the default width
+        content = content
+            .push(
+                self.directional_row(vec![
+                    history_button.into(),
+                    spoofing_demo_button.into(),
+                    about_button.into(),
+                    settings_button.into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            )
+            .push(
+                self.directional_row(vec![
+                    script_associations_button.into(),
+                    mark_of_the_web_button.into(),
+                    undo_last_change_button.into(),
+                    copy_status_button.into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            )
+            // for users who would rather change things through Windows' own dialogs, or see what this program manages
+            .push(self.directional_row(windows_tool_buttons).spacing(10).align_items(Alignment::Center));
+
+        match expanded_panel {
+            Some(Panel::History) => content = content.push(self.view_history()),
+            Some(Panel::SpoofingDemo) => content = content.push(self.view_spoofing_demo()),
+            Some(Panel::ScriptAssociations) => content = content.push(self.view_script_associations()),
+            Some(Panel::MarkOfTheWeb) => content = content.push(self.view_mark_of_the_web()),
+            Some(Panel::About) => content = content.push(self.view_about()),
+            None => {}
+        }
+
+        content.into()
+    }
+
+    // The overall status, then one row per check with what it found and a way to fix it. HideFileExt comes first.
+    fn view_dashboard(&self) -> Element<Message> {
+        let mut rows: Column<Message> = Column::new().spacing(5).push(self.view_dashboard_row(
+            tr("rule-file-extensions"), self.file_extensions_status(), self.stop_hiding_file_extensions_button()
+        ));
+        for check in self.other_checks() {
+            let status: CheckStatus = self.status_of(check.as_ref());
+            let check_id: String = String::from(check.id());
+            // checks which only report send the user to where they can change things themselves
+            let fix_button: Element<Message> = match (check.can_remediate(), check.settings_program()) {
+                (false, Some(_)) => button(text(tr("open-settings-button")))
+                    .on_press(User(UserMessage::OpenCheckSettings(check_id)))
+                    .into(),
+                (true, _) if status == CheckStatus::Warn => button(text(tr("fix-check-button")))
+                    .on_press(User(UserMessage::RemediateCheck(check_id)))
+                    .into(),
+                (true, _) if check.can_undo() => button(text(tr("undo-check-button")))
+                    .on_press(User(UserMessage::UndoCheck(check_id)))
+                    .into(),
+                _ => button(text(tr("fix-check-button"))).into(),
+            };
+            rows = rows.push(self.view_dashboard_row(check.name(), status, fix_button));
+        }
+
+        let mut dashboard: Column<Message> = Column::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(self.view_status_row());
+        if let Some(protection_stats) = self.view_protection_stats() {
+            dashboard = dashboard.push(protection_stats);
+        }
+        dashboard.push(rows).into()
+    }
+
+    // A glyph, a headline and the score, so whether anything needs doing can be told at a glance. Hidden file
+    // extensions outrank every other failing check, since they're what this program is for.
+    fn view_status_row(&self) -> Element<Message> {
+        let check_results: Vec<(Severity, CheckStatus)> = self.check_results();
+        let failing_checks: usize = check_results.iter().filter(|(_, status)| *status == CheckStatus::Warn).count();
+        let score: u32 = checks::posture_score(&check_results);
+
+        let (glyph, headline, detail): (&'static [u8], String, String) = match (
+            self.file_extensions_hidden, failing_checks
+        ) {
+            (true, _) => (
+                STATUS_WARNING_SVG, tr("status-headline-extensions-hidden"), tr("status-detail-extensions-hidden")
+            ),
+            (false, 0) => (STATUS_OK_SVG, tr("status-headline-protected"), tr("status-detail-protected")),
+            (false, failing_checks) => (
+                STATUS_WARNING_SVG,
+                tr_args("status-headline-checks-failing", &[("count", FluentValue::from(failing_checks))]),
+                tr("status-detail-checks-failing"),
+            ),
+        };
+        let headline_color: Color = match failing_checks {
+            0 => SAFE_COLOR,
+            _ => DANGER_COLOR,
+        };
+
+        let glyph: Svg = svg(svg::Handle::from_memory(glyph))
+            .width(Length::Units(STATUS_GLYPH_SIZE))
+            .height(Length::Units(STATUS_GLYPH_SIZE));
+        let description: Column<Message> = Column::new()
+            .spacing(2)
+            .width(Length::Fill)
+            .push(text(headline).size(22).style(headline_color))
+            .push(text(detail).size(14))
+            .push(text(tr_args("dashboard-score", &[("score", FluentValue::from(score))])).size(14));
+        self.directional_row(vec![glyph.into(), description.into()])
+            .width(Length::Fill)
+            .spacing(15)
+            .align_items(Alignment::Center)
+            .into()
+    }
+
+    // Nothing until the audit log has recorded a change
+    fn view_protection_stats(&self) -> Option<Element<Message>> {
+        let days_protected: i64 = self.protection_stats.days_protected(Local::now())?;
+        let mut description: String = match self.protection_stats.last_incident {
+            Some(last_incident) => tr_args("stats-incidents", &[
+                ("count", FluentValue::from(self.protection_stats.incident_count())),
+                ("last", FluentValue::from(last_incident.format("%Y-%m-%d %H:%M").to_string())),
+                ("days", FluentValue::from(days_protected)),
+            ]),
+            None => tr_args("stats-no-incidents", &[("days", FluentValue::from(days_protected))]),
+        };
+        // the machine-wide default changing is worth knowing apart, since it takes administrator rights
+        let all_users_incident_count: usize = self.protection_stats.incident_count_in(SettingScope::AllUsers);
+        if all_users_incident_count > 0 {
+            description.push(' ');
+            description.push_str(&tr_args(
+                "stats-all-users-incidents", &[("count", FluentValue::from(all_users_incident_count))]
+            ));
+        }
+        Some(text(description).size(14).horizontal_alignment(Horizontal::Center).into())
+    }
+
+    fn view_dashboard_row<'a>(
+        &'a self, name: String, status: CheckStatus, fix_button: Element<'a, Message>
+    ) -> Element<'a, Message> {
+        let status_text: Text = match status {
+            CheckStatus::Pass => text(tr("check-status-pass")),
+            CheckStatus::Warn => text(tr("check-status-warn")).style(DANGER_COLOR),
+        };
+        self.directional_row(vec![
+            text(name).width(Length::Fill).into(),
+            status_text.into(),
+            fix_button,
+        ]).width(Length::Fill).spacing(10).align_items(Alignment::Center).into()
+    }
+
+    fn view_settings(&self, selected_tab: SettingsTab) -> Element<Message> {
+        let tab_buttons: Vec<Element<Message>> = SettingsTab::ALL.iter()
+            .map(|tab| {
+                let tab_button = button(text(tab.to_string()));
+                // the selected tab is shown as a disabled button
+                match *tab == selected_tab {
+                    true => tab_button,
+                    false => tab_button.on_press(User(UserMessage::SelectSettingsTab(*tab))),
+                }.into()
+            })
+            .collect();
+
+        let tab_contents: Element<Message> = match selected_tab {
+            SettingsTab::Notifications => self.view_notification_settings(),
+            SettingsTab::Startup => self.view_startup_settings(),
+            SettingsTab::Enforcement => self.view_enforcement_settings(),
+            SettingsTab::Advanced => self.view_advanced_settings(),
+        };
+
+        Column::new()
+            .align_items(Alignment::Center)
+            .spacing(20)
+            .padding(20)
+            .push(self.directional_row(tab_buttons).spacing(5))
+            .push(container(tab_contents).height(Length::Fill).center_y())
+            .push(button(text(tr("settings-back-button"))).on_press(User(UserMessage::CloseSettings)))
+            .into()
+    }
+
+    fn view_notification_settings(&self) -> Element<Message> {
+        let sound_picker = pick_list(
+            &NotificationSound::ALL[..],
+            Some(self.config.notification_sound),
+            |notification_sound| User(UserMessage::ChangeNotificationSound(notification_sound))
+        );
+
+        let quiet_hours_description: String = match self.config.quiet_hours {
+            Some(quiet_hours) => tr_args("settings-quiet-hours", &[
+                ("start", FluentValue::from(quiet_hours.start.format("%H:%M").to_string())),
+                ("end", FluentValue::from(quiet_hours.end.format("%H:%M").to_string())),
+            ]),
+            None => tr("settings-quiet-hours-off"),
+        };
+
+        Column::new()
+            .spacing(15)
+            .align_items(Alignment::Center)
+            .push(
+                self.directional_row(vec![
+                    text(tr("sound-label")).into(),
+                    sound_picker.into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            )
+            .push(text(quiet_hours_description).horizontal_alignment(Horizontal::Center))
+            .push(checkbox(
+                tr("keep-warning-on-top-checkbox"),
+                self.config.keep_warning_on_top,
+                |keep_warning_on_top| User(UserMessage::ChangeKeepWarningOnTop(keep_warning_on_top))
+            ))
+            .push(
+                self.directional_row(vec![
+                    text(tr("webhook-url-label")).into(),
+                    text_input(
+                        &tr("webhook-url-placeholder"),
+                        &self.webhook_url_draft,
+                        |webhook_url| User(UserMessage::ChangeWebhookUrl(webhook_url))
+                    ).on_submit(User(UserMessage::SaveWebhookUrl)).width(Length::Fill).into(),
+                    // nothing to save until the field differs from the config
+                    match self.webhook_url_draft == self.config.webhook_url.as_deref().unwrap_or_default() {
+                        true => button(text(tr("webhook-url-save-button"))),
+                        false => button(text(tr("webhook-url-save-button")))
+                            .on_press(User(UserMessage::SaveWebhookUrl)),
+                    }.into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            )
+            .push(match self.webhook_url_invalid {
+                true => text(tr("webhook-url-invalid")).style(DANGER_COLOR),
+                false => text(""),
+            })
+            .into()
+    }
+
+    fn view_startup_settings(&self) -> Element<Message> {
+        let run_at_startup_checkbox = checkbox(
+            tr("run-at-startup-checkbox"),
+            self.run_at_startup,
+            |run_at_startup| match run_at_startup {
+                true => User(UserMessage::RunAtStartup),
+                false => User(UserMessage::DontRunAtStartup)
+            }
+        );
+
+        let startup_method_picker = pick_list(
+            &StartupMethod::ALL[..],
+            Some(self.config.startup_method),
+            |startup_method| User(UserMessage::ChangeStartupMethod(startup_method))
+        );
+
+        let run_at_startup_explanation: String = match self.config.startup_method {
+            StartupMethod::RunKey => tr("run-at-startup-run-key-tooltip"),
+            StartupMethod::ScheduledTask => tr("run-at-startup-scheduled-task-tooltip"),
+        };
+
+        let mut startup_settings: Column<Message> = Column::new()
+            .spacing(15)
+            .align_items(Alignment::Center)
+            .push(self.with_tooltip(run_at_startup_checkbox, run_at_startup_explanation))
+            .push(
+                self.directional_row(vec![
+                    text(tr("startup-method-label")).into(),
+                    startup_method_picker.into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            );
+        // builds without the tray feature have nowhere to start minimized to
+        if cfg!(feature = "tray") {
+            startup_settings = startup_settings.push(checkbox(
+                tr("start-minimized-checkbox"),
+                self.config.start_minimized,
+                |start_minimized| User(UserMessage::ChangeStartMinimized(start_minimized))
+            ));
+            startup_settings = startup_settings.push(checkbox(
+                tr("tray-only-checkbox"),
+                self.config.tray_only,
+                |tray_only| User(UserMessage::ChangeTrayOnly(tray_only))
+            ));
+        }
+        if let Some(old_path) = &self.startup_repaired_from {
+            startup_settings = startup_settings.push(
+                text(tr_args("startup-entry-repaired", &[("path", FluentValue::from(old_path.as_str()))]))
+            );
+        }
+        if self.config.startup_method == StartupMethod::ScheduledTask {
+            startup_settings = startup_settings.push(checkbox(
+                tr("startup-task-elevated-checkbox"),
+                self.config.startup_task_elevated,
+                |startup_task_elevated| User(UserMessage::ChangeStartupTaskElevated(startup_task_elevated))
+            ));
+        }
+        startup_settings.into()
+    }
+
+    fn view_enforcement_settings(&self) -> Element<Message> {
+        let hive_value_description = |value: Option<u32>| -> String {
+            match value {
+                Some(0) => tr("hive-value-visible"),
+                Some(_) => tr("hive-value-hidden"),
+                None => tr("hive-value-not-set"),
+            }
+        };
+
+        let all_users_button = button(text(tr("apply-for-all-users-button")));
+        let all_users_button: Element<Message> = match self.running_elevated {
+            true => all_users_button.on_press(User(UserMessage::HideFileExtensionsForAllUsers)).into(),
+            false => Column::new()
+                .spacing(5)
+                .align_items(Alignment::Center)
+                .push(all_users_button.on_press(
+                    User(UserMessage::RelaunchElevated(PendingAction::TurnOffFileExtensionHidingForAllUsers))
+                ))
+                .push(text(tr("requires-administrator")).size(14))
+                .into(),
+        };
+
+        let loaded_users_button = button(text(tr("apply-for-signed-in-users-button")));
+        let loaded_users_button = match self.running_elevated {
+            true => loaded_users_button.on_press(User(UserMessage::HideFileExtensionsForLoadedUsers)),
+            false => loaded_users_button.on_press(
+                User(UserMessage::RelaunchElevated(PendingAction::TurnOffFileExtensionHidingForLoadedUsers))
+            ),
+        };
+
+        let new_accounts_button = button(text(tr("apply-for-new-accounts-button")));
+        let new_accounts_button = match self.running_elevated {
+            true => new_accounts_button.on_press(User(UserMessage::HideFileExtensionsForNewAccounts)),
+            false => new_accounts_button.on_press(
+                User(UserMessage::RelaunchElevated(PendingAction::TurnOffFileExtensionHidingForNewAccounts))
+            ),
+        };
+
+        let mut enforcement_settings: Column<Message> = Column::new()
+            .spacing(15)
+            .align_items(Alignment::Center)
+            .push(text(tr("settings-enforcement-description")).horizontal_alignment(Horizontal::Center))
+            .push(
+                Column::new()
+                    .spacing(2)
+                    .align_items(Alignment::Center)
+                    .push(text(tr_args("hive-status-current-user", &[
+                        ("value", FluentValue::from(hive_value_description(self.hive_values.current_user)))
+                    ])))
+                    .push(text(tr_args("hive-status-all-users", &[
+                        ("value", FluentValue::from(hive_value_description(self.hive_values.all_users)))
+                    ])))
+            )
+            .push(
+                self.directional_row(vec![
+                    self.stop_hiding_file_extensions_button(),
+                    all_users_button,
+                ]).spacing(10).align_items(Alignment::Center)
+            )
+            .push(loaded_users_button)
+            .push(self.view_check_toggles())
+            .push(checkbox(
+                tr("defer-explorer-restart-checkbox"),
+                self.config.defer_explorer_restart,
+                |defer_explorer_restart| User(UserMessage::ChangeDeferExplorerRestart(defer_explorer_restart))
+            ))
+            .push(checkbox(
+                tr("always-restart-explorer-checkbox"),
+                self.config.always_restart_explorer,
+                |always_restart_explorer| User(UserMessage::ChangeAlwaysRestartExplorer(always_restart_explorer))
+            ))
+            .push(self.view_change_attribution_toggle());
+
+        if let Some(loaded_user_results) = &self.loaded_user_results {
+            enforcement_settings = enforcement_settings.push(self.view_loaded_user_results(loaded_user_results));
+        }
+
+        enforcement_settings = enforcement_settings.push(new_accounts_button);
+        match self.new_accounts_result {
+            Some(true) => enforcement_settings = enforcement_settings.push(text(tr("new-accounts-fixed")).size(14)),
+            Some(false) => enforcement_settings = enforcement_settings.push(text(tr("new-accounts-already-visible")).size(14)),
+            None => {}
+        }
+        scrollable(enforcement_settings).into()
+    }
+
+    // Setting up auditing, reading the Security event log and tracing all take administrator rights
+    fn view_change_attribution_toggle(&self) -> Element<Message> {
+        let running_elevated: bool = self.running_elevated;
+        let audit_toggle = checkbox(
+            tr("attribute-changes-checkbox"),
+            self.config.attribute_changes,
+            move |attribute_changes| match (attribute_changes, running_elevated) {
+                (true, true) => User(UserMessage::TurnOnChangeAttribution),
+                (false, true) => User(UserMessage::TurnOffChangeAttribution),
+                (true, false) => User(UserMessage::RelaunchElevated(PendingAction::TurnOnChangeAttribution)),
+                (false, false) => User(UserMessage::RelaunchElevated(PendingAction::TurnOffChangeAttribution)),
+            }
+        );
+        // turning tracing off needs no rights, it just stops
+        let trace_toggle = checkbox(
+            tr("trace-changes-checkbox"),
+            self.config.trace_changes,
+            move |trace_changes| match (trace_changes, running_elevated) {
+                (true, false) => User(UserMessage::RelaunchElevated(PendingAction::TurnOnChangeTracing)),
+                _ => User(UserMessage::ChangeTraceChanges(trace_changes)),
+            }
+        );
+        let mut toggles: Column<Message> = Column::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(audit_toggle);
+        // builds without ETW tracing can't attribute a change to the process behind it
+        if cfg!(feature = "watcher-etw") {
+            toggles = toggles.push(trace_toggle);
+        }
+        match running_elevated {
+            true => toggles.into(),
+            false => toggles.push(text(tr("requires-administrator")).size(14)).into(),
+        }
+    }
+
+    // HideFileExt's own check is what this program is for, so it can't be turned off
+    fn view_check_toggles(&self) -> Element<Message> {
+        checks::all_checks(&self.config).iter()
+            .filter(|check| check.id() != FILE_EXTENSIONS_RULE_ID)
+            .fold(Column::new().spacing(5).push(text(tr("checks-heading"))), |checkboxes, check| {
+                let check_id: String = String::from(check.id());
+                checkboxes.push(checkbox(
+                    check.name(),
+                    checks::is_enabled(check.as_ref(), &self.config),
+                    move |enabled| User(UserMessage::ToggleCheck(check_id.clone(), enabled))
+                ))
+            })
+            .into()
+    }
+
+    fn view_loaded_user_results<'a>(&self, results: &'a [UserHiveResult]) -> Element<'a, Message> {
+        if results.is_empty() {
+            return text(tr("loaded-users-none")).into();
+        }
+
+        results.iter()
+            .fold(Column::new().spacing(2).align_items(Alignment::Center), |entries, result| {
+                let outcome: Text = match &result.outcome {
+                    Ok(true) => text(tr("loaded-user-fixed")),
+                    Ok(false) => text(tr("loaded-user-already-visible")),
+                    Err(error) => text(tr_args("loaded-user-failed", &[("error", FluentValue::from(error.as_str()))]))
+                        .style(DANGER_COLOR),
+                };
+                entries.push(
+                    self.directional_row(vec![
+                        text(result.display_name()).size(14).into(),
+                        outcome.size(14).into(),
+                    ]).spacing(10)
+                )
+            })
+            .into()
+    }
+
+    fn view_advanced_settings(&self) -> Element<Message> {
+        let theme_picker = pick_list(
+            &ThemePreference::ALL[..],
+            Some(self.config.theme_preference),
+            |theme_preference| User(UserMessage::ChangeThemePreference(theme_preference))
+        );
+
+        let text_scale_picker = pick_list(
+            &TextScale::PRESETS[..],
+            Some(self.config.text_scale),
+            |text_scale| User(UserMessage::ChangeTextScale(text_scale))
+        );
+
+        let poll_interval_picker = pick_list(
+            &PollInterval::PRESETS[..],
+            Some(self.config.poll_interval),
+            |poll_interval| User(UserMessage::ChangePollInterval(poll_interval))
+        );
+
+        let scan_interval_picker = pick_list(
+            &ScanInterval::PRESETS[..],
+            Some(self.config.scan_interval),
+            |scan_interval| User(UserMessage::ChangeScanInterval(scan_interval))
+        );
+
+        Column::new()
+            .spacing(15)
+            .align_items(Alignment::Center)
+            .push(
+                self.directional_row(vec![
+                    text(tr("theme-label")).into(),
+                    theme_picker.into(),
+                    text(tr("text-size-label")).into(),
+                    text_scale_picker.into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            )
+            .push(
+                self.directional_row(vec![
+                    text(tr("poll-interval-label")).into(),
+                    poll_interval_picker.into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            )
+            .push(
+                self.directional_row(vec![
+                    text(tr("scan-interval-label")).into(),
+                    scan_interval_picker.into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            )
+            .push(
+                self.directional_row(vec![
+                    checkbox(
+                        tr("check-for-updates-checkbox"),
+                        self.config.check_for_updates,
+                        |check_for_updates| User(UserMessage::ChangeCheckForUpdates(check_for_updates))
+                    ).into(),
+                    match self.update_check_in_progress {
+                        true => button(text(tr("checking-for-updates-button"))),
+                        false => button(text(tr("check-for-updates-button")))
+                            .on_press(User(UserMessage::CheckForUpdates)),
+                    }.into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            )
+            .push(
+                self.directional_row(vec![
+                    text(match self.latest_backup_time {
+                        Some(latest_backup_time) => tr_args("latest-backup", &[
+                            ("time", FluentValue::from(latest_backup_time.format("%Y-%m-%d %H:%M").to_string()))
+                        ]),
+                        None => tr("no-backup"),
+                    }).into(),
+                    match self.latest_backup_time {
+                        Some(_) => button(text(tr("restore-backup-button")))
+                            .on_press(User(UserMessage::RestoreAdvancedKeyBackup)),
+                        None => button(text(tr("restore-backup-button"))),
+                    }.into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            )
+            .push(checkbox(
+                tr("verbose-logging-checkbox"),
+                self.config.verbose_logging,
+                |verbose_logging| User(UserMessage::ChangeVerboseLogging(verbose_logging))
+            ))
+            .push(checkbox(
+                tr("advanced-mode-checkbox"),
+                self.config.advanced_mode,
+                |advanced_mode| User(UserMessage::ChangeAdvancedMode(advanced_mode))
+            ))
+            .push(match self.confirmed_up_to_date {
+                true => text(tr("up-to-date")),
+                false => text(""),
+            })
+            .into()
+    }
+
+    fn stop_hiding_file_extensions_button(&self) -> Element<Message> {
+        if self.file_extensions_setting_source.is_group_policy() {
+            return Column::new()
+                .spacing(5)
+                .align_items(Alignment::Center)
+                .push(button(text(tr("stop-hiding-button"))))
+                .push(text(tr("managed-by-organization")).size(14).horizontal_alignment(Horizontal::Center))
+                .into();
+        }
+
+        let stop_hiding_button: Button<Message> = match self.can_turn_off_file_extension_hiding() {
+            true => button(text(tr("stop-hiding-button"))).on_press(User(UserMessage::RequestFix)),
+            false => button(text(tr("stop-hiding-button")))
+        };
+        // restarting Windows Explorer closes its windows, which shouldn't come as a surprise
+        let explanation: String = match self.explorer_restart_policy() {
+            ExplorerRestartPolicy::RestartIfNeeded => tr("stop-hiding-tooltip"),
+            ExplorerRestartPolicy::AlwaysRestart => tr("stop-hiding-restart-tooltip"),
+            ExplorerRestartPolicy::Defer => tr("stop-hiding-deferred-restart-tooltip"),
+        };
+        self.with_tooltip(stop_hiding_button, explanation)
+    }
+
+    // Shows what a control does while the mouse rests on it
+    fn with_tooltip<'a>(&self, content: impl Into<Element<'a, Message>>, explanation: String) -> Element<'a, Message> {
+        tooltip(content, explanation, Position::Bottom)
+            .size(14)
+            .gap(5)
+            .padding(10)
+            .style(theme::Container::Box)
+            .into()
+    }
+
+    // Changes go ahead even when the backup fails
+    fn back_up_before_change(&mut self, scopes: &[SettingScope]) {
+        advanced_backup::back_up_before_change(scopes);
+        self.latest_backup_time = latest_backup_time();
+    }
+
+    fn can_turn_off_file_extension_hiding(&self) -> bool {
+        self.file_extensions_hidden && !self.file_extensions_setting_source.is_group_policy()
+    }
+
+    fn explorer_restart_policy(&self) -> ExplorerRestartPolicy {
+        match (self.config.defer_explorer_restart, self.config.always_restart_explorer) {
+            (true, _) => ExplorerRestartPolicy::Defer,
+            (false, true) => ExplorerRestartPolicy::AlwaysRestart,
+            (false, false) => ExplorerRestartPolicy::RestartIfNeeded,
+        }
+    }
+
+    // Whether Windows Explorer will restart or only may, so the user knows what they're agreeing to
+    fn fix_confirmation_message(&self) -> String {
+        match self.explorer_restart_policy() {
+            ExplorerRestartPolicy::AlwaysRestart => tr("fix-confirmation-restart-message"),
+            _ => tr("fix-confirmation-message"),
+        }
+    }
+
+    fn record_fix_outcome(&mut self, fix_outcome: FixOutcome, remediation: HistoryEventKind) {
+        if fix_outcome != FixOutcome::AlreadyVisible {
+            self.record_history_event(remediation);
+        }
+        self.note_explorer_state_after(fix_outcome);
+    }
+
+    // Offers a restart wherever Windows Explorer may still show the old setting
+    fn note_explorer_state_after(&mut self, fix_outcome: FixOutcome) {
+        match fix_outcome {
+            FixOutcome::AlreadyVisible => {},
+            FixOutcome::Applied => self.set_explorer_restart_pending(false),
+            FixOutcome::Refreshed => {
+                self.set_explorer_restart_pending(true);
+                self.explorer_only_refreshed = true;
+            },
+            FixOutcome::RestartPending => self.set_explorer_restart_pending(true),
+            FixOutcome::RestartRateLimited => {
+                self.set_explorer_restart_pending(true);
+                self.setting_keeps_changing = true;
+            },
+        }
+    }
+
+    // Starts this program at sign-in the way the user chose, and takes away the other way so it can't start twice
+    fn register_startup(&self) -> Result<()> {
+        match self.config.startup_method {
+            StartupMethod::RunKey => {
+                windows_ops::run_this_program_at_startup(&WinregRegistry)?;
+                startup_task::unregister()?;
+            },
+            StartupMethod::ScheduledTask => {
+                startup_task::register(&std::env::current_exe()?, self.config.startup_task_elevated)?;
+                windows_ops::dont_run_this_program_at_startup(&WinregRegistry)?;
+            },
+        }
+        Ok(())
+    }
+
+    // Switches over to the startup method the user just chose, if this program starts at sign-in at all
+    fn reregister_startup(&mut self) -> Command<Message> {
+        match self.run_at_startup {
+            true => self.handle_message(User(UserMessage::RunAtStartup)),
+            false => Command::none(),
+        }
+    }
+
+    fn set_explorer_restart_pending(&mut self, explorer_restart_pending: bool) {
+        self.explorer_restart_pending = explorer_restart_pending;
+        self.explorer_only_refreshed = false;
+        if !explorer_restart_pending {
+            self.setting_keeps_changing = false;
+        }
+        tray::set_explorer_restart_pending(explorer_restart_pending);
+    }
+
+    fn view_history(&self) -> Element<Message> {
+        // the audit log behind the export is kept apart from the history, so it can be exported either way
+        let mut export_row: Vec<Element<Message>> = vec![
+            button(text(tr("export-events-button"))).on_press(User(UserMessage::ExportEvents)).into(),
+        ];
+        if let Some(export_summary) = &self.export_summary {
+            export_row.push(text(export_summary).size(14).into());
+        }
+        let export_row = self.directional_row(export_row).spacing(10).align_items(Alignment::Center);
+
+        if self.history.is_empty() {
+            return Column::new().spacing(10).push(export_row).push(text(tr("history-empty"))).into();
+        }
+
+        let (entry_alignment, text_alignment): (Alignment, Horizontal) = match self.layout_direction {
+            LayoutDirection::LeftToRight => (Alignment::Start, Horizontal::Left),
+            LayoutDirection::RightToLeft => (Alignment::End, Horizontal::Right),
+        };
+
+        let entries: Column<Message> = self.history.newest_first()
+            .fold(Column::new().spacing(5).width(Length::Fill).align_items(entry_alignment), |entries, event| {
+                let timestamp: String = event.timestamp.format("%Y-%m-%d %H:%M:%S").to_string();
+                let entry: String = match self.layout_direction {
+                    LayoutDirection::LeftToRight => format!("{}  {}", timestamp, event.kind.description()),
+                    LayoutDirection::RightToLeft => format!("{}  {}", event.kind.description(), timestamp),
+                };
+                entries.push(text(entry).horizontal_alignment(text_alignment))
+            });
+
+        Column::new()
+            .spacing(10)
+            .push(export_row)
+            .push(scrollable(entries).height(Length::Fill))
+            .into()
+    }
+
+    // Compares a download folder with extensions hidden against the same folder with them visible
+    fn view_spoofing_demo(&self) -> Element<Message> {
+        let file_list = |extensions_hidden: bool, heading: String| -> Element<Message> {
+            DEMO_FILES.iter()
+                .fold(Column::new().spacing(5).push(text(heading).size(18)), |files, file| {
+                    let name: String = String::from(name_as_shown_by_explorer(file.real_name, extensions_hidden));
+                    // only give the game away in the column where extensions are visible
+                    let name_text: Text = match !extensions_hidden && file.is_dangerous {
+                        true => text(name).style(DANGER_COLOR),
+                        false => text(name),
+                    };
+                    files.push(name_text)
+                })
+                .into()
+        };
+
+        Column::new()
+            .spacing(15)
+            .align_items(Alignment::Center)
+            .push(text(tr("demo-explanation")).horizontal_alignment(Horizontal::Center))
+            .push(
+                self.directional_row(vec![
+                    file_list(true, tr("demo-hidden-heading")),
+                    file_list(false, tr("demo-visible-heading")),
+                ]).spacing(40)
+            )
+            .into()
+    }
+
+    // Where the file dropped onto the window came from, going by its mark of the web
+    fn view_mark_of_the_web(&self) -> Element<Message> {
+        let mut content: Column<Message> = Column::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(text(tr("motw-explanation")).horizontal_alignment(Horizontal::Center));
+
+        if let Some(inspection) = &self.inspection {
+            content = content.push(text(inspection.path.display().to_string()).size(14));
+            for finding in inspection.findings() {
+                content = content.push(text(finding).size(14).horizontal_alignment(Horizontal::Center));
+            }
+            if inspection.is_dangerous() {
+                content = content.push(
+                    text(tr("motw-executable-warning")).style(DANGER_COLOR).horizontal_alignment(Horizontal::Center)
+                );
+            }
+        }
+        content.into()
+    }
+
+    // What double-clicking each kind of script file does, with the ones which run it called out
+    fn view_script_associations(&self) -> Element<Message> {
+        let associations: Column<Message> = self.script_associations.iter()
+            .fold(Column::new().spacing(5).width(Length::Fill), |associations, association| {
+                let opens_with: String = match &association.prog_id {
+                    Some(prog_id) => String::from(prog_id),
+                    None => tr("association-none"),
+                };
+                let verdict: Text = match association.launches_script_host() {
+                    true => text(tr("association-runs-script")).style(DANGER_COLOR),
+                    false => text(tr("association-safe")),
+                };
+                associations.push(
+                    self.directional_row(vec![
+                        text(&association.extension).width(Length::Units(50)).into(),
+                        text(opens_with).size(14).width(Length::Fill).into(),
+                        verdict.size(14).into(),
+                    ]).spacing(10).align_items(Alignment::Center)
+                )
+            });
+
+        let check_id: String = String::from(SCRIPT_ASSOCIATIONS_CHECK_ID);
+        let open_in_notepad_button = button(text(tr("open-scripts-in-notepad-button")));
+        let open_in_notepad_button = match self.script_associations.iter().any(Association::launches_script_host) {
+            true => open_in_notepad_button.on_press(User(UserMessage::RemediateCheck(check_id.clone()))),
+            false => open_in_notepad_button,
+        };
+        let undo_button = button(text(tr("undo-check-button")));
+        let undo_button = match associations::has_backup() {
+            true => undo_button.on_press(User(UserMessage::UndoCheck(check_id))),
+            false => undo_button,
+        };
+
+        Column::new()
+            .spacing(15)
+            .align_items(Alignment::Center)
+            .push(text(tr("script-associations-explanation")).horizontal_alignment(Horizontal::Center))
+            .push(scrollable(associations).height(Length::Fill))
+            .push(
+                self.directional_row(vec![
+                    open_in_notepad_button.into(),
+                    undo_button.into(),
+                ]).spacing(10).align_items(Alignment::Center)
+            )
+            .into()
+    }
+
+    // Everything needed to verify what this program touches, or to file a useful bug report
+    fn view_about(&self) -> Element<Message> {
+        let (startup_key, startup_value) = STARTUP_REGISTRY_VALUE;
+        let registry_values: Column<Message> = self.checks.iter()
+            .flat_map(|check| check.managed_values())
+            .chain(std::iter::once(format!("HKEY_CURRENT_USER\\{startup_key}\\{startup_value}")))
+            .fold(Column::new().spacing(2), |values, location| values.push(text(location).size(14)));
+
+        Column::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(text(format!("{} {}", APPLICATION_DISPLAY_NAME, build_info::VERSION)).size(22))
+            .push(text(tr_args("about-built", &[("timestamp", FluentValue::from(build_info::build_timestamp()))])))
+            .push(text(tr("about-registry-heading")))
+            .push(registry_values)
+            .push(text(tr_args("about-license", &[("license", FluentValue::from(build_info::LICENSE))])))
+            .push(text(build_info::REPOSITORY))
+            .into()
+    }
+
+    fn panel_toggle_button(&self, panel: Panel, show_label_id: &str, hide_label_id: &str) -> Button<Message> {
+        match self.active_view == (ActiveView::Main { expanded_panel: Some(panel) }) {
+            true => button(text(tr(hide_label_id))).on_press(User(UserMessage::CollapsePanel)),
+            false => button(text(tr(show_label_id))).on_press(User(UserMessage::ExpandPanel(panel))),
+        }
+    }
+
+    // Lays out children in reading order, which runs from the right for right-to-left languages
+    fn directional_row<'a>(&self, mut children: Vec<Element<'a, Message>>) -> Row<'a, Message> {
+        if self.layout_direction == LayoutDirection::RightToLeft {
+            children.reverse();
+        }
+        Row::with_children(children)
+    }
+
+    // Grabs the user's attention, unless quiet hours say the notification has to wait
+    fn notify_user(&mut self, notification: Notification) -> Command<Message> {
+        // whatever still needs attention once the pause is over is brought up then
+        if self.config.protection_paused(Local::now()) {
+            trace!("Not notifying about {:?} while protection is paused", notification);
+            return Command::none();
+        }
+        match self.notification_scheduler.submit(notification, Local::now()) {
+            true => self.get_commands_which_notify_user_unless_busy(),
+            false => Command::none()
+        }
+    }
+
+    // Being yanked out of a fullscreen game or presentation is worse than a delayed warning,
+    // so flag the warning in the tray instead when the user is busy
+    fn get_commands_which_notify_user_unless_busy(&mut self) -> Command<Message> {
+        if self.session_locked {
+            trace!("Not grabbing attention while the workstation is locked, waiting for the unlock");
+            tray::set_warning_indicator(true);
+            self.attention_pending_unlock = true;
+            return Command::none();
+        }
+        match windows_ops::get_do_not_disturb_reason() {
+            Ok(Some(reason)) => {
+                trace!("Not grabbing attention because of {:?}, using the tray icon instead", reason);
+                tray::set_warning_indicator(true);
+                return Command::none();
+            },
+            Ok(None) => {},
+            Err(error) => warn!("Failed to check whether the user is busy: {:?}", error),
+        }
+
+        if let Err(error) = sound::play(self.config.notification_sound) {
+            warn!("Failed to play the notification sound: {:?}", error);
+        }
+        self.window_hidden = false;
+        // only hidden file extensions are urgent enough to keep the window in the way
+        if !(self.config.keep_warning_on_top && self.file_extensions_hidden) {
+            return get_commands_which_notify_user();
+        }
+        self.warning_kept_on_top = true;
+        Command::batch(vec![get_commands_which_notify_user(), window::change_always_on_top(true)])
+    }
+
+    // Rounded up, so the countdown doesn't show 0 while some of the pause is left
+    fn pause_minutes_left(&self) -> Option<i64> {
+        let paused_until: DateTime<Local> = self.config.paused_until?;
+        let seconds_left: i64 = (paused_until - Local::now()).num_seconds();
+        match seconds_left > 0 {
+            true => Some((seconds_left + 59) / 60),
+            false => None,
+        }
+    }
+
+    fn resume_protection(&mut self) -> Command<Message> {
+        self.config.paused_until = None;
+        self.save_config();
+        tray::set_pause_remaining(None);
+        match self.file_extensions_hidden {
+            true => self.notify_user(Notification::FileExtensionsHidden),
+            false => Command::none(),
+        }
+    }
+
+    // While file extensions are hidden and nobody has seen the window since, the tray icon is all there is to see.
+    // A paused protection shouldn't call attention to anything, so the pulse starts once the pause is over.
+    fn tray_icon_pulses(&self) -> bool {
+        self.file_extensions_hidden && self.window_hidden && !self.warning_acknowledged && tray::exists()
+            && !self.config.protection_paused(Local::now())
+    }
+
+    fn stop_keeping_warning_on_top(&mut self) -> Command<Message> {
+        if !self.warning_kept_on_top {
+            return Command::none();
+        }
+        self.warning_kept_on_top = false;
+        window::change_always_on_top(false)
+    }
+
+    // The window has a fixed size per view, so it has to grow along with the scale of its contents
+    fn resize_window_to_fit(&self) -> Command<Message> {
+        let base_size: (u32, u32) = match self.active_view {
+            ActiveView::Main { expanded_panel: Some(_) } => WINDOW_SIZE_WITH_EXPANDED_PANEL,
+            ActiveView::Main { expanded_panel: None } => DEFAULT_WINDOW_SIZE,
+            ActiveView::Settings { .. } => SETTINGS_WINDOW_SIZE,
+        };
+        let (width, height) = scale_window_size(base_size, self.scale_factor());
+        window::resize(width, height)
+    }
+
+    // A short report to paste into a support chat. Like crash reports, it's in English for whoever reads it.
+    fn plain_text_status(&self) -> String {
+        let yes_no = |value: bool| if value { "yes" } else { "no" };
+        let startup: String = match self.run_at_startup {
+            true => format!("yes, through {:?}", self.config.startup_method),
+            false => String::from("no"),
+        };
+        let failing_checks: String = match self.failing_checks.is_empty() {
+            true => String::from("none"),
+            false => self.failing_checks.join(", "),
+        };
+        let mut status: String = format!(
+            "{} {} (built {})\nFile extensions hidden: {} (from {:?})\nHideFileExt: current user {:?}, all users {:?}\n\
+            Starts at sign-in: {}\nRunning as administrator: {}\nExplorer restart pending: {}\nFailing checks: {}\n\
+            Monitoring degraded: {}\n\nLast events:\n",
+            APPLICATION_DISPLAY_NAME,
+            build_info::VERSION,
+            build_info::build_timestamp(),
+            yes_no(self.file_extensions_hidden),
+            self.file_extensions_setting_source,
+            self.hive_values.current_user,
+            self.hive_values.all_users,
+            startup,
+            yes_no(self.running_elevated),
+            yes_no(self.explorer_restart_pending),
+            failing_checks,
+            yes_no(self.monitoring_degraded),
+        );
+        for event in self.history.newest_first().take(EVENTS_IN_STATUS_REPORT) {
+            status.push_str(&format!("  {}  {}\n", event.timestamp.format("%Y-%m-%d %H:%M:%S"), event.kind.description()));
+        }
+        if self.history.is_empty() {
+            status.push_str("  none\n");
+        }
+        status
+    }
+
+    // What a crash report should say about the app. History is left out since it can be long.
+    fn state_snapshot(&self) -> String {
+        format!(
+            "file_extensions_hidden: {} (from {:?})\nrun_at_startup: {}\nexplorer_restart_pending: {}\n\
+            active_view: {:?}\nerror_banner: {:?}\ndeferred_summary: {:?}\nconfig: {:?}",
+            self.file_extensions_hidden,
+            self.file_extensions_setting_source,
+            self.run_at_startup,
+            self.explorer_restart_pending,
+            self.active_view,
+            self.error_banner,
+            self.deferred_summary,
+            self.config,
+        )
+    }
+
+    // What other processes are told when they send a command over the named pipe
+    fn status_report(&self) -> StatusReport {
+        StatusReport {
+            file_extensions_hidden: self.file_extensions_hidden,
+            setting_source: format!("{:?}", self.file_extensions_setting_source),
+            explorer_restart_pending: self.explorer_restart_pending,
+            failing_checks: self.failing_checks.clone(),
+            error: self.error_banner.as_ref().map(|error_banner| error_banner.details.clone()),
+        }
+    }
+
+    // Re-reads the registry state which the window displays
+    fn refresh_state(&mut self) -> Result<()> {
+        let effective_state: EffectiveState = effective_state::file_extensions_hidden(&WinregRegistry)?;
+        self.file_extensions_hidden = effective_state.file_extensions_hidden;
+        self.file_extensions_setting_source = effective_state.source;
+        self.refresh_hive_values(false);
+        self.run_at_startup = windows_ops::will_app_run_at_startup(&WinregRegistry)? || is_startup_task_registered();
+
+        let mut failing_checks: Vec<String> = Vec::new();
+        for check in self.other_checks() {
+            if check.evaluate()? == CheckStatus::Warn {
+                failing_checks.push(String::from(check.id()));
+            }
+        }
+        self.failing_checks = failing_checks;
+        Ok(())
+    }
+
+    fn refresh_script_associations(&mut self) {
+        match associations::audit_script_associations() {
+            Ok(script_associations) => self.script_associations = script_associations,
+            Err(error) => self.report_error(FailedOperation::AuditScriptAssociations, error),
+        }
+    }
+
+    fn refresh_script_associations_if_shown(&mut self) {
+        if self.active_view == (ActiveView::Main { expanded_panel: Some(Panel::ScriptAssociations) }) {
+            self.refresh_script_associations();
+        }
+    }
+
+    // Asks the Security event log which program just hid file extensions, falling back to what event tracing saw.
+    // Only an elevated instance can read the log, so anything going wrong only leaves the change unattributed.
+    fn attribute_change(&self) -> Option<ChangeAttribution> {
+        let audited_change: Option<ChangeAttribution> = match self.config.attribute_changes {
+            true => registry_audit::find_latest_change().unwrap_or_else(|error| {
+                warn!("Failed to find out which program hid file extensions: {:?}", error);
+                None
+            }),
+            false => None,
+        };
+        audited_change.or_else(|| self.traced_change.clone())
+    }
+
+    fn file_extensions_hidden_event(&self) -> HistoryEventKind {
+        match &self.change_attribution {
+            Some(change_attribution) => HistoryEventKind::FileExtensionsHiddenBy(change_attribution.process_name()),
+            None => HistoryEventKind::FileExtensionsHidden,
+        }
+    }
+
+    // Re-reads HideFileExt in each hive and adds whatever changed to the audit log
+    fn refresh_hive_values(&mut self, remediated_by_app: bool) {
+        let hive_values: HiveValues = read_hive_values();
+        metrics::record_check();
+        let changes = [
+            (SettingScope::CurrentUser, self.hive_values.current_user, hive_values.current_user),
+            (SettingScope::AllUsers, self.hive_values.all_users, hive_values.all_users),
+        ];
+        for (scope, old_value, new_value) in changes {
+            if old_value == new_value {
+                continue;
+            }
+            if remediated_by_app && new_value == Some(0) {
+                metrics::record_remediation();
+            }
+            let entry: AuditEntry = AuditEntry::new(scope, old_value, new_value, remediated_by_app);
+            if let Err(error) = audit_log::record(&entry) {
+                warn!("Failed to add the change to HideFileExt for {:?} to the audit log: {:?}", scope, error);
+            }
+            event_sinks::forward_in_background(event_sinks::configured_sinks(&self.config), entry);
+        }
+        if self.hive_values != hive_values {
+            self.protection_stats = load_protection_stats();
+        }
+        self.hive_values = hive_values;
+    }
+
+    // Shows a failure in the window along with a way to retry it
+    fn report_error(&mut self, operation: FailedOperation, error: anyhow::Error) {
+        let details: String = format!("{:#}", error);
+        warn!("{:?} failed: {}", operation, details);
+        let elevated_retry: Option<PendingAction> = match elevation::is_access_denied(&error) && !self.running_elevated {
+            true => operation.pending_action(),
+            false => None,
+        };
+        self.error_banner = Some(ErrorBanner { operation, details, elevated_retry });
+    }
+
+    // Failing to save preferences shouldn't interrupt the user, but they will be lost on restart
+    fn save_config(&self) {
+        if let Err(error) = config::save(&self.config) {
+            warn!("Failed to save the config: {:?}", error);
+        }
+    }
+
+    // Re-reads every watched setting and announces whichever of them started or stopped hiding things.
+    // Used to catch up on any changes the registry watcher missed.
+    fn reconcile_with_registry(&mut self) -> Command<Message> {
+        self.reconcile_checks(|_| true)
+    }
+
+    // Like `reconcile_with_registry()`, but only for the checks which read one of the values which changed
+    fn reconcile_changed_values(&mut self, changed_key: &WatchedKey, changed_values: &[String]) -> Command<Message> {
+        let affected_check_ids: Vec<String> = self.checks.iter()
+            .filter(|check| checks::is_affected_by(check.as_ref(), changed_key, changed_values))
+            .map(|check| String::from(check.id()))
+            .collect();
+        self.reconcile_checks(|check_id| {
+            affected_check_ids.iter().any(|affected_check_id| affected_check_id == check_id)
+        })
+    }
+
+    fn reconcile_checks(&mut self, is_affected: impl Fn(&str) -> bool) -> Command<Message> {
+        let mut commands: Vec<Command<Message>> = Vec::new();
+
+        if is_affected(FILE_EXTENSIONS_RULE_ID) {
+            commands.push(self.reconcile_file_extensions());
+        }
+        let other_checks: Vec<Arc<dyn SecurityCheck>> = self.other_checks()
+            .filter(|check| is_affected(check.id()))
+            .cloned()
+            .collect();
+        for check in other_checks {
+            let check_id: String = String::from(check.id());
+            let was_failing: bool = self.failing_checks.contains(&check_id);
+            match check.evaluate() {
+                Ok(CheckStatus::Warn) if !was_failing =>
+                    commands.push(self.handle_message(Backend(BackendMessage::CheckStartedFailing(check_id)))),
+                Ok(CheckStatus::Pass) if was_failing =>
+                    commands.push(self.handle_message(Backend(BackendMessage::CheckStartedPassing(check_id)))),
+                Ok(_) => {},
+                Err(error) => self.report_error(FailedOperation::CheckState, error),
+            }
+        }
+        Command::batch(commands)
+    }
+
+    // Only a change between hidden and visible is reported, so it isn't reported again while the state stays put
+    fn reconcile_file_extensions(&mut self) -> Command<Message> {
+        return match effective_state::file_extensions_hidden(&WinregRegistry) {
+            Ok(EffectiveState { file_extensions_hidden: true, source }) if !self.file_extensions_hidden =>
+                self.handle_message(Backend(BackendMessage::FileExtensionsAreNowHidden(source))),
+            Ok(EffectiveState { file_extensions_hidden: false, source }) if self.file_extensions_hidden =>
+                self.handle_message(Backend(BackendMessage::FileExtensionsAreNoLongerHidden(source))),
+            // e.g. a policy now enforces what the user had already chosen
+            Ok(EffectiveState { source, .. }) => {
+                self.file_extensions_setting_source = source;
+                self.refresh_hive_values(false);
+                Command::none()
+            },
+            Err(error) => {
+                self.report_error(FailedOperation::CheckState, error);
+                Command::none()
+            },
+        };
+    }
+
+    // Every check besides HideFileExt's, which has its own status and controls
+    fn other_checks(&self) -> impl Iterator<Item = &Arc<dyn SecurityCheck>> {
+        self.checks.iter().filter(|check| check.id() != FILE_EXTENSIONS_RULE_ID)
+    }
+
+    fn status_of(&self, check: &dyn SecurityCheck) -> CheckStatus {
+        match self.failing_checks.iter().any(|failing_check_id| failing_check_id == check.id()) {
+            true => CheckStatus::Warn,
+            false => CheckStatus::Pass,
+        }
+    }
+
+    fn file_extensions_status(&self) -> CheckStatus {
+        match self.file_extensions_hidden {
+            true => CheckStatus::Warn,
+            false => CheckStatus::Pass,
+        }
+    }
+
+    // What every check last found, HideFileExt's included, for scoring
+    fn check_results(&self) -> Vec<(Severity, CheckStatus)> {
+        let file_extensions_severity: Severity = self.find_check(FILE_EXTENSIONS_RULE_ID)
+            .map(|check| check.severity())
+            .unwrap_or(Severity::Critical);
+        std::iter::once((file_extensions_severity, self.file_extensions_status()))
+            .chain(self.other_checks().map(|check| (check.severity(), self.status_of(check.as_ref()))))
+            .collect()
+    }
+
+    // Checks which are turned off can still be remediated from the panels which show what they look at
+    fn remediate_check(&mut self, check_id: String) {
+        let Some(check) = self.find_any_check(&check_id) else {
+            trace!("Not remediating {}, which no longer exists", check_id);
+            return;
+        };
+        self.back_up_before_change(&[SettingScope::CurrentUser]);
+        match check.remediate(self.explorer_restart_policy()) {
+            Ok(fix_outcome) => {
+                // the change notification which follows has already been accounted for
+                self.failing_checks.retain(|failing_check_id| *failing_check_id != check_id);
+                self.record_fix_outcome(fix_outcome, HistoryEventKind::CheckRemediatedByUser(check_id));
+            },
+            Err(error) => self.report_error(
+                FailedOperation::RemediateCheck { check_id, check_name: check.name() }, error
+            ),
+        }
+        self.refresh_script_associations_if_shown();
+    }
+
+    fn find_check(&self, check_id: &str) -> Option<Arc<dyn SecurityCheck>> {
+        self.checks.iter().find(|check| check.id() == check_id).cloned()
+    }
+
+    // Like `find_check()`, but also finds checks which are turned off
+    fn find_any_check(&self, check_id: &str) -> Option<Arc<dyn SecurityCheck>> {
+        checks::all_checks(&self.config).into_iter().find(|check| check.id() == check_id)
+    }
+
+    // Failing to record history shouldn't stop the user from being warned
+    fn record_history_event(&mut self, kind: HistoryEventKind) {
+        if let Err(error) = self.history.record(kind.clone()) {
+            warn!("Failed to record history event {:?}: {:?}", kind, error);
+        }
+    }
+}
+
+fn latest_backup_time() -> Option<DateTime<Local>> {
+    let latest_backup: Option<AdvancedKeyBackup> = advanced_backup::latest().unwrap_or_else(|error| {
+        warn!("Failed to look for backups of the Advanced key: {:?}", error);
+        None
+    });
+    latest_backup.map(|backup| backup.timestamp)
+}
+
+// Task Scheduler's service can be turned off, which only means this program can't be starting through it
+fn is_startup_task_registered() -> bool {
+    startup_task::is_registered().unwrap_or_else(|error| {
+        warn!("Failed to look for the scheduled task which starts this program: {:?}", error);
+        false
+    })
+}
+
+// Only shown for information, so a log which can't be read just shows nothing
+fn load_protection_stats() -> ProtectionStats {
+    match audit_log::load() {
+        Ok(entries) => ProtectionStats::from_entries(&entries),
+        Err(error) => {
+            warn!("Failed to read the audit log: {:?}", error);
+            ProtectionStats::default()
+        }
+    }
+}
+
+// Only shown for information, so failing to read a hive isn't worth interrupting the user over
+fn read_hive_values() -> HiveValues {
+    let read = |scope: SettingScope| {
+        windows_ops::get_hide_file_ext_value(&WinregRegistry, scope).unwrap_or_else(|error| {
+            warn!("Failed to read HideFileExt for {:?}: {:?}", scope, error);
+            None
+        })
+    };
+    HiveValues {
+        current_user: read(SettingScope::CurrentUser),
+        all_users: read(SettingScope::AllUsers),
+    }
+}
+
+// One listener per key the checks watch. Checks which share a key share its listener, which the watchdog
+// restarts if it dies or gets stuck. Changes to values under the key which no check reads, e.g. Windows Explorer's
+// other options, are ignored.
+fn get_listener_for_registry_changes(watched_key: WatchedKey) -> Subscription<Message> {
+    worker::supervise(
+        (std::any::TypeId::of::<WatchedKey>(), watched_key.clone()),
+        "registry watcher",
+        move |sender: WorkerSender<Message>| watch_registry_key(&watched_key, sender),
+    )
+}
+
+fn watch_registry_key(watched_key: &WatchedKey, sender: WorkerSender<Message>) {
+    let mut state: Option<(RegistryWatcher, ValueSnapshot)> = None;
+    loop {
+        sender.heartbeat();
+        let (watcher, earlier_snapshot): (RegistryWatcher, ValueSnapshot) = match state.take() {
+            Some(state) => state,
+            None => {
+                trace!("Waiting for a change in {:?}", watched_key);
+                // the snapshot is taken after the watcher is armed, so no change can slip in between
+                match watch_and_snapshot(watched_key) {
+                    Ok(state) => state,
+                    Err(error) => {
+                        // don't spin on an error which won't go away by itself
+                        std::thread::sleep(WATCH_RETRY_DELAY);
+                        match sender.send(Message::Error(FailedOperation::WatchForChanges, format!("{:#}", error))) {
+                            true => continue,
+                            false => return,
+                        }
+                    },
+                }
+            },
+        };
+
+        // the wait times out now and then, so the watchdog can tell a quiet key from a stuck thread
+        let message: Option<Message> = match watcher.wait_for_change_within(worker::HEARTBEAT_INTERVAL) {
+            Ok(None) => {
+                state = Some((watcher, earlier_snapshot));
+                match sender.is_closed() {
+                    true => return,
+                    false => None,
+                }
+            },
+            Ok(Some(WatchEvent::Stopped)) => return,
+            // the UI checks the registry itself after whatever asked for the rearm
+            Ok(Some(WatchEvent::RearmRequested)) => None,
+            Ok(Some(WatchEvent::Changed)) => match watched_key.snapshot(&WinregRegistry) {
+                Ok(snapshot) => {
+                    let changed_values: Vec<String> = snapshot.changed_since(&earlier_snapshot);
+                    state = Some((watcher, snapshot));
+                    match changed_values.is_empty() {
+                        true => {
+                            trace!("Ignoring a change in {:?} to values no check reads", watched_key);
+                            None
+                        },
+                        false => {
+                            trace!("Received a change in {:?} to {:?}", watched_key, changed_values);
+                            Some(Backend(BackendMessage::WatchedValuesChanged(watched_key.clone(), changed_values)))
+                        },
+                    }
+                },
+                Err(error) => Some(Message::Error(FailedOperation::WatchForChanges, format!("{:#}", error))),
+            },
+            Err(error) => {
+                std::thread::sleep(WATCH_RETRY_DELAY);
+                Some(Message::Error(FailedOperation::WatchForChanges, format!("{:#}", error)))
+            }
+        };
+        if message.is_some_and(|message| !sender.send(message)) {
+            return;
+        }
+    }
+}
+
+fn watch_and_snapshot(watched_key: &WatchedKey) -> Result<(RegistryWatcher, ValueSnapshot)> {
+    let watcher: RegistryWatcher = watched_key.watch(&WinregRegistry)?;
+    Ok((watcher, watched_key.snapshot(&WinregRegistry)?))
+}
+
+fn get_listener_for_ui_messages() -> Subscription<Message> {
+    subscription::events_with(|event, status|
+        match event {
+            iced::Event::Window(window_event) => {
+                match window_event {
+                    // these are typical values when user clicks on the minimize button
+                    Event::Resized {width: 0, height: 0} => {
+                        Some(Ui(UiMessage::MinimizeToTray))
+                    },
+                    Event::FileDropped(path) => Some(User(UserMessage::InspectFile(path))),
+                    _ => None
+                }
+            },
+            // keys a text field already acted on, e.g. typing, are left alone
+            iced::Event::Keyboard(KeyboardEvent::KeyPressed { key_code, modifiers })
+                if status == iced::event::Status::Ignored => shortcut_for(key_code, modifiers),
+            _ => None
+        }
+    )
+}
+
+fn shortcut_for(key_code: KeyCode, modifiers: Modifiers) -> Option<Message> {
+    match (key_code, modifiers.control(), modifiers.shift()) {
+        (KeyCode::Tab, false, false) => Some(Ui(UiMessage::FocusNext)),
+        (KeyCode::Tab, false, true) => Some(Ui(UiMessage::FocusPrevious)),
+        (KeyCode::Enter | KeyCode::NumpadEnter, false, _) => Some(Ui(UiMessage::Confirm)),
+        (KeyCode::Escape, false, _) => Some(Ui(UiMessage::GoBack)),
+        (KeyCode::F, true, false) => Some(Ui(UiMessage::FixFromKeyboard)),
+        (KeyCode::M, true, false) => Some(Ui(UiMessage::MinimizeFromKeyboard)),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "tray")]
+fn get_listener_for_window_resize_messages() -> Subscription<Message> {
+    worker::supervise(std::any::TypeId::of::<UiMessage>(), "tray events", |sender: WorkerSender<Message>| loop {
+        sender.heartbeat();
+        let tray_event: TrayEvent = match TrayEvent::receiver().recv_timeout(worker::HEARTBEAT_INTERVAL) {
+            Ok(tray_event) => tray_event,
+            Err(error) if error.is_timeout() => match sender.is_closed() {
+                true => return,
+                false => continue,
+            },
+            // only once tray-icon has shut down, and the watchdog starts listening again
+            Err(error) => {
+                warn!("Stopped listening for tray events: {:?}", error);
+                return;
+            },
+        };
+        // right clicks open the context menu instead
+        if matches!(tray_event.event, ClickEvent::Left | ClickEvent::Double)
+            && !sender.send(Ui(UiMessage::RestoreFromTray)) {
+            return;
+        }
+    })
+}
+
+#[cfg(feature = "tray")]
+fn get_listener_for_tray_menu_events() -> Subscription<Message> {
+    worker::supervise(std::any::TypeId::of::<MenuEvent>(), "tray menu events", |sender: WorkerSender<Message>| loop {
+        sender.heartbeat();
+        let menu_event: MenuEvent = match MenuEvent::receiver().recv_timeout(worker::HEARTBEAT_INTERVAL) {
+            Ok(menu_event) => menu_event,
+            Err(error) if error.is_timeout() => match sender.is_closed() {
+                true => return,
+                false => continue,
+            },
+            Err(error) => {
+                warn!("Stopped listening for tray menu events: {:?}", error);
+                return;
+            },
+        };
+        let message: Message = match tray::menu_action_for(menu_event.id) {
+            Some(TrayMenuAction::Open) => Ui(UiMessage::RestoreFromTray),
+            Some(TrayMenuAction::About) => Ui(UiMessage::OpenPanelFromTray(Panel::About)),
+            Some(TrayMenuAction::RestartExplorer) => User(UserMessage::RestartExplorerNow),
+            Some(TrayMenuAction::ToggleVerboseLogging) => User(UserMessage::ToggleVerboseLogging),
+            None => {
+                trace!("Ignoring event for unknown menu item {}", menu_event.id);
+                continue;
+            },
+        };
+        if !sender.send(message) {
+            return;
+        }
+    })
+}
+
+fn get_listener_for_system_theme_changes() -> Subscription<Message> {
+    worker::listen(std::any::TypeId::of::<Theme>(), "theme watcher", |sender: WorkerSender<Message>| {
+        let mut watcher: Option<RegistryWatcher> = None;
+        loop {
+            trace!("Waiting for a change in the theme personalization registry key");
+            let watch_result: Result<(RegistryWatcher, WatchEvent)> = watcher.take()
+                .map_or_else(|| windows_ops::watch_personalize_regkey(&WinregRegistry), Ok)
+                .and_then(|watcher| watcher.wait_for_change().map(|watch_event| (watcher, watch_event)));
+            match watch_result {
+                Ok((_, WatchEvent::Stopped)) => return,
+                Ok((_, WatchEvent::RearmRequested)) => {},
+                Ok((kept_watcher, WatchEvent::Changed)) => {
+                    trace!("Received a change in the theme personalization registry key");
+                    watcher = Some(kept_watcher);
+                },
+                Err(error) => {
+                    // following the system theme isn't worth bothering the user about
+                    warn!("Failed to wait for a change in the theme personalization registry key: {:?}", error);
+                    std::thread::sleep(WATCH_RETRY_DELAY);
+                    continue;
+                }
+            }
+            if !sender.send(Backend(BackendMessage::SystemThemeChanged(detect_system_theme()))) {
+                return;
+            }
+        }
+    })
+}
+
+fn get_listener_for_screen_reader_actions() -> Subscription<Message> {
+    worker::listen(
+        std::any::TypeId::of::<AccessibleControl>(),
+        "screen reader actions",
+        |sender: WorkerSender<Message>| loop {
+            match accessibility::wait_for_action() {
+                Ok(control) => if !sender.send(User(UserMessage::ScreenReaderAction(control))) {
+                    return;
+                },
+                Err(error) => {
+                    warn!("Stopped listening for screen reader actions: {:?}", error);
+                    return;
+                },
+            }
+        }
+    )
+}
+
+fn get_listener_for_resume_from_sleep() -> Subscription<Message> {
+    worker::listen(
+        std::any::TypeId::of::<power::ResumeNotification>(),
+        "resume listener",
+        |sender: WorkerSender<Message>| loop {
+            match power::wait_for_resume() {
+                Ok(_) => if !sender.send(Backend(BackendMessage::ResumedFromSleep)) {
+                    return;
+                },
+                Err(error) => {
+                    warn!("Stopped listening for resume notifications: {:?}", error);
+                    return;
+                },
+            }
+        }
+    )
+}
+
+fn get_listener_for_ipc_requests() -> Subscription<Message> {
+    worker::listen(std::any::TypeId::of::<IpcRequest>(), "ipc listener", |sender: WorkerSender<Message>| loop {
+        match ipc::wait_for_request() {
+            Ok(request) => if !sender.send(Backend(BackendMessage::IpcRequestReceived(request))) {
+                return;
+            },
+            Err(error) => {
+                warn!("Stopped listening for requests over the pipe: {:?}", error);
+                return;
+            },
+        }
+    })
+}
+
+fn get_listener_for_session_changes() -> Subscription<Message> {
+    worker::listen(
+        std::any::TypeId::of::<SessionChange>(),
+        "session change listener",
+        |sender: WorkerSender<Message>| loop {
+            match session::wait_for_session_change() {
+                Ok(change) => if !sender.send(Backend(BackendMessage::SessionChanged(change))) {
+                    return;
+                },
+                Err(error) => {
+                    warn!("Stopped listening for session changes: {:?}", error);
+                    return;
+                },
+            }
+        }
+    )
+}
+
+// Changing the hotkeys in the config file starts a new listener, and the old one releases its hotkeys
+// once it notices the UI stopped listening to it
+fn get_listener_for_hotkeys(hotkeys: Vec<(HotkeyAction, String)>) -> Subscription<Message> {
+    worker::listen(
+        (std::any::TypeId::of::<HotkeyListener>(), hotkeys.clone()),
+        "hotkey listener",
+        move |sender: WorkerSender<Message>| {
+            // the hotkeys which can't be used are reported once, and the rest still work
+            let mut failures: Vec<(String, anyhow::Error)> = Vec::new();
+            let mut valid_hotkeys: Vec<(HotkeyAction, Hotkey)> = Vec::new();
+            for (action, text) in hotkeys {
+                match Hotkey::parse(&text) {
+                    Ok(hotkey) => valid_hotkeys.push((action, hotkey)),
+                    Err(error) => failures.push((text, error)),
+                }
+            }
+            let (listener, registration_failures) = HotkeyListener::start(valid_hotkeys);
+            failures.extend(registration_failures.into_iter()
+                .map(|(hotkey, error)| (String::from(hotkey.text()), error)));
+            for (hotkey, error) in &failures {
+                warn!("Can't use the hotkey {}: {:?}", hotkey, error);
+            }
+            if let Some((hotkey, error)) = failures.into_iter().next() {
+                if !sender.send(Message::Error(FailedOperation::RegisterHotkey(hotkey), format!("{:#}", error))) {
+                    return;
+                }
+            }
+
+            loop {
+                match listener.wait_for_press(worker::CLOSE_CHECK_INTERVAL) {
+                    Ok(Some(action)) => if !sender.send(Backend(BackendMessage::HotkeyPressed(action))) {
+                        return;
+                    },
+                    Ok(None) => if sender.is_closed() {
+                        return;
+                    },
+                    Err(_) => return,
+                }
+            }
+        }
+    )
+}
+
+// A safety net for change notifications which never arrive. Changing the interval starts a new listener.
+fn get_listener_for_polls(poll_interval: Duration) -> Subscription<Message> {
+    worker::listen(
+        (std::any::TypeId::of::<PollInterval>(), poll_interval),
+        "poll timer",
+        move |sender: WorkerSender<Message>| loop {
+            std::thread::sleep(poll_interval);
+            if !sender.send(Backend(BackendMessage::PollTick)) {
+                return;
+            }
+        }
+    )
+}
+
+fn get_listener_for_scans(scan_interval: Duration) -> Subscription<Message> {
+    worker::listen(
+        (std::any::TypeId::of::<ScanInterval>(), scan_interval),
+        "scan timer",
+        move |sender: WorkerSender<Message>| loop {
+            std::thread::sleep(scan_interval);
+            if !sender.send(Backend(BackendMessage::ScanDue)) {
+                return;
+            }
+        }
+    )
+}
+
+// Watches the Downloads folder for programs whose names use Unicode tricks to pass for documents
+fn get_listener_for_disguised_downloads() -> Subscription<Message> {
+    worker::listen(std::any::TypeId::of::<FolderWatcher>(), "downloads watcher", |sender: WorkerSender<Message>| {
+        let mut watcher: Option<FolderWatcher> = None;
+        loop {
+            let watch_result: Result<(FolderWatcher, Vec<PathBuf>)> = watcher.take()
+                .map_or_else(|| folder_watcher::downloads_folder().and_then(FolderWatcher::new), Ok)
+                .and_then(|watcher| watcher.wait_for_new_files().map(|new_files| (watcher, new_files)));
+            let new_files: Vec<PathBuf> = match watch_result {
+                Ok((kept_watcher, new_files)) => {
+                    watcher = Some(kept_watcher);
+                    new_files
+                },
+                Err(error) => {
+                    // the Downloads folder may be missing or on a drive which went away
+                    warn!("Failed to watch the Downloads folder: {:?}", error);
+                    std::thread::sleep(WATCH_RETRY_DELAY);
+                    continue;
+                }
+            };
+
+            let disguised_files: Vec<PathBuf> = new_files.into_iter()
+                .filter(|new_file| filenames::is_disguised(&file_name_of(new_file)))
+                .collect();
+            if disguised_files.is_empty() {
+                continue;
+            }
+            if !sender.send(Backend(BackendMessage::DisguisedFilesFound(disguised_files))) {
+                return;
+            }
+        }
+    })
+}
+
+#[cfg(feature = "watcher-etw")]
+fn get_listener_for_traced_changes() -> Subscription<Message> {
+    worker::listen(std::any::TypeId::of::<ChangeTracer>(), "change tracer", |sender: WorkerSender<Message>| {
+        let mut tracer: Option<ChangeTracer> = None;
+        loop {
+            let trace_result: Result<(ChangeTracer, ChangeAttribution)> = tracer.take()
+                .map_or_else(ChangeTracer::start, Ok)
+                .and_then(|tracer| tracer.wait_for_change().map(|change| (tracer, change)));
+            match trace_result {
+                Ok((kept_tracer, change)) => {
+                    tracer = Some(kept_tracer);
+                    if !sender.send(Backend(BackendMessage::HideFileExtTurnedOnBy(change))) {
+                        return;
+                    }
+                },
+                Err(error) => {
+                    warn!("Failed to trace registry changes: {:?}", error);
+                    std::thread::sleep(WATCH_RETRY_DELAY);
+                }
+            }
+        }
+    })
+}
+
+// The name of a file without its folder, as Windows Explorer would list it
+fn file_name_of(path: &Path) -> String {
+    path.file_name()
+        .map(OsStr::to_string_lossy)
+        .map(String::from)
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "tray")]
+fn get_listener_for_tray_retries() -> Subscription<Message> {
+    worker::listen(
+        std::any::TypeId::of::<TrayMenuAction>(),
+        "tray retry timer",
+        |sender: WorkerSender<Message>| loop {
+            std::thread::sleep(TRAY_RETRY_INTERVAL);
+            if !sender.send(Ui(UiMessage::RetryTrayCreation)) {
+                return;
+            }
+        }
+    )
+}
+
+fn get_listener_for_clock_ticks() -> Subscription<Message> {
+    worker::listen(std::any::TypeId::of::<Duration>(), "clock", |sender: WorkerSender<Message>| loop {
+        std::thread::sleep(CLOCK_TICK_INTERVAL);
+        if !sender.send(Backend(BackendMessage::ClockTick)) {
+            return;
+        }
+    })
+}
+
+// Ticks the clock once the pause is over, which resumes protection
+fn get_listener_for_pause_end(paused_until: DateTime<Local>) -> Subscription<Message> {
+    let id: (std::any::TypeId, DateTime<Local>) = (std::any::TypeId::of::<PauseDuration>(), paused_until);
+    worker::listen(id, "pause timer", move |sender: WorkerSender<Message>| {
+        if let Ok(pause_left) = (paused_until - Local::now()).to_std() {
+            std::thread::sleep(pause_left);
+        }
+        sender.send(Backend(BackendMessage::ClockTick));
+    })
+}
+
+fn get_listener_for_tray_pulses() -> Subscription<Message> {
+    worker::listen(std::any::TypeId::of::<Instant>(), "tray pulse", |sender: WorkerSender<Message>| loop {
+        std::thread::sleep(TRAY_PULSE_INTERVAL);
+        if !sender.send(Ui(UiMessage::PulseTrayIcon)) {
+            return;
+        }
+    })
+}
+
+// Checks once right away and then once a day. Failures are only logged, since the user didn't ask this time.
+fn get_listener_for_update_checks() -> Subscription<Message> {
+    worker::listen(std::any::TypeId::of::<Release>(), "update checker", |sender: WorkerSender<Message>| loop {
+        // the user may have turned update checks off while this slept
+        if sender.is_closed() {
+            return;
+        }
+        match update_check::find_newer_release() {
+            Ok(Some(release)) => if !sender.send(Backend(BackendMessage::NewerReleaseFound(release))) {
+                return;
+            },
+            Ok(None) => {},
+            Err(error) => warn!("Failed to check for updates: {:?}", error),
+        }
+        std::thread::sleep(update_check::UPDATE_CHECK_INTERVAL);
+    })
+}
+
+// The request can take until it times out, so it runs on its own thread rather than freezing the window
+fn check_for_updates_in_background() -> Command<Message> {
+    run_in_background(
+        "manual update check",
+        update_check::find_newer_release,
+        |result| Backend(BackendMessage::UpdateCheckFinished(result)),
+    )
+}
+
+// Downloading and swapping the executable takes a while, so it runs on its own thread rather than freezing the window
+fn install_update_in_background(release: Release) -> Command<Message> {
+    run_in_background(
+        "update installer",
+        move || self_update::install(&release),
+        |result| Backend(BackendMessage::UpdateInstallFinished(result)),
+    )
+}
+
+// Runs the work on a thread of its own and delivers its result, with the error's details if it failed, as a message
+fn run_in_background<T: Send + 'static>(
+    thread_name: &str,
+    work: impl FnOnce() -> Result<T> + Send + 'static,
+    to_message: fn(Result<T, String>) -> Message,
+) -> Command<Message> {
+    let (sender, receiver) = oneshot::channel();
+    let spawned = std::thread::Builder::new()
+        .name(String::from(thread_name))
+        .spawn(move || {
+            let result: Result<T, String> = work().map_err(|error| format!("{:#}", error));
+            // nothing is left to tell if the window closed in the meantime
+            let _ = sender.send(result);
+        });
+    let spawn_error: Option<String> = spawned.err()
+        .map(|error| format!("Failed to start the {} thread: {:?}", thread_name, error));
+    Command::perform(
+        async move {
+            match spawn_error {
+                Some(details) => Err(details),
+                None => receiver.await.unwrap_or_else(|_| Err(String::from("The thread ended without a result"))),
+            }
+        },
+        to_message,
+    )
+}
+
+fn get_commands_which_notify_user() -> Command<Message> {
+    Command::batch(vec![
+        window::change_mode(Mode::Windowed),
+        window::minimize(false),
+        window::request_user_attention(Some(UserAttention::Informational)),
+        window::gain_focus(),
+    ])
+}
@@ -0,0 +1,106 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Deserialize;
+use tracing::{instrument, trace};
+
+use crate::build_info;
+use crate::err;
+use crate::http;
+
+// Draft and pre-releases are left out of "latest"
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/Philosobyte/no-hidden-extensions/releases/latest";
+const GITHUB_API_HEADERS: &str = "Accept: application/vnd.github+json\r\n";
+
+// GitHub allows 60 unauthenticated requests an hour, so once a day is well within bounds
+pub(crate) const UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+// A version number such as 1.2.3. Pre-release and build labels are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+impl Version {
+    // Release tags usually start with a v, e.g. v1.2.3
+    pub(crate) fn parse(version: &str) -> Option<Version> {
+        let version: &str = version.trim().trim_start_matches(['v', 'V']);
+        let version: &str = version.split(['-', '+']).next()?;
+        let mut numbers = version.split('.').map(|number| number.parse::<u64>());
+        let major: u64 = numbers.next()?.ok()?;
+        // e.g. v2 or v2.1
+        let minor: u64 = numbers.next().unwrap_or(Ok(0)).ok()?;
+        let patch: u64 = numbers.next().unwrap_or(Ok(0)).ok()?;
+        Some(Version { major, minor, patch })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+// What a release has to include for this program to update itself
+const EXECUTABLE_ASSET_NAME: &str = "no-hidden-extensions.exe";
+// holds the executable's SHA-256 hash in hex, as written by sha256sum or Get-FileHash
+const CHECKSUM_ASSET_NAME: &str = "no-hidden-extensions.exe.sha256";
+
+// A published release, as far as the user needs to know about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Release {
+    pub(crate) version: Version,
+    // the release's page, where the user can read what changed and download it
+    pub(crate) url: String,
+    // where to download the new executable and its hash from, if the release has them
+    pub(crate) executable_url: Option<String>,
+    pub(crate) checksum_url: Option<String>,
+}
+
+// The parts of GitHub's answer which matter here
+#[derive(Debug, Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<GitHubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl GitHubRelease {
+    fn asset_url(&self, name: &str) -> Option<String> {
+        self.assets.iter()
+            .find(|asset| asset.name.eq_ignore_ascii_case(name))
+            .map(|asset| asset.browser_download_url.clone())
+    }
+}
+
+// Asks GitHub for the latest release. Returns it if it's newer than this executable, or None if this is up to date.
+#[instrument]
+pub(crate) fn find_newer_release() -> Result<Option<Release>> {
+    let response: String = http::get(LATEST_RELEASE_URL, GITHUB_API_HEADERS)?;
+    let latest: GitHubRelease = serde_json::from_str(&response)?;
+    let latest_version: Version = Version::parse(&latest.tag_name)
+        .ok_or_else(|| err::InvalidVersionError(latest.tag_name.clone()))?;
+    let current_version: Version = Version::parse(build_info::VERSION)
+        .ok_or_else(|| err::InvalidVersionError(String::from(build_info::VERSION)))?;
+
+    trace!("The latest release is {}, this is {}", latest_version, current_version);
+    return match latest_version > current_version {
+        true => Ok(Some(Release {
+            version: latest_version,
+            executable_url: latest.asset_url(EXECUTABLE_ASSET_NAME),
+            checksum_url: latest.asset_url(CHECKSUM_ASSET_NAME),
+            url: latest.html_url,
+        })),
+        false => Ok(None),
+    };
+}
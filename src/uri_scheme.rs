@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use tracing::{instrument, trace};
+use winreg::RegKey;
+use winreg::enums::HKEY_CURRENT_USER;
+
+use crate::ipc::IpcCommand;
+use crate::registry::WinregRegistry;
+use crate::windows_ops;
+
+// Links such as nhe://fix start or bring up this program
+pub(crate) const URI_SCHEME: &str = "nhe";
+
+// Under HKEY_CURRENT_USER, so no administrator rights are needed
+const URI_SCHEME_REGKEY_SUBPATH: &str = "Software\\Classes\\nhe";
+const URI_SCHEME_COMMAND_REGKEY_SUBPATH: &str = "Software\\Classes\\nhe\\shell\\open\\command";
+// marks the key as a URI scheme rather than a file type
+const URL_PROTOCOL_VALUE_NAME: &str = "URL Protocol";
+// the key's default value
+const DEFAULT_VALUE_NAME: &str = "";
+
+// Has Windows hand nhe:// links to this executable. Called on every start, so the links follow the executable
+// when it moves. This method returns whether a change was made.
+#[instrument]
+pub(crate) fn register() -> Result<bool> {
+    let current_executable_path: PathBuf = std::env::current_exe()?;
+    let command: String = format!("\"{}\" --uri \"%1\"", current_executable_path.display());
+
+    let description_changed: bool = windows_ops::set_or_update_registry_value(
+        &WinregRegistry,
+        HKEY_CURRENT_USER,
+        URI_SCHEME_REGKEY_SUBPATH,
+        DEFAULT_VALUE_NAME,
+        String::from("URL:no-hidden-extensions"),
+    )?;
+    let protocol_changed: bool = windows_ops::set_or_update_registry_value(
+        &WinregRegistry, HKEY_CURRENT_USER, URI_SCHEME_REGKEY_SUBPATH, URL_PROTOCOL_VALUE_NAME, String::new()
+    )?;
+    let command_changed: bool = windows_ops::set_or_update_registry_value(
+        &WinregRegistry, HKEY_CURRENT_USER, URI_SCHEME_COMMAND_REGKEY_SUBPATH, DEFAULT_VALUE_NAME, command
+    )?;
+    Ok(description_changed || protocol_changed || command_changed)
+}
+
+// Stops Windows from handing nhe:// links to this program.
+// This method returns whether a change was made.
+#[instrument]
+pub(crate) fn unregister() -> Result<bool> {
+    return match RegKey::predef(HKEY_CURRENT_USER).delete_subkey_all(URI_SCHEME_REGKEY_SUBPATH) {
+        Ok(()) => Ok(true),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(error.into()),
+    };
+}
+
+// What a link asks for, e.g. nhe://fix. Browsers may add a trailing slash, and schemes are case-insensitive.
+// Returns None for links this program doesn't know.
+pub(crate) fn parse(uri: &str) -> Option<IpcCommand> {
+    let (scheme, action) = uri.split_once("://")?;
+    if !scheme.eq_ignore_ascii_case(URI_SCHEME) {
+        return None;
+    }
+    trace!("Following the link {}", uri);
+    match action.trim_end_matches('/').to_ascii_lowercase().as_str() {
+        "fix" => Some(IpcCommand::Fix),
+        "open" => Some(IpcCommand::ShowWindow),
+        _ => None,
+    }
+}
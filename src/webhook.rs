@@ -0,0 +1,129 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use crate::audit_log::AuditEntry;
+use crate::err;
+use crate::event_sinks::EventSink;
+use crate::http;
+use crate::windows_ops::SettingScope;
+
+// What HideFileExt meant for file extensions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum FileExtensionState {
+    Hidden,
+    Visible,
+    // the value doesn't exist, which Windows takes as hidden
+    NotSet,
+}
+
+impl FileExtensionState {
+    fn from_value(value: Option<u32>) -> FileExtensionState {
+        match value {
+            Some(0) => FileExtensionState::Visible,
+            Some(_) => FileExtensionState::Hidden,
+            None => FileExtensionState::NotSet,
+        }
+    }
+}
+
+// The JSON body posted to the webhook for every change to HideFileExt
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct WebhookPayload {
+    hostname: String,
+    user: String,
+    scope: SettingScope,
+    old_state: FileExtensionState,
+    new_state: FileExtensionState,
+    remediated_by_app: bool,
+    timestamp: DateTime<Local>,
+}
+
+impl WebhookPayload {
+    fn from_entry(entry: &AuditEntry) -> WebhookPayload {
+        WebhookPayload {
+            hostname: std::env::var("COMPUTERNAME").unwrap_or_default(),
+            user: std::env::var("USERNAME").unwrap_or_default(),
+            scope: entry.scope,
+            old_state: FileExtensionState::from_value(entry.old_value),
+            new_state: FileExtensionState::from_value(entry.new_value),
+            remediated_by_app: entry.remediated_by_app,
+            timestamp: entry.timestamp,
+        }
+    }
+}
+
+// Trims the URL the user entered and checks that it's a web address, so a typo or a file: link is refused
+// rather than posted to
+pub(crate) fn parse_url(url: &str) -> Result<String> {
+    let url: &str = url.trim();
+    let address: Option<&str> = ["https://", "http://"].iter()
+        .find(|scheme| url.get(..scheme.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme)))
+        .map(|scheme| &url[scheme.len()..]);
+    let host: Option<&str> = address.and_then(|address| address.split(['/', '?', '#']).next());
+    match (address, host) {
+        (Some(address), Some(host)) if !host.is_empty() && !address.contains(char::is_whitespace) =>
+            Ok(String::from(url)),
+        _ => Err(err::HttpError::InvalidUrl(String::from(url)).into()),
+    }
+}
+
+// Posts every change as JSON to a URL
+#[derive(Debug)]
+pub(crate) struct WebhookSink {
+    url: String,
+}
+
+impl WebhookSink {
+    pub(crate) fn new(url: &str) -> WebhookSink {
+        WebhookSink { url: String::from(url) }
+    }
+}
+
+impl EventSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn send(&self, entry: &AuditEntry) -> Result<()> {
+        let body: String = serde_json::to_string(&WebhookPayload::from_entry(entry))?;
+        http::post_json(&self.url, &body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::webhook::parse_url;
+
+    #[test]
+    fn surrounding_whitespace_is_trimmed() {
+        assert_eq!(parse_url("  https://siem.example.com/hook \n").unwrap(), "https://siem.example.com/hook");
+    }
+
+    #[test]
+    fn http_and_https_urls_are_accepted_in_any_case() {
+        assert!(parse_url("http://10.0.0.5:8080/events").is_ok());
+        assert!(parse_url("HTTPS://siem.example.com").is_ok());
+    }
+
+    #[test]
+    fn other_schemes_are_rejected() {
+        assert!(parse_url("file:///C:/Windows/System32/calc.exe").is_err());
+        assert!(parse_url("ftp://siem.example.com").is_err());
+        assert!(parse_url("javascript:alert(1)").is_err());
+    }
+
+    #[test]
+    fn urls_without_a_scheme_or_host_are_rejected() {
+        assert!(parse_url("siem.example.com/hook").is_err());
+        assert!(parse_url("https://").is_err());
+        assert!(parse_url("https:///hook").is_err());
+        assert!(parse_url("").is_err());
+    }
+
+    #[test]
+    fn urls_with_whitespace_inside_are_rejected() {
+        assert!(parse_url("https://siem.example.com/my hook").is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use tracing::{instrument, trace, warn};
+use windows::core::{BSTR, PCWSTR};
+use windows::Win32::System::Com::{
+    CLSCTX_INPROC_SERVER, CoCreateInstance, CoSetProxyBlanket, EOAC_NONE, RPC_C_AUTHN_LEVEL_CALL,
+    RPC_C_IMP_LEVEL_IMPERSONATE, VARIANT, VT_BSTR, VT_I4, VT_UI4,
+};
+use windows::Win32::System::Ole::VariantClear;
+use windows::Win32::System::Rpc::{RPC_C_AUTHN_WINNT, RPC_C_AUTHZ_NONE};
+use windows::Win32::System::Wmi::{
+    IEnumWbemClassObject, IWbemClassObject, IWbemLocator, IWbemServices, WBEM_FLAG_FORWARD_ONLY,
+    WBEM_FLAG_RETURN_IMMEDIATELY, WBEM_GENERIC_FLAG_TYPE, WBEM_INFINITE, WbemLocator,
+};
+
+use crate::com::ComApartment;
+use crate::err;
+use crate::windows_ops;
+
+// The only query language WMI understands
+const QUERY_LANGUAGE: &str = "WQL";
+
+// A property of a WMI object, for the types this program reads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WmiValue {
+    Integer(i64),
+    String(String),
+    // null, or a type this program has no use for
+    Other,
+}
+
+// The requested properties of one object a query found
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WmiObject {
+    properties: HashMap<String, WmiValue>,
+}
+
+impl WmiObject {
+    pub(crate) fn integer(&self, name: &str) -> Option<i64> {
+        match self.properties.get(name)? {
+            WmiValue::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn string(&self, name: &str) -> Option<&str> {
+        match self.properties.get(name)? {
+            WmiValue::String(value) => Some(value.as_str()),
+            _ => None,
+        }
+    }
+}
+
+// Runs a WQL query in a WMI namespace such as root\SecurityCenter2, and reads the given properties
+// of every object it finds. Blocks until WMI has answered.
+#[instrument]
+pub(crate) fn query(namespace: &str, query: &str, property_names: &[&str]) -> Result<Vec<WmiObject>> {
+    let _com: ComApartment = ComApartment::enter()?;
+
+    let services: IWbemServices = connect(namespace)?;
+    let enumerator: IEnumWbemClassObject = unsafe {
+        services.ExecQuery(
+            &BSTR::from(QUERY_LANGUAGE),
+            &BSTR::from(query),
+            WBEM_GENERIC_FLAG_TYPE(WBEM_FLAG_FORWARD_ONLY.0 | WBEM_FLAG_RETURN_IMMEDIATELY.0),
+            None,
+        )
+    }.map_err(|error| err::WmiError::FailedToQuery { query: String::from(query), source: error })?;
+
+    let mut objects: Vec<WmiObject> = Vec::new();
+    loop {
+        let mut found: [Option<IWbemClassObject>; 1] = [None];
+        let mut found_count: u32 = 0;
+        unsafe { enumerator.Next(WBEM_INFINITE.0, &mut found, &mut found_count) }
+            .ok()
+            .map_err(|error| err::WmiError::FailedToQuery { query: String::from(query), source: error })?;
+        if found_count == 0 {
+            break;
+        }
+        let [Some(object)] = found else {
+            break;
+        };
+        objects.push(read_properties(&object, property_names));
+    }
+    trace!("{} found {} objects", query, objects.len());
+    Ok(objects)
+}
+
+fn connect(namespace: &str) -> Result<IWbemServices> {
+    let to_connection_error = |error: windows::core::Error| {
+        err::WmiError::FailedToConnect { namespace: String::from(namespace), source: error }
+    };
+
+    let locator: IWbemLocator = unsafe { CoCreateInstance(&WbemLocator, None, CLSCTX_INPROC_SERVER) }
+        .map_err(to_connection_error)?;
+    // empty credentials connect as the current user
+    let services: IWbemServices = unsafe {
+        locator.ConnectServer(&BSTR::from(namespace), &BSTR::new(), &BSTR::new(), &BSTR::new(), 0, &BSTR::new(), None)
+    }.map_err(to_connection_error)?;
+
+    // WMI checks who is asking on every call, so it has to be allowed to act as the current user
+    unsafe {
+        CoSetProxyBlanket(
+            &services,
+            RPC_C_AUTHN_WINNT,
+            RPC_C_AUTHZ_NONE,
+            PCWSTR::null(),
+            RPC_C_AUTHN_LEVEL_CALL,
+            RPC_C_IMP_LEVEL_IMPERSONATE,
+            None,
+            EOAC_NONE,
+        )
+    }.map_err(to_connection_error)?;
+    Ok(services)
+}
+
+// A property which can't be read is left out, as if the object didn't have it
+fn read_properties(object: &IWbemClassObject, property_names: &[&str]) -> WmiObject {
+    let mut properties: HashMap<String, WmiValue> = HashMap::new();
+    for property_name in property_names {
+        let wide_name: Vec<u16> = windows_ops::to_wide_string(property_name);
+        let mut variant: VARIANT = VARIANT::default();
+        match unsafe { object.Get(PCWSTR(wide_name.as_ptr()), 0, &mut variant, None, None) } {
+            Ok(()) => {
+                properties.insert(String::from(*property_name), value_from_variant(&variant));
+            },
+            Err(error) => warn!("Failed to read the WMI property {}: {:?}", property_name, error),
+        }
+        if let Err(error) = unsafe { VariantClear(&mut variant) } {
+            warn!("Failed to free the WMI property {}: {:?}", property_name, error);
+        }
+    }
+    WmiObject { properties }
+}
+
+fn value_from_variant(variant: &VARIANT) -> WmiValue {
+    unsafe {
+        let inner = &variant.Anonymous.Anonymous;
+        match inner.vt {
+            VT_I4 => WmiValue::Integer(i64::from(inner.Anonymous.lVal)),
+            VT_UI4 => WmiValue::Integer(i64::from(inner.Anonymous.ulVal)),
+            VT_BSTR => WmiValue::String(inner.Anonymous.bstrVal.to_string()),
+            _ => WmiValue::Other,
+        }
+    }
+}
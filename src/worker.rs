@@ -0,0 +1,257 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use iced::futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use iced::futures::StreamExt;
+use iced::{subscription, Subscription};
+use tracing::{trace, warn};
+
+// How often a worker whose wait can't be interrupted looks up to see whether the UI stopped listening
+pub(crate) const CLOSE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+// Supervised workers beat at least this often, so their waits have to time out at least this often too
+pub(crate) const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+// A supervised worker which hasn't beaten for this long is taken to be stuck, and replaced
+const STALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+// How often the watchdog looks at the supervised workers
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(10);
+
+// Monitoring counts as degraded for this long after a worker had to be replaced, since it may have missed changes
+const DEGRADED_PERIOD: Duration = Duration::from_secs(5 * 60);
+
+// Every supervised worker whose subscription is still around
+static SUPERVISED_WORKERS: Mutex<Vec<Box<dyn Supervised>>> = Mutex::new(Vec::new());
+
+static WATCHDOG: OnceLock<()> = OnceLock::new();
+
+// The end of a worker's channel which stays on its thread
+#[derive(Debug)]
+pub(crate) struct WorkerSender<T> {
+    sender: UnboundedSender<T>,
+    last_heartbeat: Arc<Mutex<Instant>>,
+    // set once the watchdog replaced this worker, so a stuck thread which wakes up again bows out
+    retired: Arc<AtomicBool>,
+}
+
+impl<T> WorkerSender<T> {
+    fn new(sender: UnboundedSender<T>) -> WorkerSender<T> {
+        WorkerSender {
+            sender,
+            last_heartbeat: Arc::new(Mutex::new(Instant::now())),
+            retired: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // This method returns false once the UI has stopped listening, at which point the worker should return
+    pub(crate) fn send(&self, item: T) -> bool {
+        if self.retired.load(Ordering::SeqCst) {
+            return false;
+        }
+        self.heartbeat();
+        self.sender.unbounded_send(item).is_ok()
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.retired.load(Ordering::SeqCst) || self.sender.is_closed()
+    }
+
+    // Tells the watchdog this worker is still making progress. Sending counts too.
+    pub(crate) fn heartbeat(&self) {
+        *self.last_heartbeat.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Instant::now();
+    }
+
+    fn since_last_heartbeat(&self) -> Duration {
+        self.last_heartbeat.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).elapsed()
+    }
+}
+
+// Starts whatever feeds the channel
+type Starter<T> = Box<dyn FnOnce(UnboundedSender<T>) -> std::io::Result<()> + Send>;
+
+type Work<T> = Arc<dyn Fn(WorkerSender<T>) + Send + Sync>;
+
+enum WorkerState<T> {
+    NotStarted(Starter<T>),
+    Running(UnboundedReceiver<T>),
+    Finished,
+}
+
+// Runs `work` on a dedicated thread, which may block on Windows for as long as it likes, and hands whatever it
+// sends to the UI. The subscription itself only drains the channel, so none of iced's executor threads is ever
+// blocked. Once the subscription goes away, e.g. because its id changed, the worker's next send fails.
+pub(crate) fn listen<I, T>(
+    id: I, thread_name: &'static str, work: impl FnOnce(WorkerSender<T>) + Send + 'static
+) -> Subscription<T>
+    where I: Hash + 'static, T: Debug + Send + 'static
+{
+    subscribe(id, thread_name, Box::new(move |sender: UnboundedSender<T>| {
+        std::thread::Builder::new()
+            .name(String::from(thread_name))
+            .spawn(move || work(WorkerSender::new(sender)))?;
+        Ok(())
+    }))
+}
+
+// Like `listen()`, but a watchdog starts `work` over on a new thread whenever it panics, returns while the UI
+// still listens, or stops beating. `work` has to call `WorkerSender::heartbeat()` at least every
+// `HEARTBEAT_INTERVAL`, even while nothing happens.
+pub(crate) fn supervise<I, T>(
+    id: I, thread_name: &'static str, work: impl Fn(WorkerSender<T>) + Send + Sync + 'static
+) -> Subscription<T>
+    where I: Hash + 'static, T: Debug + Send + 'static
+{
+    let work: Work<T> = Arc::new(work);
+    subscribe(id, thread_name, Box::new(move |sender: UnboundedSender<T>| {
+        let worker: SupervisedWorker<T> = SupervisedWorker::start(thread_name, work, sender)?;
+        SUPERVISED_WORKERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(Box::new(worker));
+        start_watchdog();
+        Ok(())
+    }))
+}
+
+fn subscribe<I, T>(id: I, thread_name: &'static str, starter: Starter<T>) -> Subscription<T>
+    where I: Hash + 'static, T: Debug + Send + 'static
+{
+    subscription::unfold(
+        id,
+        WorkerState::NotStarted(starter),
+        move |state: WorkerState<T>| async move {
+            match state {
+                WorkerState::NotStarted(starter) => {
+                    let (sender, receiver): (UnboundedSender<T>, UnboundedReceiver<T>) = unbounded();
+                    match starter(sender) {
+                        Ok(()) => (None, WorkerState::Running(receiver)),
+                        Err(error) => {
+                            warn!("Failed to start the {} thread: {:?}", thread_name, error);
+                            (None, WorkerState::Finished)
+                        },
+                    }
+                },
+                WorkerState::Running(mut receiver) => match receiver.next().await {
+                    Some(item) => (Some(item), WorkerState::Running(receiver)),
+                    None => {
+                        trace!("The {} thread stopped", thread_name);
+                        (None, WorkerState::Finished)
+                    },
+                },
+                WorkerState::Finished => iced::futures::future::pending().await,
+            }
+        }
+    )
+}
+
+// The names of the workers which had to be replaced recently, if any
+pub(crate) fn degraded_workers() -> Vec<&'static str> {
+    SUPERVISED_WORKERS.lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter()
+        .filter(|worker| worker.is_degraded())
+        .map(|worker| worker.thread_name())
+        .collect()
+}
+
+trait Supervised: Send {
+    fn thread_name(&self) -> &'static str;
+
+    // Replaces the worker's thread if it died or got stuck. This method returns false once the UI stopped
+    // listening, so the worker can be forgotten.
+    fn check(&mut self) -> bool;
+
+    fn is_degraded(&self) -> bool;
+}
+
+struct SupervisedWorker<T> {
+    thread_name: &'static str,
+    work: Work<T>,
+    sender: UnboundedSender<T>,
+    // what the current thread reports through
+    current: WorkerSender<T>,
+    thread: JoinHandle<()>,
+    last_replaced: Option<Instant>,
+}
+
+impl<T: Send + 'static> SupervisedWorker<T> {
+    fn start(
+        thread_name: &'static str, work: Work<T>, sender: UnboundedSender<T>
+    ) -> std::io::Result<SupervisedWorker<T>> {
+        let (current, thread) = spawn(thread_name, &work, &sender)?;
+        Ok(SupervisedWorker { thread_name, work, sender, current, thread, last_replaced: None })
+    }
+
+    fn replace(&mut self) {
+        self.current.retired.store(true, Ordering::SeqCst);
+        match spawn(self.thread_name, &self.work, &self.sender) {
+            Ok((current, thread)) => {
+                self.current = current;
+                self.thread = thread;
+            },
+            // tried again at the watchdog's next look
+            Err(error) => warn!("Failed to restart the {} thread: {:?}", self.thread_name, error),
+        }
+        self.last_replaced = Some(Instant::now());
+    }
+}
+
+impl<T: Send + 'static> Supervised for SupervisedWorker<T> {
+    fn thread_name(&self) -> &'static str {
+        self.thread_name
+    }
+
+    fn check(&mut self) -> bool {
+        if self.sender.is_closed() {
+            self.current.retired.store(true, Ordering::SeqCst);
+            return false;
+        }
+        if self.thread.is_finished() {
+            warn!("The {} thread stopped, starting it again", self.thread_name);
+            self.replace();
+        } else if self.current.since_last_heartbeat() > STALL_TIMEOUT {
+            warn!("The {} thread stopped responding, starting another in its place", self.thread_name);
+            self.replace();
+        }
+        true
+    }
+
+    fn is_degraded(&self) -> bool {
+        self.last_replaced.is_some_and(|last_replaced| last_replaced.elapsed() < DEGRADED_PERIOD)
+    }
+}
+
+// The worker's own `WorkerSender` shares its heartbeat and retirement with the returned one
+fn spawn<T: Send + 'static>(
+    thread_name: &'static str, work: &Work<T>, sender: &UnboundedSender<T>
+) -> std::io::Result<(WorkerSender<T>, JoinHandle<()>)> {
+    let worker_sender: WorkerSender<T> = WorkerSender::new(sender.clone());
+    let watched_sender: WorkerSender<T> = WorkerSender {
+        sender: sender.clone(),
+        last_heartbeat: worker_sender.last_heartbeat.clone(),
+        retired: worker_sender.retired.clone(),
+    };
+    let work: Work<T> = work.clone();
+    let thread: JoinHandle<()> = std::thread::Builder::new()
+        .name(String::from(thread_name))
+        .spawn(move || work(worker_sender))?;
+    Ok((watched_sender, thread))
+}
+
+fn start_watchdog() {
+    WATCHDOG.get_or_init(|| {
+        let spawned = std::thread::Builder::new()
+            .name(String::from("watchdog"))
+            .spawn(|| loop {
+                std::thread::sleep(WATCHDOG_INTERVAL);
+                SUPERVISED_WORKERS.lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .retain_mut(|worker| worker.check());
+            });
+        if let Err(error) = spawned {
+            warn!("Failed to start the watchdog, so stuck monitoring threads won't be restarted: {:?}", error);
+        }
+    });
+}